@@ -0,0 +1,51 @@
+/*
+ * C interface to the auto clicker's engine, built with
+ *
+ *     cargo rustc --lib --release --features ffi --crate-type cdylib
+ *
+ * Functions taking an engine do nothing, or return 0/false, when given NULL.
+ * The engine's state is global, so only create one per process.
+ */
+
+#ifndef AUTO_CLICKER_H
+#define AUTO_CLICKER_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Engine Engine;
+
+/* Starts an engine, stopped and with default settings. Returns NULL while
+ * another engine is alive. */
+Engine *ac_engine_new(void);
+/* Stops the engine and waits for its thread to finish. */
+void ac_engine_free(Engine *engine);
+
+void ac_engine_start(const Engine *engine);
+void ac_engine_stop(const Engine *engine);
+void ac_engine_toggle(const Engine *engine);
+bool ac_engine_is_running(const Engine *engine);
+/* Clicks in the current or last run. */
+uint64_t ac_engine_clicks(const Engine *engine);
+
+void ac_engine_set_interval_ms(const Engine *engine, uint64_t interval_ms);
+/* Stops runs after limit_ms, or never with 0. */
+void ac_engine_set_run_limit_ms(const Engine *engine, uint64_t limit_ms);
+/* Clicks at (x, y) on the whole desktop. */
+void ac_engine_set_position(const Engine *engine, int32_t x, int32_t y);
+/* Clicks wherever the cursor is, the default. */
+void ac_engine_set_cursor_position(const Engine *engine);
+/* 0 = left, 1 = middle, 2 = right. Returns false for other values. */
+bool ac_engine_set_button(Engine *engine, uint32_t button);
+/* 1 to 10 clicks each trigger. Returns false for other counts. */
+bool ac_engine_set_clicks_per_trigger(Engine *engine, uint32_t clicks);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AUTO_CLICKER_H */
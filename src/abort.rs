@@ -0,0 +1,111 @@
+//! The emergency abort: holding Esc for a second stops everything that
+//! sends input, wherever the focus is. Unlike the hotkeys it cannot be
+//! rebound or switched off.
+//!
+//! Besides the clicker and the jobs, which are stopped through their usual
+//! commands, long-running senders such as macro playback, scripts and
+//! sequence delays watch [`generation`] and give up once it changes.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{RecvTimeoutError, Sender},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::{Button, EventType, Key};
+
+use crate::{
+    clicker::{self, Command},
+    jobs, listener, overlay,
+    timeline::{self, Activity},
+};
+
+/// The key to hold.
+pub const KEY: Key = Key::Escape;
+
+/// How long [`KEY`] has to be held.
+pub const HOLD: Duration = Duration::from_secs(1);
+
+/// How often a held key is looked at, and how often [`sleep`] checks for
+/// an abort.
+const POLL: Duration = Duration::from_millis(20);
+
+/// Counts the aborts so far.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Changes with every abort. Senders note it when they start and stop once
+/// it differs.
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Whether there was an abort since [`generation`] returned `since`.
+pub fn is_aborted(since: u64) -> bool {
+    generation() != since
+}
+
+/// Sleeps for `duration` unless there is an abort since `since`, checking
+/// every few milliseconds. Returns `false` if it was cut short.
+pub fn sleep(duration: Duration, since: u64) -> bool {
+    let until = Instant::now() + duration;
+    loop {
+        if is_aborted(since) {
+            return false;
+        }
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        thread::sleep(left.min(POLL));
+    }
+}
+
+/// Stops all clicking right away: the clicker with its sequence and
+/// scheduled start, the jobs, macro playback and scripts, then releases the
+/// mouse buttons in case a hold or drag was cut off while pressed.
+pub fn trigger(tx_command: &Sender<Command>) {
+    log::warn!("Emergency abort, stopping everything");
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    tx_command.send(Command::Stop).ok();
+    jobs::stop_all();
+    for button in [Button::Left, Button::Right, Button::Middle] {
+        clicker::send(&EventType::ButtonRelease(button));
+    }
+    overlay::flash_stop();
+    timeline::record(Activity::Safety);
+}
+
+/// Starts a thread that triggers the abort once [`KEY`] has been held for
+/// [`HOLD`].
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        // Since when the key is held, and whether this hold fired already.
+        let mut held: Option<Instant> = None;
+        let mut fired = false;
+        loop {
+            match rx_events.recv_timeout(POLL) {
+                // Held keys repeat their presses; the first one counts.
+                Ok(event) => match event.event_type {
+                    EventType::KeyPress(key) if key == KEY => {
+                        held.get_or_insert_with(Instant::now);
+                    }
+                    EventType::KeyRelease(key) if key == KEY => {
+                        held = None;
+                        fired = false;
+                    }
+                    _ => {}
+                },
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+            if !fired && held.is_some_and(|since| since.elapsed() >= HOLD) {
+                trigger(&tx_command);
+                fired = true;
+            }
+        }
+    });
+}
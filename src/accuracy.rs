@@ -0,0 +1,83 @@
+use std::{
+    fmt, mem,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The report of the last finished run, for the GUI and headless mode.
+static LAST_REPORT: Mutex<Option<AccuracyReport>> = Mutex::new(None);
+
+pub fn last_report() -> Option<AccuracyReport> {
+    *LAST_REPORT.lock().unwrap()
+}
+
+/// How closely a run kept to the requested interval, measured between the
+/// ends of consecutive cycles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccuracyReport {
+    pub requested: Duration,
+    pub intervals: usize,
+    pub mean: Duration,
+    pub std_dev: Duration,
+    /// The achieved interval furthest from the requested one.
+    pub worst: Duration,
+}
+
+impl fmt::Display for AccuracyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            "{} intervals, requested {:.1} ms, achieved {:.1} ± {:.1} ms, worst {:.1} ms",
+            self.intervals,
+            ms(self.requested),
+            ms(self.mean),
+            ms(self.std_dev),
+            ms(self.worst)
+        )
+    }
+}
+
+/// Timestamps the cycles of one run.
+#[derive(Debug, Default)]
+pub struct IntervalLog {
+    last: Option<Instant>,
+    intervals: Vec<Duration>,
+}
+
+impl IntervalLog {
+    /// Marks the end of a cycle.
+    pub fn record(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last.replace(now) {
+            self.intervals.push(now - last);
+        }
+    }
+
+    /// Ends the run and publishes its report. A run with fewer than two
+    /// cycles has no intervals and keeps the previous report.
+    pub fn finish(&mut self, requested: Duration) {
+        self.last = None;
+        let intervals = mem::take(&mut self.intervals);
+        if intervals.is_empty() {
+            return;
+        }
+
+        let secs: Vec<f64> = intervals.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+        let worst = intervals
+            .iter()
+            .copied()
+            .max_by_key(|interval| interval.abs_diff(requested))
+            .unwrap_or_default();
+
+        *LAST_REPORT.lock().unwrap() = Some(AccuracyReport {
+            requested,
+            intervals: intervals.len(),
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            worst,
+        });
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    sync::{mpsc::Sender, Mutex},
+    thread,
+};
+
+use rdev::{Button, EventType};
+
+use crate::{
+    clicker::Command,
+    i18n::{tr, tr_args},
+    keys, listener,
+    modifiers::Modifiers,
+};
+
+/// A key or mouse button, see [`Trigger`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Input {
+    Key(rdev::Key),
+    Button(Button),
+}
+
+/// A key or mouse button, with the modifiers that have to be held with it,
+/// that starts and stops the clicker, see [`Activation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trigger {
+    pub modifiers: Modifiers,
+    pub input: Input,
+}
+
+impl Trigger {
+    /// E.g. `Ctrl+Alt+S` or `Shift+Mouse button 4`.
+    pub fn name(self) -> String {
+        let input = match self.input {
+            Input::Key(key) => keys::label(key),
+            Input::Button(Button::Left) => "Left mouse button".to_owned(),
+            Input::Button(Button::Right) => "Right mouse button".to_owned(),
+            Input::Button(Button::Middle) => "Middle mouse button".to_owned(),
+            Input::Button(Button::Unknown(code)) => format!("Mouse button {code}"),
+        };
+        format!("{}{input}", self.modifiers)
+    }
+
+    /// [`Trigger::name`] in the user's language, for showing. Not read back
+    /// by [`Trigger::parse`].
+    pub fn label(self) -> String {
+        let input = match self.input {
+            Input::Key(key) => keys::label(key),
+            Input::Button(Button::Left) => tr("Left mouse button").into_owned(),
+            Input::Button(Button::Right) => tr("Right mouse button").into_owned(),
+            Input::Button(Button::Middle) => tr("Middle mouse button").into_owned(),
+            Input::Button(Button::Unknown(code)) => {
+                tr_args("Mouse button {code}", &[("code", &code)])
+            }
+        };
+        format!("{}{input}", self.modifiers)
+    }
+
+    /// Parses what [`Trigger::name`] returns.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (modifiers, s) = Modifiers::parse_prefix(s);
+        let button = match s {
+            "Left mouse button" => Some(Button::Left),
+            "Right mouse button" => Some(Button::Right),
+            "Middle mouse button" => Some(Button::Middle),
+            _ => s
+                .strip_prefix("Mouse button ")
+                .and_then(|code| code.parse().ok())
+                .map(Button::Unknown),
+        };
+        let input = match button {
+            Some(button) => Input::Button(button),
+            None => Input::Key(keys::parse(s)?),
+        };
+        Some(Self { modifiers, input })
+    }
+
+    /// `Some(true)` if `event_type` presses this key or button while
+    /// exactly its modifiers are held, `Some(false)` if it releases it,
+    /// whatever is held then.
+    pub fn pressed(self, event_type: &EventType, held: Modifiers) -> Option<bool> {
+        match (self.input, *event_type) {
+            (Input::Key(key), EventType::KeyPress(pressed)) if pressed == key => {
+                Some(true).filter(|_| held == self.modifiers)
+            }
+            (Input::Key(key), EventType::KeyRelease(released)) if released == key => Some(false),
+            (Input::Button(button), EventType::ButtonPress(pressed)) if pressed == button => {
+                Some(true).filter(|_| held == self.modifiers)
+            }
+            (Input::Button(button), EventType::ButtonRelease(released)) if released == button => {
+                Some(false)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How a [`Trigger`] runs the clicker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    /// Runs while the trigger is held down and stops on release.
+    Hold,
+    /// Each press starts or stops the clicker.
+    Toggle,
+}
+
+static HOLD: Mutex<Option<Trigger>> = Mutex::new(None);
+static TOGGLE: Mutex<Option<Trigger>> = Mutex::new(None);
+
+/// The activation whose trigger the next press becomes.
+static CAPTURING: Mutex<Option<Activation>> = Mutex::new(None);
+
+/// The press that ended the last capture, until taken.
+static CAPTURED: Mutex<Option<(Activation, Trigger)>> = Mutex::new(None);
+
+fn slot(activation: Activation) -> &'static Mutex<Option<Trigger>> {
+    match activation {
+        Activation::Hold => &HOLD,
+        Activation::Toggle => &TOGGLE,
+    }
+}
+
+/// Sets the key or button for `activation`, or `None` to turn it off.
+pub fn set_trigger(activation: Activation, trigger: Option<Trigger>) {
+    *slot(activation).lock().unwrap() = trigger;
+}
+
+/// The key or button set for `activation`, if any.
+pub fn trigger(activation: Activation) -> Option<Trigger> {
+    *slot(activation).lock().unwrap()
+}
+
+/// Makes the next key or mouse button press anywhere, with the modifiers
+/// held then, the new trigger of `activation` instead of acting on it, see
+/// [`take_captured`]. A modifier pressed and released alone becomes the
+/// trigger itself.
+pub fn capture(activation: Activation) {
+    *CAPTURING.lock().unwrap() = Some(activation);
+}
+
+pub fn capturing() -> Option<Activation> {
+    *CAPTURING.lock().unwrap()
+}
+
+/// The trigger captured since [`capture`], once it was pressed.
+pub fn take_captured() -> Option<(Activation, Trigger)> {
+    CAPTURED.lock().unwrap().take()
+}
+
+/// Starts a thread that runs the clicker from the triggers, wherever the
+/// focus is: while the hold trigger is held, stopping the instant it is
+/// released, and from one press of the toggle trigger to the next. The
+/// listener leaves out the clicks the clicker sends, so a trigger can be
+/// the button it clicks.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let mut hold_held = false;
+        let mut toggle_held = false;
+        let mut held = Modifiers::NONE;
+        // The modifier last pressed while capturing, until another key is.
+        let mut lone_modifier = None;
+        for event in rx_events {
+            let before = held;
+            let is_modifier = held.update(&event.event_type);
+
+            let mut capturing = CAPTURING.lock().unwrap();
+            if let Some(activation) = *capturing {
+                let captured = match event.event_type {
+                    EventType::KeyPress(key) if is_modifier => {
+                        lone_modifier = Some(key);
+                        None
+                    }
+                    EventType::KeyRelease(key) if lone_modifier == Some(key) => Some(Trigger {
+                        modifiers: Modifiers::NONE,
+                        input: Input::Key(key),
+                    }),
+                    EventType::KeyPress(key) => Some(Trigger {
+                        modifiers: held,
+                        input: Input::Key(key),
+                    }),
+                    EventType::ButtonPress(button) => Some(Trigger {
+                        modifiers: held,
+                        input: Input::Button(button),
+                    }),
+                    _ => None,
+                };
+                if let Some(trigger) = captured {
+                    *capturing = None;
+                    lone_modifier = None;
+                    *CAPTURED.lock().unwrap() = Some((activation, trigger));
+                }
+                continue;
+            }
+            drop(capturing);
+            lone_modifier = None;
+
+            // A modifier trigger is pressed with itself held.
+            let modifiers = if is_modifier { before } else { held };
+            let pressed = |activation| {
+                slot(activation)
+                    .lock()
+                    .unwrap()
+                    .and_then(|trigger| trigger.pressed(&event.event_type, modifiers))
+            };
+            // Held keys repeat their presses.
+            let command = match (pressed(Activation::Hold), pressed(Activation::Toggle)) {
+                (Some(true), _) if !hold_held => {
+                    hold_held = true;
+                    Command::Start
+                }
+                (Some(false), _) if hold_held => {
+                    hold_held = false;
+                    Command::Stop
+                }
+                (_, Some(true)) if !toggle_held => {
+                    toggle_held = true;
+                    Command::Toggle
+                }
+                (_, Some(false)) => {
+                    toggle_held = false;
+                    continue;
+                }
+                _ => continue,
+            };
+            if tx_command.send(command).is_err() {
+                break;
+            }
+        }
+    });
+}
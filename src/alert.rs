@@ -0,0 +1,84 @@
+//! Native error dialogs, for failures that keep the app window from opening
+//! at all, e.g. no usable graphics adapter. The message is logged as well,
+//! so it is not lost where no dialog can be shown.
+
+#[cfg(not(windows))]
+use std::process::Command;
+
+/// Shows `message` in a blocking error dialog titled `title`. Falls back to
+/// the terminal when no dialog is available.
+pub fn show_error(title: &str, message: &str) {
+    log::error!("{title}: {message}");
+    if !show_native(title, message) {
+        eprintln!("{title}\n\n{message}");
+    }
+}
+
+/// Versions and platform details to paste into a bug report.
+pub fn diagnostics() -> String {
+    let mut lines = vec![
+        format!("Auto Clicker {}", env!("CARGO_PKG_VERSION")),
+        format!("OS: {} {}", std::env::consts::OS, std::env::consts::ARCH),
+    ];
+    for var in ["WGPU_BACKEND", "WAYLAND_DISPLAY", "DISPLAY"] {
+        if let Ok(value) = std::env::var(var) {
+            lines.push(format!("{var}={value}"));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(windows)]
+fn show_native(title: &str, message: &str) -> bool {
+    const MB_OK: u32 = 0x0;
+    const MB_ICONERROR: u32 = 0x10;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn MessageBoxW(window: isize, text: *const u16, caption: *const u16, kind: u32) -> i32;
+    }
+
+    let wide = |text: &str| text.encode_utf16().chain([0]).collect::<Vec<u16>>();
+    let (text, caption) = (wide(message), wide(title));
+    // SAFETY: both strings are NUL-terminated and outlive the call, and a
+    // null owner window is allowed.
+    unsafe { MessageBoxW(0, text.as_ptr(), caption.as_ptr(), MB_OK | MB_ICONERROR) != 0 }
+}
+
+#[cfg(target_os = "macos")]
+fn show_native(title: &str, message: &str) -> bool {
+    // Passed as arguments rather than spliced into the script, so quotes in
+    // the message need no escaping.
+    Command::new("osascript")
+        .args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display alert (item 1 of argv) message (item 2 of argv) as critical",
+            "-e",
+            "end run",
+            title,
+            message,
+        ])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Tries the dialog tools of the common desktops in turn.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn show_native(title: &str, message: &str) -> bool {
+    let attempts: [(&str, Vec<&str>); 3] = [
+        (
+            "zenity",
+            vec!["--error", "--title", title, "--text", message],
+        ),
+        ("kdialog", vec!["--title", title, "--error", message]),
+        ("xmessage", vec!["-center", message]),
+    ];
+    attempts.into_iter().any(|(program, args)| {
+        Command::new(program)
+            .args(args)
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
@@ -0,0 +1,167 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    accuracy,
+    config::{self, Table},
+    profile::Profile,
+    recorder::Recording,
+    stats,
+};
+
+/// First line of every bundle.
+const HEADER: &str = "auto-clicker bundle 1";
+
+/// Starts each file in a bundle, followed by its path.
+const FILE_MARKER: &str = "=== ";
+
+/// Directories of the config dir that are bundled, besides `settings.toml`.
+const DIRS: &[&str] = &["profiles", "themes"];
+
+/// What an imported bundle holds besides config files, for the GUI to
+/// apply.
+#[derive(Debug, Default)]
+pub struct Imported {
+    /// The click settings that were current at export.
+    pub current: Option<Profile>,
+    pub recording: Option<Recording>,
+}
+
+/// Writes the whole app state to one text file at `path`: the settings,
+/// every profile and theme, the current click settings, the macro
+/// recording and the figures of the last run.
+///
+/// ```text
+/// auto-clicker bundle 1
+/// === settings.toml
+/// [dwell]
+/// enabled = true
+/// === profiles/work.toml
+/// ...
+/// ```
+pub fn export(path: &Path, current: &Profile, recording: &Recording) -> io::Result<()> {
+    let mut files = Vec::new();
+
+    if let Some(dir) = config::config_dir() {
+        let settings = dir.join("settings.toml");
+        if settings.exists() {
+            files.push(("settings.toml".to_owned(), fs::read_to_string(settings)?));
+        }
+        for name in DIRS {
+            let Ok(entries) = fs::read_dir(dir.join(name)) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| Some(entry.ok()?.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                files.push((format!("{name}/{file_name}"), fs::read_to_string(&path)?));
+            }
+        }
+    }
+
+    files.push(("current.toml".to_owned(), current.to_table().to_string()));
+    if !recording.events.is_empty() {
+        files.push(("macro.txt".to_owned(), recording.to_text()));
+    }
+    files.push(("stats.toml".to_owned(), stats_table().to_string()));
+
+    let mut bundle = format!("{HEADER}\n");
+    for (name, contents) in files {
+        bundle.push_str(&format!("{FILE_MARKER}{name}\n{contents}"));
+        if !contents.ends_with('\n') {
+            bundle.push('\n');
+        }
+    }
+    fs::write(path, bundle)
+}
+
+/// Restores a bundle written by [`export`]: config files are written to
+/// the config dir, replacing those with the same name, and the current
+/// click settings and recording are returned. The run figures are only
+/// kept for reference and are not restored.
+pub fn import(path: &Path) -> io::Result<Imported> {
+    let source = fs::read_to_string(path)?;
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut lines = source.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(invalid(format!("{} is not a bundle", path.display())));
+    }
+
+    let mut files: Vec<(&str, String)> = Vec::new();
+    for line in lines {
+        if let Some(name) = line.strip_prefix(FILE_MARKER) {
+            files.push((name.trim(), String::new()));
+        } else if let Some((_, contents)) = files.last_mut() {
+            contents.push_str(line);
+            contents.push('\n');
+        } else {
+            return Err(invalid("content before the first file".to_owned()));
+        }
+    }
+
+    // Nothing is written unless every table in the bundle reads.
+    for (name, contents) in &files {
+        if name.ends_with(".toml") {
+            Table::parse(contents).map_err(|e| invalid(format!("{name}: {e}")))?;
+        }
+    }
+
+    let dir = config::config_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+    let mut imported = Imported::default();
+    for (name, contents) in files {
+        match name {
+            "current.toml" => {
+                let table = Table::parse(&contents).map_err(invalid)?;
+                imported.current = Some(Profile::from_table(&table));
+            }
+            "macro.txt" => imported.recording = Some(Recording::parse(&contents)),
+            "stats.toml" => {}
+            name if is_config_file(name) => {
+                let path = dir.join(name);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, contents)?;
+            }
+            name => log::warn!("Skipping unexpected bundle entry {name:?}"),
+        }
+    }
+    Ok(imported)
+}
+
+/// Whether a bundle entry may be written to the config dir: the settings
+/// file or a `.toml` directly inside one of [`DIRS`].
+fn is_config_file(name: &str) -> bool {
+    if name == "settings.toml" {
+        return true;
+    }
+    let components: Vec<Component> = Path::new(name).components().collect();
+    match components[..] {
+        [Component::Normal(dir), Component::Normal(file)] => {
+            DIRS.iter().any(|name| dir == *name)
+                && Path::new(file).extension().is_some_and(|ext| ext == "toml")
+        }
+        _ => false,
+    }
+}
+
+fn stats_table() -> Table {
+    let stats = stats::current();
+    let mut table = Table::default();
+    table.set("last_run.clicks", stats.clicks);
+    table.set("last_run.elapsed_secs", stats.elapsed().as_secs_f64());
+    if let Some(report) = accuracy::last_report() {
+        table.set_str("last_run.accuracy", &report.to_string());
+    }
+    table
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// Clicking in bursts: `size` clicks `inner_ms` apart, then `outer_ms`
+/// until the next burst. Replaces the click interval when enabled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Burst {
+    pub enabled: bool,
+    pub size: usize,
+    pub inner_ms: usize,
+    pub outer_ms: usize,
+}
+
+impl Default for Burst {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            size: 5,
+            inner_ms: 50,
+            outer_ms: 1000,
+        }
+    }
+}
+
+impl Burst {
+    /// The wait after the `clicked`-th click of the current burst, counting
+    /// from 1: the outer interval after the last click, the inner one
+    /// otherwise.
+    pub fn wait_after(&self, clicked: usize) -> Duration {
+        let ms = if self.ends_after(clicked) {
+            self.outer_ms
+        } else {
+            self.inner_ms
+        };
+        Duration::from_millis(ms as u64)
+    }
+
+    /// Whether the `clicked`-th click is the last of its burst.
+    pub fn ends_after(&self, clicked: usize) -> bool {
+        clicked >= self.size.max(1)
+    }
+}
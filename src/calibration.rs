@@ -0,0 +1,151 @@
+//! Calibration: runs the clicker's timing for a few seconds against a sink
+//! that sends no events, to measure how late this machine wakes up for a
+//! click and how fast it can click at best. The lateness can then be taken
+//! off every wait, see [`crate::clicker::set_wake_offset`].
+
+use std::{
+    fmt, hint,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{clicker, engine::ClickOptions};
+
+/// How long the configured interval is measured.
+const INTERVAL_PHASE: Duration = Duration::from_secs(2);
+
+/// How long the shortest possible wait is measured, for the top speed.
+const SHORTEST_PHASE: Duration = Duration::from_secs(1);
+
+static CALIBRATING: AtomicBool = AtomicBool::new(false);
+
+static LAST_RESULT: Mutex<Option<Calibration>> = Mutex::new(None);
+
+/// What one calibration measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    pub interval: Duration,
+    /// Clicks per second the configured interval achieved.
+    pub achieved_cps: f64,
+    /// How long after it was due a click went out, on average.
+    pub mean_lateness: Duration,
+    /// The time one cycle takes besides waiting.
+    pub overhead: Duration,
+    /// The most clicks per second these settings allow on this machine,
+    /// within the event gap and CPS cap.
+    pub max_cps: f64,
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            "{:.1} ms interval: {:.1} clicks/s achieved, {:.2} ms late on average, \
+             {:.3} ms overhead, at most {:.0} clicks/s",
+            ms(self.interval),
+            self.achieved_cps,
+            ms(self.mean_lateness),
+            ms(self.overhead),
+            self.max_cps
+        )
+    }
+}
+
+pub fn is_running() -> bool {
+    CALIBRATING.load(Ordering::SeqCst)
+}
+
+pub fn last_result() -> Option<Calibration> {
+    *LAST_RESULT.lock().unwrap()
+}
+
+/// Starts a calibration of `interval` and `click_options` on its own
+/// thread, unless one is running or the clicker is. Nothing is clicked.
+pub fn start(interval: Duration, precise: bool, click_options: ClickOptions) {
+    if clicker::is_running() || CALIBRATING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(move || {
+        let measured = measure(interval, INTERVAL_PHASE);
+        let shortest = measure(Duration::ZERO, SHORTEST_PHASE);
+
+        // Precision mode spins instead of sleeping, so only the overhead
+        // limits it; otherwise every wait takes at least the shortest sleep.
+        let cycle = if precise {
+            shortest.overhead
+        } else {
+            shortest.cycle
+        };
+        let cycle = cycle
+            .max(clicker::event_gap(precise))
+            .max(clicker::min_wait(&click_options));
+        let clicks = click_options.click_type.clicks() as f64;
+        let max_cps = if cycle.is_zero() {
+            f64::INFINITY
+        } else {
+            clicks / cycle.as_secs_f64()
+        };
+
+        let result = Calibration {
+            interval,
+            achieved_cps: clicks * measured.cycles as f64 / INTERVAL_PHASE.as_secs_f64(),
+            mean_lateness: measured.lateness,
+            overhead: measured.overhead,
+            max_cps,
+        };
+        log::info!("Calibrated {result}");
+        *LAST_RESULT.lock().unwrap() = Some(result);
+        CALIBRATING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// The averages of one phase.
+struct Phase {
+    cycles: usize,
+    /// Wake-up lateness per cycle.
+    lateness: Duration,
+    /// Time per cycle besides the wait.
+    overhead: Duration,
+    /// Time per cycle in all.
+    cycle: Duration,
+}
+
+/// Waits out `interval` after `interval` for `duration`, scheduled like the
+/// clicking loop: each cycle is due an interval after the one before, and a
+/// cycle more than an interval behind starts the schedule over.
+fn measure(interval: Duration, duration: Duration) -> Phase {
+    let start = Instant::now();
+    let mut due = start;
+    let mut cycles = 0;
+    let mut lateness = Duration::ZERO;
+    let mut overhead = Duration::ZERO;
+    while start.elapsed() < duration {
+        due += interval;
+        let now = Instant::now();
+        if now < due {
+            thread::sleep(due - now);
+        }
+        let woke = Instant::now();
+        lateness += woke.saturating_duration_since(due);
+        // The sink: the cycle's click is dropped here.
+        hint::black_box(cycles);
+        let done = Instant::now();
+        overhead += done - woke;
+        cycles += 1;
+        if done > due + interval {
+            due = done;
+        }
+    }
+    let per_cycle = |total: Duration| total / cycles.max(1) as u32;
+    Phase {
+        cycles,
+        lateness: per_cycle(lateness),
+        overhead: per_cycle(overhead),
+        cycle: per_cycle(start.elapsed()),
+    }
+}
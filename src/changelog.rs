@@ -0,0 +1,87 @@
+/// The embedded `CHANGELOG.md`, newest release first.
+const CHANGELOG: &str = include_str!("../CHANGELOG.md");
+
+/// A window of the app a changelog entry can open, written as a
+/// `[text](app:<name>)` link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    Settings,
+    Script,
+    Sequence,
+}
+
+impl Link {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "settings" => Some(Self::Settings),
+            "script" => Some(Self::Script),
+            "sequence" => Some(Self::Sequence),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The entry with the link markup removed.
+    pub text: String,
+    pub link: Option<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    pub entries: Vec<Entry>,
+}
+
+/// Every release in the changelog, newest first.
+pub fn releases() -> Vec<Release> {
+    let mut releases: Vec<Release> = Vec::new();
+    for line in CHANGELOG.lines() {
+        if let Some(version) = line.strip_prefix("## ") {
+            releases.push(Release {
+                version: version.trim().to_owned(),
+                entries: Vec::new(),
+            });
+        } else if let (Some(text), Some(release)) = (line.strip_prefix("- "), releases.last_mut()) {
+            release.entries.push(parse_entry(text));
+        }
+    }
+    releases
+}
+
+/// The releases after `last_seen`, i.e. what is new since the app was last
+/// started. All of them if `last_seen` is not in the changelog.
+pub fn since(last_seen: &str) -> Vec<Release> {
+    releases()
+        .into_iter()
+        .take_while(|release| release.version != last_seen)
+        .collect()
+}
+
+/// Splits off the first `[text](app:<name>)` link of an entry.
+fn parse_entry(text: &str) -> Entry {
+    let link = (|| {
+        let open = text.find('[')?;
+        let close = open + text[open..].find("](app:")?;
+        let end = close + text[close..].find(')')?;
+        let link = Link::from_name(&text[close + "](app:".len()..end])?;
+        let plain = format!(
+            "{}{}{}",
+            &text[..open],
+            &text[open + 1..close],
+            &text[end + 1..]
+        );
+        Some((plain, link))
+    })();
+    match link {
+        Some((text, link)) => Entry {
+            text,
+            link: Some(link),
+        },
+        None => Entry {
+            text: text.to_owned(),
+            link: None,
+        },
+    }
+}
@@ -0,0 +1,701 @@
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    abort, accuracy,
+    clicker::{self, Config},
+    display,
+    engine::{ClickInterval, ClickOptions, ClickPosition, Origin},
+    failsafe, humanize, keys, permission, priority,
+    profile::Profile,
+    ratelimit,
+    schedule::{StartMode, StartSchedule},
+    script::ScriptRunner,
+    session, trace, turbo,
+};
+
+const USAGE: &str = "\
+Usage: auto-clicker [OPTIONS]
+       auto-clicker install <OPTIONS>
+       auto-clicker uninstall
+       auto-clicker send <COMMAND>
+
+Without options, or with only --start-minimized, the GUI is started. With
+any of the other options below the clicker runs headless until the click
+count or run time is reached or Ctrl+C is pressed. With --gui, --autostart
+or --start-minimized the GUI is started with the click options filled in
+instead.
+
+Commands:
+  install    Run headless with the given options at every login, as a systemd
+             user service on Linux or a scheduled task on Windows
+  uninstall  Remove that service or task again
+  send       Pass start, stop, toggle, pause, resume, status or
+             \"set interval <DURATION>\" to the running app and print its reply
+
+Options:
+      --interval <DURATION>     Time between clicks, e.g. 100ms, 1s, 2m [default: 100ms]
+      --adaptive                Make up for clicks that run long to hold the rate at the interval
+      --precise                 Spin out each wait for accurate intervals below ~20ms
+      --trace <FILE>            Record engine phase timings and write them to FILE as a Chrome trace
+      --high-priority           Raise the clicker thread's priority for steadier short intervals
+      --turbo                   High-resolution timers and batched clicks for 500+ clicks per second
+      --no-failsafe             Keep clicking when the mouse is slammed into a screen corner
+      --seed <N>                Draw the same jitter and pauses in every run [default: a fresh seed]
+      --max-cps <N>             Never send more than N clicks or key presses per second
+      --display <DISPLAY>       X display to click on, e.g. :1 (Linux only) [default: $DISPLAY]
+      --button <BUTTON>         left, middle or right [default: left]
+      --type <TYPE>             single, double, triple or a count from 1 to 10 [default: single]
+      --burst-delay <DURATION>  Pause between the clicks of one trigger [default: 0ms]
+      --press-time <DURATION>   Hold the button this long in every click [default: 0ms]
+      --key <KEY>               Press this key instead of a mouse button, e.g. a, 5, Space, Return, F1
+      --hold <DURATION>         Hold the button down this long instead of clicking; 0s holds until Ctrl+C
+      --start-in <DURATION>     Wait this many whole seconds before the first click, e.g. 10s
+      --start-at <TIME>         Wait until this local time, as HH:MM or HH:MM:SS, before the first click
+      --run-for <DURATION>      Stop after this much time, e.g. 30s, 1h 30m [default: run until Ctrl+C]
+      --count <N>               Stop after N clicks [default: run until Ctrl+C]
+      --only-in <TITLE>         Pause while no window whose title contains TITLE is focused
+      --x <X>                   Click at this X coordinate (requires --y)
+      --y <Y>                   Click at this Y coordinate (requires --x)
+      --position <X,Y>          Click at these coordinates, e.g. 640,480
+      --script <FILE>           Run this Rhai script instead of clicking
+      --param <NAME=VALUE>      Set a parameter the script declares with `// @param NAME`; repeatable
+      --gui                     Start the GUI with the click options above
+      --autostart               Start the GUI and begin clicking once the window is open
+      --start-minimized         Start the GUI with its window minimized
+  -h, --help                    Print this help
+  -V, --version                 Print the version
+
+Environment:
+  AUTOCLICKER_PROFILE           Start from this saved profile's click settings
+  AUTOCLICKER_INTERVAL          Like --interval
+  AUTOCLICKER_BUTTON            Like --button
+  AUTOCLICKER_TYPE              Like --type
+  AUTOCLICKER_POSITION          Like --position
+  AUTOCLICKER_RUN_FOR           Like --run-for
+  AUTOCLICKER_HEADLESS          Run headless even without options, when 1 or true
+
+Options on the command line take precedence over the environment, which
+takes precedence over the profile.";
+
+/// Environment variables that stand in for command line options, which are
+/// applied after them and so win.
+const ENV_OPTIONS: [(&str, &str); 5] = [
+    ("AUTOCLICKER_INTERVAL", "--interval"),
+    ("AUTOCLICKER_BUTTON", "--button"),
+    ("AUTOCLICKER_TYPE", "--type"),
+    ("AUTOCLICKER_POSITION", "--position"),
+    ("AUTOCLICKER_RUN_FOR", "--run-for"),
+];
+
+/// Options for a headless run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    pub interval: Duration,
+    pub adaptive: bool,
+    pub precise: bool,
+    pub high_priority: bool,
+    /// See [`turbo::set_enabled`].
+    pub turbo: bool,
+    /// Stop when the mouse is slammed into a corner, see
+    /// [`failsafe::spawn_watcher`].
+    pub failsafe: bool,
+    /// Seed of the run's randomness, see [`humanize::set_seed`].
+    pub seed: Option<u64>,
+    /// Hard cap on presses per second, see [`ratelimit::set_max_cps`].
+    pub max_cps: Option<f64>,
+    /// X display for the input backend, see [`display::set_input_display`].
+    pub display: Option<String>,
+    pub trace: Option<PathBuf>,
+    pub click_options: ClickOptions,
+    pub click_position: ClickPosition,
+    pub count: Option<usize>,
+    pub run_for: Option<Duration>,
+    pub start_schedule: StartSchedule,
+    /// Only click while a window with this in its title is focused.
+    pub focus_guard: Option<String>,
+    /// Run this script instead of the clicking loop.
+    pub script: Option<PathBuf>,
+    /// Values for the script's parameters, see [`crate::script::params`].
+    pub params: Vec<(String, String)>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            adaptive: false,
+            precise: false,
+            high_priority: false,
+            turbo: false,
+            failsafe: true,
+            seed: None,
+            max_cps: None,
+            display: None,
+            trace: None,
+            click_options: ClickOptions::default(),
+            click_position: ClickPosition::default(),
+            count: None,
+            run_for: None,
+            start_schedule: StartSchedule::default(),
+            focus_guard: None,
+            script: None,
+            params: Vec::new(),
+        }
+    }
+}
+
+/// What `main` should do after looking at the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Gui {
+        start_minimized: bool,
+        /// Begin clicking as soon as the window is open.
+        autostart: bool,
+        /// Click options given on the command line, which replace the
+        /// restored ones.
+        preset: Option<Box<Args>>,
+    },
+    Headless(Box<Args>),
+    /// Register these headless arguments to run at every login.
+    Install(Vec<String>),
+    Uninstall,
+    /// Pass this command to the running app, see [`crate::ipc`].
+    Send(String),
+    Exit,
+}
+
+/// Parses the process arguments (without the program name), layered over
+/// the `AUTOCLICKER_*` variables listed in the usage, taken from `env`, see
+/// [`crate::config::environment`].
+pub fn parse(
+    args: impl IntoIterator<Item = String>,
+    env: &HashMap<String, String>,
+) -> Result<Command, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    match args.first().map(String::as_str) {
+        Some("install") => {
+            let rest = args[1..].to_vec();
+            return match parse(rest.clone(), env)? {
+                Command::Headless(_) => Ok(Command::Install(rest)),
+                Command::Exit => Ok(Command::Exit),
+                _ => Err(format!("install needs headless options\n\n{USAGE}")),
+            };
+        }
+        Some("uninstall") if args.len() == 1 => return Ok(Command::Uninstall),
+        Some("send") if args.len() > 1 => return Ok(Command::Send(args[1..].join(" "))),
+        Some("send") => return Err(format!("send needs a command\n\n{USAGE}")),
+        _ => {}
+    }
+    let headless = match env.get("AUTOCLICKER_HEADLESS") {
+        Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" => true,
+            "" | "0" | "false" | "no" => false,
+            _ => return Err(format!("invalid AUTOCLICKER_HEADLESS {value:?}")),
+        },
+        None => false,
+    };
+    // Without options the GUI starts, with the environment's as a preset.
+    let mut gui = !headless && args.iter().all(|arg| arg == "--start-minimized");
+    let env_options = ENV_OPTIONS.iter().filter_map(|(var, flag)| {
+        let value = env.get(*var)?;
+        Some(format!("{flag}={value}"))
+    });
+    let mut args = env_options.collect::<Vec<_>>().into_iter().chain(args);
+
+    let mut parsed = match env.get("AUTOCLICKER_PROFILE") {
+        Some(name) => profile_args(name)?,
+        None => Args::default(),
+    };
+    let mut x = None;
+    let mut y = None;
+    let mut autostart = false;
+    let mut start_minimized = false;
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(Command::Exit);
+            }
+            "-V" | "--version" => {
+                println!("auto-clicker {}", env!("CARGO_PKG_VERSION"));
+                return Ok(Command::Exit);
+            }
+            "--interval" => {
+                let value = value()?;
+                parsed.interval = humantime::parse_duration(&value)
+                    .map_err(|e| format!("invalid --interval {value:?}: {e}"))?;
+            }
+            "--adaptive" => parsed.adaptive = true,
+            "--precise" => parsed.precise = true,
+            "--high-priority" => parsed.high_priority = true,
+            "--turbo" => parsed.turbo = true,
+            "--no-failsafe" => parsed.failsafe = false,
+            "--seed" => parsed.seed = Some(parse_number(&flag, &value()?)?),
+            "--max-cps" => {
+                let max_cps: f64 = parse_number(&flag, &value()?)?;
+                if max_cps <= 0.0 {
+                    return Err(format!("{flag} must be more than 0"));
+                }
+                parsed.max_cps = Some(max_cps);
+            }
+            "--display" => parsed.display = Some(value()?),
+            "--trace" => parsed.trace = Some(PathBuf::from(value()?)),
+            "--button" => parsed.click_options.mouse_button = value()?.parse()?,
+            "--type" => parsed.click_options.click_type = value()?.parse()?,
+            "--burst-delay" => {
+                let value = value()?;
+                parsed.click_options.burst_delay_ms = humantime::parse_duration(&value)
+                    .map_err(|e| format!("invalid --burst-delay {value:?}: {e}"))?
+                    .as_millis() as usize;
+            }
+            "--press-time" => {
+                let value = value()?;
+                parsed.click_options.press_ms = humantime::parse_duration(&value)
+                    .map_err(|e| format!("invalid --press-time {value:?}: {e}"))?
+                    .as_millis() as usize;
+            }
+            "--key" => {
+                let value = value()?;
+                parsed.click_options.key =
+                    Some(keys::parse(&value).ok_or_else(|| format!("unknown --key {value:?}"))?);
+            }
+            "--hold" => {
+                let value = value()?;
+                parsed.click_options.hold_mode = true;
+                parsed.click_options.hold_ms = humantime::parse_duration(&value)
+                    .map_err(|e| format!("invalid --hold {value:?}: {e}"))?
+                    .as_millis() as usize;
+            }
+            "--start-in" => {
+                let value = value()?;
+                let countdown = humantime::parse_duration(&value)
+                    .map_err(|e| format!("invalid --start-in {value:?}: {e}"))?;
+                // The countdown counts whole seconds.
+                if countdown.subsec_nanos() != 0 {
+                    return Err(format!(
+                        "invalid --start-in {value:?}: use whole seconds, e.g. 10s"
+                    ));
+                }
+                parsed.start_schedule = StartSchedule {
+                    mode: StartMode::Countdown,
+                    countdown_secs: countdown.as_secs() as usize,
+                    ..StartSchedule::default()
+                };
+            }
+            "--start-at" => parsed.start_schedule = StartSchedule::parse_time(&value()?)?,
+            "--run-for" => {
+                let value = value()?;
+                parsed.run_for = Some(
+                    humantime::parse_duration(&value)
+                        .map_err(|e| format!("invalid --run-for {value:?}: {e}"))?,
+                );
+            }
+            "--gui" => gui = true,
+            "--autostart" => autostart = true,
+            "--start-minimized" => start_minimized = true,
+            "--only-in" => parsed.focus_guard = Some(value()?),
+            "--count" => parsed.count = Some(parse_number(&flag, &value()?)?),
+            "--x" => x = Some(parse_number(&flag, &value()?)?),
+            "--y" => y = Some(parse_number(&flag, &value()?)?),
+            "--position" => {
+                let value = value()?;
+                let (x_value, y_value) = value
+                    .split_once(',')
+                    .ok_or_else(|| format!("--position expects X,Y, got {value:?}"))?;
+                x = Some(parse_number(&flag, x_value.trim())?);
+                y = Some(parse_number(&flag, y_value.trim())?);
+            }
+            "--script" => parsed.script = Some(PathBuf::from(value()?)),
+            "--param" => {
+                let param = value()?;
+                let (name, value) = param
+                    .split_once('=')
+                    .ok_or_else(|| format!("--param expects NAME=VALUE, got {param:?}"))?;
+                parsed
+                    .params
+                    .push((name.trim().to_owned(), value.to_owned()));
+            }
+            _ => return Err(format!("unexpected argument {flag:?}\n\n{USAGE}")),
+        }
+    }
+
+    parsed.click_position = match (x, y) {
+        (Some(x), Some(y)) => ClickPosition::Custom {
+            x,
+            y,
+            origin: Origin::Desktop,
+        },
+        (None, None) => parsed.click_position,
+        _ => return Err("--x and --y must be given together".to_owned()),
+    };
+    if parsed.script.is_none() && !parsed.params.is_empty() {
+        return Err("--param requires --script".to_owned());
+    }
+
+    if gui || autostart || start_minimized {
+        let headless_only = [
+            (parsed.count.is_some(), "--count"),
+            (parsed.script.is_some(), "--script"),
+            (parsed.trace.is_some(), "--trace"),
+            (parsed.display.is_some(), "--display"),
+            (parsed.high_priority, "--high-priority"),
+            (parsed.turbo, "--turbo"),
+            (!parsed.failsafe, "--no-failsafe"),
+            (parsed.seed.is_some(), "--seed"),
+            (parsed.max_cps.is_some(), "--max-cps"),
+        ];
+        if let Some((_, flag)) = headless_only.iter().find(|(given, _)| *given) {
+            return Err(format!("{flag} only applies to headless runs"));
+        }
+        let preset = (parsed != Args::default()).then(|| Box::new(parsed));
+        return Ok(Command::Gui {
+            start_minimized,
+            autostart,
+            preset,
+        });
+    }
+
+    Ok(Command::Headless(Box::new(parsed)))
+}
+
+/// Arguments that start from the click settings of the saved profile `name`.
+fn profile_args(name: &str) -> Result<Args, String> {
+    let profile = Profile::load(name)
+        .map_err(|e| format!("cannot load AUTOCLICKER_PROFILE {name:?}: {e}"))?;
+    let interval = profile.click_interval;
+    Ok(Args {
+        interval: Duration::from_millis(interval.total_millis() as u64),
+        adaptive: interval.adaptive,
+        precise: interval.precise,
+        click_options: profile.click_options,
+        click_position: profile.click_position,
+        ..Args::default()
+    })
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, String>
+where
+    T::Err: fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e| format!("invalid {flag} {value:?}: {e}"))
+}
+
+/// Runs the clicking loop without creating a window, returning when the
+/// click count or run time is reached or Ctrl+C is pressed.
+pub fn run_headless(args: Args) {
+    // Before anything is sent, scripts included.
+    ratelimit::set_max_cps(args.max_cps);
+    if !permission::is_granted() {
+        eprintln!(
+            "Accessibility access is off for this terminal, so clicks will not arrive. \
+             Turn it on in System Settings > Privacy & Security > Accessibility."
+        );
+    }
+    if let Some(display) = &args.display {
+        display::set_input_display(display);
+    }
+    priority::set_high_priority(args.high_priority);
+    turbo::set_enabled(args.turbo);
+    failsafe::set_enabled(args.failsafe);
+    let (tx_command, rx_command) = mpsc::channel::<clicker::Command>();
+    abort::spawn_watcher(tx_command.clone());
+    session::spawn_watcher(tx_command.clone());
+    failsafe::spawn_watcher(tx_command.clone());
+
+    // A script runs instead of the clicker, so none of its settings are
+    // sent.
+    if let Some(path) = &args.script {
+        run_script(path, args.params);
+        return;
+    }
+
+    let configs = [
+        Config::ClickInterval(ClickInterval {
+            adaptive: args.adaptive,
+            precise: args.precise,
+            ..ClickInterval::from_millis(args.interval.as_millis() as usize)
+        }),
+        Config::RunLimit(args.run_for),
+        Config::StartSchedule(args.start_schedule),
+        Config::ClickOptions(Box::new(args.click_options)),
+        Config::ClickPosition(args.click_position),
+        Config::FocusGuard(args.focus_guard),
+    ];
+    for config in configs {
+        tx_command.send(config.into()).unwrap();
+    }
+    tx_command.send(clicker::Command::Start).unwrap();
+
+    humanize::set_seed(args.seed);
+    trace::set_enabled(args.trace.is_some());
+    #[cfg(any(feature = "webhooks", feature = "mqtt"))]
+    let settings = crate::settings::Settings::load();
+    #[cfg(feature = "webhooks")]
+    if !settings.webhook_url.is_empty() {
+        crate::webhook::spawn_notifier(settings.webhook_url.clone());
+    }
+    #[cfg(feature = "mqtt")]
+    if !settings.mqtt.host.is_empty() {
+        tokio::spawn(crate::mqtt::run(settings.mqtt.clone(), tx_command.clone()));
+    }
+    install_interrupt_handler();
+
+    // The clicker returns by itself once the run stops, e.g. when the run
+    // time is up.
+    let clicker_thread = thread::spawn(move || clicker::run(rx_command, args.count, true));
+    while !clicker_thread.is_finished() {
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            tx_command.send(clicker::Command::Shutdown).ok();
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    clicker_thread.join().unwrap();
+
+    if let Some(report) = accuracy::last_report() {
+        println!("Interval accuracy: {report}");
+    }
+    let options = &args.click_options;
+    if let Some(seed) = humanize::last_seed().filter(|_| options.humanize.enabled || options.wiggle)
+    {
+        println!("Random seed: {seed} (repeat the run with --seed {seed})");
+    }
+
+    if let Some(path) = &args.trace {
+        if let Err(e) = trace::export(path) {
+            eprintln!("Could not write trace to {}: {e}", path.display());
+        }
+    }
+}
+
+/// Runs the script at `path` until it ends or Ctrl+C is pressed.
+fn run_script(path: &Path, values: Vec<(String, String)>) {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Could not read script {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    };
+    install_interrupt_handler();
+
+    let runner = ScriptRunner::default();
+    runner.run(source, values);
+    while runner.is_running() {
+        if INTERRUPTED.load(Ordering::Relaxed) {
+            runner.stop();
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    if let Some(e) = runner.error() {
+        eprintln!("Script failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Set from the Ctrl+C handler; only async-signal-safe work is done there.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+fn install_interrupt_handler() {
+    extern "C" fn handler(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+    }
+
+    // SAFETY: the handler only stores to an atomic.
+    unsafe {
+        libc::signal(libc::SIGINT, handler as *const () as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, handler as *const () as libc::sighandler_t);
+    }
+}
+
+#[cfg(windows)]
+fn install_interrupt_handler() {
+    extern "system" {
+        fn SetConsoleCtrlHandler(
+            handler: Option<unsafe extern "system" fn(u32) -> i32>,
+            add: i32,
+        ) -> i32;
+    }
+
+    unsafe extern "system" fn handler(_: u32) -> i32 {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+        1
+    }
+
+    // SAFETY: the handler only stores to an atomic.
+    unsafe {
+        SetConsoleCtrlHandler(Some(handler), 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MouseButton;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse_with_env(args, &[])
+    }
+
+    fn parse_with_env(args: &[&str], env: &[(&str, &str)]) -> Result<Command, String> {
+        let env = env
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        parse(args.iter().map(|arg| arg.to_string()), &env)
+    }
+
+    fn headless(args: &[&str]) -> Args {
+        match parse_args(args) {
+            Ok(Command::Headless(args)) => *args,
+            other => panic!("expected a headless run, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn no_options_start_the_gui() {
+        assert_eq!(
+            parse_args(&[]),
+            Ok(Command::Gui {
+                start_minimized: false,
+                autostart: false,
+                preset: None,
+            })
+        );
+        assert_eq!(
+            parse_args(&["--start-minimized"]),
+            Ok(Command::Gui {
+                start_minimized: true,
+                autostart: false,
+                preset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn reads_headless_options() {
+        let args = headless(&["--interval", "250ms", "--button=right", "--count", "5"]);
+        assert_eq!(args.interval, Duration::from_millis(250));
+        assert_eq!(args.click_options.mouse_button, MouseButton::Right);
+        assert_eq!(args.count, Some(5));
+        assert!(!args.adaptive);
+
+        let args = headless(&["--interval", "5ms", "--adaptive"]);
+        assert!(args.adaptive);
+
+        let args = headless(&["--position", "640, 480"]);
+        assert_eq!(
+            args.click_position,
+            ClickPosition::Custom {
+                x: 640,
+                y: 480,
+                origin: Origin::Desktop,
+            }
+        );
+    }
+
+    #[test]
+    fn environment_options_yield_to_the_command_line() {
+        let env = [
+            ("AUTOCLICKER_INTERVAL", "2s"),
+            ("AUTOCLICKER_BUTTON", "middle"),
+        ];
+        let Ok(Command::Gui {
+            preset: Some(args), ..
+        }) = parse_with_env(&[], &env)
+        else {
+            panic!("expected the GUI with a preset");
+        };
+        assert_eq!(args.interval, Duration::from_secs(2));
+
+        let Ok(Command::Headless(args)) = parse_with_env(&["--interval", "50ms"], &env) else {
+            panic!("expected a headless run");
+        };
+        assert_eq!(args.interval, Duration::from_millis(50));
+        assert_eq!(args.click_options.mouse_button, MouseButton::Middle);
+
+        assert!(matches!(
+            parse_with_env(&[], &[("AUTOCLICKER_HEADLESS", "yes")]),
+            Ok(Command::Headless(_))
+        ));
+        assert!(parse_with_env(&[], &[("AUTOCLICKER_HEADLESS", "maybe")]).is_err());
+    }
+
+    #[test]
+    fn gui_options_become_a_preset() {
+        let Ok(Command::Gui {
+            autostart: true,
+            preset: Some(preset),
+            ..
+        }) = parse_args(&["--autostart", "--interval", "1s"])
+        else {
+            panic!("expected the GUI with a preset");
+        };
+        assert_eq!(preset.interval, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn subcommands() {
+        assert_eq!(
+            parse_args(&["send", "set", "interval", "1s"]),
+            Ok(Command::Send("set interval 1s".to_owned()))
+        );
+        assert_eq!(parse_args(&["uninstall"]), Ok(Command::Uninstall));
+        assert_eq!(
+            parse_args(&["install", "--count", "3"]),
+            Ok(Command::Install(vec!["--count".to_owned(), "3".to_owned()]))
+        );
+        assert!(parse_args(&["send"]).is_err());
+        assert!(parse_args(&["install", "--gui"]).is_err());
+    }
+
+    #[test]
+    fn start_in_takes_whole_seconds() {
+        let args = headless(&["--start-in", "2m 5s"]);
+        assert_eq!(args.start_schedule.mode, StartMode::Countdown);
+        assert_eq!(args.start_schedule.countdown_secs, 125);
+        // Rather than quietly starting early or late.
+        assert!(parse_args(&["--start-in", "500ms"]).is_err());
+        assert!(parse_args(&["--start-in", "1s 500ms"]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert!(parse_args(&["--bogus"]).is_err());
+        assert!(parse_args(&["--interval"]).is_err());
+        assert!(parse_args(&["--interval", "soon"]).is_err());
+        assert!(parse_args(&["--x", "10"]).is_err());
+        assert!(parse_args(&["--position", "10"]).is_err());
+        assert!(parse_args(&["--max-cps", "0"]).is_err());
+        assert!(parse_args(&["--param", "a=1"]).is_err());
+        assert_eq!(
+            parse_args(&["--gui", "--count", "3"]),
+            Err("--count only applies to headless runs".to_owned())
+        );
+    }
+}
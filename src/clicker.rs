@@ -0,0 +1,1443 @@
+use std::{
+    hint,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvError, RecvTimeoutError},
+        Mutex, MutexGuard, PoisonError,
+    },
+    thread::{self, sleep, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+use crate::{
+    abort,
+    accuracy::IntervalLog,
+    clipboard,
+    drag::{self, Drag},
+    engine::{ClickInterval, ClickOptions, ClickPosition, MouseButton, Targets},
+    humanize::Rng,
+    jobs, keys, listener, modifiers, overlay,
+    priority::ThreadPriority,
+    profile::Profile,
+    ratelimit, recorder, reminder,
+    rotation::KeyRotation,
+    sandbox,
+    schedule::StartSchedule,
+    sequence::{self, ClickPoint, OnTimeout, Progress, SequenceLoop, StepAction},
+    source::{self, simulate},
+    stats, supervisor, takeover, target,
+    template::{ImageMode, ImageTarget, ImageWatch},
+    timeline::{self, Activity},
+    trace, turbo,
+    typing::TypingText,
+    vsync,
+};
+
+/// How much of a precision-mode wait is spun instead of slept: a little more
+/// than the sleep granularity, which is ~15.6 ms on Windows by default.
+#[cfg(windows)]
+const SPIN_MARGIN: Duration = Duration::from_millis(17);
+#[cfg(not(windows))]
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+/// The least time between two sent events by default, see [`catch_up`].
+/// Only macOS drops events that come faster.
+#[cfg(target_os = "macos")]
+pub(crate) const DEFAULT_EVENT_GAP_MS: usize = 20;
+#[cfg(not(target_os = "macos"))]
+pub(crate) const DEFAULT_EVENT_GAP_MS: usize = 0;
+
+/// The least time between two sent events, see [`set_event_gap`].
+static EVENT_GAP: Mutex<Duration> = Mutex::new(Duration::from_millis(DEFAULT_EVENT_GAP_MS as u64));
+
+/// The event gap in precision mode, short enough for intervals of a few
+/// milliseconds.
+const PRECISE_EVENT_GAP: Duration = Duration::from_millis(1);
+
+/// When the last event went out, see [`catch_up`].
+static LAST_SENT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Set while the clicking loop is in precision mode.
+static PRECISE: AtomicBool = AtomicBool::new(false);
+
+/// How much earlier than due waits end, see [`set_wake_offset`].
+static WAKE_OFFSET: Mutex<Duration> = Mutex::new(Duration::ZERO);
+
+/// How often the focus guard looks up the focused window; on Linux each
+/// lookup runs `xprop`.
+const FOCUS_POLL: Duration = Duration::from_millis(250);
+
+/// How often a run aimed at a window checks that it is still open; on Linux
+/// each check runs `xwininfo`.
+const WINDOW_POLL: Duration = Duration::from_secs(1);
+
+/// How often the pixel trigger reads the screen; on Linux each read runs
+/// ImageMagick's `import`.
+const PIXEL_POLL: Duration = Duration::from_millis(50);
+
+/// How often a running clicker with a guard or trigger wakes to check it.
+const GUARD_POLL: Duration = Duration::from_millis(50);
+
+/// How long the first click of a run waits for modifier keys, e.g. Ctrl of
+/// a Ctrl+F1 slot hotkey, to be released before releasing them itself.
+const MODIFIER_WAIT: Duration = Duration::from_millis(1000);
+
+/// When the current run stops itself, if it has a time limit.
+static RUN_DEADLINE: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub(crate) fn run_deadline() -> Option<Instant> {
+    *RUN_DEADLINE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// When the current run begins, while it is waiting for its start schedule.
+static SCHEDULED_START: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub(crate) fn scheduled_start() -> Option<Instant> {
+    *SCHEDULED_START
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Serializes the clicks of the clicking loop and the click jobs, so each
+/// move and the click after it go out together.
+static SENDING: Mutex<()> = Mutex::new(());
+
+/// Holds off the other senders' clicks until the guard is dropped.
+pub(crate) fn lock_sending() -> MutexGuard<'static, ()> {
+    SENDING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Set while the user is picking a click position on screen.
+static SHIELD: AtomicBool = AtomicBool::new(false);
+
+/// The app's own window as `(left, top, width, height)`, which the clicker
+/// never clicks on.
+static OWN_WINDOW: Mutex<Option<(i32, i32, u32, u32)>> = Mutex::new(None);
+
+/// Notes where the app's window is, e.g. after it moved, so clicks that
+/// would land on it are skipped. `None` while it has no known position.
+pub(crate) fn set_own_window(rect: Option<(i32, i32, u32, u32)>) {
+    *OWN_WINDOW.lock().unwrap_or_else(PoisonError::into_inner) = rect;
+}
+
+/// Hard-locks the clicker off while `shielded`: Start, the hotkeys and dwell
+/// clicks are ignored, so it cannot click into the screen mid-setup. Jobs
+/// running then are stopped.
+pub(crate) fn set_shield(shielded: bool) {
+    SHIELD.store(shielded, Ordering::SeqCst);
+    if shielded {
+        jobs::stop_all();
+    }
+}
+
+pub(crate) fn is_shielded() -> bool {
+    SHIELD.load(Ordering::SeqCst)
+}
+
+/// A message to the clicking loop.
+pub enum Command {
+    /// Starts a run, or resumes a paused one.
+    Start,
+    /// Ends the run, paused or not.
+    Stop,
+    /// Stops a running clicker and starts a stopped one. A paused one
+    /// resumes.
+    Toggle,
+    /// Halts clicking but keeps the run: its clicks, click budget and
+    /// run time carry on after [`Command::Resume`].
+    Pause,
+    Resume,
+    UpdateConfig(Config),
+    /// Makes the clicking loop return.
+    Shutdown,
+}
+
+/// One setting of the clicking loop, sent whenever it changes.
+#[derive(Clone)]
+pub enum Config {
+    ClickInterval(ClickInterval),
+    RunLimit(Option<Duration>),
+    StartSchedule(StartSchedule),
+    ClickOptions(Box<ClickOptions>),
+    ClickPosition(ClickPosition),
+    ClickSequence(Vec<ClickPoint>),
+    SequenceLoop(SequenceLoop),
+    TypingText(TypingText),
+    KeyRotation(KeyRotation),
+    FocusGuard(Option<String>),
+    ImageTarget(Option<ImageTarget>),
+}
+
+impl From<Config> for Command {
+    fn from(config: Config) -> Self {
+        Self::UpdateConfig(config)
+    }
+}
+
+/// Whether the clicking loop is clicking, or waiting for its start
+/// schedule. Only the loop itself changes this.
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Whether the clicker is running. Send [`Command::Start`] or
+/// [`Command::Stop`] to change it.
+pub(crate) fn is_running() -> bool {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Whether a run is paused, see [`Command::Pause`]. Only the loop itself
+/// changes this.
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Clears what a clicking loop that panicked left set, before
+/// [`crate::supervisor`] starts another. The locks the loop takes, here and
+/// in the modules it reports to, only guard plain values, so one it
+/// poisoned by panicking is used as it is.
+pub(crate) fn reset() {
+    RUNNING.store(false, Ordering::SeqCst);
+    PAUSED.store(false, Ordering::SeqCst);
+    *RUN_DEADLINE.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    *SCHEDULED_START
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Panics while holding the loop's locks, as a loop failing mid-run might.
+#[cfg(test)]
+pub(crate) fn panic_holding_locks() -> ! {
+    let _deadline = RUN_DEADLINE.lock().unwrap();
+    let _scheduled_start = SCHEDULED_START.lock().unwrap();
+    let _last_sent = LAST_SENT.lock().unwrap();
+    panic!("the clicking loop failed");
+}
+
+/// Set while runs only show where they would click, see [`set_preview`].
+static PREVIEW: AtomicBool = AtomicBool::new(false);
+
+/// Where the cursor would be in a preview run, which moves nothing.
+static PREVIEW_CURSOR: Mutex<Option<(i32, i32)>> = Mutex::new(None);
+
+/// Runs the engine as usual but sends no input while `preview` is set.
+/// Each click is drawn on the overlay where and when it would have landed
+/// instead, and counted as if it had been sent.
+pub(crate) fn set_preview(preview: bool) {
+    PREVIEW.store(preview, Ordering::SeqCst);
+    if preview {
+        // Clicks at the cursor are marked where the listener sees it.
+        listener::start();
+    }
+}
+
+pub(crate) fn is_preview() -> bool {
+    PREVIEW.load(Ordering::SeqCst)
+}
+
+/// Ends every wait outside precision mode `offset` early and spins the
+/// rest, to make up for how late the OS wakes the clicker, as measured by
+/// [`crate::calibration`].
+pub(crate) fn set_wake_offset(offset: Duration) {
+    *WAKE_OFFSET.lock().unwrap_or_else(PoisonError::into_inner) = offset;
+}
+
+/// The shortest wait between triggers of `click_options` that stays under
+/// the CPS cap, see [`ratelimit::set_max_cps`]. Runs wait at least this
+/// long so their pace stays even rather than stalling on the cap.
+pub(crate) fn min_wait(click_options: &ClickOptions) -> Duration {
+    match ratelimit::max_cps() {
+        Some(cps) => Duration::from_secs_f64(click_options.click_type.clicks() as f64 / cps),
+        None => Duration::ZERO,
+    }
+}
+
+/// Where the current run last moved the cursor to click, see
+/// [`last_target`].
+static LAST_TARGET: Mutex<Option<(i32, i32)>> = Mutex::new(None);
+
+/// The point on the desktop the current run last clicked, after its
+/// position was resolved and humanized. `None` before the first click and
+/// while clicking wherever the cursor is.
+pub(crate) fn last_target() -> Option<(i32, i32)> {
+    *LAST_TARGET.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Leaves at least `gap` between two sent events, for systems that drop
+/// events that come faster. Precision mode shortens it to at most 1 ms.
+pub(crate) fn set_event_gap(gap: Duration) {
+    *EVENT_GAP.lock().unwrap_or_else(PoisonError::into_inner) = gap;
+}
+
+/// The clicking loop shared by the GUI and headless modes.
+///
+/// Clicks between [`Command::Start`] and [`Command::Stop`], picking up new
+/// settings from [`Command::UpdateConfig`]. While stopped the loop blocks on
+/// `rx_command`; while running it waits on it only until the next click or
+/// check is due, so commands take effect at once.
+///
+/// A non-empty click sequence replaces the single click position: each
+/// cycle then visits every point in order. Otherwise, non-empty typing text
+/// replaces clicking: each cycle types it once. After Start, the first
+/// click waits for the start schedule, and for modifier keys still held
+/// from a hotkey to be released, see [`MODIFIER_WAIT`]. A run limit stops the clicker once
+/// that much time has passed since it started. While a focus guard is set,
+/// the clicker pauses whenever no window with that title is focused, and
+/// while the pixel trigger of the click options is not met. While an image
+/// target is set, the clicker pauses while it is not on screen, and may
+/// click its center instead of the click position.
+///
+/// Returns on [`Command::Shutdown`] or once every sender is gone, after
+/// `limit` cycles if one is given, and when the first run stops if `once`
+/// is set.
+pub(crate) fn run(rx_command: Receiver<Command>, limit: Option<usize>, once: bool) {
+    // A run is stopped (`!running`, no `started`), running, or paused
+    // (`!running` with `started` and `paused_at`).
+    let mut running = false;
+    let mut paused_at: Option<Instant> = None;
+    let mut delay = Duration::from_secs(0);
+    let mut click_interval = ClickInterval::default();
+    let mut precise = false;
+    // When the loop looks again if no command arrives first; `None` to wait
+    // for a command.
+    let mut wake: Option<Instant> = None;
+    // When the next cycle may start; `None` for right away.
+    let mut next_cycle: Option<Instant> = None;
+    let mut next_due: Option<Instant> = None;
+    let mut run_limit = None;
+    let mut start_schedule = StartSchedule::default();
+    let mut started: Option<Instant> = None;
+    let mut click_options = ClickOptions::default();
+    let mut click_position = ClickPosition::default();
+    let mut targets = click_position.targets();
+    let mut click_sequence: Vec<ClickPoint> = Vec::new();
+    let mut sequence_loop = SequenceLoop::default();
+    // Runs of the sequence so far in this run.
+    let mut sequence_loops = 0;
+    let mut typing_text = TypingText::default();
+    let mut key_rotation = KeyRotation::default();
+    // Keys of the rotation pressed so far in this run.
+    let mut rotation_presses = 0;
+    // Clicks of the button rotation so far in this run.
+    let mut button_clicks = 0;
+    let mut focus_guard: Option<String> = None;
+    let mut focus_checked: Option<Instant> = None;
+    let mut focused = true;
+    // The window the focus guard last found focused, to stop once it closes.
+    let mut guard_window: Option<u64> = None;
+    let mut window_checked: Option<Instant> = None;
+    let mut pixel_checked: Option<Instant> = None;
+    let mut pixel_met = true;
+    let mut paused = false;
+    let mut image_watch: Option<ImageWatch> = None;
+    let mut held: Option<(EventType, Instant)> = None;
+    // Until when a new run waits for held modifiers to be released.
+    let mut modifier_wait: Option<Instant> = None;
+    let mut clicks = 0;
+    // Clicks of the current burst so far.
+    let mut burst_clicks = 0;
+    let mut interval_log = IntervalLog::default();
+    let mut priority = ThreadPriority::default();
+    let mut timer = turbo::HighResTimer::default();
+    let mut rng = Rng::default();
+    // A command that came in during a step of the sequence, handled first
+    // on the next iteration.
+    let mut pending: Option<Command> = None;
+
+    'commands: loop {
+        // Frame-perfect clicks are due at a vblank, which a sleep would miss.
+        let spin = precise || vsync::is_enabled() || turbo::is_enabled();
+        let Ok(mut command) = pending.take().map_or_else(
+            || next_command(&rx_command, wake, running && spin),
+            |command| Ok(Some(command)),
+        ) else {
+            break;
+        };
+
+        priority.update();
+        timer.update();
+
+        let recv_span = trace::span("recv command");
+        while let Some(received) = command {
+            match received {
+                Command::Start | Command::Toggle | Command::Resume if paused_at.is_some() => {
+                    resume(&mut paused_at, &mut started, &mut next_due);
+                    running = !is_shielded();
+                }
+                Command::Start => running = !is_shielded(),
+                Command::Stop => {
+                    running = false;
+                    paused_at = None;
+                }
+                Command::Toggle => running = !running && !is_shielded(),
+                // Not while waiting for the start schedule, before the run
+                // has anything to keep.
+                Command::Pause if running && stats::current().is_running() => {
+                    running = false;
+                    paused_at = Some(Instant::now());
+                    stats::pause();
+                    timeline::record(Activity::Suspend);
+                }
+                Command::Pause | Command::Resume => {}
+                Command::Shutdown => break 'commands,
+                Command::UpdateConfig(Config::ClickInterval(interval)) => {
+                    click_interval = interval;
+                    delay = convert_time_to_duration(
+                        interval.hours,
+                        interval.minutes,
+                        interval.seconds,
+                        interval.milliseconds,
+                    );
+                    precise = interval.precise;
+                    PRECISE.store(precise, Ordering::Relaxed);
+                }
+                Command::UpdateConfig(Config::RunLimit(limit)) => run_limit = limit,
+                Command::UpdateConfig(Config::StartSchedule(schedule)) => {
+                    start_schedule = schedule;
+                }
+                Command::UpdateConfig(Config::ClickOptions(options)) => {
+                    if options.wiggle {
+                        // Tracks the cursor for wiggles at the cursor.
+                        listener::start();
+                    }
+                    reminder::set_reminder(options.reminder);
+                    click_options = *options;
+                    pixel_checked = None;
+                }
+                Command::UpdateConfig(Config::ClickPosition(position)) => {
+                    click_position = position;
+                    targets = position.targets();
+                }
+                Command::UpdateConfig(Config::ClickSequence(sequence)) => {
+                    click_sequence = sequence;
+                }
+                Command::UpdateConfig(Config::SequenceLoop(options)) => sequence_loop = options,
+                Command::UpdateConfig(Config::TypingText(typing)) => typing_text = typing,
+                Command::UpdateConfig(Config::KeyRotation(rotation)) => key_rotation = rotation,
+                Command::UpdateConfig(Config::FocusGuard(guard)) => {
+                    focus_guard = guard.filter(|title| !title.is_empty());
+                    focus_checked = None;
+                    focused = true;
+                    guard_window = None;
+                }
+                Command::UpdateConfig(Config::ImageTarget(target)) => {
+                    image_watch =
+                        target.and_then(|target| match ImageWatch::start(target.clone()) {
+                            Ok(watch) => Some(watch),
+                            Err(e) => {
+                                log::error!("Could not load {}: {e}", target.path.display());
+                                None
+                            }
+                        });
+                }
+            }
+            command = rx_command.try_recv().ok();
+        }
+        drop(recv_span);
+
+        if is_shielded() {
+            running = false;
+        }
+        RUNNING.store(running, Ordering::SeqCst);
+        PAUSED.store(paused_at.is_some(), Ordering::SeqCst);
+
+        let now = Instant::now();
+        let mut deadline = None;
+        let mut finished = false;
+        if running {
+            let started = *started.get_or_insert_with(|| now + start_schedule.wait());
+            if now < started {
+                *SCHEDULED_START
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = Some(started);
+                wake = Some(started);
+                continue;
+            }
+            *SCHEDULED_START
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = None;
+            if !stats::current().is_running() {
+                stats::start();
+                stats::describe_run(
+                    (click_sequence.is_empty() && !typing_text.is_active() && !delay.is_zero())
+                        .then(|| click_options.click_type.clicks() as f64 / delay.as_secs_f64()),
+                    describe_run(
+                        delay,
+                        &click_options,
+                        &click_position,
+                        &click_sequence,
+                        &typing_text,
+                        &key_rotation,
+                    ),
+                    Profile {
+                        click_interval,
+                        click_options,
+                        click_position,
+                    },
+                );
+                timeline::record(Activity::Start);
+                if let Some(cap) = ratelimit::max_cps().filter(|_| turbo::is_enabled()) {
+                    log::warn!("Turbo mode is held to the cap of {cap} clicks per second");
+                }
+                modifier_wait = Some(now + MODIFIER_WAIT);
+                rng = Rng::for_run();
+                sequence_loops = 0;
+                rotation_presses = 0;
+                button_clicks = 0;
+                guard_window = None;
+                window_checked = None;
+                *LAST_TARGET.lock().unwrap_or_else(PoisonError::into_inner) = None;
+                *PREVIEW_CURSOR
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = None;
+            }
+
+            deadline = run_limit.map(|limit| started + limit);
+            *RUN_DEADLINE.lock().unwrap_or_else(PoisonError::into_inner) = deadline;
+            if deadline.is_some_and(|deadline| now >= deadline) {
+                running = false;
+                finished = true;
+                RUNNING.store(false, Ordering::SeqCst);
+            }
+        }
+
+        if !running {
+            if let Some((release, _)) = held.take() {
+                send(&release);
+            }
+            next_due = None;
+            next_cycle = None;
+            wake = None;
+            if paused_at.is_some() {
+                // The run keeps its clicks, click budget and targets for
+                // Resume; the deadline moves on by the pause.
+                *RUN_DEADLINE.lock().unwrap_or_else(PoisonError::into_inner) = None;
+                continue;
+            }
+            burst_clicks = 0;
+            if started.take().is_some() {
+                *RUN_DEADLINE.lock().unwrap_or_else(PoisonError::into_inner) = None;
+                *SCHEDULED_START
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner) = None;
+                stats::stop();
+                sequence::set_progress(None);
+                timeline::record(if finished {
+                    Activity::Finish
+                } else {
+                    Activity::Stop
+                });
+                // The next run starts over at the first grid cell or path point.
+                targets = click_position.targets();
+                if once {
+                    break;
+                }
+            }
+            interval_log.finish(delay);
+            continue;
+        }
+
+        // The window clicks go to, unless a sequence, text or drag runs
+        // instead, or else the one the focus guard matched.
+        let tracked_window = match click_position {
+            ClickPosition::Window { id, .. }
+                if click_sequence.is_empty()
+                    && !typing_text.is_active()
+                    && !click_options.drag.enabled =>
+            {
+                Some(id)
+            }
+            _ => guard_window,
+        };
+
+        // Guards are looked at again this often while running, and the run
+        // limit is kept even while nothing else is due.
+        let guarded = focus_guard.is_some()
+            || tracked_window.is_some()
+            || takeover::is_enabled()
+            || click_options.pixel_trigger.enabled
+            || image_watch.is_some();
+        let checks = [deadline, guarded.then(|| now + GUARD_POLL)];
+
+        if let Some(id) = tracked_window {
+            if window_checked.is_none_or(|checked| checked.elapsed() >= WINDOW_POLL) {
+                window_checked = Some(Instant::now());
+                if !target::is_open(id) {
+                    // Also while the focus guard pauses the run.
+                    target_closed();
+                    running = false;
+                    wake = Some(Instant::now());
+                    continue;
+                }
+            }
+        }
+
+        if let Some(title) = &focus_guard {
+            if focus_checked.is_none_or(|checked| checked.elapsed() >= FOCUS_POLL) {
+                let window = target::focused_matching(title);
+                if let Some(window) = &window {
+                    guard_window = Some(window.id);
+                }
+                focused = !target::is_foreground_supported() || window.is_some();
+                focus_checked = Some(Instant::now());
+            }
+        }
+        let pixel_trigger = click_options.pixel_trigger;
+        if pixel_trigger.enabled {
+            if pixel_checked.is_none_or(|checked| checked.elapsed() >= PIXEL_POLL) {
+                pixel_met = pixel_trigger.is_met();
+                pixel_checked = Some(Instant::now());
+                timeline::record(Activity::Trigger { met: pixel_met });
+            }
+        } else {
+            pixel_met = true;
+        }
+        let image_found = image_watch.as_ref().map(ImageWatch::found);
+        let taken_over = takeover::paused_until();
+        if !focused || !pixel_met || image_found == Some(None) || taken_over.is_some() {
+            // Paused, not stopped: clicking resumes once the window is
+            // focused again, the pixel or image meets the condition, or
+            // the user has let go of the mouse.
+            if !paused {
+                paused = true;
+                timeline::record(Activity::Pause);
+            }
+            if let Some((release, _)) = held.take() {
+                send(&release);
+            }
+            next_due = None;
+            next_cycle = None;
+            wake = earliest(checks);
+            continue;
+        }
+        paused = false;
+
+        if let Some(until) = modifier_wait {
+            let modifiers = modifiers::held();
+            if modifiers.is_empty() {
+                modifier_wait = None;
+            } else if now < until {
+                // So the first clicks are not Ctrl-clicks.
+                wake = earliest([Some(now + GUARD_POLL)].into_iter().chain(checks));
+                continue;
+            } else {
+                for &key in &modifiers {
+                    send(&EventType::KeyRelease(key));
+                }
+                modifiers::forget(&modifiers);
+                modifier_wait = None;
+            }
+        }
+
+        if next_cycle.is_some_and(|at| now < at) {
+            // Woken by a command or a check before the next click is due.
+            wake = earliest([next_cycle].into_iter().chain(checks));
+            continue;
+        }
+
+        let image_center = image_watch
+            .as_ref()
+            .filter(|watch| watch.mode() == ImageMode::ClickCenter)
+            .and_then(ImageWatch::found)
+            .map(|found| (found.x as i32, found.y as i32));
+
+        let cycle_start = Instant::now();
+        let cycle_span = trace::span("cycle");
+        let mut cycle_finished = false;
+        // The key of the rotation this cycle pressed, whose wait comes next.
+        let mut rotated = None;
+        // Triggers this cycle sent at once, see [`turbo::batch`].
+        let mut batch = 1;
+        next_cycle = None;
+        if !click_sequence.is_empty() {
+            let mut aborted = false;
+            let since = abort::generation();
+            'steps: for (i, point) in click_sequence.iter().enumerate() {
+                sequence::set_progress(Some(Progress {
+                    loop_number: sequence_loops + 1,
+                    step: i + 1,
+                }));
+                for _ in 0..point.repeat.max(1) {
+                    let target = point.action.moves().then(|| {
+                        click_options
+                            .humanize
+                            .position(point.x as i32, point.y as i32, &mut rng)
+                    });
+                    match point.action {
+                        // Clicking our own buttons could stop or restart the
+                        // run, so the step only waits its delay.
+                        action if action.sends_input() && is_own_window(target) => {}
+                        StepAction::Click => {
+                            let _sending = lock_sending();
+                            if let Some((x, y)) = target {
+                                move_to(x, y);
+                            }
+                            trigger(&ClickOptions {
+                                key: None,
+                                ..point.click_options
+                            });
+                        }
+                        StepAction::Move => {
+                            if let Some((x, y)) = target {
+                                move_to(x, y);
+                            }
+                        }
+                        StepAction::Key if point.click_options.key.is_some() => {
+                            trigger(&point.click_options)
+                        }
+                        StepAction::SetClipboard => {
+                            if let Err(e) = clipboard::set_text(&point.text) {
+                                log::error!("Could not set the clipboard: {e}");
+                            }
+                        }
+                        StepAction::Paste => paste(),
+                        action if action.is_conditional() => match wait_for(point, &rx_command) {
+                            Wait::Met => {}
+                            Wait::TimedOut if point.on_timeout == OnTimeout::Skip => {
+                                log::info!("Sequence step {} timed out, skipping it", i + 1);
+                                continue 'steps;
+                            }
+                            Wait::TimedOut => {
+                                log::warn!("Sequence step {} timed out, stopping", i + 1);
+                                aborted = true;
+                                break 'steps;
+                            }
+                            Wait::Interrupted(command) => {
+                                pending = Some(command);
+                                break 'steps;
+                            }
+                        },
+                        _ => {}
+                    }
+                    let delay = Duration::from_millis(point.delay_ms as u64);
+                    match step_delay(delay, since, &rx_command) {
+                        Ok(()) => {}
+                        Err(Some(command)) => {
+                            pending = Some(command);
+                            break 'steps;
+                        }
+                        Err(None) => {
+                            aborted = true;
+                            break 'steps;
+                        }
+                    }
+                }
+            }
+            if aborted {
+                running = false;
+            } else if pending.is_none() {
+                sequence_loops += 1;
+                cycle_finished = true;
+                if sequence_loop.is_done(sequence_loops) {
+                    running = false;
+                }
+            }
+        } else if typing_text.is_active() {
+            type_text(
+                &typing_text.text,
+                Duration::from_millis(typing_text.char_delay_ms as u64),
+            );
+            cycle_finished = true;
+            running = typing_text.repeat;
+        } else if click_options.drag.enabled {
+            // A drag has its own start point, the click position is not used.
+            let _sending = lock_sending();
+            trigger(&click_options);
+            cycle_finished = true;
+        } else if click_options.hold_mode {
+            // The hold is tracked across iterations instead of sleeping
+            // through it, so Stop releases the button right away.
+            match held {
+                None => {
+                    let _sending = lock_sending();
+                    match move_to_next(&mut targets, image_center, &click_options, &mut rng) {
+                        None => running = false,
+                        Some(point) if is_own_window(point) => {
+                            next_cycle = Some(Instant::now() + delay);
+                        }
+                        Some(_) => {
+                            let (press, release) = press_and_release(&click_options);
+                            send(&press);
+                            held = Some((release, Instant::now()));
+                        }
+                    }
+                }
+                Some((release, since)) => {
+                    let hold = Duration::from_millis(click_options.hold_ms as u64);
+                    if click_options.hold_ms > 0 && since.elapsed() >= hold {
+                        send(&release);
+                        held = None;
+                        cycle_finished = true;
+                    }
+                }
+            }
+            if let Some((_, since)) = held.filter(|_| click_options.hold_ms > 0) {
+                next_cycle = Some(since + Duration::from_millis(click_options.hold_ms as u64));
+            }
+        } else {
+            let step = key_rotation.step(rotation_presses);
+            let options = ClickOptions {
+                key: step.map(|step| step.key).or(click_options.key),
+                mouse_button: click_options
+                    .button_rotation
+                    .button(button_clicks)
+                    .or_else(|| click_options.button_weights.sample(&mut rng))
+                    .unwrap_or(click_options.mouse_button),
+                ..click_options
+            };
+            let _sending = lock_sending();
+            match move_to_next(&mut targets, image_center, &options, &mut rng) {
+                None => running = false,
+                // Clicking our own buttons could stop or restart the run.
+                Some(point) if is_own_window(point) => {
+                    next_cycle = Some(Instant::now() + delay);
+                }
+                Some(point) => {
+                    if options.wiggle && options.key.is_none() {
+                        wiggle(point, &mut rng);
+                    }
+                    if step.is_none() && !options.burst.enabled && !options.ramp.enabled {
+                        let left = limit.map_or(usize::MAX, |limit| limit.saturating_sub(clicks));
+                        batch = turbo::batch(delay, &options).min(left).max(1);
+                    }
+                    if batch > 1 {
+                        trigger_batch(&options, batch);
+                    } else {
+                        trigger(&options);
+                    }
+                    cycle_finished = true;
+                    if step.is_some() {
+                        rotated = step;
+                        rotation_presses += 1;
+                    } else if options.key.is_none() {
+                        button_clicks += 1;
+                    }
+                }
+            }
+        }
+
+        drop(cycle_span);
+
+        if cycle_finished {
+            clicks += batch;
+            interval_log.record();
+            if limit.is_some_and(|limit| clicks >= limit) {
+                interval_log.finish(delay);
+                stats::stop();
+                sequence::set_progress(None);
+                timeline::record(Activity::Finish);
+                RUNNING.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            let burst = click_options.burst;
+            let interval = if let Some(step) = rotated {
+                Duration::from_millis(step.delay_ms as u64)
+            } else if burst.enabled {
+                burst_clicks += 1;
+                let wait = burst.wait_after(burst_clicks);
+                if burst.ends_after(burst_clicks) {
+                    burst_clicks = 0;
+                }
+                wait
+            } else if click_options.ramp.enabled {
+                let elapsed = started.map_or(Duration::ZERO, |started| {
+                    now.saturating_duration_since(started)
+                });
+                click_options.ramp.interval_at(elapsed)
+            } else {
+                delay * batch as u32
+            };
+            let mut wait = click_options
+                .humanize
+                .interval(interval, &mut rng)
+                .max(min_wait(&click_options) * batch as u32);
+            if !click_sequence.is_empty() {
+                wait += Duration::from_millis(sequence_loop.delay_ms as u64);
+            }
+            // Click n is due at the run's first click plus the n waits
+            // before it, not a wait after the previous click ended, so the
+            // time spent clicking comes out of the wait rather than adding
+            // to it and no drift builds up over a long run.
+            let now = Instant::now();
+            let mut due = next_due.unwrap_or(cycle_start) + wait;
+            // More than an interval behind, e.g. after the machine was
+            // suspended: drop the debt instead of bursting to catch up.
+            // Adaptive runs make up for clicks that take longer than the
+            // interval by starting the next ones right away, up to a bound.
+            let max_debt = if click_interval.adaptive {
+                wait.max(ClickInterval::MAX_DEBT)
+            } else {
+                wait
+            };
+            if now > due + max_debt {
+                due = now;
+            }
+            next_due = Some(due);
+            next_cycle = Some(vsync::align(due));
+        }
+
+        wake = if running && pending.is_none() {
+            earliest([next_cycle].into_iter().chain(checks))
+        } else {
+            // Stopped or interrupted by the cycle itself; go on right away.
+            Some(Instant::now())
+        };
+    }
+
+    if let Some((release, _)) = held {
+        send(&release);
+    }
+    RUNNING.store(false, Ordering::SeqCst);
+    interval_log.finish(delay);
+    stats::stop();
+    sequence::set_progress(None);
+}
+
+/// The settings of a run in a few words, for its statistics record.
+fn describe_run(
+    delay: Duration,
+    click_options: &ClickOptions,
+    click_position: &ClickPosition,
+    click_sequence: &[ClickPoint],
+    typing_text: &TypingText,
+    key_rotation: &KeyRotation,
+) -> String {
+    let mut words = vec![format!("interval={}", humantime::format_duration(delay))];
+    if !click_sequence.is_empty() {
+        words.push(format!("sequence_steps={}", click_sequence.len()));
+    } else if typing_text.is_active() {
+        words.push(format!("typed_chars={}", typing_text.text.chars().count()));
+    } else {
+        words.push(match click_options.key {
+            _ if key_rotation.is_active() => {
+                let names: Vec<_> = key_rotation
+                    .steps
+                    .iter()
+                    .map(|step| keys::name(step.key))
+                    .collect();
+                format!("keys={}", names.join(","))
+            }
+            Some(key) => format!("key={}", keys::name(key)),
+            None if click_options.button_rotation.enabled => {
+                let names: Vec<_> = click_options
+                    .button_rotation
+                    .buttons()
+                    .iter()
+                    .map(|button| format!("{button:?}").to_lowercase())
+                    .collect();
+                format!("buttons={}", names.join(","))
+            }
+            None if click_options.button_weights.enabled => {
+                let weights: Vec<_> = click_options
+                    .button_weights
+                    .weights()
+                    .iter()
+                    .map(|(button, weight)| format!("{button:?}:{weight}").to_lowercase())
+                    .collect();
+                format!("buttons={}", weights.join(","))
+            }
+            None => format!("button={:?}", click_options.mouse_button).to_lowercase(),
+        });
+        words.push(format!("clicks={}", click_options.click_type.clicks()));
+        for (enabled, name) in [
+            (click_options.hold_mode, "hold"),
+            (click_options.drag.enabled, "drag"),
+            (click_options.burst.enabled, "burst"),
+            (click_options.ramp.enabled, "ramp"),
+        ] {
+            if enabled {
+                words.push(name.to_owned());
+            }
+        }
+    }
+    words.push(match click_position {
+        ClickPosition::CurrentCursorPosition => "position=cursor".to_owned(),
+        ClickPosition::Custom { x, y, .. } => format!("position={x},{y}"),
+        ClickPosition::Grid(grid) => format!("position=grid:{}x{}", grid.columns, grid.rows),
+        ClickPosition::Path(_) => "position=path".to_owned(),
+        ClickPosition::Window { .. } => "position=window".to_owned(),
+    });
+    if turbo::is_enabled() {
+        words.push("turbo".to_owned());
+    }
+    if is_preview() {
+        words.push("preview".to_owned());
+    }
+    words.join(" ")
+}
+
+/// Ends a pause. The run's start moves on by the time it was paused, and
+/// with it the run limit's deadline.
+fn resume(
+    paused_at: &mut Option<Instant>,
+    started: &mut Option<Instant>,
+    next_due: &mut Option<Instant>,
+) {
+    let Some(since) = paused_at.take() else {
+        return;
+    };
+    if let Some(started) = started {
+        *started += since.elapsed();
+    }
+    // Precise intervals start a fresh schedule instead of catching up.
+    *next_due = None;
+    stats::resume();
+    timeline::record(Activity::Resume);
+}
+
+/// Waits for the next command, until `wake` if one is given. In precision
+/// mode only the bulk of the wait blocks and the rest is spun, since an OS
+/// wait can overshoot by a whole scheduler tick; this keeps a CPU core busy
+/// for up to `SPIN_MARGIN` per wait. Other waits spin the wake offset the
+/// same way, see [`set_wake_offset`]. Fails once every sender is gone.
+fn next_command(
+    rx_command: &Receiver<Command>,
+    wake: Option<Instant>,
+    precise: bool,
+) -> Result<Option<Command>, RecvError> {
+    let Some(wake) = wake else {
+        return rx_command.recv().map(Some);
+    };
+
+    let _span = trace::span("sleep");
+    let margin = if precise {
+        SPIN_MARGIN
+    } else {
+        *WAKE_OFFSET.lock().unwrap_or_else(PoisonError::into_inner)
+    };
+    let coarse = wake.checked_sub(margin).unwrap_or(wake);
+    match rx_command.recv_timeout(coarse.saturating_duration_since(Instant::now())) {
+        Ok(command) => return Ok(Some(command)),
+        Err(RecvTimeoutError::Disconnected) => return Err(RecvError),
+        Err(RecvTimeoutError::Timeout) => {}
+    }
+    while Instant::now() < wake {
+        hint::spin_loop();
+    }
+    Ok(None)
+}
+
+/// How a conditional sequence step's wait ended.
+enum Wait {
+    Met,
+    TimedOut,
+    /// A command came in first, e.g. Stop.
+    Interrupted(Command),
+}
+
+/// Waits until the condition of `point` holds or its timeout runs out,
+/// looking again every [`GUARD_POLL`]. Gives up as soon as a command comes
+/// in, so Stop does not wait for the condition.
+fn wait_for(point: &ClickPoint, rx_command: &Receiver<Command>) -> Wait {
+    let timeout = (point.timeout_ms > 0)
+        .then(|| Instant::now() + Duration::from_millis(point.timeout_ms as u64));
+    loop {
+        if point.is_met() {
+            return Wait::Met;
+        }
+        let now = Instant::now();
+        if timeout.is_some_and(|timeout| now >= timeout) {
+            return Wait::TimedOut;
+        }
+        let poll = timeout.map_or(GUARD_POLL, |timeout| (timeout - now).min(GUARD_POLL));
+        match rx_command.recv_timeout(poll) {
+            Ok(command) => return Wait::Interrupted(command),
+            Err(RecvTimeoutError::Disconnected) => return Wait::Interrupted(Command::Shutdown),
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+}
+
+/// Waits out the pause after a sequence step. Like [`wait_for`] it gives up
+/// as soon as a command comes in, returning it, so Stop does not wait for a
+/// long delay; `Err(None)` after an emergency abort.
+fn step_delay(
+    delay: Duration,
+    since: u64,
+    rx_command: &Receiver<Command>,
+) -> Result<(), Option<Command>> {
+    let until = Instant::now() + delay;
+    loop {
+        if abort::is_aborted(since) {
+            return Err(None);
+        }
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Ok(());
+        }
+        match rx_command.recv_timeout(left) {
+            Ok(command) => return Err(Some(command)),
+            Err(RecvTimeoutError::Disconnected) => return Err(Some(Command::Shutdown)),
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+}
+
+/// The earliest of `times`, if any is set.
+fn earliest(times: impl IntoIterator<Item = Option<Instant>>) -> Option<Instant> {
+    times.into_iter().flatten().min()
+}
+
+/// Presses the button, or the key if one is set, as configured in
+/// `click_options`: held for `hold_ms` in hold mode, otherwise one trigger's
+/// worth of presses. A hold of 0 ms ("until Stop") only makes sense for the
+/// main loop and is sent as a normal press here. An enabled drag replaces
+/// all of that.
+pub(crate) fn trigger(click_options: &ClickOptions) {
+    let (press, release) = press_and_release(click_options);
+    if click_options.drag.enabled {
+        drag(&click_options.drag, rdev_button(click_options.mouse_button));
+    } else if click_options.hold_mode && click_options.hold_ms > 0 {
+        send(&press);
+        sleep(Duration::from_millis(click_options.hold_ms as u64));
+        send(&release);
+    } else {
+        let burst_delay = Duration::from_millis(click_options.burst_delay_ms as u64);
+        let press_time = Duration::from_millis(click_options.press_ms as u64);
+        let clicks = click_options.click_type.clicks();
+        if !press_time.is_zero() {
+            for i in 0..clicks {
+                if i > 0 {
+                    sleep(burst_delay);
+                }
+                send(&press);
+                sleep(press_time);
+                send(&release);
+            }
+        } else if burst_delay.is_zero() {
+            // Without a pause between clicks the whole burst goes out at once.
+            let events: Vec<_> = (0..clicks).flat_map(|_| [press, release]).collect();
+            send_batch(&events);
+        } else {
+            for i in 0..clicks {
+                if i > 0 {
+                    sleep(burst_delay);
+                }
+                send_batch(&[press, release]);
+            }
+        }
+    }
+}
+
+/// Sends `batch` plain clicks of `click_options` back to back in a single
+/// batch, for turbo mode.
+fn trigger_batch(click_options: &ClickOptions, batch: usize) {
+    let (press, release) = press_and_release(click_options);
+    let clicks = click_options.click_type.clicks() * batch;
+    let events: Vec<_> = (0..clicks).flat_map(|_| [press, release]).collect();
+    send_batch(&events);
+}
+
+/// Nudges the cursor 1-3 px off `at` and back right before a click. Clicks
+/// at the cursor wiggle around where the listener last saw it, if it did.
+fn wiggle(at: Option<(i32, i32)>, rng: &mut Rng) {
+    let Some((x, y)) = at
+        .or_else(|| listener::cursor_position().map(|(x, y)| (x.round() as i32, y.round() as i32)))
+    else {
+        return;
+    };
+    let distance = 1 + (rng.next_u64() % 3) as i32;
+    let (dx, dy) = match rng.next_u64() % 4 {
+        0 => (distance, 0),
+        1 => (-distance, 0),
+        2 => (0, distance),
+        _ => (0, -distance),
+    };
+    move_to(x + dx, y + dy);
+    move_to(x, y);
+}
+
+/// Presses `button` at the drag's start point, moves to its end point in
+/// [`drag::STEP`]s and releases it there. Stop takes effect once the drag
+/// is done, so the button is never left pressed.
+fn drag(drag: &Drag, button: rdev::Button) {
+    move_to(drag.from_x, drag.from_y);
+    send(&EventType::ButtonPress(button));
+    for (x, y) in drag.moves() {
+        sleep(drag::STEP);
+        move_to(x, y);
+    }
+    send(&EventType::ButtonRelease(button));
+}
+
+/// Fires a single trigger with `click_options` at `click_position` after
+/// `delay`, on its own thread, so a setup can be checked without starting a
+/// run.
+pub(crate) fn test_click(
+    click_options: ClickOptions,
+    click_position: ClickPosition,
+    delay: Duration,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        sleep(delay);
+        if let Some((x, y)) = click_position
+            .targets()
+            .and_then(|mut targets| targets.next())
+        {
+            move_to(x, y);
+        }
+        trigger(&click_options);
+    })
+}
+
+/// Moves the cursor to `found`, a found image's center, if given, or
+/// otherwise to the next target, unless clicks go wherever the cursor is.
+/// Returns where the cursor was moved to, `Some(None)` if it was not, and
+/// `None`, for the run to stop, when the targets run out, which happens
+/// when a target window closes.
+fn move_to_next(
+    targets: &mut Option<Targets>,
+    found: Option<(i32, i32)>,
+    click_options: &ClickOptions,
+    rng: &mut Rng,
+) -> Option<Option<(i32, i32)>> {
+    let next = match (found, targets) {
+        (Some(found), _) => Some(found),
+        (None, Some(targets)) => targets.next(),
+        (None, None) => return Some(None),
+    };
+    let Some((x, y)) = next else {
+        target_closed();
+        return None;
+    };
+    let (x, y) = click_options.humanize.position(x, y, rng);
+    move_to(x, y);
+    *LAST_TARGET.lock().unwrap_or_else(PoisonError::into_inner) = Some((x, y));
+    Some(Some((x, y)))
+}
+
+/// Reports that the window the run is aimed at is gone; the caller stops
+/// the run.
+fn target_closed() {
+    log::info!("The target window is gone, stopping the clicker");
+    overlay::flash_stop();
+    timeline::record(Activity::TargetClosed);
+}
+
+/// Whether a click at `point`, or at the cursor without one, would land on
+/// the app's own window. Its sandbox panel is fair game.
+pub(crate) fn is_own_window(point: Option<(i32, i32)>) -> bool {
+    if sandbox::contains(point) {
+        return false;
+    }
+    match point {
+        Some((x, y)) => OWN_WINDOW
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some_and(|(left, top, width, height)| {
+                (left..left + width as i32).contains(&x) && (top..top + height as i32).contains(&y)
+            }),
+        None => recorder::is_own_window_hovered(),
+    }
+}
+
+/// The events that press and release the key or button of `click_options`.
+fn press_and_release(click_options: &ClickOptions) -> (EventType, EventType) {
+    match click_options.key {
+        Some(key) => (EventType::KeyPress(key), EventType::KeyRelease(key)),
+        None => {
+            let button = rdev_button(click_options.mouse_button);
+            (
+                EventType::ButtonPress(button),
+                EventType::ButtonRelease(button),
+            )
+        }
+    }
+}
+
+pub(crate) fn rdev_button(mouse_button: MouseButton) -> rdev::Button {
+    match mouse_button {
+        MouseButton::Left => rdev::Button::Left,
+        MouseButton::Middle => rdev::Button::Middle,
+        MouseButton::Right => rdev::Button::Right,
+    }
+}
+
+/// Types `text` key by key, holding Shift where the character needs it.
+/// Characters without a key on a US layout are skipped.
+fn type_text(text: &str, char_delay: Duration) {
+    for c in text.chars() {
+        let Some((key, shift)) = keys::for_char(c) else {
+            log::warn!("Cannot type {c:?}, skipping it");
+            continue;
+        };
+        if shift {
+            send(&EventType::KeyPress(rdev::Key::ShiftLeft));
+        }
+        send(&EventType::KeyPress(key));
+        send(&EventType::KeyRelease(key));
+        if shift {
+            send(&EventType::KeyRelease(rdev::Key::ShiftLeft));
+        }
+        sleep(char_delay);
+    }
+}
+
+/// Presses Ctrl+V, or Cmd+V on macOS.
+fn paste() {
+    let modifier = if cfg!(target_os = "macos") {
+        rdev::Key::MetaLeft
+    } else {
+        rdev::Key::ControlLeft
+    };
+    send(&EventType::KeyPress(modifier));
+    send(&EventType::KeyPress(rdev::Key::KeyV));
+    send(&EventType::KeyRelease(rdev::Key::KeyV));
+    send(&EventType::KeyRelease(modifier));
+}
+
+pub(crate) fn move_to(x: i32, y: i32) {
+    send(&EventType::MouseMove {
+        x: x as f64,
+        y: y as f64,
+    });
+}
+
+/// Sends one event, at least the event gap after the previous one.
+pub(crate) fn send(event_type: &EventType) {
+    catch_up();
+    let _span = trace::span(match event_type {
+        EventType::MouseMove { .. } => "move",
+        EventType::ButtonPress(_) | EventType::KeyPress(_) => "press",
+        EventType::ButtonRelease(_) | EventType::KeyRelease(_) => "release",
+        EventType::Wheel { .. } => "wheel",
+    });
+    if is_preview() {
+        preview(event_type);
+    } else {
+        match simulate(event_type) {
+            Ok(()) => {
+                supervisor::send_succeeded();
+                count(event_type);
+            }
+            Err(_) => {
+                log::error!("We could not send {event_type:?}");
+                supervisor::send_failed(&format!("Could not send {event_type:?}"));
+            }
+        }
+    }
+    *LAST_SENT.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+}
+
+/// Stands in for sending `event_type` in a preview run: moves only change
+/// where the cursor would be, presses are marked there on the overlay.
+fn preview(event_type: &EventType) {
+    let mut cursor = PREVIEW_CURSOR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    match event_type {
+        EventType::MouseMove { x, y } => *cursor = Some((x.round() as i32, y.round() as i32)),
+        EventType::ButtonPress(_) => {
+            let at = cursor.or_else(|| {
+                listener::cursor_position().map(|(x, y)| (x.round() as i32, y.round() as i32))
+            });
+            if let Some((x, y)) = at {
+                overlay::mark_preview(x, y);
+            }
+        }
+        _ => {}
+    }
+    drop(cursor);
+    count(event_type);
+}
+
+/// The least time between two sent events with the backend in use, in
+/// precision mode or not. Backends that queue their events in order, e.g.
+/// XTEST, need no gap, and turbo mode skips it.
+pub(crate) fn event_gap(precise: bool) -> Duration {
+    if turbo::is_enabled() || !source::input().needs_event_gap() {
+        Duration::ZERO
+    } else if precise {
+        EVENT_GAP
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .min(PRECISE_EVENT_GAP)
+    } else {
+        *EVENT_GAP.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Lets the OS catch up (at least macOS) by waiting until the event gap has
+/// passed since the last event. Waiting before an event rather than after
+/// it lets the gap overlap the wait between clicks instead of adding to it.
+fn catch_up() {
+    let gap = event_gap(PRECISE.load(Ordering::Relaxed));
+    if gap.is_zero() {
+        return;
+    }
+    let Some(ready) = LAST_SENT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .map(|last| last + gap)
+    else {
+        return;
+    };
+    let now = Instant::now();
+    if now < ready {
+        let _span = trace::span("sleep");
+        sleep(ready - now);
+    }
+}
+
+/// Sends `events` in a single batch where the platform supports it, which
+/// keeps a double or triple click together, and one by one otherwise.
+fn send_batch(events: &[EventType]) {
+    catch_up();
+    let _span = trace::span("batch");
+    if is_preview() {
+        events.iter().for_each(preview);
+        *LAST_SENT.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+        return;
+    }
+    match source::simulate_batch(events) {
+        Some(Ok(())) => {
+            supervisor::send_succeeded();
+            events.iter().for_each(count);
+        }
+        Some(Err(e)) => {
+            log::error!("We could not send {events:?}");
+            supervisor::send_failed(&e.to_string());
+        }
+        None => {
+            events.iter().for_each(send);
+            return;
+        }
+    }
+    *LAST_SENT.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+}
+
+/// Updates the overlay, statistics and timeline for an event that was sent.
+fn count(event_type: &EventType) {
+    match event_type {
+        EventType::ButtonPress(_) => {
+            // Preview runs mark their clicks themselves.
+            if !is_preview() {
+                overlay::mark_click();
+            }
+            stats::count_click();
+            timeline::record(Activity::Click);
+        }
+        // Shift is held for typed capitals, not a key press of its own.
+        EventType::KeyPress(key) if *key != rdev::Key::ShiftLeft => {
+            stats::count_click();
+            timeline::record(Activity::Click);
+        }
+        _ => {}
+    }
+}
+
+fn convert_time_to_duration(
+    hours: usize,
+    minutes: usize,
+    seconds: usize,
+    milliseconds: usize,
+) -> Duration {
+    let total_milliseconds =
+        milliseconds + (seconds * 1000) + (minutes * 60 * 1000) + (hours * 60 * 60 * 1000);
+    let seconds = total_milliseconds / 1000;
+    let nanos = (total_milliseconds % 1000) * 1_000_000;
+    Duration::new(seconds as u64, nanos as u32)
+}
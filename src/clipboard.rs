@@ -0,0 +1,23 @@
+//! Text on the system clipboard, for sequence steps that paste values into
+//! forms.
+
+use std::cell::RefCell;
+
+thread_local! {
+    /// Kept for the thread's life: on Linux the clipboard only holds what
+    /// an app put there while the app's handle to it lives.
+    static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+}
+
+/// Puts `text` on the clipboard.
+pub fn set_text(text: &str) -> Result<(), arboard::Error> {
+    CLIPBOARD.with(|clipboard| {
+        let mut clipboard = clipboard.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new()?);
+        }
+        clipboard
+            .as_mut()
+            .map_or(Ok(()), |clipboard| clipboard.set_text(text))
+    })
+}
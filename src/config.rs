@@ -0,0 +1,342 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fmt,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use toml::Value;
+
+/// Returns the per-user configuration directory for the app, e.g.
+/// `~/.config/auto-clicker` on Linux.
+pub fn config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let base = env::var_os("APPDATA").map(PathBuf::from);
+
+    #[cfg(target_os = "macos")]
+    let base =
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"));
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    base.map(|dir| dir.join("auto-clicker"))
+}
+
+/// Prefix of the environment variables that override settings.
+pub const ENV_PREFIX: &str = "AUTOCLICKER_";
+
+/// The `AUTOCLICKER_*` variables of the process environment, for
+/// [`Table::override_from_env`] and the command line. Variables that are
+/// not valid Unicode are left out.
+pub fn environment() -> HashMap<String, String> {
+    env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect()
+}
+
+/// The environment variable that overrides the setting `key`, e.g.
+/// `AUTOCLICKER_OVERLAY_ENABLED` for `overlay.enabled`.
+pub fn env_var(key: &str) -> String {
+    format!("{ENV_PREFIX}{}", key.replace('.', "_").to_uppercase())
+}
+
+/// A flat view of a TOML document. Keys are stored with their tables as a
+/// dotted prefix, so `[interval]\nhours = 1` is read back as
+/// `interval.hours`, and so is `interval = { hours = 1 }`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Table {
+    entries: BTreeMap<String, Value>,
+}
+
+impl Table {
+    /// Reads a TOML document, or says where it is malformed. Keys may not
+    /// contain dots themselves, as they would clash with the dotted path
+    /// of a nested key.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let document: toml::Table = source.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let mut table = Self::default();
+        table.flatten("", document)?;
+        Ok(table)
+    }
+
+    fn flatten(&mut self, prefix: &str, document: toml::Table) -> Result<(), String> {
+        for (key, value) in document {
+            if key.is_empty() || key.contains('.') {
+                let place = if prefix.is_empty() {
+                    String::new()
+                } else {
+                    format!(" in [{prefix}]")
+                };
+                return Err(format!(
+                    "the key {key:?}{place} is empty or has a dot, which would clash with a nested key"
+                ));
+            }
+            let key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match value {
+                Value::Table(nested) => self.flatten(&key, nested)?,
+                value => {
+                    self.entries.insert(key, value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The value of `key` parsed as `T`, or `None` if it is missing, an
+    /// array or does not parse.
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        match self.entries.get(key)? {
+            Value::String(value) => value.parse().ok(),
+            Value::Array(_) | Value::Table(_) => None,
+            value => value.to_string().parse().ok(),
+        }
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<String> {
+        self.entries.get(key)?.as_str().map(str::to_owned)
+    }
+
+    /// Sets `key` to `value` read as a TOML value, e.g. `3`, `true` or
+    /// `[1, 2]`, or as a string if it is not one.
+    pub fn set(&mut self, key: &str, value: impl fmt::Display) {
+        let text = value.to_string();
+        let value = format!("value = {text}")
+            .parse::<toml::Table>()
+            .ok()
+            .filter(|document| document.len() == 1)
+            .and_then(|mut document| document.remove("value"))
+            .unwrap_or(Value::String(text));
+        self.entries.insert(key.to_owned(), value);
+    }
+
+    /// Replaces the entries of the keys in `known` whose [`env_var`] is
+    /// set in `env`, see [`environment`]. Keys that hold a string in
+    /// `known` take the variable as a string, the others as a raw value.
+    pub fn override_from_env(&mut self, known: &Table, env: &HashMap<String, String>) {
+        for (key, value) in &known.entries {
+            let Some(var) = env.get(&env_var(key)) else {
+                continue;
+            };
+            if value.is_str() {
+                self.set_str(key, var);
+            } else {
+                self.set(key, var.trim());
+            }
+        }
+    }
+
+    /// Every key with its value, sorted by key.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// Drops the entry of `key`, if any.
+    pub fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+
+    pub fn set_str(&mut self, key: &str, value: &str) {
+        self.entries
+            .insert(key.to_owned(), Value::String(value.to_owned()));
+    }
+}
+
+/// `key` as written in a document: bare if it can be, quoted otherwise.
+fn quote_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_owned()
+    } else {
+        Value::String(key.to_owned()).to_string()
+    }
+}
+
+/// Writes each key under a header for everything before its last dot.
+/// Sections are ordered by their dotted parts, so a nested section follows
+/// its parent, e.g. `[a]`, `[a.b]`, `[a-b]`.
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sections: BTreeMap<Vec<&str>, Vec<(&str, &Value)>> = BTreeMap::new();
+        for (key, value) in &self.entries {
+            let (section, key) = key.rsplit_once('.').unwrap_or(("", key));
+            let path = if section.is_empty() {
+                Vec::new()
+            } else {
+                section.split('.').collect()
+            };
+            sections.entry(path).or_default().push((key, value));
+        }
+
+        let mut first = true;
+        for (path, entries) in sections {
+            if !path.is_empty() {
+                if !first {
+                    writeln!(f)?;
+                }
+                let path: Vec<_> = path.into_iter().map(quote_key).collect();
+                writeln!(f, "[{}]", path.join("."))?;
+            }
+            for (key, value) in entries {
+                writeln!(f, "{} = {value}", quote_key(key))?;
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::{ClickPosition, Origin},
+        profile::Profile,
+    };
+
+    #[test]
+    fn reads_sections_as_dotted_keys() {
+        let table =
+            Table::parse("top = 1\n[interval]\nhours = 2\n\n[position.window]\nx = 3\n").unwrap();
+        assert_eq!(table.get("top"), Some(1));
+        assert_eq!(table.get("interval.hours"), Some(2));
+        assert_eq!(table.get("position.window.x"), Some(3));
+    }
+
+    #[test]
+    fn skips_comments_outside_strings() {
+        let table = Table::parse(
+            "# a profile\n\
+             [interval] # the click interval\n\
+             hours = 1 # an hour\n\
+             name = \"a # b\" # not part of the name\n\
+             quote = \"say \\\"#1\\\"\"#comment\n\
+             path = \"C:\\\\\" # ends in a backslash\n",
+        )
+        .unwrap();
+        assert_eq!(table.get("interval.hours"), Some(1));
+        assert_eq!(table.get_str("interval.name").as_deref(), Some("a # b"));
+        assert_eq!(
+            table.get_str("interval.quote").as_deref(),
+            Some("say \"#1\"")
+        );
+        assert_eq!(table.get_str("interval.path").as_deref(), Some("C:\\"));
+    }
+
+    #[test]
+    fn flattens_inline_tables_and_keeps_arrays() {
+        let table = Table::parse(
+            "position = { x = 3, window = { y = -4 } }\n\
+             [schedule]\n\
+             days = [1, 2, 5]\n",
+        )
+        .unwrap();
+        assert_eq!(table.get("position.x"), Some(3));
+        assert_eq!(table.get("position.window.y"), Some(-4));
+        assert_eq!(table.get::<i64>("schedule.days"), None);
+        let written = table.to_string();
+        assert!(written.contains("days = [1, 2, 5]"), "{written}");
+        assert_eq!(Table::parse(&written).unwrap(), table);
+    }
+
+    #[test]
+    fn reports_malformed_documents() {
+        for source in [
+            "[a]]\nx = 1\n",
+            "[a\nx = 1\n",
+            "x = 1\nnot a key value pair\n",
+            "x = \"unterminated\n",
+            "x = 1\nx = 2\n",
+            "a.b = 1\n[a]\nb = 2\n",
+        ] {
+            assert!(Table::parse(source).is_err(), "{source:?} parsed");
+        }
+    }
+
+    #[test]
+    fn rejects_keys_that_clash_with_dotted_paths() {
+        assert!(Table::parse("\"a.b\" = 1\n[a]\nb = 2\n").is_err());
+        assert!(Table::parse("[a]\n\"b.c\" = 1\n").is_err());
+        assert!(Table::parse("\"\" = 1\n").is_err());
+        let table = Table::parse("\"odd key\" = 1\n").unwrap();
+        assert_eq!(Table::parse(&table.to_string()).unwrap(), table);
+    }
+
+    #[test]
+    fn strings_keep_their_escapes() {
+        let mut table = Table::default();
+        let value = "quote \" backslash \\ newline \n tab \t hash #";
+        table.set_str("text.value", value);
+        let read = Table::parse(&table.to_string()).unwrap();
+        assert_eq!(read.get_str("text.value").as_deref(), Some(value));
+    }
+
+    #[test]
+    fn nested_sections_round_trip() {
+        let mut table = Table::default();
+        table.set("top", 1);
+        table.set("a.x", 2);
+        table.set("a.b.y", 3);
+        table.set("a.b.c.z", 4);
+        table.set("a-b.w", 5);
+        table.set_str("a.name", "first");
+        let written = table.to_string();
+        assert_eq!(
+            written,
+            "top = 1\n\n[a]\nname = \"first\"\nx = 2\n\n[a.b]\ny = 3\n\n[a.b.c]\nz = 4\n\n[a-b]\nw = 5\n"
+        );
+        assert_eq!(Table::parse(&written).unwrap(), table);
+    }
+
+    #[test]
+    fn environment_overrides_known_keys() {
+        let mut known = Table::default();
+        known.set("interval.milliseconds", 100);
+        known.set_str("language", "en");
+        known.set("overlay.enabled", false);
+        let env = HashMap::from([
+            (
+                "AUTOCLICKER_INTERVAL_MILLISECONDS".to_owned(),
+                " 250 ".to_owned(),
+            ),
+            ("AUTOCLICKER_LANGUAGE".to_owned(), "de \"CH\"".to_owned()),
+            ("AUTOCLICKER_UNKNOWN".to_owned(), "1".to_owned()),
+        ]);
+
+        let mut table = Table::parse("[overlay]\nenabled = true\n").unwrap();
+        table.override_from_env(&known, &env);
+        assert_eq!(table.get("interval.milliseconds"), Some(250));
+        assert_eq!(table.get_str("language").as_deref(), Some("de \"CH\""));
+        // Settings without a variable keep their value, unknown variables
+        // add nothing.
+        assert_eq!(table.get("overlay.enabled"), Some(true));
+        assert_eq!(table.entries().count(), 3);
+    }
+
+    #[test]
+    fn profiles_round_trip() {
+        let mut profile = Profile::default();
+        profile.click_interval.milliseconds = 250;
+        profile.click_position = ClickPosition::Custom {
+            x: 10,
+            y: -20,
+            origin: Origin::ActiveWindow,
+        };
+        for table in [Profile::default().to_table(), profile.to_table()] {
+            assert_eq!(Table::parse(&table.to_string()).unwrap(), table);
+        }
+    }
+}
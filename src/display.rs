@@ -0,0 +1,19 @@
+/// Points the input backend at another X display, e.g. `:1` for a nested
+/// or secondary X server, by setting `DISPLAY` for the connections it opens
+/// from now on. Windows that are already open stay where they are. Call this
+/// before the clicker and listener threads start; an empty `display` keeps
+/// the current one. Only X11 has displays to choose from.
+#[cfg(target_os = "linux")]
+pub fn set_input_display(display: &str) {
+    let display = display.trim();
+    if !display.is_empty() {
+        std::env::set_var("DISPLAY", display);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_input_display(_display: &str) {}
+
+pub const fn is_supported() -> bool {
+    cfg!(target_os = "linux")
+}
@@ -0,0 +1,188 @@
+//! The system's do-not-disturb mode: Focus Assist on Windows, a Focus on
+//! macOS, and on Linux GNOME's "Do Not Disturb" or any notification server
+//! that reports itself inhibited. While it is on, the sounds, announcements
+//! and notifications of runs are held back: whatever alerts about a run
+//! checks [`holds_alerts`] first. Clicking goes on.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// How long the mode as last looked up is trusted; looking it up can start
+/// a process.
+const CACHE_FOR: Duration = Duration::from_secs(5);
+
+static RESPECTED: AtomicBool = AtomicBool::new(true);
+
+/// When the mode was last looked up, and whether it was on.
+static LAST: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+/// Whether run alerts are held back while the system is in do-not-disturb
+/// mode. On by default.
+pub fn set_respected(respected: bool) {
+    RESPECTED.store(respected, Ordering::SeqCst);
+}
+
+pub fn is_respected() -> bool {
+    RESPECTED.load(Ordering::SeqCst)
+}
+
+/// Whether run alerts should be held back now: [`is_active`] and
+/// [`is_respected`].
+pub fn holds_alerts() -> bool {
+    is_respected() && is_active()
+}
+
+/// Whether the system is in do-not-disturb mode, looked up at most every
+/// [`CACHE_FOR`]. `false` where it cannot be told.
+pub fn is_active() -> bool {
+    let mut last = LAST.lock().unwrap();
+    if let Some((at, active)) = *last {
+        if at.elapsed() < CACHE_FOR {
+            return active;
+        }
+    }
+    let active = query();
+    *last = Some((Instant::now(), active));
+    active
+}
+
+#[cfg(windows)]
+fn query() -> bool {
+    // QUERY_USER_NOTIFICATION_STATE values for presenting and quiet time.
+    // The fullscreen ones are left out, as beeps are most wanted in a
+    // fullscreen game.
+    const QUNS_PRESENTATION_MODE: i32 = 4;
+    const QUNS_QUIET_TIME: i32 = 6;
+    // WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED, the Focus Assist profile:
+    // 0 off, 1 priority only, 2 alarms only. There is no documented API for
+    // it; this is what the Settings app itself reads.
+    const QUIET_HOURS_PROFILE: u64 = 0x0d83_063e_a3bf_5075;
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+    }
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtQueryWnfStateData(
+            state_name: *const u64,
+            type_id: *const u8,
+            explicit_scope: *const u8,
+            change_stamp: *mut u32,
+            buffer: *mut u32,
+            buffer_size: *mut u32,
+        ) -> i32;
+    }
+
+    let mut profile = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let mut change_stamp = 0;
+    // SAFETY: the state name, stamp, buffer and its size are valid for the
+    // call, and the buffer holds as many bytes as `size` says.
+    let status = unsafe {
+        NtQueryWnfStateData(
+            &QUIET_HOURS_PROFILE,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile,
+            &mut size,
+        )
+    };
+    if status == 0 && size as usize == std::mem::size_of::<u32>() && profile != 0 {
+        return true;
+    }
+
+    let mut state = 0;
+    // SAFETY: only writes the state.
+    if unsafe { SHQueryUserNotificationState(&mut state) } != 0 {
+        return false;
+    }
+    matches!(state, QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME)
+}
+
+#[cfg(target_os = "macos")]
+fn query() -> bool {
+    use std::{env, path::PathBuf, process::Command};
+
+    // Since macOS 12 a Focus turned on shows up as an assertion, which only
+    // apps with Full Disk Access may read.
+    let assertions = env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join("Library/DoNotDisturb/DB/Assertions.json"))
+        .filter(|path| path.exists());
+    if let Some(path) = assertions {
+        return Command::new("plutil")
+            .args([
+                "-extract",
+                "data.0.storeAssertionRecords",
+                "json",
+                "-o",
+                "-",
+            ])
+            .arg(path)
+            .output()
+            .is_ok_and(|output| {
+                output.status.success() && String::from_utf8_lossy(&output.stdout).trim() != "[]"
+            });
+    }
+
+    // macOS 11 and older.
+    Command::new("defaults")
+        .args([
+            "-currentHost",
+            "read",
+            "com.apple.notificationcenterui",
+            "doNotDisturb",
+        ])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn query() -> bool {
+    use std::process::Command;
+
+    let output = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+
+    // GNOME's "Do Not Disturb" switch.
+    if output(
+        "gsettings",
+        &["get", "org.gnome.desktop.notifications", "show-banners"],
+    )
+    .is_some_and(|banners| banners == "false")
+    {
+        return true;
+    }
+    // KDE Plasma and other notification servers that implement
+    // inhibition.
+    output(
+        "busctl",
+        &[
+            "--user",
+            "get-property",
+            "org.freedesktop.Notifications",
+            "/org/freedesktop/Notifications",
+            "org.freedesktop.Notifications",
+            "Inhibited",
+        ],
+    )
+    .is_some_and(|inhibited| inhibited == "b true")
+}
+
+#[cfg(not(any(windows, unix)))]
+fn query() -> bool {
+    false
+}
@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    window::Window,
+};
+
+/// A window dropped this close to a screen edge snaps to it.
+const SNAP_DISTANCE: i32 = 24;
+
+/// How long the window has to stay put after a move before it snaps, so it
+/// does not jump while still being dragged.
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// Thickness of the collapsed bar, in logical pixels.
+const BAR: f64 = 28.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Left,
+    Right,
+    Top,
+}
+
+/// Snaps the main window to the screen edges and collapses it to a slim bar
+/// there while the cursor is elsewhere, expanding it again on hover.
+#[derive(Debug, Default)]
+pub struct Dock {
+    edge: Option<Edge>,
+    moved_at: Option<Instant>,
+    /// The inner size to restore, while collapsed.
+    expanded_size: Option<PhysicalSize<u32>>,
+}
+
+impl Dock {
+    /// Notes a move of the window, by the user or by us.
+    pub fn moved(&mut self) {
+        self.moved_at = Some(Instant::now());
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.expanded_size.is_some()
+    }
+
+    /// Snaps the window once it has settled after a move, or expands it
+    /// when docking was turned off. Returns when to call again if the
+    /// window is still settling.
+    pub fn update(&mut self, window: &Window, enabled: bool) -> Option<Instant> {
+        if !enabled {
+            self.expand(window);
+            self.edge = None;
+            self.moved_at = None;
+            return None;
+        }
+        let moved_at = self.moved_at?;
+        let settled = moved_at + SETTLE;
+        if Instant::now() < settled {
+            return Some(settled);
+        }
+        self.moved_at = None;
+        if !self.is_collapsed() {
+            self.snap(window);
+        }
+        None
+    }
+
+    /// Collapses the window to a bar if it is docked.
+    pub fn cursor_left(&mut self, window: &Window, enabled: bool) {
+        if !enabled || self.is_collapsed() {
+            return;
+        }
+        let (Some(edge), Some(monitor)) = (self.edge, window.current_monitor()) else {
+            return;
+        };
+        let bar = (BAR * window.scale_factor()) as u32;
+        let size = window.inner_size();
+        let outer = window.outer_size();
+        let Ok(position) = window.outer_position() else {
+            return;
+        };
+
+        self.expanded_size = Some(size);
+        match edge {
+            Edge::Top => window.set_inner_size(PhysicalSize::new(size.width, bar)),
+            Edge::Left => window.set_inner_size(PhysicalSize::new(bar, size.height)),
+            Edge::Right => {
+                window.set_inner_size(PhysicalSize::new(bar, size.height));
+                let right = monitor.position().x + monitor.size().width as i32;
+                let frame = (outer.width - size.width) as i32;
+                window.set_outer_position(PhysicalPosition::new(
+                    right - bar as i32 - frame,
+                    position.y,
+                ));
+            }
+        }
+    }
+
+    /// Restores the collapsed bar to the full window, e.g. while the cursor
+    /// is over it.
+    pub fn expand(&mut self, window: &Window) {
+        let Some(size) = self.expanded_size.take() else {
+            return;
+        };
+        window.set_inner_size(size);
+        if let (Some(Edge::Right), Some(monitor), Ok(position)) =
+            (self.edge, window.current_monitor(), window.outer_position())
+        {
+            let frame = (window.outer_size().width - window.inner_size().width) as i32;
+            let right = monitor.position().x + monitor.size().width as i32;
+            window.set_outer_position(PhysicalPosition::new(
+                right - size.width as i32 - frame,
+                position.y,
+            ));
+        }
+    }
+
+    /// Moves the window flush against the nearest edge within
+    /// [`SNAP_DISTANCE`], and remembers which edge that is.
+    fn snap(&mut self, window: &Window) {
+        let (Ok(position), Some(monitor)) = (window.outer_position(), window.current_monitor())
+        else {
+            return;
+        };
+        let size = window.outer_size();
+        let (left, top) = (monitor.position().x, monitor.position().y);
+        let right = left + monitor.size().width as i32;
+        let window_right = position.x + size.width as i32;
+
+        let (edge, snapped) = if (position.x - left).abs() <= SNAP_DISTANCE {
+            (Some(Edge::Left), PhysicalPosition::new(left, position.y))
+        } else if (right - window_right).abs() <= SNAP_DISTANCE {
+            (
+                Some(Edge::Right),
+                PhysicalPosition::new(right - size.width as i32, position.y),
+            )
+        } else if (position.y - top).abs() <= SNAP_DISTANCE {
+            (Some(Edge::Top), PhysicalPosition::new(position.x, top))
+        } else {
+            (None, position)
+        };
+        self.edge = edge;
+        if snapped != position {
+            window.set_outer_position(snapped);
+        }
+    }
+}
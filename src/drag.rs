@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+/// Time between the moves of a drag.
+pub const STEP: Duration = Duration::from_millis(10);
+
+/// Dragging instead of clicking: the button is pressed at the start point,
+/// moved to the end point over `duration_ms` and released there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drag {
+    pub enabled: bool,
+    pub from_x: i32,
+    pub from_y: i32,
+    pub to_x: i32,
+    pub to_y: i32,
+    pub duration_ms: usize,
+}
+
+impl Default for Drag {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            from_x: 0,
+            from_y: 0,
+            to_x: 200,
+            to_y: 0,
+            duration_ms: 300,
+        }
+    }
+}
+
+impl Drag {
+    /// The positions the cursor moves through after the press, one every
+    /// [`STEP`], ending at the end point.
+    pub fn moves(&self) -> impl Iterator<Item = (i32, i32)> {
+        let steps = (self.duration_ms as u128 / STEP.as_millis()).max(1) as i32;
+        let (from_x, from_y) = (self.from_x as f64, self.from_y as f64);
+        let (dx, dy) = (
+            (self.to_x - self.from_x) as f64,
+            (self.to_y - self.from_y) as f64,
+        );
+        (1..=steps).map(move |step| {
+            let t = step as f64 / steps as f64;
+            (
+                (from_x + dx * t).round() as i32,
+                (from_y + dy * t).round() as i32,
+            )
+        })
+    }
+}
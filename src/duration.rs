@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+/// Parses a duration as typed by the user, e.g. `250ms`, `1,5s`, `2m30s` or
+/// `1h 15m`. Units are `h`, `m`/`min`, `s`/`sec` and `ms`; the decimal
+/// separator can be a point or a comma. A bare number such as `1,5` is taken
+/// in `default_unit`.
+pub fn parse(text: &str, default_unit: Duration) -> Option<Duration> {
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    let mut total = 0.0;
+    let mut parts = 0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            return None;
+        }
+        let number: f64 = rest[..number_len].replace(',', ".").parse().ok()?;
+
+        let after = rest[number_len..].trim_start();
+        let unit_len = after
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(after.len());
+        let unit = match after[..unit_len].to_lowercase().as_str() {
+            "" if parts == 0 && after.is_empty() => default_unit.as_secs_f64(),
+            "h" | "hr" | "hrs" => 3600.0,
+            "m" | "min" | "mins" => 60.0,
+            "s" | "sec" | "secs" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+
+        total += number * unit;
+        parts += 1;
+        rest = after[unit_len..].trim_start();
+    }
+
+    Duration::try_from_secs_f64(total).ok()
+}
+
+/// A DragValue parser that accepts the same input as [`parse`] and converts
+/// it into the field's own `unit`.
+pub fn drag_value_parser(unit: Duration) -> impl Fn(&str) -> Option<f64> {
+    move |text| parse(text, unit).map(|duration| duration.as_secs_f64() / unit.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn bare_numbers_take_the_default_unit() {
+        assert_eq!(parse("1,5", SECOND), Some(Duration::from_millis(1500)));
+        assert_eq!(parse("1.5", SECOND), Some(Duration::from_millis(1500)));
+        assert_eq!(
+            parse("250", Duration::from_millis(1)),
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn parts_with_units_add_up() {
+        assert_eq!(parse("2m30s", SECOND), Some(Duration::from_secs(150)));
+        assert_eq!(parse("1h 15m", SECOND), Some(Duration::from_secs(4500)));
+        assert_eq!(parse(" 250ms ", SECOND), Some(Duration::from_millis(250)));
+        assert_eq!(parse("1 MIN", SECOND), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(parse("", SECOND), None);
+        assert_eq!(parse("   ", SECOND), None);
+        // Only a lone number may leave out its unit.
+        assert_eq!(parse("1m 30", SECOND), None);
+        assert_eq!(parse("5 days", SECOND), None);
+        assert_eq!(parse("ms", SECOND), None);
+        assert_eq!(parse("1.2.3s", SECOND), None);
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+use crate::{clicker, engine::ClickOptions, listener};
+
+/// Movements smaller than this many pixels count as holding still, so hand
+/// tremor does not keep resetting the dwell timer.
+const STILL_THRESHOLD: f64 = 3.0;
+
+/// Clicks automatically once the real cursor has rested for the dwell time
+/// after moving. Only one click is sent per rest; the cursor has to move
+/// again before the next one.
+#[derive(Default)]
+pub struct DwellClicker {
+    enabled: Arc<AtomicBool>,
+    dwell_ms: Arc<AtomicUsize>,
+    click_options: Arc<Mutex<ClickOptions>>,
+    started: bool,
+}
+
+impl DwellClicker {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+        if enabled {
+            self.start();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_dwell_ms(&self, dwell_ms: usize) {
+        self.dwell_ms.store(dwell_ms, Ordering::SeqCst);
+    }
+
+    pub fn set_click_options(&self, click_options: ClickOptions) {
+        *self.click_options.lock().unwrap() = click_options;
+    }
+
+    fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+
+        let enabled = self.enabled.clone();
+        let dwell_ms = self.dwell_ms.clone();
+        let click_options = self.click_options.clone();
+        let rx_events = listener::subscribe();
+
+        thread::spawn(move || {
+            let mut anchor: Option<(f64, f64)> = None;
+            let mut last_move = Instant::now();
+            let mut armed = false;
+
+            loop {
+                match rx_events.recv_timeout(Duration::from_millis(10)) {
+                    Ok(event) => match event.event_type {
+                        EventType::MouseMove { x, y } => {
+                            let moved = anchor
+                                .is_none_or(|(ax, ay)| (x - ax).hypot(y - ay) > STILL_THRESHOLD);
+                            if moved {
+                                anchor = Some((x, y));
+                                last_move = Instant::now();
+                                armed = true;
+                            }
+                        }
+                        // A real click during the rest replaces the dwell click.
+                        EventType::ButtonPress(_) => armed = false,
+                        _ => {}
+                    },
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+
+                let dwell = Duration::from_millis(dwell_ms.load(Ordering::SeqCst) as u64);
+                if armed
+                    && enabled.load(Ordering::SeqCst)
+                    && !clicker::is_shielded()
+                    && last_move.elapsed() >= dwell
+                {
+                    armed = false;
+                    let options = *click_options.lock().unwrap();
+                    clicker::trigger(&options);
+                }
+            }
+        });
+    }
+}
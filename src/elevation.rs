@@ -0,0 +1,186 @@
+//! Administrator rights on Windows. Windows drops input a normal process
+//! sends to a window of an elevated one, e.g. an installer or a game
+//! running as Administrator, without telling the sender.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::target;
+
+/// How long the foreground window's elevation is trusted before it is
+/// looked up again.
+const CHECK_EVERY: Duration = Duration::from_secs(1);
+
+/// The foreground window last looked at, whether clicks to it are ignored,
+/// and when it was checked.
+static FOREGROUND: Mutex<Option<(u64, bool, Instant)>> = Mutex::new(None);
+
+pub const fn is_supported() -> bool {
+    cfg!(windows)
+}
+
+/// Whether the focused window belongs to an elevated process while this one
+/// is not, so the clicks it gets are likely ignored.
+pub fn is_foreground_blocked() -> bool {
+    if !is_supported() {
+        return false;
+    }
+    let Some(window) = target::foreground() else {
+        return false;
+    };
+    let mut foreground = FOREGROUND.lock().unwrap();
+    if let Some((id, blocked, checked)) = *foreground {
+        if id == window.id && checked.elapsed() < CHECK_EVERY {
+            return blocked;
+        }
+    }
+    let blocked = !is_elevated() && is_window_elevated(window.id);
+    *foreground = Some((window.id, blocked, Instant::now()));
+    blocked
+}
+
+#[cfg(windows)]
+pub use windows::{is_elevated, is_window_elevated, relaunch_as_admin};
+
+/// Whether this process runs with administrator rights.
+#[cfg(not(windows))]
+pub fn is_elevated() -> bool {
+    false
+}
+
+/// Whether the process owning window `id` runs with administrator rights.
+#[cfg(not(windows))]
+pub fn is_window_elevated(_id: u64) -> bool {
+    false
+}
+
+/// Starts the app again as Administrator, after the UAC prompt, with the
+/// same arguments. Returns whether it started; the caller then exits.
+#[cfg(not(windows))]
+pub fn relaunch_as_admin() -> bool {
+    false
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::{ffi::c_void, iter, ptr};
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentProcess() -> isize;
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> isize;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn OpenProcessToken(process: isize, access: u32, token: *mut isize) -> i32;
+        fn GetTokenInformation(
+            token: isize,
+            class: u32,
+            info: *mut c_void,
+            length: u32,
+            returned: *mut u32,
+        ) -> i32;
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetWindowThreadProcessId(hwnd: isize, pid: *mut u32) -> u32;
+    }
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn ShellExecuteW(
+            hwnd: isize,
+            operation: *const u16,
+            file: *const u16,
+            parameters: *const u16,
+            directory: *const u16,
+            show: i32,
+        ) -> isize;
+    }
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const TOKEN_QUERY: u32 = 0x0008;
+    const TOKEN_ELEVATION: u32 = 20;
+    const SW_SHOWNORMAL: i32 = 1;
+
+    pub fn is_elevated() -> bool {
+        // SAFETY: the pseudo handle of the current process needs no
+        // closing.
+        token_elevation(unsafe { GetCurrentProcess() }).unwrap_or(false)
+    }
+
+    /// A process whose token cannot be read is taken to be elevated: a
+    /// normal process may only read the tokens of other normal ones.
+    pub fn is_window_elevated(id: u64) -> bool {
+        let mut pid = 0;
+        // SAFETY: the call validates the handle and writes to `pid`.
+        unsafe { GetWindowThreadProcessId(id as isize, &mut pid) };
+        if pid == 0 {
+            return false;
+        }
+        // SAFETY: the process handle is closed once read.
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process == 0 {
+                return true;
+            }
+            let elevated = token_elevation(process).unwrap_or(true);
+            CloseHandle(process);
+            elevated
+        }
+    }
+
+    fn token_elevation(process: isize) -> Option<bool> {
+        let mut token = 0;
+        let mut elevation = 0u32;
+        let mut returned = 0;
+        // SAFETY: `token` is closed once read; `elevation` is the
+        // TOKEN_ELEVATION struct, a single DWORD, of the size passed.
+        unsafe {
+            if OpenProcessToken(process, TOKEN_QUERY, &mut token) == 0 {
+                return None;
+            }
+            let read = GetTokenInformation(
+                token,
+                TOKEN_ELEVATION,
+                &mut elevation as *mut u32 as *mut c_void,
+                std::mem::size_of::<u32>() as u32,
+                &mut returned,
+            );
+            CloseHandle(token);
+            (read != 0).then_some(elevation != 0)
+        }
+    }
+
+    pub fn relaunch_as_admin() -> bool {
+        let Ok(exe) = std::env::current_exe() else {
+            return false;
+        };
+        let args = std::env::args()
+            .skip(1)
+            .map(|arg| format!("\"{}\"", arg.replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let wide = |s: &str| s.encode_utf16().chain(iter::once(0)).collect::<Vec<u16>>();
+        let (operation, file, parameters) =
+            (wide("runas"), wide(&exe.to_string_lossy()), wide(&args));
+        // SAFETY: the strings are NUL-terminated and outlive the call.
+        let result = unsafe {
+            ShellExecuteW(
+                0,
+                operation.as_ptr(),
+                file.as_ptr(),
+                parameters.as_ptr(),
+                ptr::null(),
+                SW_SHOWNORMAL,
+            )
+        };
+        // Values above 32 mean success; the user declining UAC gives 5.
+        result > 32
+    }
+}
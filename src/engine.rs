@@ -0,0 +1,550 @@
+use std::{
+    error::Error,
+    fmt, iter,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    clicker::{self, Command, Config},
+    monitor, supervisor, target, timeline,
+};
+
+// Every type the public API mentions, e.g. in `Config` and what
+// `subscribe` reports, re-exported for embedders since their own modules
+// are internal.
+pub use crate::{
+    burst::Burst,
+    drag::Drag,
+    humanize::{Humanize, Rng},
+    monitor::MonitorId,
+    path::{PathKind, PathPattern, PathPoints},
+    pattern::{GridCells, GridPattern},
+    pixel::{PixelCondition, PixelTrigger},
+    ramp::{Curve, Ramp},
+    region::Region,
+    reminder::Reminder,
+    rotation::{KeyRotation, KeyStep},
+    schedule::{StartMode, StartSchedule},
+    sequence::{ClickPoint, OnTimeout, SequenceLoop, StepAction},
+    template::{ImageMode, ImageTarget},
+    timeline::Activity,
+    typing::TypingText,
+};
+
+/// Whether a [`ClickerEngine`] is alive in this process.
+static SPAWNED: AtomicBool = AtomicBool::new(false);
+
+/// The clicking engine on its own thread, for programs that embed the auto
+/// clicker without its window.
+///
+/// ```no_run
+/// use auto_clicker::{
+///     clicker::Config,
+///     engine::{ClickInterval, ClickPosition, ClickerEngine, Origin},
+/// };
+///
+/// let engine = ClickerEngine::spawn().expect("no other engine is running");
+/// engine.configure(Config::ClickInterval(ClickInterval::from_millis(100)));
+/// engine.configure(Config::ClickPosition(ClickPosition::Custom {
+///     x: 200,
+///     y: 300,
+///     origin: Origin::Desktop,
+/// }));
+/// let events = engine.subscribe();
+/// engine.start();
+/// for activity in events.iter().take(10) {
+///     println!("{activity:?}");
+/// }
+/// engine.stop();
+/// ```
+///
+/// Dropping the engine stops it and waits for its thread to finish. If the
+/// clicking loop panics it is started again with the settings it was last
+/// sent. The engine's state is global, so only one can run per process at
+/// a time.
+pub struct ClickerEngine {
+    tx_command: Sender<Command>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ClickerEngine {
+    /// Starts the engine thread, stopped and with default settings. Fails
+    /// while another engine is alive; one can be spawned again once that
+    /// one is dropped.
+    pub fn spawn() -> Result<Self, AlreadySpawned> {
+        if SPAWNED.swap(true, Ordering::SeqCst) {
+            return Err(AlreadySpawned);
+        }
+        let (tx_command, rx_command) = mpsc::channel();
+        let thread = thread::spawn(move || supervisor::run(rx_command));
+        Ok(Self {
+            tx_command,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn start(&self) {
+        self.send(Command::Start);
+    }
+
+    pub fn stop(&self) {
+        self.send(Command::Stop);
+    }
+
+    pub fn toggle(&self) {
+        self.send(Command::Toggle);
+    }
+
+    /// Halts clicking but keeps the run's progress, see [`Command::Pause`].
+    pub fn pause(&self) {
+        self.send(Command::Pause);
+    }
+
+    pub fn resume(&self) {
+        self.send(Command::Resume);
+    }
+
+    /// Changes one setting; a running engine picks it up before its next
+    /// click.
+    pub fn configure(&self, config: Config) {
+        self.send(config.into());
+    }
+
+    /// Whether the engine is clicking or waiting for its start schedule.
+    /// Commands are handled on the engine thread, so this changes shortly
+    /// after [`ClickerEngine::start`] or [`ClickerEngine::stop`].
+    pub fn is_running(&self) -> bool {
+        clicker::is_running()
+    }
+
+    /// Returns a receiver for everything the engine does from now on: runs
+    /// starting and stopping, clicks, pauses, trigger checks and safety
+    /// stops.
+    pub fn subscribe(&self) -> Receiver<Activity> {
+        timeline::subscribe()
+    }
+
+    /// A sender for the engine's commands, e.g. to hand to a hotkey thread.
+    pub fn commands(&self) -> Sender<Command> {
+        self.tx_command.clone()
+    }
+
+    fn send(&self, command: Command) {
+        // The supervisor only exits once the engine is dropped.
+        if self.tx_command.send(command).is_err() {
+            log::warn!("The clicker engine is not running");
+        }
+    }
+}
+
+impl Drop for ClickerEngine {
+    fn drop(&mut self) {
+        self.tx_command.send(Command::Shutdown).ok();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+        SPAWNED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Returned by [`ClickerEngine::spawn`] while another engine is alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadySpawned;
+
+impl fmt::Display for AlreadySpawned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a clicker engine is already running in this process")
+    }
+}
+
+impl Error for AlreadySpawned {}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClickInterval {
+    pub hours: usize,
+    pub minutes: usize,
+    pub seconds: usize,
+    pub milliseconds: usize,
+    /// Hold the long-run rate at the interval: clicks that run late are
+    /// made up by the following ones starting sooner, up to
+    /// [`ClickInterval::MAX_DEBT`] behind, rather than the schedule
+    /// starting over once a click is an interval late.
+    pub adaptive: bool,
+    /// Spin through the end of each wait instead of sleeping and shorten
+    /// the pause between sent events, for intervals shorter than the OS
+    /// sleep granularity.
+    pub precise: bool,
+}
+
+impl ClickInterval {
+    /// How far an adaptive run can fall behind its schedule and still
+    /// catch up.
+    pub const MAX_DEBT: Duration = Duration::from_secs(1);
+
+    /// Splits a millisecond count into hours, minutes, seconds and milliseconds.
+    pub fn from_millis(total: usize) -> Self {
+        Self {
+            hours: total / 3_600_000,
+            minutes: total / 60_000 % 60,
+            seconds: total / 1000 % 60,
+            milliseconds: total % 1000,
+            adaptive: false,
+            precise: false,
+        }
+    }
+
+    pub fn total_millis(&self) -> usize {
+        self.milliseconds + self.seconds * 1000 + self.minutes * 60_000 + self.hours * 3_600_000
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum MouseButton {
+    #[default]
+    Left,
+    Middle,
+    Right,
+}
+
+impl FromStr for MouseButton {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" => Ok(Self::Left),
+            "middle" => Ok(Self::Middle),
+            "right" => Ok(Self::Right),
+            _ => Err(format!("unknown mouse button {s:?}")),
+        }
+    }
+}
+
+/// Mouse buttons successive clicks take in turn instead of the mouse
+/// button, e.g. left, right, left, right for a game that wants alternating
+/// actions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonRotation {
+    pub enabled: bool,
+    /// The buttons in order; only the first `len` are used.
+    pub buttons: [MouseButton; ButtonRotation::MAX_BUTTONS],
+    pub len: usize,
+}
+
+impl Default for ButtonRotation {
+    fn default() -> Self {
+        let mut buttons = [MouseButton::Left; Self::MAX_BUTTONS];
+        buttons[1] = MouseButton::Right;
+        Self {
+            enabled: false,
+            buttons,
+            len: 2,
+        }
+    }
+}
+
+impl ButtonRotation {
+    pub const MAX_BUTTONS: usize = 8;
+
+    /// The buttons in use, at least one.
+    pub fn buttons(&self) -> &[MouseButton] {
+        &self.buttons[..self.len.clamp(1, Self::MAX_BUTTONS)]
+    }
+
+    /// The button of the `n`th click of a run, counting from 0, or `None`
+    /// while the rotation is off.
+    pub fn button(&self, n: usize) -> Option<MouseButton> {
+        let buttons = self.buttons();
+        self.enabled.then(|| buttons[n % buttons.len()])
+    }
+
+    /// Takes the buttons of a list like `Left,Right`, as profiles store it.
+    /// Unknown names are skipped and the list is cut at
+    /// [`ButtonRotation::MAX_BUTTONS`].
+    pub fn set_buttons(&mut self, list: &str) {
+        let buttons: Vec<MouseButton> = list
+            .split(',')
+            .filter_map(|name| name.trim().parse().ok())
+            .take(Self::MAX_BUTTONS)
+            .collect();
+        if !buttons.is_empty() {
+            self.buttons[..buttons.len()].copy_from_slice(&buttons);
+            self.len = buttons.len();
+        }
+    }
+}
+
+/// Chances of the mouse buttons, so each click draws its button at random,
+/// e.g. mostly left with the odd right click.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonWeights {
+    pub enabled: bool,
+    /// Relative weights; they need not add up to 100.
+    pub left: usize,
+    pub middle: usize,
+    pub right: usize,
+}
+
+impl Default for ButtonWeights {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            left: 80,
+            middle: 5,
+            right: 15,
+        }
+    }
+}
+
+impl ButtonWeights {
+    pub fn weights(&self) -> [(MouseButton, usize); 3] {
+        [
+            (MouseButton::Left, self.left),
+            (MouseButton::Middle, self.middle),
+            (MouseButton::Right, self.right),
+        ]
+    }
+
+    /// The share of `weight` in all weights, in percent.
+    pub fn percent(&self, weight: usize) -> f64 {
+        let total = self.left + self.middle + self.right;
+        if total == 0 {
+            0.0
+        } else {
+            weight as f64 * 100.0 / total as f64
+        }
+    }
+
+    /// Draws the button of the next click, or `None` while off or with all
+    /// weights at 0.
+    pub fn sample(&self, rng: &mut Rng) -> Option<MouseButton> {
+        let total = (self.left + self.middle + self.right) as u64;
+        if !self.enabled || total == 0 {
+            return None;
+        }
+        let mut pick = rng.next_u64() % total;
+        self.weights().into_iter().find_map(|(button, weight)| {
+            if pick < weight as u64 {
+                Some(button)
+            } else {
+                pick -= weight as u64;
+                None
+            }
+        })
+    }
+}
+
+/// How many clicks are sent each time the clicker triggers.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ClickType {
+    #[default]
+    Single,
+    Double,
+    Triple,
+    /// Between 1 and [`ClickType::MAX_CLICKS`] clicks.
+    Custom(usize),
+}
+
+impl ClickType {
+    pub const MAX_CLICKS: usize = 10;
+
+    pub fn from_clicks(clicks: usize) -> Self {
+        match clicks {
+            1 => Self::Single,
+            2 => Self::Double,
+            3 => Self::Triple,
+            clicks => Self::Custom(clicks.clamp(1, Self::MAX_CLICKS)),
+        }
+    }
+
+    pub fn clicks(self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+            Self::Triple => 3,
+            Self::Custom(clicks) => clicks.clamp(1, Self::MAX_CLICKS),
+        }
+    }
+}
+
+impl fmt::Display for ClickType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Single => write!(f, "Single"),
+            Self::Double => write!(f, "Double"),
+            Self::Triple => write!(f, "Triple"),
+            Self::Custom(clicks) => write!(f, "Custom ({clicks})"),
+        }
+    }
+}
+
+impl FromStr for ClickType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "single" => Ok(Self::Single),
+            "double" => Ok(Self::Double),
+            "triple" => Ok(Self::Triple),
+            other => match other.parse() {
+                Ok(clicks @ 1..=Self::MAX_CLICKS) => Ok(Self::from_clicks(clicks)),
+                _ => Err(format!(
+                    "unknown click type {s:?}, expected single, double, triple or 1-{}",
+                    Self::MAX_CLICKS
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClickOptions {
+    pub mouse_button: MouseButton,
+    /// Buttons successive clicks take in turn instead of `mouse_button`,
+    /// when enabled.
+    pub button_rotation: ButtonRotation,
+    /// Chances each click draws its button by instead of `mouse_button`,
+    /// when enabled and no rotation is.
+    pub button_weights: ButtonWeights,
+    pub click_type: ClickType,
+    /// Extra pause between the clicks of a double, triple or custom click,
+    /// for apps that miss back-to-back presses.
+    pub burst_delay_ms: usize,
+    /// How long each click holds the button between press and release, for
+    /// apps that ignore instant clicks.
+    pub press_ms: usize,
+    /// Move the cursor a few pixels away and back right before each click,
+    /// for apps that ignore clicks without preceding motion.
+    pub wiggle: bool,
+    /// Press and hold the button instead of clicking.
+    pub hold_mode: bool,
+    /// How long to hold in hold mode; 0 holds until the clicker is stopped.
+    pub hold_ms: usize,
+    /// Pressed instead of the mouse button when set.
+    pub key: Option<rdev::Key>,
+    /// Timing and position noise, applied by the worker loop. Only the main
+    /// options' setting is used for sequences.
+    pub humanize: Humanize,
+    /// Screen pixel condition the worker loop waits for. Only the main
+    /// options' trigger is used for sequences.
+    pub pixel_trigger: PixelTrigger,
+    /// Drag between two points instead of clicking, when enabled.
+    pub drag: Drag,
+    /// Click in bursts instead of at the click interval, when enabled.
+    pub burst: Burst,
+    /// Change the interval over the run instead of keeping the click
+    /// interval, when enabled and no burst is.
+    pub ramp: Ramp,
+    /// Reminders that the run is still going.
+    pub reminder: Reminder,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum ClickPosition {
+    #[default]
+    CurrentCursorPosition,
+    /// `(x, y)` measured from `origin`. Either can be negative left of or
+    /// above it.
+    Custom { x: i32, y: i32, origin: Origin },
+    /// Each click goes to the next cell of the grid.
+    Grid(GridPattern),
+    /// Each click goes to the next point along the path.
+    Path(PathPattern),
+    /// `(x, y)` inside the client area of another application's window,
+    /// wherever that window is at the time of the click.
+    Window { id: u64, x: usize, y: usize },
+}
+
+/// What the numbers of a [`ClickPosition::Custom`] are measured from.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Origin {
+    /// The top-left corner of the primary screen, on the whole desktop.
+    #[default]
+    Desktop,
+    /// The top-left corner of a screen, wherever it is arranged at the
+    /// time of the click.
+    Monitor(MonitorId),
+    /// The top-left corner of the client area of the window that has the
+    /// focus.
+    ActiveWindow,
+}
+
+impl Origin {
+    /// `(x, y)` measured from this origin as global coordinates, with the
+    /// monitors and active window of now. Without the monitor or an active
+    /// window, the position is taken as global already.
+    pub fn to_global(self, x: i32, y: i32) -> (i32, i32) {
+        match self {
+            Origin::Desktop => (x, y),
+            Origin::Monitor(id) => monitor::to_global(Some(id), x, y),
+            Origin::ActiveWindow => match active_window_origin() {
+                Some((left, top)) => (left + x, top + y),
+                None => (x, y),
+            },
+        }
+    }
+
+    /// The global position `(x, y)` measured from this origin, the inverse
+    /// of [`Origin::to_global`].
+    pub fn to_local(self, x: i32, y: i32) -> (i32, i32) {
+        match self {
+            Origin::Desktop => (x, y),
+            Origin::Monitor(id) => monitor::to_local(Some(id), x, y),
+            Origin::ActiveWindow => match active_window_origin() {
+                Some((left, top)) => (x - left, y - top),
+                None => (x, y),
+            },
+        }
+    }
+}
+
+fn active_window_origin() -> Option<(i32, i32)> {
+    target::client_origin(target::foreground()?.id)
+}
+
+/// The screen positions successive clicks go to, see [`ClickPosition::targets`].
+pub type Targets = Box<dyn Iterator<Item = (i32, i32)> + Send>;
+
+impl ClickPosition {
+    /// The positions successive clicks go to, or `None` to click wherever
+    /// the cursor is. A window's targets end when the window closes.
+    pub fn targets(self) -> Option<Targets> {
+        match self {
+            ClickPosition::CurrentCursorPosition => None,
+            // Translated at every click, so it follows a monitor that moved
+            // or the window that is active then.
+            ClickPosition::Custom { x, y, origin } => {
+                Some(Box::new(iter::repeat_with(move || origin.to_global(x, y))))
+            }
+            ClickPosition::Grid(grid) => {
+                Some(Box::new(grid.cells().map(|(x, y)| (x as i32, y as i32))))
+            }
+            ClickPosition::Path(path) => {
+                Some(Box::new(path.points().map(|(x, y)| (x as i32, y as i32))))
+            }
+            ClickPosition::Window { id, x, y } => Some(Box::new(iter::from_fn(move || {
+                let (left, top) = target::client_origin(id)?;
+                Some((left + x as i32, top + y as i32))
+            }))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_one_engine_runs_at_a_time() {
+        let engine = ClickerEngine::spawn().unwrap();
+        assert_eq!(ClickerEngine::spawn().err(), Some(AlreadySpawned));
+        drop(engine);
+        let engine = ClickerEngine::spawn().unwrap();
+        assert!(!engine.is_running());
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+use crate::{
+    clicker::{self, Command},
+    jobs, listener, overlay, screen, script, source,
+    timeline::{self, Activity},
+};
+
+/// How close to a corner, in pixels, the cursor has to get.
+const CORNER_MARGIN: f64 = 2.0;
+
+/// A flick across this share of the screen width within [`FLICK_WINDOW`]
+/// also counts.
+const FLICK_SHARE: f64 = 0.5;
+const FLICK_WINDOW: Duration = Duration::from_millis(100);
+
+/// Used when the screen size cannot be queried; only the top-left corner is
+/// checked then.
+const FALLBACK_FLICK_DISTANCE: f64 = 1000.0;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Starts a thread that stops the clicker, jobs and scripts when the user
+/// slams the mouse into a screen corner or flicks it across the screen, for
+/// when runaway clicking makes the Stop button impossible to reach. Cursor
+/// moves the clicker made itself are ignored.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let bounds = screen::bounds();
+        let flick_distance = bounds.map_or(FALLBACK_FLICK_DISTANCE, |(_, _, width, _)| {
+            width as f64 * FLICK_SHARE
+        });
+        let mut recent: VecDeque<((f64, f64), Instant)> = VecDeque::new();
+
+        for event in rx_events {
+            let EventType::MouseMove { x, y } = event.event_type else {
+                continue;
+            };
+            if !is_enabled() || source::is_own_move(x, y) {
+                recent.clear();
+                continue;
+            }
+
+            let now = Instant::now();
+            recent.retain(|(_, at)| now.duration_since(*at) <= FLICK_WINDOW);
+            recent.push_back(((x, y), now));
+            let flicked = recent
+                .iter()
+                .any(|((px, py), _)| (x - px).hypot(y - py) >= flick_distance);
+
+            if !(in_corner(x, y, bounds) || flicked) {
+                continue;
+            }
+            recent.clear();
+            if clicker::is_running() || jobs::any_running() || script::any_running() {
+                log::info!("Fail-safe triggered, stopping the clicker");
+                tx_command.send(Command::Stop).ok();
+                jobs::stop_all();
+                script::stop_all();
+                overlay::flash_stop();
+                timeline::record(Activity::Safety);
+            }
+        }
+    });
+}
+
+fn in_corner(x: f64, y: f64, bounds: Option<(i32, i32, i32, i32)>) -> bool {
+    let near = |value: f64, edge: f64| (value - edge).abs() <= CORNER_MARGIN;
+    let Some((left, top, width, height)) = bounds else {
+        return near(x, 0.0) && near(y, 0.0);
+    };
+    let (left, top) = (left as f64, top as f64);
+    let (right, bottom) = (left + width as f64 - 1.0, top + height as f64 - 1.0);
+    (near(x, left) || near(x, right)) && (near(y, top) || near(y, bottom))
+}
@@ -0,0 +1,218 @@
+//! A C ABI over [`ClickerEngine`], declared in `include/auto_clicker.h`,
+//! for driving the engine from Python, Node and other languages with a
+//! foreign function interface.
+//!
+//! Every function takes the handle returned by [`ac_engine_new`] and does
+//! nothing, or returns a zero value, when given a null pointer.
+
+use std::{ptr, time::Duration};
+
+use crate::{
+    clicker::Config,
+    engine::{
+        ClickInterval, ClickOptions, ClickPosition, ClickType, ClickerEngine, MouseButton, Origin,
+    },
+    stats,
+};
+
+/// The engine with the click options changed so far, since the engine only
+/// takes all of them at once.
+pub struct Engine {
+    engine: ClickerEngine,
+    click_options: ClickOptions,
+}
+
+impl Engine {
+    fn update_options(&mut self, update: impl FnOnce(&mut ClickOptions)) {
+        update(&mut self.click_options);
+        self.engine
+            .configure(Config::ClickOptions(Box::new(self.click_options)));
+    }
+}
+
+/// Starts an engine, stopped and with default settings. Free it with
+/// [`ac_engine_free`]. Returns null while another engine is alive.
+#[no_mangle]
+pub extern "C" fn ac_engine_new() -> *mut Engine {
+    match ClickerEngine::spawn() {
+        Ok(engine) => Box::into_raw(Box::new(Engine {
+            engine,
+            click_options: ClickOptions::default(),
+        })),
+        Err(e) => {
+            log::error!("{e}");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Stops the engine and waits for its thread to finish.
+///
+/// # Safety
+///
+/// `engine` must come from [`ac_engine_new`] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_free(engine: *mut Engine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_start(engine: *const Engine) {
+    if let Some(engine) = engine.as_ref() {
+        engine.engine.start();
+    }
+}
+
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_stop(engine: *const Engine) {
+    if let Some(engine) = engine.as_ref() {
+        engine.engine.stop();
+    }
+}
+
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_toggle(engine: *const Engine) {
+    if let Some(engine) = engine.as_ref() {
+        engine.engine.toggle();
+    }
+}
+
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_is_running(engine: *const Engine) -> bool {
+    engine
+        .as_ref()
+        .is_some_and(|engine| engine.engine.is_running())
+}
+
+/// Clicks in the current or last run.
+///
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_clicks(engine: *const Engine) -> u64 {
+    match engine.as_ref() {
+        Some(_) => stats::current().clicks as u64,
+        None => 0,
+    }
+}
+
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_set_interval_ms(engine: *const Engine, interval_ms: u64) {
+    if let Some(engine) = engine.as_ref() {
+        engine
+            .engine
+            .configure(Config::ClickInterval(ClickInterval::from_millis(
+                interval_ms as usize,
+            )));
+    }
+}
+
+/// Stops runs after `limit_ms`, or never with 0.
+///
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_set_run_limit_ms(engine: *const Engine, limit_ms: u64) {
+    if let Some(engine) = engine.as_ref() {
+        let limit = (limit_ms > 0).then(|| Duration::from_millis(limit_ms));
+        engine.engine.configure(Config::RunLimit(limit));
+    }
+}
+
+/// Clicks at `(x, y)` on the whole desktop.
+///
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_set_position(engine: *const Engine, x: i32, y: i32) {
+    if let Some(engine) = engine.as_ref() {
+        engine
+            .engine
+            .configure(Config::ClickPosition(ClickPosition::Custom {
+                x,
+                y,
+                origin: Origin::Desktop,
+            }));
+    }
+}
+
+/// Clicks wherever the cursor is, the default.
+///
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_set_cursor_position(engine: *const Engine) {
+    if let Some(engine) = engine.as_ref() {
+        engine
+            .engine
+            .configure(Config::ClickPosition(ClickPosition::CurrentCursorPosition));
+    }
+}
+
+/// Sets the button from 0 (left), 1 (middle) or 2 (right). Returns `false`
+/// for any other value.
+///
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_set_button(engine: *mut Engine, button: u32) -> bool {
+    let mouse_button = match button {
+        0 => MouseButton::Left,
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        _ => return false,
+    };
+    match engine.as_mut() {
+        Some(engine) => {
+            engine.update_options(|options| options.mouse_button = mouse_button);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sets how many clicks each trigger sends, from 1 to 10. Returns `false`
+/// for other counts.
+///
+/// # Safety
+///
+/// `engine` must be null or come from [`ac_engine_new`] and not be freed.
+#[no_mangle]
+pub unsafe extern "C" fn ac_engine_set_clicks_per_trigger(
+    engine: *mut Engine,
+    clicks: u32,
+) -> bool {
+    let clicks = clicks as usize;
+    if !(1..=ClickType::MAX_CLICKS).contains(&clicks) {
+        return false;
+    }
+    match engine.as_mut() {
+        Some(engine) => {
+            engine.update_options(|options| options.click_type = ClickType::from_clicks(clicks));
+            true
+        }
+        None => false,
+    }
+}
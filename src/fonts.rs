@@ -0,0 +1,48 @@
+use std::{fs, io, path::Path};
+
+use egui::{FontData, FontDefinitions, FontFamily};
+
+const CUSTOM_FONT: &str = "custom";
+
+/// The default egui fonts, with the font at `path` (if any) appended as a
+/// fallback for both the proportional and monospace families so glyphs the
+/// built-in fonts lack are still rendered.
+pub fn font_definitions(path: Option<&Path>) -> FontDefinitions {
+    let mut fonts = FontDefinitions::default();
+
+    if let Some(path) = path {
+        match load_font(path) {
+            Ok(data) => {
+                fonts.font_data.insert(CUSTOM_FONT.to_owned(), data);
+                for family in [FontFamily::Proportional, FontFamily::Monospace] {
+                    fonts
+                        .families
+                        .entry(family)
+                        .or_default()
+                        .push(CUSTOM_FONT.to_owned());
+                }
+            }
+            Err(e) => log::error!("Could not load font {}: {e}", path.display()),
+        }
+    }
+
+    fonts
+}
+
+fn load_font(path: &Path) -> io::Result<FontData> {
+    let bytes = fs::read(path)?;
+    check_signature(&bytes)?;
+    Ok(FontData::from_owned(bytes))
+}
+
+/// egui panics on font data it cannot parse, so reject anything that does
+/// not start with a TrueType/OpenType signature.
+fn check_signature(bytes: &[u8]) -> io::Result<()> {
+    match bytes.get(..4) {
+        Some([0x00, 0x01, 0x00, 0x00]) | Some(b"OTTO") | Some(b"true") | Some(b"ttcf") => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a TTF/OTF font file",
+        )),
+    }
+}
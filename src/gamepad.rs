@@ -0,0 +1,98 @@
+use std::{
+    sync::{mpsc::Sender, Mutex},
+    thread,
+    time::Duration,
+};
+
+use gilrs::{Button, EventType, Gilrs};
+
+use crate::{
+    clicker::{self, Command},
+    jobs,
+    settings::GamepadSettings,
+};
+
+/// How often controllers are polled for button presses.
+const POLL: Duration = Duration::from_millis(10);
+
+/// The buttons that can be bound, with the names the settings store.
+pub const BUTTONS: [(Button, &str); 17] = [
+    (Button::South, "South"),
+    (Button::East, "East"),
+    (Button::North, "North"),
+    (Button::West, "West"),
+    (Button::LeftTrigger, "LB"),
+    (Button::LeftTrigger2, "LT"),
+    (Button::RightTrigger, "RB"),
+    (Button::RightTrigger2, "RT"),
+    (Button::Select, "Select"),
+    (Button::Start, "Start"),
+    (Button::Mode, "Mode"),
+    (Button::LeftThumb, "Left stick"),
+    (Button::RightThumb, "Right stick"),
+    (Button::DPadUp, "D-pad up"),
+    (Button::DPadDown, "D-pad down"),
+    (Button::DPadLeft, "D-pad left"),
+    (Button::DPadRight, "D-pad right"),
+];
+
+static SETTINGS: Mutex<Option<GamepadSettings>> = Mutex::new(None);
+
+pub fn configure(settings: &GamepadSettings) {
+    *SETTINGS.lock().unwrap() = Some(settings.clone());
+}
+
+/// Starts a thread that runs the clicker from the buttons of any connected
+/// game controller, bound with [`configure`], like the window keys F6 to
+/// F9. Controllers plugged in later are picked up as well.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(e) => {
+                log::warn!("Game controllers are not available: {e}");
+                return;
+            }
+        };
+        loop {
+            while let Some(event) = gilrs.next_event() {
+                let EventType::ButtonPressed(button, _) = event.event else {
+                    continue;
+                };
+                let Some(command) = command(button) else {
+                    continue;
+                };
+                if let Command::Stop = command {
+                    jobs::stop_all();
+                }
+                if tx_command.send(command).is_err() {
+                    return;
+                }
+            }
+            thread::sleep(POLL);
+        }
+    });
+}
+
+/// What a press of `button` does, if it is bound.
+fn command(button: Button) -> Option<Command> {
+    let (_, name) = BUTTONS.iter().find(|(b, _)| *b == button)?;
+    let settings = SETTINGS.lock().unwrap();
+    let settings = settings.as_ref()?;
+    let is = |bound: &Option<String>| bound.as_deref() == Some(*name);
+    if is(&settings.toggle) {
+        Some(Command::Toggle)
+    } else if is(&settings.start) {
+        Some(Command::Start)
+    } else if is(&settings.stop) {
+        Some(Command::Stop)
+    } else if is(&settings.pause) {
+        Some(if clicker::is_paused() {
+            Command::Resume
+        } else {
+            Command::Pause
+        })
+    } else {
+        None
+    }
+}
@@ -1,63 +1,228 @@
-use std::sync::{mpsc::Sender, Arc, Mutex};
+mod burst;
+mod drag;
+mod path;
+mod pattern;
+mod ramp;
+mod reminder;
+mod rotation;
+mod schedule;
+mod sequence;
+mod slots;
+mod typing;
 
-use egui::{self, DragValue, Response, Vec2};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::mpsc::{Receiver, Sender},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct ClickInterval {
-    pub hours: usize,
-    pub minutes: usize,
-    pub seconds: usize,
-    pub milliseconds: usize,
-}
+use egui::{
+    self,
+    plot::{HLine, Legend, Line, Plot},
+    DragValue, Response, Vec2,
+};
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub enum MouseButton {
-    #[default]
-    Left,
-    Middle,
-    Right,
-}
+use crate::{
+    accuracy,
+    activation::{self, Activation, Input, Trigger},
+    bundle, calibration,
+    changelog::{self, Link, Release},
+    cli::Args,
+    clicker::{self, Command, Config},
+    config, display, dnd, duration,
+    dwell::DwellClicker,
+    elevation,
+    engine::{
+        ButtonRotation, ClickInterval, ClickOptions, ClickPosition, ClickType, MouseButton, Origin,
+    },
+    failsafe, fonts, history, hotkeys, humanize,
+    i18n::{self, tr, tr_args, tr_n, Language},
+    idle, importer, ipc,
+    jobs::{self, Job},
+    keys, latency,
+    library::{self, MacroInfo},
+    listener, logging, monitor, overlay,
+    path::PathPattern,
+    pattern::GridPattern,
+    permission, pick,
+    pixel::PixelCondition,
+    pointer,
+    portable::{self, Portable},
+    presets::{self, IntervalPreset},
+    priority,
+    profile::Profile,
+    ratelimit,
+    recorder::{self, CoordinateSpace, Playback, Recorder, Simplify},
+    region,
+    rotation::KeyRotation,
+    sandbox::{self, Sandbox},
+    schedule::StartSchedule,
+    screen,
+    script::{self, ScriptRunner},
+    sequence::{ClickPoint, SequenceLoop},
+    settings::{Settings, MAX_UI_SCALE, MIN_UI_SCALE},
+    slots::Slot,
+    source::{self, BackendChoice},
+    stats::{self, RunRecord},
+    supervisor::{self, Failure},
+    takeover,
+    target::{self, WindowInfo},
+    taskbar,
+    template::{self, ImageMode, ImageTarget},
+    theme::{self, Appearance, Theme},
+    timeline::{self, Activity},
+    trace, turbo,
+    typing::TypingText,
+    vsync,
+    watch::{self, WatchAction},
+    wizard::{Setup, Wizard},
+};
+
+const HOUR: Duration = Duration::from_secs(3600);
+const MINUTE: Duration = Duration::from_secs(60);
+const SECOND: Duration = Duration::from_secs(1);
+const MILLISECOND: Duration = Duration::from_millis(1);
 
+/// How long the test click waits, so the cursor can be moved into place.
+const TEST_CLICK_DELAY: Duration = Duration::from_secs(2);
+
+/// Starting from the window with a shorter time between triggers, the CPS
+/// cap included, asks for confirmation first.
+const FAST_INTERVAL_MS: f64 = 10.0;
+
+/// How long a run lasts before the clicker stops itself.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub enum ClickType {
-    #[default]
-    Single,
-    Double,
+pub struct RunLimit {
+    pub enabled: bool,
+    pub hours: usize,
+    pub minutes: usize,
+    pub seconds: usize,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct ClickOptions {
-    pub mouse_button: MouseButton,
-    pub click_type: ClickType,
-}
+impl RunLimit {
+    pub fn from_duration(duration: Option<Duration>) -> Self {
+        let Some(duration) = duration else {
+            return Self::default();
+        };
+        let secs = duration.as_secs() as usize;
+        Self {
+            enabled: true,
+            hours: secs / 3600,
+            minutes: secs / 60 % 60,
+            seconds: secs % 60,
+        }
+    }
 
-#[derive(Debug, Default, PartialEq, Clone, Copy)]
-pub enum ClickPosition {
-    #[default]
-    CurrentCursorPosition,
-    Custom {
-        x: usize,
-        y: usize,
-    },
+    pub fn duration(&self) -> Option<Duration> {
+        self.enabled.then(|| {
+            Duration::from_secs((self.hours * 3600 + self.minutes * 60 + self.seconds) as u64)
+        })
+    }
 }
 
 pub struct MainApp {
     click_interval: ClickInterval,
-    tx_click_interval: Sender<ClickInterval>,
+    interval_text: String,
+    interval_text_invalid: bool,
+    /// Name to save the current interval as a preset under.
+    preset_name: String,
+    run_limit: RunLimit,
+    start_schedule: StartSchedule,
     click_options: ClickOptions,
-    tx_click_options: Sender<ClickOptions>,
+    capturing_key: bool,
+    test_click: Option<JoinHandle<()>>,
+    /// Input events while the pixel trigger's eyedropper is armed.
+    picking_pixel: Option<Receiver<rdev::Event>>,
+    /// Set while a run is paused because one of the app's dialogs is open.
+    dialog_paused: bool,
     click_position: ClickPosition,
-    tx_click_position: Sender<ClickPosition>,
-    is_running: Arc<Mutex<bool>>,
+    click_sequence: Vec<ClickPoint>,
+    sequence_loop: SequenceLoop,
+    show_sequence: bool,
+    sandbox: Sandbox,
+    show_sandbox: bool,
+    show_jobs: bool,
+    show_library: bool,
+    /// The saved recordings, as last listed.
+    library: Vec<MacroInfo>,
+    library_name: String,
+    /// The saved recording being renamed, with its new name as typed.
+    renaming_macro: Option<(String, String)>,
+    library_status: Option<String>,
+    /// The job whose position the next click on screen sets, with the
+    /// input events since.
+    picking_job: Option<(u64, Receiver<rdev::Event>)>,
+    /// Hotkeys of jobs as typed, by job id, until they parse.
+    job_hotkeys: HashMap<u64, String>,
+    script: String,
+    /// Values for the script's parameters, by name.
+    script_values: BTreeMap<String, String>,
+    script_runner: ScriptRunner,
+    show_script: bool,
+    target_windows: Vec<WindowInfo>,
+    typing_text: TypingText,
+    key_rotation: KeyRotation,
+    /// The key of the rotation waiting to be pressed, see
+    /// [`rotation::editor_ui`].
+    capturing_rotation_key: Option<usize>,
+    /// Title the focused window must contain for the clicker to click.
+    focus_guard: Option<String>,
+    /// The image to look for on screen, sent when applied.
+    image_target: Option<ImageTarget>,
+    image_path: String,
+    tx_command: Sender<Command>,
+    profiles: Vec<String>,
+    profile_name: String,
+    show_settings: bool,
+    themes: Vec<String>,
+    theme: Option<Theme>,
+    languages: Vec<Language>,
+    /// The UI scale on its slider, applied once it is let go.
+    ui_scale: f32,
+    system_visuals: egui::Visuals,
+    settings: Settings,
+    font_path: String,
+    saved_trace: Option<PathBuf>,
+    recorder: Recorder,
+    simplify: Simplify,
+    macro_import_path: String,
+    macro_import_status: Option<String>,
+    dwell: DwellClicker,
+    bundle_path: String,
+    config_path: String,
+    bundle_status: Option<String>,
+    /// Where "Export runs" writes the run statistics, see
+    /// [`stats::write_csv`].
+    runs_csv_path: String,
+    runs_csv_status: Option<String>,
+    show_history: bool,
+    show_help: bool,
+    /// The saved runs, as last loaded.
+    history: Vec<RunRecord>,
+    history_status: Option<String>,
+    /// The window is docked and collapsed to a bar, see
+    /// [`crate::dock::Dock`].
+    collapsed: bool,
+    /// Releases listed in the "What's new" window.
+    whats_new: Vec<Release>,
+    show_whats_new: bool,
+    /// The quick setup wizard, while it is open.
+    wizard: Option<Wizard>,
+    /// Set while a start waits for the very short interval to be confirmed.
+    confirm_start: bool,
+    fast_understood: bool,
+    /// Set while macOS withholds the Accessibility permission, see
+    /// [`permission`], until it is granted or the user goes on without it.
+    permission_missing: bool,
+    /// Set once the app should close, e.g. after relaunching elevated.
+    exit_requested: bool,
 }
 
 impl MainApp {
-    pub fn new(
-        is_running: Arc<Mutex<bool>>,
-        tx_click_interval: Sender<ClickInterval>,
-        tx_click_options: Sender<ClickOptions>,
-        tx_click_position: Sender<ClickPosition>,
-    ) -> Self {
+    pub fn new(tx_command: Sender<Command>, settings: Settings) -> Self {
         let click_interval = ClickInterval::default();
         let click_options = ClickOptions::default();
         let click_position = ClickPosition::default();
@@ -66,181 +231,4501 @@ impl MainApp {
         // Restore app state using cc.storage (requires the "persistence" feature).
         // Use the cc.gl (a glow::Context) to create graphics shaders and buffers that you can use
         // for e.g. egui::PaintCallback.
-        Self {
+        let mut app = Self {
             click_interval,
-            tx_click_interval,
+            interval_text: String::new(),
+            interval_text_invalid: false,
+            preset_name: String::new(),
+            run_limit: RunLimit::default(),
+            start_schedule: StartSchedule::default(),
             click_options,
-            tx_click_options,
+            capturing_key: false,
+            test_click: None,
+            picking_pixel: None,
+            dialog_paused: false,
             click_position,
-            tx_click_position,
-            is_running,
+            click_sequence: Vec::new(),
+            sequence_loop: SequenceLoop::default(),
+            show_sequence: false,
+            sandbox: Sandbox::default(),
+            show_sandbox: false,
+            show_jobs: false,
+            show_library: false,
+            library: Vec::new(),
+            library_name: String::new(),
+            renaming_macro: None,
+            library_status: None,
+            picking_job: None,
+            job_hotkeys: HashMap::new(),
+            script: script::load().unwrap_or_else(|| script::EXAMPLE.to_owned()),
+            script_values: BTreeMap::new(),
+            script_runner: ScriptRunner::default(),
+            show_script: false,
+            target_windows: Vec::new(),
+            typing_text: TypingText::default(),
+            key_rotation: KeyRotation::default(),
+            capturing_rotation_key: None,
+            focus_guard: None,
+            image_target: None,
+            image_path: String::new(),
+            tx_command,
+            profiles: Profile::list(),
+            profile_name: String::new(),
+            show_settings: false,
+            themes: Theme::list(),
+            languages: i18n::languages(),
+            ui_scale: settings.ui_scale,
+            theme: settings
+                .theme
+                .as_deref()
+                .and_then(|name| match Theme::load(name) {
+                    Ok(theme) => Some(theme),
+                    Err(e) => {
+                        log::error!("Could not load theme {name:?}: {e}");
+                        None
+                    }
+                }),
+            system_visuals: egui::Visuals::default(),
+            font_path: settings
+                .font_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+            saved_trace: None,
+            recorder: Recorder::default(),
+            simplify: Simplify::default(),
+            macro_import_path: String::new(),
+            macro_import_status: None,
+            dwell: DwellClicker::default(),
+            bundle_path: String::new(),
+            config_path: String::new(),
+            bundle_status: None,
+            runs_csv_path: String::new(),
+            runs_csv_status: None,
+            show_history: false,
+            show_help: false,
+            history: Vec::new(),
+            history_status: None,
+            collapsed: false,
+            whats_new: Vec::new(),
+            show_whats_new: false,
+            wizard: None,
+            confirm_start: false,
+            fast_understood: false,
+            permission_missing: !permission::is_granted(),
+            exit_requested: false,
+            settings,
+        };
+        app.apply_settings();
+        app.note_version();
+        if let Some(profile) = Profile::load_last() {
+            app.apply_profile(profile);
         }
+        app
     }
-}
 
-impl MainApp {
-    pub fn update(&mut self, ctx: &egui::Context) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.group(|ui| {
-                ui.heading("Click Interval");
-                ui.horizontal(|ui| {
-                    if ui
-                        .add(egui::DragValue::new(&mut self.click_interval.hours))
-                        .changed()
-                    {
-                        self.tx_click_interval.send(self.click_interval).unwrap();
+    /// Takes over the click options given on the command line; the ones
+    /// left at their default keep the restored values.
+    pub fn apply_args(&mut self, args: &Args) {
+        let defaults = Args::default();
+        if (args.interval, args.adaptive, args.precise)
+            != (defaults.interval, defaults.adaptive, defaults.precise)
+        {
+            self.click_interval = ClickInterval {
+                adaptive: args.adaptive,
+                precise: args.precise,
+                ..ClickInterval::from_millis(args.interval.as_millis() as usize)
+            };
+        }
+        if args.click_options != defaults.click_options {
+            self.click_options = args.click_options;
+        }
+        if args.click_position != defaults.click_position {
+            self.click_position = args.click_position;
+        }
+        if args.run_for.is_some() {
+            self.run_limit = RunLimit::from_duration(args.run_for);
+        }
+        if args.start_schedule != defaults.start_schedule {
+            self.start_schedule = args.start_schedule;
+        }
+        if args.focus_guard.is_some() {
+            self.focus_guard = args.focus_guard.clone();
+        }
+
+        let configs = [
+            Config::ClickInterval(self.click_interval),
+            Config::ClickOptions(Box::new(self.click_options)),
+            Config::ClickPosition(self.click_position),
+            Config::RunLimit(self.run_limit.duration()),
+            Config::StartSchedule(self.start_schedule),
+            Config::FocusGuard(self.focus_guard.clone()),
+        ];
+        for config in configs {
+            send_command(&self.tx_command, config.into());
+        }
+    }
+
+    /// Saves what should survive a restart: the click settings and where
+    /// the window is and how large.
+    pub fn save_on_exit(
+        &mut self,
+        window_position: Option<(i32, i32)>,
+        window_size: Option<(u32, u32)>,
+    ) {
+        if let Err(e) = self.current_profile().save_last() {
+            log::error!("Could not save the click settings: {e}");
+        }
+        if window_position.is_some() {
+            self.settings.window_position = window_position;
+        }
+        if window_size.is_some() {
+            self.settings.window_size = window_size;
+        }
+        if let Err(e) = self.settings.save() {
+            log::error!("Could not save settings: {e}");
+        }
+    }
+
+    /// Shows what is new after an update, and remembers the running version.
+    /// A first start opens the quick setup wizard instead.
+    fn note_version(&mut self) {
+        let version = env!("CARGO_PKG_VERSION");
+        if self.settings.last_seen_version.as_deref() == Some(version) {
+            return;
+        }
+        match &self.settings.last_seen_version {
+            Some(last_seen) => {
+                self.whats_new = changelog::since(last_seen);
+                self.show_whats_new = !self.whats_new.is_empty();
+            }
+            None => self.wizard = Some(Wizard::default()),
+        }
+        self.settings.last_seen_version = Some(version.to_owned());
+        if let Err(e) = self.settings.save() {
+            log::error!("Could not save settings: {e}");
+        }
+    }
+
+    /// Hands the settings that take effect outside the GUI to the modules
+    /// they configure.
+    fn apply_settings(&mut self) {
+        self.dwell.set_dwell_ms(self.settings.dwell_ms);
+        self.dwell.set_enabled(self.settings.dwell_enabled);
+        overlay::set_enabled(self.settings.click_visualizer);
+        overlay::set_halo_enabled(self.settings.cursor_halo);
+        priority::set_high_priority(self.settings.high_priority);
+        clicker::set_event_gap(Duration::from_millis(self.settings.event_gap_ms as u64));
+        turbo::set_enabled(self.settings.turbo);
+        vsync::set_enabled(self.settings.frame_perfect);
+        pointer::set_enabled(self.settings.second_pointer);
+        jobs::set_jobs(&self.settings.jobs);
+        source::set_choice(self.settings.input_backend);
+        activation::set_trigger(Activation::Hold, self.settings.hold_to_click);
+        activation::set_trigger(Activation::Toggle, self.settings.toggle_trigger);
+        failsafe::set_enabled(self.settings.failsafe);
+        takeover::set_enabled(self.settings.auto_pause);
+        takeover::set_grace(Duration::from_millis(self.settings.auto_pause_ms as u64));
+        idle::set_enabled(self.settings.idle_start);
+        idle::set_idle_time(Duration::from_secs(self.settings.idle_start_secs as u64));
+        #[cfg(feature = "speech")]
+        crate::speech::set_enabled(self.settings.speech);
+        #[cfg(feature = "sound")]
+        crate::sound::configure(&self.settings.sound);
+        #[cfg(feature = "gamepad")]
+        crate::gamepad::configure(&self.settings.gamepad);
+        #[cfg(feature = "notifications")]
+        crate::notification::set_enabled(self.settings.notifications);
+        self.recorder.set_filter(self.settings.record_filter);
+        self.recorder.set_relative(self.settings.record_relative);
+        recorder::set_playback(self.settings.playback);
+        source::set_tag(self.settings.event_tag);
+        dnd::set_respected(self.settings.respect_do_not_disturb);
+        humanize::set_seed(self.settings.random_seed);
+        ratelimit::set_max_cps(self.settings.max_cps);
+        clicker::set_wake_offset(Duration::from_micros(self.settings.wake_offset_us as u64));
+        crate::slots::set_slots(&self.settings.slots);
+        watch::set_watch(&self.settings.watch);
+        i18n::set_language(self.settings.language.as_deref());
+    }
+
+    /// Export and import of the whole app state as a single file.
+    fn bundle_ui(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let rtl = self.settings.is_right_to_left();
+        row(ui, rtl, |ui| {
+            let label = ui.label(tr("Backup"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.bundle_path)
+                    .hint_text(tr("Path to a bundle file"))
+                    .desired_width(160.0),
+            )
+            .labelled_by(label.id);
+            let path = PathBuf::from(self.bundle_path.trim());
+            let has_path = !self.bundle_path.trim().is_empty();
+
+            if ui
+                .add_enabled(has_path, egui::Button::new(tr("Export everything")))
+                .on_hover_text(tr(
+                    "Settings, profiles, themes, the current click settings, the macro \
+                     recording and the last run's figures",
+                ))
+                .clicked()
+            {
+                let result =
+                    bundle::export(&path, &self.current_profile(), &self.recorder.recording());
+                self.bundle_status = Some(match result {
+                    Ok(()) => tr_args("Exported to {path}", &[("path", &path.display())]),
+                    Err(e) => tr_args("Could not export: {error}", &[("error", &e)]),
+                });
+            }
+
+            if ui
+                .add_enabled(has_path, egui::Button::new(tr("Import")))
+                .clicked()
+            {
+                self.bundle_status = Some(match bundle::import(&path) {
+                    Ok(imported) => {
+                        self.settings = Settings::load();
+                        self.apply_settings();
+                        self.font_path = self
+                            .settings
+                            .font_path
+                            .as_ref()
+                            .map(|path| path.display().to_string())
+                            .unwrap_or_default();
+                        ctx.set_fonts(fonts::font_definitions(self.settings.font_path.as_deref()));
+                        self.profiles = Profile::list();
+                        self.themes = Theme::list();
+                        if let Some(profile) = imported.current {
+                            self.apply_profile(profile);
+                        }
+                        if let Some(recording) = imported.recording {
+                            self.recorder.set_recording(recording);
+                        }
+                        tr_args("Imported {path}", &[("path", &path.display())])
+                    }
+                    Err(e) => tr_args("Could not import: {error}", &[("error", &e)]),
+                });
+            }
+        });
+        row(ui, rtl, |ui| {
+            let label = ui.label(tr("Config"));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.config_path)
+                    .hint_text(tr("Path to a JSON file"))
+                    .desired_width(160.0),
+            )
+            .labelled_by(label.id);
+            let path = PathBuf::from(self.config_path.trim());
+            let has_path = !self.config_path.trim().is_empty();
+
+            if ui
+                .add_enabled(has_path, egui::Button::new(tr("Export config")))
+                .on_hover_text(tr(
+                    "The click settings, the click sequence and the settings with their \
+                     hotkeys, as versioned JSON to share or keep in dotfiles",
+                ))
+                .clicked()
+            {
+                let config = Portable {
+                    profile: self.current_profile(),
+                    sequence: self.click_sequence.clone(),
+                    sequence_loop: self.sequence_loop,
+                    settings: self.settings.clone(),
+                };
+                self.bundle_status = Some(match portable::export(&path, &config) {
+                    Ok(()) => tr_args("Exported to {path}", &[("path", &path.display())]),
+                    Err(e) => tr_args("Could not export: {error}", &[("error", &e)]),
+                });
+            }
+
+            if ui
+                .add_enabled(has_path, egui::Button::new(tr("Import config")))
+                .clicked()
+            {
+                self.bundle_status = Some(match portable::import(&path, &self.settings) {
+                    Ok(config) => {
+                        self.settings = config.settings;
+                        self.apply_settings();
+                        if let Err(e) = self.settings.save() {
+                            log::error!("Could not save settings: {e}");
+                        }
+                        self.apply_profile(config.profile);
+                        self.click_sequence = config.sequence;
+                        self.sequence_loop = config.sequence_loop;
+                        send_command(
+                            &self.tx_command,
+                            Config::ClickSequence(self.click_sequence.clone()).into(),
+                        );
+                        send_command(
+                            &self.tx_command,
+                            Config::SequenceLoop(self.sequence_loop).into(),
+                        );
+                        tr_args("Imported {path}", &[("path", &path.display())])
+                    }
+                    Err(e) => tr_args("Could not import: {error}", &[("error", &e)]),
+                });
+            }
+        });
+        if let Some(status) = &self.bundle_status {
+            ui.label(status);
+        }
+    }
+
+    fn recorder_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.heading(tr("Macro"));
+            row(ui, rtl, |ui| {
+                let idle = !self.recorder.is_recording() && !self.recorder.is_playing();
+
+                if self.recorder.is_recording() {
+                    if ui.button(tr("Stop Recording")).clicked() {
+                        self.recorder.stop_recording();
+                    }
+                } else if ui
+                    .add_enabled(idle, egui::Button::new(tr("Record")))
+                    .clicked()
+                {
+                    self.recorder.start_recording();
+                }
+
+                if self.recorder.is_playing() {
+                    if ui.button(tr("Stop")).clicked() {
+                        self.recorder.stop_playing();
+                    }
+                } else if ui
+                    .add_enabled(
+                        idle && !self.recorder.is_empty(),
+                        egui::Button::new(tr("Play")),
+                    )
+                    .clicked()
+                {
+                    self.recorder.play();
+                }
+
+                ui.label(tr_args(
+                    "{events} events",
+                    &[("events", &self.recorder.len())],
+                ));
+
+                if ui.button(tr("Library")).clicked() {
+                    self.show_library = !self.show_library;
+                    if self.show_library {
+                        self.library = library::list();
+                    }
+                }
+
+                if ui.button(tr("Script")).clicked() {
+                    self.show_script = !self.show_script;
+                }
+
+                let jobs_label = tr_args("Jobs ({jobs})", &[("jobs", &self.settings.jobs.len())]);
+                if ui
+                    .button(jobs_label)
+                    .on_hover_text(tr("Clickers of their own that run besides this one"))
+                    .clicked()
+                {
+                    self.show_jobs = !self.show_jobs;
+                }
+            });
+
+            let playback = &mut self.settings.playback;
+            let mut playback_changed = false;
+            row(ui, rtl, |ui| {
+                let label = ui.label(tr("Speed:"));
+                playback_changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut playback.speed)
+                            .clamp_range(Playback::MIN_SPEED..=Playback::MAX_SPEED)
+                            .speed(0.05)
+                            .max_decimals(2)
+                            .suffix("×"),
+                    )
+                    .labelled_by(label.id)
+                    .changed();
+                let label = ui.label(tr("Delays from"));
+                playback_changed |= ui
+                    .add(egui::DragValue::new(&mut playback.min_delay_ms).suffix(" ms"))
+                    .labelled_by(label.id)
+                    .changed();
+                let label = ui.label(tr("to"));
+                playback_changed |= ui
+                    .add(egui::DragValue::new(&mut playback.max_delay_ms).suffix(" ms"))
+                    .labelled_by(label.id)
+                    .on_hover_text(tr("0 for no limit"))
+                    .changed();
+            });
+            if playback_changed {
+                recorder::set_playback(*playback);
+                if let Err(e) = self.settings.save() {
+                    log::error!("Could not save settings: {e}");
+                }
+            }
+
+            row(ui, rtl, |ui| {
+                let idle = !self.recorder.is_recording() && !self.recorder.is_playing();
+                if ui
+                    .add_enabled(
+                        idle && !self.recorder.is_empty(),
+                        egui::Button::new(tr("Simplify")),
+                    )
+                    .on_hover_text(tr(
+                        "Thin out mouse paths, merge repeated moves and round timings.",
+                    ))
+                    .clicked()
+                {
+                    self.recorder.simplify(self.simplify);
+                }
+                let label = ui.label(tr("Tolerance:"));
+                ui.add(
+                    egui::DragValue::new(&mut self.simplify.tolerance)
+                        .clamp_range(0.0..=50.0)
+                        .speed(0.1)
+                        .suffix(" px"),
+                )
+                .labelled_by(label.id);
+                let label = ui.label(tr("Round to:"));
+                ui.add(
+                    egui::DragValue::new(&mut self.simplify.round_ms)
+                        .clamp_range(0..=1000)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id);
+            });
+
+            row(ui, rtl, |ui| {
+                let idle = !self.recorder.is_recording() && !self.recorder.is_playing();
+                let label = ui.label(tr("From"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.macro_import_path)
+                        .hint_text(tr("Path to a .ahk or TinyTask .rec file"))
+                        .desired_width(160.0),
+                )
+                .labelled_by(label.id);
+                let path = PathBuf::from(self.macro_import_path.trim());
+                let has_path = !self.macro_import_path.trim().is_empty();
+                if ui
+                    .add_enabled(idle && has_path, egui::Button::new(tr("Import")))
+                    .on_hover_text(tr(
+                        "Replaces the recording with an AutoHotkey script's Click, MouseMove, \
+                         Sleep and Send commands, or a TinyTask recording",
+                    ))
+                    .clicked()
+                {
+                    self.macro_import_status = Some(match importer::import(&path) {
+                        Ok(imported) => {
+                            let events = imported.recording.events.len();
+                            self.recorder.set_recording(imported.recording);
+                            match imported.skipped.len() {
+                                0 => tr_args("Imported {events} events", &[("events", &events)]),
+                                skipped => tr_args(
+                                    "Imported {events} events, skipped {skipped}: {lines}",
+                                    &[
+                                        ("events", &events),
+                                        ("skipped", &skipped),
+                                        ("lines", &imported.skipped.join("; ")),
+                                    ],
+                                ),
+                            }
+                        }
+                        Err(e) => tr_args("Could not import: {error}", &[("error", &e)]),
+                    });
+                }
+            });
+            if let Some(status) = &self.macro_import_status {
+                ui.label(status);
+            }
+
+            let filter = &mut self.settings.record_filter;
+            let mut changed = false;
+            row(ui, rtl, |ui| {
+                changed |= ui
+                    .checkbox(&mut filter.ignore_moves, tr("Clicks and keys only"))
+                    .on_hover_text(tr("Leave mouse movement out of the recording."))
+                    .changed();
+                ui.add_enabled_ui(!filter.ignore_moves, |ui| {
+                    let label = ui.label(tr("Move grid:"));
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut filter.move_grid)
+                                .clamp_range(0..=100)
+                                .suffix(" px"),
+                        )
+                        .on_hover_text(tr(
+                            "Snap moves to a grid and keep one per cell. 0 keeps every move.",
+                        ))
+                        .labelled_by(label.id);
+                    changed |= response.changed();
+                });
+            });
+            changed |= ui
+                .checkbox(
+                    &mut filter.ignore_own_window,
+                    tr("Ignore input on this window"),
+                )
+                .changed();
+            row(ui, rtl, |ui| {
+                if ui
+                    .checkbox(
+                        &mut self.settings.record_relative,
+                        tr("Relative to the focused window"),
+                    )
+                    .on_hover_text(tr(
+                        "Record positions from the corner of the window you work in, so the \
+                         macro still fits after the window moves.",
+                    ))
+                    .changed()
+                {
+                    self.recorder.set_relative(self.settings.record_relative);
+                    changed = true;
+                }
+                if let CoordinateSpace::Window(title) = self.recorder.space() {
+                    ui.label(tr_args("In {title}", &[("title", &format!("{title:?}"))]));
+                }
+            });
+            if changed {
+                self.recorder.set_filter(self.settings.record_filter);
+                if let Err(e) = self.settings.save() {
+                    log::error!("Could not save settings: {e}");
+                }
+            }
+        });
+    }
+
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Called with the visuals matching the OS light/dark setting. They are
+    /// only applied while the appearance follows the system and no custom
+    /// theme sets its base.
+    pub fn set_system_visuals(&mut self, ctx: &egui::Context, visuals: egui::Visuals) {
+        self.system_visuals = visuals;
+        self.apply_style(ctx);
+    }
+
+    fn apply_style(&self, ctx: &egui::Context) {
+        let style = egui::Style {
+            visuals: self.settings.appearance.visuals(&self.system_visuals),
+            ..Default::default()
+        };
+
+        let mut style = match &self.theme {
+            Some(theme) => theme.style(&style),
+            None => style,
+        };
+        if let Some([r, g, b]) = self.settings.accent {
+            theme::set_accent(&mut style.visuals, egui::Color32::from_rgb(r, g, b));
+        }
+        ctx.set_style(style);
+    }
+
+    fn select_theme(&mut self, ctx: &egui::Context, name: Option<&str>) {
+        self.theme = match name {
+            Some(name) => match Theme::load(name) {
+                Ok(theme) => Some(theme),
+                Err(e) => {
+                    log::error!("Could not load theme {name:?}: {e}");
+                    return;
+                }
+            },
+            None => None,
+        };
+        self.apply_style(ctx);
+
+        self.settings.theme = name.map(str::to_owned);
+        if let Err(e) = self.settings.save() {
+            log::error!("Could not save settings: {e}");
+        }
+    }
+
+    fn settings_ui(&mut self, ctx: &egui::Context) {
+        let rtl = self.settings.is_right_to_left();
+        let mut show_settings = self.show_settings;
+        egui::Window::new(tr("Settings"))
+            .open(&mut show_settings)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                row(ui, rtl, |ui| {
+                    let current = self.theme.as_ref().map(|theme| theme.name.clone());
+                    let mut selected = current.clone();
+                    egui::ComboBox::from_label(tr("Theme"))
+                        .selected_text(selected.clone().map_or_else(|| tr("System"), Cow::from))
+                        .show_ui(ui, |ui| {
+                            ui.style_mut().wrap = Some(false);
+                            ui.set_min_width(60.0);
+                            ui.selectable_value(&mut selected, None, tr("System"));
+                            for name in &self.themes {
+                                ui.selectable_value(&mut selected, Some(name.clone()), name);
+                            }
+                        });
+                    if selected != current {
+                        self.select_theme(ctx, selected.as_deref());
+                    }
+
+                    if ui.button(tr("Rescan")).clicked() {
+                        self.themes = Theme::list();
+                    }
+                });
+
+                row(ui, rtl, |ui| {
+                    let mut style_changed = false;
+                    let appearance = &mut self.settings.appearance;
+                    egui::ComboBox::from_label(tr("Appearance"))
+                        .selected_text(match appearance {
+                            Appearance::System => tr("System"),
+                            Appearance::Light => tr("Light"),
+                            Appearance::Dark => tr("Dark"),
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.style_mut().wrap = Some(false);
+                            ui.set_min_width(60.0);
+                            for (value, name) in [
+                                (Appearance::System, tr("System")),
+                                (Appearance::Light, tr("Light")),
+                                (Appearance::Dark, tr("Dark")),
+                            ] {
+                                style_changed |=
+                                    ui.selectable_value(appearance, value, name).changed();
+                            }
+                        });
+
+                    let mut custom_accent = self.settings.accent.is_some();
+                    if ui.checkbox(&mut custom_accent, tr("Accent")).changed() {
+                        self.settings.accent = custom_accent.then(|| {
+                            let color = ctx.style().visuals.selection.bg_fill;
+                            [color.r(), color.g(), color.b()]
+                        });
+                        style_changed = true;
+                    }
+                    if let Some(accent) = &mut self.settings.accent {
+                        let response = ui.color_edit_button_srgb(accent);
+                        style_changed |=
+                            accessible_name(ui, response, &tr("Accent color")).changed();
+                    }
+
+                    if style_changed {
+                        self.apply_style(ctx);
+                        if let Err(e) = self.settings.save() {
+                            log::error!("Could not save settings: {e}");
+                        }
+                    }
+                });
+
+                row(ui, rtl, |ui| {
+                    let current = self.settings.language.clone();
+                    let mut selected = current.clone();
+                    let name = |code: &str| {
+                        self.languages
+                            .iter()
+                            .find(|language| language.code == code)
+                            .map_or_else(|| code.to_owned(), |language| language.name.clone())
                     };
-                    ui.label("Hours");
+                    egui::ComboBox::from_label(tr("Language"))
+                        .selected_text(
+                            selected
+                                .as_deref()
+                                .map_or_else(|| tr("System"), |code| name(code).into()),
+                        )
+                        .show_ui(ui, |ui| {
+                            ui.style_mut().wrap = Some(false);
+                            ui.set_min_width(60.0);
+                            ui.selectable_value(&mut selected, None, tr("System"));
+                            for language in &self.languages {
+                                ui.selectable_value(
+                                    &mut selected,
+                                    Some(language.code.clone()),
+                                    &language.name,
+                                );
+                            }
+                        });
+                    if selected != current {
+                        self.settings.language = selected;
+                        i18n::set_language(self.settings.language.as_deref());
+                        if let Err(e) = self.settings.save() {
+                            log::error!("Could not save settings: {e}");
+                        }
+                    }
+                });
+
+                row(ui, rtl, |ui| {
+                    let label = ui.label(tr("UI scale"));
+                    let response = ui
+                        .add(
+                            egui::Slider::new(&mut self.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE)
+                                .step_by(0.1)
+                                .custom_formatter(|scale, _| format!("{:.0}%", scale * 100.0)),
+                        )
+                        .labelled_by(label.id);
+                    // The window resizes along, which would pull the slider
+                    // away from the cursor while dragging.
+                    let mut apply =
+                        response.drag_released() || response.changed() && !response.dragged();
+                    if ui.button("100%").clicked() {
+                        self.ui_scale = 1.0;
+                        apply = true;
+                    }
+                    if apply && self.ui_scale != self.settings.ui_scale {
+                        self.settings.ui_scale = self.ui_scale;
+                        if let Err(e) = self.settings.save() {
+                            log::error!("Could not save settings: {e}");
+                        }
+                    }
+                });
+
+                row(ui, rtl, |ui| {
+                    let label = ui.label(tr("Font"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.font_path)
+                            .hint_text(tr("Path to a TTF/OTF file"))
+                            .desired_width(200.0),
+                    )
+                    .labelled_by(label.id);
+                    if ui.button(tr("Apply")).clicked() {
+                        let path = self.font_path.trim();
+                        self.settings.font_path = (!path.is_empty()).then(|| path.into());
+                        ctx.set_fonts(fonts::font_definitions(self.settings.font_path.as_deref()));
+                        if let Err(e) = self.settings.save() {
+                            log::error!("Could not save settings: {e}");
+                        }
+                    }
+                });
+
+                let mut changed = false;
+                row(ui, rtl, |ui| {
                     if ui
-                        .add(egui::DragValue::new(&mut self.click_interval.minutes))
+                        .checkbox(&mut self.settings.dwell_enabled, tr("Dwell click after"))
                         .changed()
                     {
-                        self.tx_click_interval.send(self.click_interval).unwrap();
-                    };
-                    ui.label("Minutes");
+                        self.dwell.set_enabled(self.settings.dwell_enabled);
+                        changed = true;
+                    }
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.settings.dwell_ms)
+                            .clamp_range(100..=10_000)
+                            .suffix(" ms"),
+                    );
+                    if accessible_name(ui, response, &tr("Dwell time")).changed() {
+                        self.dwell.set_dwell_ms(self.settings.dwell_ms);
+                        changed = true;
+                    }
+                    ui.label(tr("of holding still"));
+                });
+
+                if ui
+                    .checkbox(
+                        &mut self.settings.click_visualizer,
+                        tr("Show a marker where clicks land"),
+                    )
+                    .changed()
+                {
+                    overlay::set_enabled(self.settings.click_visualizer);
+                    changed = true;
+                }
+                if ui
+                    .checkbox(
+                        &mut self.settings.cursor_halo,
+                        tr("Highlight the cursor while clicking"),
+                    )
+                    .changed()
+                {
+                    overlay::set_halo_enabled(self.settings.cursor_halo);
+                    changed = true;
+                }
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.target_crosshair,
+                        tr("Mark the custom click position on screen"),
+                    )
+                    .changed();
+                if ui
+                    .checkbox(
+                        &mut self.settings.respect_do_not_disturb,
+                        tr("Quiet while the system is in do-not-disturb mode"),
+                    )
+                    .on_hover_text(tr(
+                        "Holds the notifications, beeps and announcements of runs back \
+                         during Focus Assist, a Focus or Do Not Disturb. Clicking goes on.",
+                    ))
+                    .changed()
+                {
+                    dnd::set_respected(self.settings.respect_do_not_disturb);
+                    changed = true;
+                }
+                if ui
+                    .checkbox(
+                        &mut self.settings.failsafe,
+                        tr("Stop when the mouse is slammed into a corner"),
+                    )
+                    .on_hover_text(tr("Also when it is flicked across half the screen."))
+                    .changed()
+                {
+                    failsafe::set_enabled(self.settings.failsafe);
+                    changed = true;
+                }
+                row(ui, rtl, |ui| {
                     if ui
-                        .add(egui::DragValue::new(&mut self.click_interval.seconds))
+                        .checkbox(
+                            &mut self.settings.auto_pause,
+                            tr("Pause while I move the mouse, resuming"),
+                        )
                         .changed()
                     {
-                        self.tx_click_interval.send(self.click_interval).unwrap();
-                    };
-                    ui.label("Seconds");
+                        takeover::set_enabled(self.settings.auto_pause);
+                        changed = true;
+                    }
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.settings.auto_pause_ms)
+                            .clamp_range(100..=10_000)
+                            .suffix(" ms"),
+                    );
+                    if accessible_name(ui, response, &tr("Auto-pause grace period")).changed() {
+                        takeover::set_grace(Duration::from_millis(
+                            self.settings.auto_pause_ms as u64,
+                        ));
+                        changed = true;
+                    }
+                    ui.label(tr("after it stops"));
+                });
+                row(ui, rtl, |ui| {
                     if ui
-                        .add(egui::DragValue::new(&mut self.click_interval.milliseconds))
+                        .checkbox(
+                            &mut self.settings.idle_start,
+                            tr("Start when I have been idle for"),
+                        )
                         .changed()
                     {
-                        self.tx_click_interval.send(self.click_interval).unwrap();
-                    };
-                    ui.label("Milliseconds");
-                })
-            });
-
-            ui.horizontal(|ui| {
-                ui.group(|ui| {
-                    ui.set_width(408.5);
-                    ui.vertical(|ui| {
-                        ui.heading("Click Options");
-                        egui::ComboBox::from_label("Mouse Button")
-                            .selected_text(format!("{:?}", self.click_options.mouse_button))
-                            .show_ui(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                ui.set_min_width(60.0);
-                                if ui
-                                    .selectable_value(
-                                        &mut self.click_options.mouse_button,
-                                        MouseButton::Left,
-                                        "Left",
-                                    )
-                                    .changed()
-                                {
-                                    self.tx_click_options.send(self.click_options).unwrap();
-                                };
-                                if ui
-                                    .selectable_value(
-                                        &mut self.click_options.mouse_button,
-                                        MouseButton::Middle,
-                                        "Middle",
-                                    )
-                                    .changed()
-                                {};
-                                if ui
-                                    .selectable_value(
-                                        &mut self.click_options.mouse_button,
-                                        MouseButton::Right,
-                                        "Right",
-                                    )
-                                    .changed()
-                                {
-                                    self.tx_click_options.send(self.click_options).unwrap();
-                                };
-                            });
-
-                        egui::ComboBox::from_label("Click Type")
-                            .selected_text(format!("{:?}", self.click_options.click_type))
-                            .show_ui(ui, |ui| {
-                                ui.style_mut().wrap = Some(false);
-                                ui.set_min_width(60.0);
-                                ui.selectable_value(
-                                    &mut self.click_options.click_type,
-                                    ClickType::Single,
-                                    "Single",
-                                );
-                                ui.selectable_value(
-                                    &mut self.click_options.click_type,
-                                    ClickType::Double,
-                                    "Double",
-                                );
-                            });
-                    });
+                        idle::set_enabled(self.settings.idle_start);
+                        changed = true;
+                    }
+                    let response = ui.add(
+                        egui::DragValue::new(&mut self.settings.idle_start_secs)
+                            .clamp_range(5..=3600)
+                            .suffix(" s"),
+                    );
+                    if accessible_name(ui, response, &tr("Idle time")).changed() {
+                        idle::set_idle_time(Duration::from_secs(
+                            self.settings.idle_start_secs as u64,
+                        ));
+                        changed = true;
+                    }
+                    ui.label(tr("and stop when I'm back"));
                 });
-            });
-
-            ui.group(|ui| {
-                ui.set_width(408.5);
-                ui.heading("Click Position");
-
+                ui.label(tr(
+                    "Holding Esc for a second always stops all clicking, jobs, macros and \
+                     scripts.",
+                ));
+                #[cfg(feature = "notifications")]
                 if ui
-                    .radio_value(
-                        &mut self.click_position,
-                        ClickPosition::CurrentCursorPosition,
-                        "Current Cursor Position",
+                    .checkbox(
+                        &mut self.settings.notifications,
+                        tr("Notify on start and stop"),
                     )
+                    .on_hover_text(tr(
+                        "Show a desktop notification when a run starts, stops or completes.",
+                    ))
                     .changed()
                 {
-                    self.tx_click_position.send(self.click_position).unwrap();
-                };
-
-                ui.horizontal(|ui| {
-                    ui.radio_value(
-                        &mut self.click_position,
-                        ClickPosition::Custom { x: 0, y: 0 },
-                        "",
-                    );
-                    if let ClickPosition::Custom { x, y } = &mut self.click_position.clone() {
-                        ui.label("X: ");
-                        if ui.add(egui::DragValue::new(x)).changed() {
-                            self.click_position = ClickPosition::Custom { x: *x, y: *y };
-                            self.tx_click_position.send(self.click_position).unwrap();
-                        };
-                        ui.label("Y: ");
-                        if ui.add(DragValue::new(y)).changed() {
-                            self.click_position = ClickPosition::Custom { x: *x, y: *y };
-                            self.tx_click_position.send(self.click_position).unwrap();
-                        };
-                    } else {
-                        ui.label("X: ");
-                        ui.add(egui::DragValue::new(&mut 0));
-                        ui.label("Y: ");
-                        ui.add(DragValue::new(&mut 0));
+                    crate::notification::set_enabled(self.settings.notifications);
+                    changed = true;
+                }
+                #[cfg(feature = "sound")]
+                row(ui, rtl, |ui| {
+                    let mut sound_changed = ui
+                        .checkbox(
+                            &mut self.settings.sound.enabled,
+                            tr("Beep on start and stop"),
+                        )
+                        .on_hover_text(tr("Sound files can be set in settings.toml."))
+                        .changed();
+                    let label = ui.label(tr("Volume"));
+                    sound_changed |= ui
+                        .add_enabled(
+                            self.settings.sound.enabled,
+                            egui::Slider::new(&mut self.settings.sound.volume, 0.0..=1.0)
+                                .custom_formatter(|volume, _| format!("{:.0}%", volume * 100.0)),
+                        )
+                        .labelled_by(label.id)
+                        .changed();
+                    if sound_changed {
+                        crate::sound::configure(&self.settings.sound);
+                        changed = true;
                     }
                 });
-            });
-
-            ui.horizontal(|ui| {
-                if create_button(ui, "Start (F6)").clicked() {
-                    if let Ok(is_running) = &mut self.is_running.lock() {
-                        **is_running = true;
-                    }
+                #[cfg(feature = "speech")]
+                if ui
+                    .checkbox(&mut self.settings.speech, tr("Announce start and stop"))
+                    .on_hover_text(tr("Speak when a run starts, completes or is stopped."))
+                    .changed()
+                {
+                    crate::speech::set_enabled(self.settings.speech);
+                    changed = true;
                 }
-                ui.add_space(52.5);
-
-                if create_button(ui, "Stop (F7)").clicked() {
-                    if let Ok(is_running) = &mut self.is_running.lock() {
-                        **is_running = false;
+                if ui
+                    .checkbox(
+                        &mut self.settings.high_priority,
+                        tr("High-priority clicking"),
+                    )
+                    .on_hover_text(tr(
+                        "Keeps short intervals more consistent, at the cost of CPU time, \
+                         battery and responsiveness of other programs. Linux needs the \
+                         CAP_SYS_NICE capability to raise the priority.",
+                    ))
+                    .changed()
+                {
+                    priority::set_high_priority(self.settings.high_priority);
+                    changed = true;
+                }
+                row(ui, rtl, |ui| {
+                    let label = ui.label(tr("Gap between events"));
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.event_gap_ms)
+                                .clamp_range(0..=100)
+                                .suffix(" ms"),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr(
+                            "The least time between two sent events. macOS drops events \
+                             that come faster than about 20 ms apart; elsewhere 0 allows the \
+                             highest click rates. Precision mode shortens it to 1 ms.",
+                        ));
+                    if response.changed() {
+                        clicker::set_event_gap(Duration::from_millis(
+                            self.settings.event_gap_ms as u64,
+                        ));
+                        changed = true;
                     }
+                });
+                if ui
+                    .checkbox(&mut self.settings.turbo, tr("Turbo mode"))
+                    .on_hover_text(tr(
+                        "For 500 to over 1000 clicks per second: high-resolution timers, \
+                         no pause between sent events and clicks sent in batches. Keeps a \
+                         CPU core busy, and many apps drop input this fast.",
+                    ))
+                    .changed()
+                {
+                    turbo::set_enabled(self.settings.turbo);
+                    changed = true;
+                }
+                if let Some(cps) = self.settings.max_cps.filter(|_| self.settings.turbo) {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr_args(
+                            "Turbo runs are held to the cap of {cps} clicks per second.",
+                            &[("cps", &cps)],
+                        ),
+                    );
+                }
+                if ui
+                    .checkbox(
+                        &mut self.settings.frame_perfect,
+                        tr("Frame-perfect clicking (experimental)"),
+                    )
+                    .on_hover_text(tr(
+                        "Moves every click onto the next display refresh, for rhythm \
+                         games. Keeps the window redrawing at the refresh rate and a CPU \
+                         core busy while clicking.",
+                    ))
+                    .changed()
+                {
+                    vsync::set_enabled(self.settings.frame_perfect);
+                    changed = true;
                 }
-                ui.add_space(52.5);
 
-                if create_button(ui, "Toggle (F8)").clicked() {
-                    if let Ok(is_running) = &mut self.is_running.lock() {
-                        **is_running = !**is_running;
+                row(ui, rtl, |ui| {
+                    let label = ui.label(tr("Event tag"));
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.event_tag)
+                                .clamp_range(0..=u32::MAX)
+                                .hexadecimal(8, false, true)
+                                .prefix("0x"),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(if source::is_tagging_supported() {
+                            tr("Sent in dwExtraInfo with every synthesized mouse button, \
+                             so other programs can recognize the clicker's clicks.")
+                        } else {
+                            tr("Only Windows can attach a tag to synthesized input.")
+                        });
+                    if response.changed() {
+                        source::set_tag(self.settings.event_tag);
+                        changed = true;
                     }
+                });
+
+                row(ui, rtl, |ui| {
+                    let mut fixed = self.settings.random_seed.is_some();
+                    if ui
+                        .checkbox(&mut fixed, tr("Fixed random seed"))
+                        .on_hover_text(tr(
+                            "Every run draws the same timing and position jitter and \
+                             pauses, e.g. to reproduce a run while debugging. Turning it \
+                             on keeps the last run's seed.",
+                        ))
+                        .changed()
+                    {
+                        self.settings.random_seed =
+                            fixed.then(|| humanize::last_seed().unwrap_or_default());
+                        humanize::set_seed(self.settings.random_seed);
+                        changed = true;
+                    }
+                    if let Some(seed) = &mut self.settings.random_seed {
+                        let response = ui.add(egui::DragValue::new(seed));
+                        if accessible_name(ui, response, &tr("Random seed")).changed() {
+                            humanize::set_seed(self.settings.random_seed);
+                            changed = true;
+                        }
+                    } else if let Some(seed) = humanize::last_seed() {
+                        ui.weak(tr_args("last run: {seed}", &[("seed", &seed)]));
+                    }
+                });
+
+                row(ui, rtl, |ui| {
+                    let mut capped = self.settings.max_cps.is_some();
+                    if ui
+                        .checkbox(&mut capped, tr("At most"))
+                        .on_hover_text(tr(
+                            "No run clicks faster than this, whatever its interval, burst or ramp",
+                        ))
+                        .changed()
+                    {
+                        self.settings.max_cps = capped.then_some(100.0);
+                        ratelimit::set_max_cps(self.settings.max_cps);
+                        changed = true;
+                    }
+                    if let Some(cps) = &mut self.settings.max_cps {
+                        let response = ui.add(
+                            egui::DragValue::new(cps)
+                                .clamp_range(1.0..=10_000.0)
+                                .speed(1.0)
+                                .suffix(tr(" clicks per second")),
+                        );
+                        if accessible_name(ui, response, &tr("Maximum clicks per second")).changed()
+                        {
+                            ratelimit::set_max_cps(self.settings.max_cps);
+                            changed = true;
+                        }
+                    } else {
+                        ui.label(tr("clicks per second"));
+                    }
+                });
+                ui.collapsing(tr("Advanced"), |ui| {
+                    if display::is_supported() {
+                        row(ui, rtl, |ui| {
+                            let label = ui.label(tr("Input display"));
+                            changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut self.settings.input_display)
+                                        .hint_text(tr("current"))
+                                        .desired_width(80.0),
+                                )
+                                .labelled_by(label.id)
+                                .on_hover_text(tr(
+                                    "X display to send clicks to, e.g. :1 for a second X server \
+                                     or a nested session. Applies after a restart.",
+                                ))
+                                .changed();
+                        });
+                    }
+                    changed |= ui
+                        .checkbox(
+                            &mut self.settings.ipc,
+                            tr("Take commands from local scripts"),
+                        )
+                        .on_hover_text(tr_args(
+                            "Listens on {address} for start, stop, status and \
+                             set interval. Applies after a restart.",
+                            &[("address", &ipc::address())],
+                        ))
+                        .changed();
+                    if pointer::is_supported()
+                        && ui
+                            .checkbox(
+                                &mut self.settings.second_pointer,
+                                tr("Click with a second pointer (X11)"),
+                            )
+                            .on_hover_text(tr(
+                                "Adds a separate cursor with XInput2 so clicking never \
+                                 moves yours. Needs the xinput tool.",
+                            ))
+                            .changed()
+                    {
+                        pointer::set_enabled(self.settings.second_pointer);
+                        changed = true;
+                    }
+                    row(ui, rtl, |ui| {
+                        let choice = &mut self.settings.input_backend;
+                        let before = *choice;
+                        let name = |choice| match choice {
+                            BackendChoice::Auto => tr("Automatic"),
+                            BackendChoice::Rdev => tr("rdev"),
+                            BackendChoice::Uinput => tr("uinput"),
+                            BackendChoice::Xtest => tr("XTest"),
+                            BackendChoice::SendInput => tr("SendInput"),
+                        };
+                        egui::ComboBox::from_label(tr("Input backend"))
+                            .selected_text(name(*choice))
+                            .show_ui(ui, |ui| {
+                                ui.style_mut().wrap = Some(false);
+                                for value in BackendChoice::available() {
+                                    ui.selectable_value(choice, value, name(value));
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr(
+                                "How clicks and keys are sent. uinput works on Wayland but \
+                                     needs write access to /dev/uinput. Automatic picks it in \
+                                     Wayland sessions. XTest is the fastest on X11, SendInput on \
+                                     Windows.",
+                            ));
+                        if *choice != before {
+                            source::set_choice(*choice);
+                            changed = true;
+                        }
+                        ui.label(tr_args(
+                            "Using {backend}",
+                            &[("backend", &source::backend())],
+                        ));
+                    });
+                });
+                for activation in [Activation::Hold, Activation::Toggle] {
+                    row(ui, rtl, |ui| changed |= self.trigger_ui(ui, activation));
+                }
+                if let Some((activation, trigger)) = activation::take_captured() {
+                    *self.settings.trigger_mut(activation) = Some(trigger);
+                    activation::set_trigger(activation, Some(trigger));
+                    changed = true;
+                }
+                #[cfg(feature = "gamepad")]
+                {
+                    changed |= self.gamepad_ui(ui);
+                }
+                changed |= ui
+                    .checkbox(&mut self.settings.start_minimized, tr("Start minimized"))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.minimize_on_close,
+                        tr("Hide to the tray instead of closing, keep clicking in the background"),
+                    )
+                    .on_hover_text(tr("Without a tray icon the window is minimized instead."))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.dock,
+                        tr("Snap to screen edges and collapse to a bar there"),
+                    )
+                    .on_hover_text(tr("The bar expands again when the cursor is over it."))
+                    .changed();
+                row(ui, rtl, |ui| {
+                    let mut right_to_left = self.settings.is_right_to_left();
+                    if ui
+                        .checkbox(&mut right_to_left, tr("Right-to-left layout"))
+                        .on_hover_text(tr("Follows the language unless changed here."))
+                        .changed()
+                    {
+                        self.settings.right_to_left = Some(right_to_left);
+                        changed = true;
+                    }
+                    if self.settings.right_to_left.is_some()
+                        && ui.button(tr("Follow the language")).clicked()
+                    {
+                        self.settings.right_to_left = None;
+                        changed = true;
+                    }
+                });
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.simple_mode,
+                        tr("Simplified mode with large buttons"),
+                    )
+                    .changed();
+                if changed {
+                    if let Err(e) = self.settings.save() {
+                        log::error!("Could not save settings: {e}");
+                    }
+                }
+
+                row(ui, rtl, |ui| {
+                    let mut tracing = trace::is_enabled();
+                    if ui
+                        .checkbox(&mut tracing, tr("Record a performance trace"))
+                        .changed()
+                    {
+                        trace::set_enabled(tracing);
+                    }
+                    let save =
+                        ui.add_enabled(trace::len() > 0, egui::Button::new(tr("Save trace")));
+                    if save.clicked() {
+                        if let Some(path) = config::config_dir().map(|dir| dir.join("trace.json")) {
+                            match trace::export(&path) {
+                                Ok(()) => {
+                                    trace::clear();
+                                    self.saved_trace = Some(path);
+                                }
+                                Err(e) => log::error!("Could not save trace: {e}"),
+                            }
+                        }
+                    }
+                });
+                if let Some(path) = &self.saved_trace {
+                    ui.label(tr_args(
+                        "Trace saved to {path}",
+                        &[("path", &path.display())],
+                    ));
+                }
+
+                self.bundle_ui(ui, ctx);
+
+                ui.horizontal(|ui| {
+                    if ui.button(tr("What's new")).clicked() {
+                        self.whats_new = changelog::releases();
+                        self.show_whats_new = true;
+                    }
+                    if ui.button(tr("Quick setup…")).clicked() {
+                        self.wizard = Some(Wizard::default());
+                    }
+                });
+            });
+        self.show_settings = show_settings;
+    }
+
+    fn wizard_ui(&mut self, ctx: &egui::Context) {
+        let Some(wizard) = &mut self.wizard else {
+            return;
+        };
+        let mut open = true;
+        let setup = egui::Window::new(tr("Quick setup"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| wizard.ui(ui))
+            .and_then(|response| response.inner)
+            .flatten();
+        if let Some(setup) = setup {
+            self.apply_setup(setup);
+            open = false;
+        }
+        if !open {
+            self.wizard = None;
+        }
+    }
+
+    /// Takes over what the wizard set up, with the failsafe on.
+    fn apply_setup(&mut self, setup: Setup) {
+        self.apply_profile(setup.profile);
+        self.run_limit = RunLimit::from_duration(setup.run_limit);
+        send_command(
+            &self.tx_command,
+            Config::RunLimit(self.run_limit.duration()).into(),
+        );
+        self.settings.failsafe = true;
+        failsafe::set_enabled(true);
+        if let Err(e) = self.settings.save() {
+            log::error!("Could not save settings: {e}");
+        }
+    }
+
+    /// The changelog entries in [`MainApp::whats_new`], with a button on
+    /// the ones that link to a window to open it.
+    /// The shortest time between triggers the current setup clicks at, in
+    /// milliseconds, or `None` while a burst or ramp sets the pace.
+    fn effective_interval_ms(&self) -> Option<f64> {
+        if self.click_options.burst.enabled || self.click_options.ramp.enabled {
+            return None;
+        }
+        let interval = self.click_interval.total_millis() as f64;
+        let min_wait = clicker::min_wait(&self.click_options).as_secs_f64() * 1000.0;
+        Some(interval.max(min_wait))
+    }
+
+    /// Sends `command`, [`Command::Start`] or [`Command::Toggle`], unless it
+    /// would start a run with a dangerously short interval; that asks for
+    /// confirmation first. Stopping and resuming go through at once.
+    pub fn request_start(&mut self, command: Command) {
+        if self.permission_missing {
+            return;
+        }
+        let starting = !clicker::is_running() && !clicker::is_paused();
+        let too_fast = self
+            .effective_interval_ms()
+            .is_some_and(|interval| interval < FAST_INTERVAL_MS);
+        if starting && too_fast {
+            self.confirm_start = true;
+            self.fast_understood = false;
+        } else {
+            send_command(&self.tx_command, command);
+        }
+    }
+
+    /// Whether the window should close.
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    /// Checks the Accessibility permission again, e.g. when the user comes
+    /// back from System Settings.
+    pub fn recheck_permission(&mut self) {
+        if self.permission_missing {
+            self.permission_missing = !permission::is_granted();
+        }
+    }
+
+    /// Explains the missing Accessibility permission in place of the
+    /// options, which would do nothing without it.
+    fn permission_ui(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("Accessibility access needed"));
+        ui.label(tr(
+            "macOS only lets apps click and type for you once they are allowed to \
+             control the computer. Until then every click is silently dropped.",
+        ));
+        ui.label(tr(
+            "Open System Settings, turn on Auto Clicker under Privacy & Security > \
+             Accessibility and come back here.",
+        ));
+        ui.horizontal_wrapped(|ui| {
+            if ui.button(tr("Open System Settings")).clicked() {
+                permission::open_settings();
+            }
+            if ui.button(tr("Check again")).clicked() {
+                self.recheck_permission();
+            }
+            if ui.button(tr("Continue without it")).clicked() {
+                self.permission_missing = false;
+            }
+        });
+    }
+
+    fn confirm_start_ui(&mut self, ctx: &egui::Context) {
+        if !self.confirm_start {
+            return;
+        }
+        let interval = self.effective_interval_ms().unwrap_or_default();
+        let mut open = true;
+        let mut start = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Very short interval"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                if interval == 0.0 {
+                    ui.label(tr(
+                        "The interval is 0 ms: the clicker clicks as fast as the system takes \
+                         the clicks, without any pause, until it is stopped.",
+                    ));
+                } else {
+                    let rate = self.click_options.click_type.clicks() as f64 * 1000.0 / interval;
+                    ui.label(tr_args(
+                        "The interval is {interval} ms, about {rate} clicks per second.",
+                        &[
+                            ("interval", &format!("{interval:.1}")),
+                            ("rate", &format!("{rate:.0}")),
+                        ],
+                    ));
+                }
+                ui.label(tr(
+                    "Clicking this fast can make apps or the whole desktop stop \
+                     responding. F7 stops the run; a maximum of clicks per second in \
+                     the settings caps every run.",
+                ));
+                ui.checkbox(&mut self.fast_understood, tr("I understand"));
+                ui.horizontal(|ui| {
+                    start = ui
+                        .add_enabled(self.fast_understood, egui::Button::new(tr("Start")))
+                        .clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if start {
+            send_command(&self.tx_command, Command::Start);
+        }
+        if start || cancel || !open {
+            self.confirm_start = false;
+        }
+    }
+
+    /// What the app can do, every hotkey as currently bound and what the
+    /// modes do, for finding one's way around without a manual. F1 opens
+    /// and closes it.
+    fn help_ui(&mut self, ctx: &egui::Context) {
+        if ctx.input(|input| input.key_pressed(egui::Key::F1)) {
+            self.show_help = !self.show_help;
+        }
+        let mut show_help = self.show_help;
+        egui::Window::new(tr("Help"))
+            .open(&mut show_help)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::CollapsingHeader::new(tr("Hotkeys"))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            egui::Grid::new("help_hotkeys")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for (action, trigger) in hotkeys::bindings() {
+                                        ui.label(trigger.label());
+                                        ui.label(action.name());
+                                        ui.end_row();
+                                    }
+                                    for job in &self.settings.jobs {
+                                        if let Some(hotkey) = job.hotkey {
+                                            ui.label(hotkey.label());
+                                            ui.label(tr_args(
+                                                "Start/stop job {name}",
+                                                &[("name", &job.name)],
+                                            ));
+                                            ui.end_row();
+                                        }
+                                    }
+                                });
+                            ui.weak(tr("Bind more keys under Settings."));
+                        });
+
+                    egui::CollapsingHeader::new(tr("Actions")).show(ui, |ui| {
+                        for (name, text) in [
+                            (
+                                tr("Start / Stop"),
+                                tr("Clicks at the interval until stopped, the run limit \
+                                    is reached or the target window closes."),
+                            ),
+                            (
+                                tr("Pause"),
+                                tr("Holds the run where it is; resuming carries on with \
+                                    the same run."),
+                            ),
+                            (
+                                tr("Presets"),
+                                tr("Sets a common or saved interval in one go."),
+                            ),
+                            (
+                                tr("Sequence"),
+                                tr("Clicks a list of points and keys in order, with \
+                                    waits and conditions between them."),
+                            ),
+                            (
+                                tr("Script"),
+                                tr("Runs a Rhai script that clicks, moves and waits."),
+                            ),
+                            (
+                                tr("Jobs"),
+                                tr("Small clickers of their own, each with a hotkey."),
+                            ),
+                            (
+                                tr("History"),
+                                tr("Past runs, whose settings can be applied again."),
+                            ),
+                            (
+                                tr("Calibration"),
+                                tr("Measures this machine's timing and top click rate."),
+                            ),
+                        ] {
+                            ui.label(egui::RichText::new(name).strong());
+                            ui.label(text);
+                        }
+                    });
+
+                    egui::CollapsingHeader::new(tr("Modes")).show(ui, |ui| {
+                        for (name, text) in [
+                            (
+                                tr("Precision mode"),
+                                tr("Spins through the end of each wait for accurate \
+                                    short intervals."),
+                            ),
+                            (
+                                tr("Turbo mode"),
+                                tr("High-resolution timers and batched clicks for \
+                                    hundreds of clicks per second."),
+                            ),
+                            (
+                                tr("Hold mode"),
+                                tr("Presses and holds the button instead of clicking."),
+                            ),
+                            (
+                                tr("Alternate buttons"),
+                                tr("Cycles the mouse button from click to click."),
+                            ),
+                            (
+                                tr("Random buttons"),
+                                tr("Draws each click's button by weight."),
+                            ),
+                            (
+                                tr("Humanize"),
+                                tr("Adds noise to the timing and position of clicks."),
+                            ),
+                            (
+                                tr("Simplified mode with large buttons"),
+                                tr("Start and Stop and little else, switched on in the settings."),
+                            ),
+                        ] {
+                            ui.label(egui::RichText::new(name).strong());
+                            ui.label(text);
+                        }
+                    });
+                });
+            });
+        self.show_help = show_help;
+    }
+
+    /// Points new users to the help until they dismiss it.
+    fn help_hint_ui(&mut self, ui: &mut egui::Ui) {
+        if self.settings.help_hint_seen || self.wizard.is_some() {
+            return;
+        }
+        let rtl = self.settings.is_right_to_left();
+        row(ui, rtl, |ui| {
+            ui.label(tr(
+                "New here? Press F1 or \"?\" for every action, hotkey and mode.",
+            ));
+            if ui.button(tr("Got it")).clicked() {
+                self.settings.help_hint_seen = true;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Could not save settings: {e}");
+                }
+            }
+        });
+    }
+
+    fn whats_new_ui(&mut self, ctx: &egui::Context) {
+        let mut show_whats_new = self.show_whats_new;
+        let mut open = None;
+        egui::Window::new(tr("What's new"))
+            .open(&mut show_whats_new)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for release in &self.whats_new {
+                        ui.heading(&release.version);
+                        for entry in &release.entries {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(format!("• {}", entry.text));
+                                if let Some(link) = entry.link {
+                                    if ui.small_button(tr("Show")).clicked() {
+                                        open = Some(link);
+                                    }
+                                }
+                            });
+                        }
+                    }
+                });
+            });
+        self.show_whats_new = show_whats_new;
+        match open {
+            Some(Link::Settings) => self.show_settings = true,
+            Some(Link::Script) => self.show_script = true,
+            Some(Link::Sequence) => self.show_sequence = true,
+            None => {}
+        }
+    }
+
+    fn sequence_ui(&mut self, ctx: &egui::Context) {
+        let mut show_sequence = self.show_sequence;
+        egui::Window::new(tr("Click Sequence"))
+            .open(&mut show_sequence)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(tr("When the sequence has steps, each cycle runs them in order instead of clicking the click position."));
+                if sequence::editor_ui(ui, &mut self.click_sequence) {
+                    send_command(
+                        &self.tx_command,
+                        Config::ClickSequence(self.click_sequence.clone()).into(),
+                    );
+                }
+                ui.separator();
+                if sequence::loop_ui(ui, &mut self.sequence_loop) {
+                    send_command(&self.tx_command, Config::SequenceLoop(self.sequence_loop).into());
+                }
+            });
+        self.show_sequence = show_sequence;
+    }
+
+    fn sandbox_ui(&mut self, ctx: &egui::Context) {
+        let mut show_sandbox = self.show_sandbox;
+        let window = egui::Window::new(tr("Sandbox"))
+            .open(&mut show_sandbox)
+            .collapsible(false)
+            .show(ctx, |ui| self.sandbox.ui(ui));
+        self.show_sandbox = show_sandbox;
+        // The clicker skips the app's window except for this panel.
+        let area = window
+            .filter(|_| show_sandbox)
+            .map(|window| window.response.rect);
+        let hovered = area.is_some_and(|area| {
+            ctx.pointer_hover_pos()
+                .is_some_and(|pos| area.contains(pos))
+        });
+        sandbox::set_area(area, ctx.pixels_per_point(), hovered);
+    }
+
+    fn history_ui(&mut self, ctx: &egui::Context) {
+        let rtl = self.settings.is_right_to_left();
+        let mut show_history = self.show_history;
+        let mut apply = None;
+        egui::Window::new(tr("History"))
+            .open(&mut show_history)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                row(ui, rtl, |ui| {
+                    if ui.button(tr("Refresh")).clicked() {
+                        self.history = history::load();
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.history.is_empty(),
+                            egui::Button::new(tr("Clear history")),
+                        )
+                        .clicked()
+                    {
+                        match history::clear() {
+                            Ok(()) => {
+                                self.history.clear();
+                                self.history_status = None;
+                            }
+                            Err(e) => {
+                                self.history_status = Some(tr_args(
+                                    "Could not clear the history: {error}",
+                                    &[("error", &e)],
+                                ));
+                            }
+                        }
+                    }
+                });
+
+                if self.history.is_empty() {
+                    ui.label(tr("No runs yet."));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("run_history").striped(true).show(ui, |ui| {
+                            for (i, run) in self.history.iter().enumerate().rev() {
+                                ui.label(
+                                    humantime::format_rfc3339_seconds(run.started).to_string(),
+                                );
+                                let elapsed = Duration::from_secs(run.elapsed.as_secs());
+                                ui.label(humantime::format_duration(elapsed).to_string());
+                                ui.label(tr_n(
+                                    "{clicks} click",
+                                    "{clicks} clicks",
+                                    run.clicks as u64,
+                                    &[("clicks", &run.clicks)],
+                                ));
+                                ui.label(&run.config);
+                                if ui
+                                    .button(tr("Apply"))
+                                    .on_hover_text(tr("Use the click settings of this run"))
+                                    .clicked()
+                                {
+                                    apply = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                if let Some(status) = &self.history_status {
+                    ui.label(status);
+                }
+            });
+        self.show_history = show_history;
+        if let Some(i) = apply {
+            self.apply_profile(self.history[i].profile);
+        }
+    }
+
+    fn library_ui(&mut self, ctx: &egui::Context) {
+        let rtl = self.settings.is_right_to_left();
+        let idle = !self.recorder.is_recording() && !self.recorder.is_playing();
+        let mut show_library = self.show_library;
+        let mut changed = false;
+        egui::Window::new(tr("Macro Library"))
+            .open(&mut show_library)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                row(ui, rtl, |ui| {
+                    let label = ui.label(tr("Name:"));
+                    ui.add(egui::TextEdit::singleline(&mut self.library_name).desired_width(140.0))
+                        .labelled_by(label.id);
+                    let can_save =
+                        idle && !self.recorder.is_empty() && !self.library_name.trim().is_empty();
+                    if ui
+                        .add_enabled(can_save, egui::Button::new(tr("Save recording")))
+                        .clicked()
+                    {
+                        let name = self.library_name.trim().to_owned();
+                        match library::save(&name, &self.recorder.recording()) {
+                            Ok(()) => {
+                                self.library_status = None;
+                                changed = true;
+                            }
+                            Err(e) => {
+                                self.library_status = Some(tr_args(
+                                    "Could not save {name}: {error}",
+                                    &[("name", &name), ("error", &e)],
+                                ));
+                            }
+                        }
+                    }
+                });
+
+                if self.library.is_empty() {
+                    ui.label(tr("No saved recordings yet."));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("macro_library")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for info in &self.library {
+                                    match &mut self.renaming_macro {
+                                        Some((name, new_name)) if *name == info.name => {
+                                            let response = ui.add(
+                                                egui::TextEdit::singleline(new_name)
+                                                    .desired_width(120.0),
+                                            );
+                                            accessible_name(
+                                                ui,
+                                                response,
+                                                &tr_args(
+                                                    "New name of {name}",
+                                                    &[("name", &info.name)],
+                                                ),
+                                            );
+                                        }
+                                        _ => {
+                                            ui.label(&info.name);
+                                        }
+                                    }
+                                    ui.label(info.saved.map_or_else(String::new, |saved| {
+                                        humantime::format_rfc3339_seconds(saved).to_string()
+                                    }));
+                                    let duration =
+                                        Duration::from_millis(info.duration.as_millis() as u64);
+                                    ui.label(humantime::format_duration(duration).to_string());
+                                    ui.label(tr_args(
+                                        "{events} events",
+                                        &[("events", &info.events)],
+                                    ));
+
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .add_enabled(idle, egui::Button::new(tr("Load")))
+                                            .clicked()
+                                        {
+                                            match library::load(&info.name) {
+                                                Ok(recording) => {
+                                                    self.recorder.set_recording(recording);
+                                                    self.library_name = info.name.clone();
+                                                }
+                                                Err(e) => {
+                                                    self.library_status = Some(tr_args(
+                                                        "Could not load {name}: {error}",
+                                                        &[("name", &info.name), ("error", &e)],
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                        if ui
+                                            .add_enabled(idle, egui::Button::new(tr("Play")))
+                                            .clicked()
+                                        {
+                                            match library::load(&info.name) {
+                                                Ok(recording) => {
+                                                    self.recorder.set_recording(recording);
+                                                    self.recorder.play();
+                                                }
+                                                Err(e) => {
+                                                    self.library_status = Some(tr_args(
+                                                        "Could not load {name}: {error}",
+                                                        &[("name", &info.name), ("error", &e)],
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                        match self.renaming_macro.take() {
+                                            Some((name, new_name)) if name == info.name => {
+                                                if ui.button(tr("OK")).clicked() {
+                                                    match library::rename(&name, &new_name) {
+                                                        Ok(()) => changed = true,
+                                                        Err(e) => {
+                                                            self.library_status = Some(tr_args(
+                                                                "Could not rename {name}: {error}",
+                                                                &[("name", &name), ("error", &e)],
+                                                            ));
+                                                        }
+                                                    }
+                                                } else if !ui.button(tr("Cancel")).clicked() {
+                                                    self.renaming_macro = Some((name, new_name));
+                                                }
+                                            }
+                                            other => {
+                                                self.renaming_macro = other;
+                                                if ui.button(tr("Rename")).clicked() {
+                                                    self.renaming_macro = Some((
+                                                        info.name.clone(),
+                                                        info.name.clone(),
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                        if ui.button(tr("Delete")).clicked() {
+                                            match library::delete(&info.name) {
+                                                Ok(()) => changed = true,
+                                                Err(e) => {
+                                                    self.library_status = Some(tr_args(
+                                                        "Could not delete {name}: {error}",
+                                                        &[("name", &info.name), ("error", &e)],
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if let Some(status) = &self.library_status {
+                    ui.label(status);
+                }
+            });
+        self.show_library = show_library;
+        if changed {
+            self.library = library::list();
+        }
+    }
+
+    fn jobs_ui(&mut self, ctx: &egui::Context) {
+        if let Some(picked) = self.picking_job.as_ref().and_then(|(_, rx)| poll_pick(rx)) {
+            let (id, _) = self.picking_job.take().unwrap();
+            self.update_shield();
+            if let Some(job) = self.settings.jobs.iter_mut().find(|job| job.id == id) {
+                if picked.is_some() {
+                    job.position = picked;
+                    self.jobs_changed();
+                }
+            }
+        }
+
+        let rtl = self.settings.is_right_to_left();
+        let mut show_jobs = self.show_jobs;
+        let mut changed = false;
+        let mut removed = None;
+        let mut pick = None;
+        egui::Window::new(tr("Jobs"))
+            .open(&mut show_jobs)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(tr(
+                    "Each job clicks on its own, with its own interval, button, position \
+                     and hotkey, at the same time as the others and the main clicker.",
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (index, job) in self.settings.jobs.iter_mut().enumerate() {
+                            ui.push_id(job.id, |ui| {
+                                ui.group(|ui| {
+                                    row(ui, rtl, |ui| {
+                                        let response = ui.add(
+                                            egui::TextEdit::singleline(&mut job.name)
+                                                .desired_width(120.0),
+                                        );
+                                        changed |= accessible_name(
+                                            ui,
+                                            response,
+                                            &tr_args("Name of job {job}", &[("job", &(index + 1))]),
+                                        )
+                                        .changed();
+                                        let running = jobs::is_running(job.id);
+                                        let text = if running { tr("Stop") } else { tr("Start") };
+                                        if ui.button(text).clicked() {
+                                            jobs::toggle(job.id);
+                                        }
+                                        if ui.button(tr("Remove")).clicked() {
+                                            removed = Some(index);
+                                        }
+                                    });
+                                    row(ui, rtl, |ui| {
+                                        let label = ui.label(tr("Every"));
+                                        changed |= ui
+                                            .add(
+                                                DragValue::new(&mut job.interval_ms)
+                                                    .clamp_range(jobs::MIN_INTERVAL_MS..=86_400_000)
+                                                    .suffix(" ms"),
+                                            )
+                                            .labelled_by(label.id)
+                                            .changed();
+                                        egui::ComboBox::from_id_source("button")
+                                            .selected_text(match job.button {
+                                                MouseButton::Left => tr("Left"),
+                                                MouseButton::Middle => tr("Middle"),
+                                                MouseButton::Right => tr("Right"),
+                                            })
+                                            .show_ui(ui, |ui| {
+                                                for (value, name) in [
+                                                    (MouseButton::Left, tr("Left")),
+                                                    (MouseButton::Middle, tr("Middle")),
+                                                    (MouseButton::Right, tr("Right")),
+                                                ] {
+                                                    changed |= ui
+                                                        .selectable_value(
+                                                            &mut job.button,
+                                                            value,
+                                                            name,
+                                                        )
+                                                        .changed();
+                                                }
+                                            });
+                                        let mut clicks = job.click_type.clicks();
+                                        let response = ui.add(
+                                            DragValue::new(&mut clicks)
+                                                .clamp_range(1..=ClickType::MAX_CLICKS)
+                                                .suffix("×"),
+                                        );
+                                        if accessible_name(ui, response, &tr("Clicks each time"))
+                                            .changed()
+                                        {
+                                            job.click_type = ClickType::from_clicks(clicks);
+                                            changed = true;
+                                        }
+                                    });
+                                    row(ui, rtl, |ui| {
+                                        let mut fixed = job.position.is_some();
+                                        if ui.checkbox(&mut fixed, tr("At")).changed() {
+                                            job.position = fixed.then(|| {
+                                                listener::cursor_position()
+                                                    .map_or((0, 0), |(x, y)| (x as i32, y as i32))
+                                            });
+                                            changed = true;
+                                        }
+                                        if let Some((x, y)) = &mut job.position {
+                                            let response = ui.add(DragValue::new(x).prefix("X: "));
+                                            changed |=
+                                                accessible_name(ui, response, &tr("X")).changed();
+                                            let response = ui.add(DragValue::new(y).prefix("Y: "));
+                                            changed |=
+                                                accessible_name(ui, response, &tr("Y")).changed();
+                                        } else {
+                                            ui.label(tr("the cursor"));
+                                        }
+                                        if ui.button(tr("Pick")).clicked() {
+                                            pick = Some(job.id);
+                                        }
+                                    });
+                                    row(ui, rtl, |ui| {
+                                        let label = ui.label(tr("Hotkey"));
+                                        let text =
+                                            self.job_hotkeys.entry(job.id).or_insert_with(|| {
+                                                job.hotkey.map(Trigger::name).unwrap_or_default()
+                                            });
+                                        let response = ui
+                                            .add(
+                                                egui::TextEdit::singleline(text)
+                                                    .hint_text(tr("e.g. Ctrl+F9"))
+                                                    .desired_width(120.0),
+                                            )
+                                            .labelled_by(label.id);
+                                        let parsed = Trigger::parse(text.trim());
+                                        if !text.trim().is_empty() && parsed.is_none() {
+                                            ui.colored_label(
+                                                ui.visuals().error_fg_color,
+                                                tr("Unknown key"),
+                                            );
+                                        }
+                                        if response.lost_focus() {
+                                            let hotkey = if text.trim().is_empty() {
+                                                None
+                                            } else {
+                                                parsed.or(job.hotkey)
+                                            };
+                                            if hotkey != job.hotkey {
+                                                job.hotkey = hotkey;
+                                                changed = true;
+                                            }
+                                        }
+                                    });
+                                });
+                            });
+                        }
+                    });
+                row(ui, rtl, |ui| {
+                    if ui.button(tr("Add job")).clicked() {
+                        let name =
+                            tr_args("Job {job}", &[("job", &(self.settings.jobs.len() + 1))]);
+                        self.settings.jobs.push(Job::new(name));
+                        changed = true;
+                    }
+                    if ui.button(tr("Start all")).clicked() {
+                        jobs::start_all();
+                    }
+                    if ui.button(tr("Stop all")).clicked() {
+                        jobs::stop_all();
+                    }
+                });
+            });
+        self.show_jobs = show_jobs;
+        if let Some(index) = removed {
+            let job = self.settings.jobs.remove(index);
+            self.job_hotkeys.remove(&job.id);
+            changed = true;
+        }
+        if changed {
+            self.jobs_changed();
+        }
+        if let Some(id) = pick {
+            // The press on the button came before the subscription, so only
+            // the next click is picked up.
+            self.picking_job = Some((id, listener::subscribe()));
+            self.update_shield();
+        }
+        if jobs::any_running() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+    }
+
+    /// Hands the edited jobs to the running ones and saves them.
+    fn jobs_changed(&mut self) {
+        jobs::set_jobs(&self.settings.jobs);
+        if let Err(e) = self.settings.save() {
+            log::error!("Could not save settings: {e}");
+        }
+    }
+
+    fn script_ui(&mut self, ctx: &egui::Context) {
+        let mut show_script = self.show_script;
+        egui::Window::new(tr("Script"))
+            .open(&mut show_script)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(tr(
+                    "Rhai script with click(x, y), move_to(x, y), key(name), \
+                     key_down(name), key_up(name) and wait(ms).",
+                ));
+                let running = self.script_runner.is_running();
+                let editor = egui::TextEdit::multiline(&mut self.script)
+                    .code_editor()
+                    .desired_rows(12)
+                    .desired_width(f32::INFINITY)
+                    .interactive(!running);
+                let response = ui.add(editor);
+                accessible_name(ui, response, &tr("Script"));
+
+                let params = script::params(&self.script);
+                if !params.is_empty() {
+                    egui::Grid::new("script params").show(ui, |ui| {
+                        for param in &params {
+                            let value = self
+                                .script_values
+                                .entry(param.name.clone())
+                                .or_insert_with(|| param.default.clone().unwrap_or_default());
+                            let label = ui.label(&param.name);
+                            ui.add_enabled(!running, egui::TextEdit::singleline(value))
+                                .labelled_by(label.id);
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    if running {
+                        if ui.button(tr("Stop")).clicked() {
+                            self.script_runner.stop();
+                        }
+                    } else if ui.button(tr("Run")).clicked() {
+                        if let Err(e) = script::save(&self.script) {
+                            log::error!("Could not save the script: {e}");
+                        }
+                        let values = params
+                            .iter()
+                            .filter_map(|param| {
+                                let value = self.script_values.get(&param.name)?;
+                                Some((param.name.clone(), value.clone()))
+                            })
+                            .collect();
+                        self.script_runner.run(self.script.clone(), values);
+                    }
+                    if running {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = self.script_runner.error() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        self.show_script = show_script;
+    }
+
+    /// The reduced-input layout: one large Start/Stop button pair and a
+    /// single rate slider, nothing else to aim for.
+    fn simple_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let running = clicker::is_running();
+
+        column(ui, rtl, |ui| {
+            ui.heading(if running {
+                tr("Clicking")
+            } else {
+                tr("Stopped")
+            });
+
+            let button_size = Vec2::new((ui.available_width() - 8.0) / 2.0, 150.0);
+            row(ui, rtl, |ui| {
+                let start = egui::Button::new(egui::RichText::new(tr("Start")).size(40.0))
+                    .min_size(button_size);
+                if ui.add_enabled(!running, start).clicked() {
+                    self.request_start(Command::Start);
+                }
+
+                let stop = egui::Button::new(egui::RichText::new(tr("Stop")).size(40.0))
+                    .min_size(button_size);
+                if ui.add_enabled(running, stop).clicked() {
+                    send_command(&self.tx_command, Command::Stop);
+                }
+            });
+
+            ui.add_space(12.0);
+            ui.label(egui::RichText::new(tr("Clicks per second")).size(24.0));
+            let mut rate = 1000.0 / self.click_interval.total_millis().max(1) as f64;
+            ui.spacing_mut().slider_width = ui.available_width() - 80.0;
+            ui.spacing_mut().interact_size.y = 40.0;
+            let slider = egui::Slider::new(&mut rate, 0.1..=20.0)
+                .logarithmic(true)
+                .max_decimals(1);
+            if ui.add(slider).changed() {
+                self.click_interval = ClickInterval::from_millis((1000.0 / rate).round() as usize);
+                send_command(
+                    &self.tx_command,
+                    Config::ClickInterval(self.click_interval).into(),
+                );
+            }
+
+            ui.add_space(12.0);
+            if create_button(ui, &tr("Full mode")).clicked() {
+                self.settings.simple_mode = false;
+                if let Err(e) = self.settings.save() {
+                    log::error!("Could not save settings: {e}");
+                }
+            }
+        });
+    }
+
+    fn apply_profile(&mut self, profile: Profile) {
+        self.click_interval = profile.click_interval;
+        self.click_options = profile.click_options;
+        self.click_position = profile.click_position;
+        if let ClickPosition::Window { .. } = self.click_position {
+            self.target_windows = target::list();
+        }
+
+        send_command(
+            &self.tx_command,
+            Config::ClickInterval(self.click_interval).into(),
+        );
+        send_command(
+            &self.tx_command,
+            Config::ClickOptions(Box::new(self.click_options)).into(),
+        );
+        send_command(
+            &self.tx_command,
+            Config::ClickPosition(self.click_position).into(),
+        );
+    }
+
+    fn current_profile(&self) -> Profile {
+        Profile {
+            click_interval: self.click_interval,
+            click_options: self.click_options,
+            click_position: self.click_position,
+        }
+    }
+
+    /// The folder whose new files launch a script, profile or slot macro.
+    fn watch_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let watch = &mut self.settings.watch;
+        let mut changed = false;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            row(ui, rtl, |ui| {
+                changed |= ui
+                    .checkbox(&mut watch.enabled, tr("For each new file in"))
+                    .on_hover_text(tr(
+                        "Launches the action once for every file that appears in the \
+                         folder, one after the other",
+                    ))
+                    .changed();
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut watch.folder)
+                        .hint_text(tr("Folder to watch"))
+                        .desired_width(160.0),
+                );
+                changed |= accessible_name(ui, response, &tr("Watched folder")).lost_focus();
+            });
+            row(ui, rtl, |ui| {
+                let selected = match &watch.action {
+                    WatchAction::Script => tr("Run the script"),
+                    WatchAction::Profile(name) => tr_args("Start {name}", &[("name", name)]).into(),
+                    WatchAction::SlotMacro(index) => tr_args(
+                        "Play the {hotkey} macro",
+                        &[("hotkey", &crate::slots::hotkey_label(*index))],
+                    )
+                    .into(),
+                };
+                let label = ui.label(tr("then"));
+                let mut action = watch.action.clone();
+                egui::ComboBox::from_id_source("watch_action")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        ui.selectable_value(&mut action, WatchAction::Script, tr("Run the script"));
+                        for name in &self.profiles {
+                            ui.selectable_value(
+                                &mut action,
+                                WatchAction::Profile(name.clone()),
+                                tr_args("Start {name}", &[("name", name)]),
+                            );
+                        }
+                        for (index, slot) in self.settings.slots.iter().enumerate() {
+                            if *slot == Slot::Macro {
+                                ui.selectable_value(
+                                    &mut action,
+                                    WatchAction::SlotMacro(index),
+                                    tr_args(
+                                        "Play the {hotkey} macro",
+                                        &[("hotkey", &crate::slots::hotkey_label(index))],
+                                    ),
+                                );
+                            }
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+                if action != watch.action {
+                    watch.action = action;
+                    changed = true;
+                }
+                if watch.action == WatchAction::Script {
+                    let label = ui.label(tr("with the path as"));
+                    changed |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut watch.param)
+                                .hint_text(tr("parameter"))
+                                .desired_width(80.0),
+                        )
+                        .labelled_by(label.id)
+                        .on_hover_text(tr("A parameter the script declares with // @param"))
+                        .lost_focus();
+                }
+            });
+        });
+
+        if changed {
+            watch::set_watch(&self.settings.watch);
+            if let Err(e) = self.settings.save() {
+                log::error!("Could not save settings: {e}");
+            }
+        }
+    }
+
+    fn profiles_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            row(ui, rtl, |ui| {
+                let selected = if self.profile_name.is_empty() {
+                    tr("Select profile")
+                } else {
+                    self.profile_name.clone().into()
+                };
+                let label = ui.label(tr("Profile"));
+                egui::ComboBox::from_id_source("profiles")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        ui.set_min_width(60.0);
+                        for name in &self.profiles {
+                            ui.selectable_value(&mut self.profile_name, name.clone(), name);
+                        }
+                    });
+
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.profile_name)
+                        .hint_text(tr("Name"))
+                        .desired_width(80.0),
+                )
+                .labelled_by(label.id);
+
+                if ui.button(tr("Load")).clicked() {
+                    match Profile::load(&self.profile_name) {
+                        Ok(profile) => self.apply_profile(profile),
+                        Err(e) => {
+                            log::error!("Could not load profile {:?}: {e}", self.profile_name)
+                        }
+                    }
+                }
+                if ui.button(tr("Save")).clicked() {
+                    match self.current_profile().save(&self.profile_name) {
+                        Ok(()) => self.profiles = Profile::list(),
+                        Err(e) => {
+                            log::error!("Could not save profile {:?}: {e}", self.profile_name)
+                        }
+                    }
+                }
+                if ui.button(tr("Delete")).clicked() {
+                    match Profile::delete(&self.profile_name) {
+                        Ok(()) => {
+                            self.profiles = Profile::list();
+                            self.profile_name.clear();
+                        }
+                        Err(e) => {
+                            log::error!("Could not delete profile {:?}: {e}", self.profile_name)
+                        }
+                    }
+                }
+
+                let help_button = ui.button("?").on_hover_text(tr("Help (F1)"));
+                if accessible_name(ui, help_button, &tr("Help")).clicked() {
+                    self.show_help = !self.show_help;
+                }
+                let settings_button = ui.button("⚙").on_hover_text(tr("Settings"));
+                if accessible_name(ui, settings_button, &tr("Settings")).clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+            });
+        });
+    }
+}
+
+impl MainApp {
+    /// Free-text entry for the whole interval, applied on Enter.
+    /// What the interval fields add up to, and the click rate that gives.
+    fn interval_preview_ui(&self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let millis = self.click_interval.total_millis();
+        let seconds = format!("{:.3}", millis as f64 / 1000.0);
+        let seconds = seconds.trim_end_matches('0').trim_end_matches('.');
+        let rate = if millis == 0 {
+            tr("as fast as possible")
+        } else {
+            let clicks = self.click_options.click_type.clicks() as f64;
+            tr_args(
+                "≈ {rate} clicks per second",
+                &[("rate", &format!("{:.2}", clicks * 1000.0 / millis as f64))],
+            )
+            .into()
+        };
+        row(ui, rtl, |ui| {
+            ui.weak(tr_args(
+                "= {seconds} s total, {rate}",
+                &[("seconds", &seconds), ("rate", &rate)],
+            ));
+            if self.click_options.burst.enabled || self.click_options.ramp.enabled {
+                ui.weak(tr("(replaced by the burst or ramp)"));
+            }
+        });
+    }
+
+    fn interval_text_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        row(ui, rtl, |ui| {
+            let label = ui.label(tr("Or type"));
+            let response = ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.interval_text)
+                        .hint_text(tr("e.g. 250ms, 1,5s or 2m30s"))
+                        .desired_width(160.0),
+                )
+                .labelled_by(label.id);
+            if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                match duration::parse(&self.interval_text, MILLISECOND) {
+                    Some(interval) => {
+                        self.interval_text_invalid = false;
+                        self.click_interval = ClickInterval {
+                            adaptive: self.click_interval.adaptive,
+                            precise: self.click_interval.precise,
+                            ..ClickInterval::from_millis(interval.as_millis() as usize)
+                        };
+                        send_command(
+                            &self.tx_command,
+                            Config::ClickInterval(self.click_interval).into(),
+                        );
+                    }
+                    None => self.interval_text_invalid = true,
+                }
+            }
+            if self.interval_text_invalid {
+                ui.colored_label(ui.visuals().error_fg_color, tr("Not a duration"));
+            }
+        });
+    }
+
+    /// A dropdown of common and saved intervals, and saving the current one
+    /// under a name.
+    fn interval_presets_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let mut picked = None;
+        let mut removed = None;
+        row(ui, rtl, |ui| {
+            egui::ComboBox::from_label(tr("Presets"))
+                .selected_text(tr("Pick an interval"))
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    for millis in presets::BUILT_IN_MS {
+                        let name = humantime::format_duration(Duration::from_millis(millis as u64));
+                        if ui.selectable_label(false, name.to_string()).clicked() {
+                            picked = Some(millis);
+                        }
+                    }
+                    for cps in presets::BUILT_IN_CPS {
+                        let name = tr_args("{cps} clicks per second", &[("cps", &cps)]);
+                        if ui.selectable_label(false, name).clicked() {
+                            picked = Some(1000 / cps);
+                        }
+                    }
+                    if !self.settings.interval_presets.is_empty() {
+                        ui.separator();
+                    }
+                    for (i, preset) in self.settings.interval_presets.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.selectable_label(false, preset.name.as_str()).clicked() {
+                                picked = Some(preset.interval_ms);
+                            }
+                            if ui
+                                .small_button("×")
+                                .on_hover_text(tr("Delete this preset"))
+                                .clicked()
+                            {
+                                removed = Some(i);
+                            }
+                        });
+                    }
+                });
+
+            let label = ui.label(tr("Save as"));
+            let response = ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.preset_name)
+                        .hint_text(tr("Preset name"))
+                        .desired_width(100.0),
+                )
+                .labelled_by(label.id);
+            let name = self.preset_name.trim().to_owned();
+            let entered =
+                response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+            if (ui
+                .add_enabled(!name.is_empty(), egui::Button::new(tr("Save")))
+                .clicked()
+                || entered)
+                && !name.is_empty()
+            {
+                presets::save(
+                    &mut self.settings.interval_presets,
+                    IntervalPreset {
+                        name,
+                        interval_ms: self.click_interval.total_millis(),
+                    },
+                );
+                self.preset_name.clear();
+                if let Err(e) = self.settings.save() {
+                    log::error!("Could not save settings: {e}");
+                }
+            }
+        });
+
+        if let Some(millis) = picked {
+            self.click_interval = ClickInterval {
+                adaptive: self.click_interval.adaptive,
+                precise: self.click_interval.precise,
+                ..ClickInterval::from_millis(millis)
+            };
+            send_command(
+                &self.tx_command,
+                Config::ClickInterval(self.click_interval).into(),
+            );
+        }
+        if let Some(i) = removed {
+            self.settings.interval_presets.remove(i);
+            if let Err(e) = self.settings.save() {
+                log::error!("Could not save settings: {e}");
+            }
+        }
+    }
+
+    /// Clicks, elapsed time and effective rate of the current or last run.
+    fn status_ui(&mut self, ctx: &egui::Context) {
+        let rtl = self.settings.is_right_to_left();
+        let stats = stats::current();
+        egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
+            self.state_summary_ui(ui);
+            if let Some(failure) = supervisor::failure() {
+                failure_ui(ui, rtl, &failure);
+            }
+            if clicker::is_running() && elevation::is_foreground_blocked() {
+                self.elevation_warning_ui(ui);
+            }
+            row(ui, rtl, |ui| {
+                let elapsed = stats.elapsed().as_secs();
+                ui.label(tr_args("Clicks: {clicks}", &[("clicks", &stats.clicks)]));
+                ui.separator();
+                let time = format!(
+                    "{:02}:{:02}:{:02}",
+                    elapsed / 3600,
+                    elapsed / 60 % 60,
+                    elapsed % 60
+                );
+                ui.label(tr_args("Elapsed: {time}", &[("time", &time)]));
+                ui.separator();
+                ui.label(tr_args(
+                    "{rate} CPS",
+                    &[("rate", &format!("{:.1}", stats.clicks_per_second()))],
+                ));
+            });
+
+            egui::CollapsingHeader::new(tr("Activity")).show(ui, timeline_ui);
+
+            egui::CollapsingHeader::new(tr("Statistics")).show(ui, |ui| {
+                let interval_ms = self.click_interval.total_millis();
+                let target = (interval_ms > 0).then(|| {
+                    self.click_options.click_type.clicks() as f64 * 1000.0 / interval_ms as f64
+                });
+                Plot::new("cps_history")
+                    .height(100.0)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .include_y(0.0)
+                    .legend(Legend::default())
+                    .show(ui, |plot_ui| {
+                        plot_ui
+                            .line(Line::new(stats::rate_history()).name(tr("Clicks per second")));
+                        if let Some(target) = target {
+                            plot_ui.hline(HLine::new(target).name(tr("Target")));
+                        }
+                    });
+
+                let runs = stats::runs();
+                row(ui, rtl, |ui| {
+                    let label = ui.label(tr("Runs"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.runs_csv_path)
+                            .hint_text(tr("Path to a CSV file"))
+                            .desired_width(160.0),
+                    )
+                    .labelled_by(label.id);
+                    let path = PathBuf::from(self.runs_csv_path.trim());
+                    let can_export = !self.runs_csv_path.trim().is_empty() && !runs.is_empty();
+                    if ui
+                        .add_enabled(can_export, egui::Button::new(tr("Export runs")))
+                        .on_hover_text(tr_args(
+                            "Start and end, clicks, average and target clicks per second and \
+                             the settings of the {runs} runs since the app started, as CSV",
+                            &[("runs", &runs.len())],
+                        ))
+                        .clicked()
+                    {
+                        self.runs_csv_status = Some(match stats::write_csv(&path, &runs) {
+                            Ok(()) => tr_args("Exported to {path}", &[("path", &path.display())]),
+                            Err(e) => tr_args("Could not export: {error}", &[("error", &e)]),
+                        });
+                    }
+                    if ui
+                        .button(tr("History"))
+                        .on_hover_text(tr("Runs from earlier sessions too"))
+                        .clicked()
+                    {
+                        self.show_history = !self.show_history;
+                        if self.show_history {
+                            self.history = history::load();
+                        }
+                    }
+                });
+                if let Some(status) = &self.runs_csv_status {
+                    ui.label(status);
+                }
+            });
+
+            egui::CollapsingHeader::new(tr("Latency test")).show(ui, latency_ui);
+
+            egui::CollapsingHeader::new(tr("Calibration")).show(ui, |ui| self.calibration_ui(ui));
+
+            egui::CollapsingHeader::new(tr("Log")).show(ui, log_ui);
+        });
+    }
+
+    /// Runs a calibration of the current interval and offers to make up for
+    /// the lateness it measured.
+    fn calibration_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(tr(
+            "Runs the clicker's timing for three seconds without clicking, to measure how \
+             late this machine wakes up for a click and how fast it can click.",
+        ));
+        let running = calibration::is_running();
+        if ui
+            .add_enabled(
+                !running && !clicker::is_running(),
+                egui::Button::new(tr("Calibrate")),
+            )
+            .clicked()
+        {
+            calibration::start(
+                Duration::from_millis(self.click_interval.total_millis() as u64),
+                self.click_interval.precise,
+                self.click_options,
+            );
+        }
+        if running {
+            ui.spinner();
+            ui.ctx().request_repaint();
+        }
+
+        if let Some(result) = calibration::last_result() {
+            ui.label(result.to_string());
+            if result.max_cps.is_finite() {
+                ui.label(tr_args(
+                    "These settings click at most about {cps} times per second here.",
+                    &[("cps", &format!("{:.0}", result.max_cps))],
+                ));
+            }
+            let offset = result.mean_lateness.as_micros() as usize;
+            if offset != self.settings.wake_offset_us
+                && ui
+                    .button(tr("Make up for the lateness"))
+                    .on_hover_text(tr("Ends every wait this much early and spins the rest, \
+                         keeping a CPU core busy for that long"))
+                    .clicked()
+            {
+                self.set_wake_offset(offset);
+            }
+        }
+        if self.settings.wake_offset_us > 0 {
+            ui.horizontal(|ui| {
+                ui.label(tr_args(
+                    "Waits end {offset} ms early.",
+                    &[(
+                        "offset",
+                        &format!("{:.2}", self.settings.wake_offset_us as f64 / 1000.0),
+                    )],
+                ));
+                if ui.button(tr("Reset")).clicked() {
+                    self.set_wake_offset(0);
+                }
+            });
+        }
+    }
+
+    fn set_wake_offset(&mut self, offset_us: usize) {
+        self.settings.wake_offset_us = offset_us;
+        clicker::set_wake_offset(Duration::from_micros(offset_us as u64));
+        if let Err(e) = self.settings.save() {
+            log::error!("Could not save settings: {e}");
+        }
+    }
+
+    /// Warns that the focused window runs as Administrator and offers to
+    /// relaunch the app elevated so its clicks get through.
+    fn elevation_warning_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        row(ui, rtl, |ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                tr("The focused window runs as Administrator and likely ignores these clicks."),
+            );
+            if ui.button(tr("Relaunch as Administrator")).clicked() {
+                if elevation::relaunch_as_admin() {
+                    self.exit_requested = true;
+                } else {
+                    log::warn!("The app was not relaunched as Administrator");
+                }
+            }
+        });
+    }
+
+    /// Whether the clicker is clicking, and how often, with what and where,
+    /// as the engine has it.
+    fn state_summary_ui(&self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let running = clicker::is_running();
+        let state = if let Some(start) = clicker::scheduled_start() {
+            tr_args("Starting in {time}", &[("time", &countdown(start))]).into()
+        } else if clicker::is_paused() {
+            tr("Paused")
+        } else if running && clicker::is_preview() {
+            tr("Previewing")
+        } else if running {
+            tr("Running")
+        } else {
+            tr("Stopped")
+        };
+
+        let options = &self.click_options;
+        let input = match options.key {
+            Some(key) => tr_args("Key {key}", &[("key", &keys::name(key))]),
+            None => {
+                let button = match options.mouse_button {
+                    MouseButton::Left => tr("Left"),
+                    MouseButton::Middle => tr("Middle"),
+                    MouseButton::Right => tr("Right"),
+                };
+                let click_type = match options.click_type {
+                    ClickType::Single => tr("Single"),
+                    ClickType::Double => tr("Double"),
+                    ClickType::Triple => tr("Triple"),
+                    ClickType::Custom(clicks) => tr_n(
+                        "{clicks} click",
+                        "{clicks} clicks",
+                        clicks as u64,
+                        &[("clicks", &clicks)],
+                    )
+                    .into(),
+                };
+                format!("{button}, {click_type}")
+            }
+        };
+
+        // The resolved point while a run is clicking one, the set-up
+        // position otherwise.
+        let target: Cow<str> = match (
+            running.then(clicker::last_target).flatten(),
+            self.click_position,
+        ) {
+            (Some((x, y)), _) => format!("{x}, {y}").into(),
+            (None, ClickPosition::CurrentCursorPosition) => tr("At the cursor"),
+            // Only known at click time.
+            (
+                None,
+                ClickPosition::Custom {
+                    x,
+                    y,
+                    origin: Origin::ActiveWindow,
+                },
+            ) => tr_args("{x}, {y} in the active window", &[("x", &x), ("y", &y)]).into(),
+            (None, ClickPosition::Custom { x, y, origin }) => {
+                let (x, y) = origin.to_global(x, y);
+                format!("{x}, {y}").into()
+            }
+            (None, ClickPosition::Grid(_)) => tr("Grid"),
+            (None, ClickPosition::Path(_)) => tr("Path"),
+            (None, ClickPosition::Window { .. }) => tr("In a window"),
+        };
+
+        row(ui, rtl, |ui| {
+            if running {
+                ui.strong(state);
+            } else {
+                ui.label(state);
+            }
+            ui.separator();
+            ui.label(tr_args(
+                "Every {interval} ms",
+                &[("interval", &self.click_interval.total_millis())],
+            ));
+            ui.separator();
+            ui.label(input);
+            ui.separator();
+            ui.label(target);
+        });
+    }
+
+    /// The "run for" limit, with a countdown while a limited run is going.
+    fn run_limit_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        row(ui, rtl, |ui| {
+            let mut changed = ui
+                .checkbox(&mut self.run_limit.enabled, tr("Run for"))
+                .changed();
+            for (value, name) in [
+                (&mut self.run_limit.hours, tr("Hours")),
+                (&mut self.run_limit.minutes, tr("Minutes")),
+                (&mut self.run_limit.seconds, tr("Seconds")),
+            ] {
+                let response = ui.add_enabled(self.run_limit.enabled, egui::DragValue::new(value));
+                let label = ui.label(name);
+                changed |= response.labelled_by(label.id).changed();
+            }
+            if changed {
+                send_command(
+                    &self.tx_command,
+                    Config::RunLimit(self.run_limit.duration()).into(),
+                );
+            }
+
+            if let Some(deadline) = clicker::run_deadline() {
+                ui.label(tr_args("{time} left", &[("time", &countdown(deadline))]));
+            }
+        });
+    }
+
+    /// The buttons successive clicks cycle through, in place of the mouse
+    /// button while turned on.
+    fn button_rotation_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let rotation = &mut self.click_options.button_rotation;
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            changed |= ui
+                .checkbox(&mut rotation.enabled, tr("Alternate buttons"))
+                .on_hover_text(tr(
+                    "Each click uses the next of these buttons, starting over after the last",
+                ))
+                .changed();
+            let enabled = rotation.enabled;
+            let len = rotation.buttons().len();
+            for (i, button) in rotation.buttons[..len].iter_mut().enumerate() {
+                let response = ui
+                    .add_enabled_ui(enabled, |ui| {
+                        egui::ComboBox::from_id_source(("button_rotation", i))
+                            .selected_text(match *button {
+                                MouseButton::Left => tr("Left"),
+                                MouseButton::Middle => tr("Middle"),
+                                MouseButton::Right => tr("Right"),
+                            })
+                            .show_ui(ui, |ui| {
+                                for (value, name) in [
+                                    (MouseButton::Left, "Left"),
+                                    (MouseButton::Middle, "Middle"),
+                                    (MouseButton::Right, "Right"),
+                                ] {
+                                    changed |=
+                                        ui.selectable_value(button, value, tr(name)).changed();
+                                }
+                            })
+                    })
+                    .inner
+                    .response;
+                accessible_name(
+                    ui,
+                    response,
+                    &tr_args("Button {number}", &[("number", &(i + 1).to_string())]),
+                );
+            }
+            if ui
+                .add_enabled(enabled && len > 1, egui::Button::new("−"))
+                .on_hover_text(tr("Remove the last button"))
+                .clicked()
+            {
+                rotation.len = len - 1;
+                changed = true;
+            }
+            if ui
+                .add_enabled(
+                    enabled && len < ButtonRotation::MAX_BUTTONS,
+                    egui::Button::new("+"),
+                )
+                .on_hover_text(tr("Add a button"))
+                .clicked()
+            {
+                // Repeat the button two before, so left, right becomes
+                // left, right, left.
+                rotation.buttons[len] = rotation.buttons[len.saturating_sub(2)];
+                rotation.len = len + 1;
+                changed = true;
+            }
+        });
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::ClickOptions(Box::new(self.click_options)).into(),
+            );
+        }
+    }
+
+    /// The chances each click draws its button by, with the share of each.
+    fn button_weights_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let rotating = self.click_options.button_rotation.enabled;
+        let weights = &mut self.click_options.button_weights;
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            changed |= ui
+                .checkbox(&mut weights.enabled, tr("Random buttons"))
+                .on_hover_text(tr(
+                    "Each click draws its button by these weights, unless buttons alternate",
+                ))
+                .changed();
+            let enabled = weights.enabled && !rotating;
+            let shares = weights.weights().map(|(_, weight)| weights.percent(weight));
+            for ((value, name), share) in [
+                (&mut weights.left, tr("Left")),
+                (&mut weights.middle, tr("Middle")),
+                (&mut weights.right, tr("Right")),
+            ]
+            .into_iter()
+            .zip(shares)
+            {
+                let response =
+                    ui.add_enabled(enabled, egui::DragValue::new(value).clamp_range(0..=1000));
+                let label = ui.label(format!("{name} ({share:.0}%)"));
+                changed |= response.labelled_by(label.id).changed();
+            }
+        });
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::ClickOptions(Box::new(self.click_options)).into(),
+            );
+        }
+    }
+
+    /// The start schedule, with a countdown while a run waits for it.
+    fn start_schedule_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        row(ui, rtl, |ui| {
+            if schedule::editor_ui(ui, &mut self.start_schedule) {
+                send_command(
+                    &self.tx_command,
+                    Config::StartSchedule(self.start_schedule).into(),
+                );
+            }
+
+            if let Some(start) = clicker::scheduled_start() {
+                ui.label(tr_args(
+                    "Starting in {time}",
+                    &[("time", &countdown(start))],
+                ));
+            }
+        });
+    }
+
+    /// A button that fires one configured click after a short delay, giving
+    /// the user time to move the cursor where it should land.
+    fn test_click_ui(&mut self, ui: &mut egui::Ui) {
+        if self
+            .test_click
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            self.test_click = None;
+        }
+
+        let pending = self.test_click.is_some();
+        let text = if pending {
+            tr("Clicking in 2 s…")
+        } else {
+            tr("Test click")
+        };
+        if ui.add_enabled(!pending, egui::Button::new(text)).clicked() {
+            self.test_click = Some(clicker::test_click(
+                self.click_options,
+                self.click_position,
+                TEST_CLICK_DELAY,
+            ));
+        }
+    }
+
+    /// What the custom position is measured from. Switching between the
+    /// desktop and a screen keeps the target where it is on the desktop;
+    /// the active window keeps the numbers, since it is only known at click
+    /// time. Screens are told apart by name, so a position stays on its
+    /// screen when the screens are rearranged.
+    fn origin_ui(&mut self, ui: &mut egui::Ui) {
+        let ClickPosition::Custom { x, y, origin } = self.click_position else {
+            return;
+        };
+        let monitors = monitor::list();
+        let name = |index: usize, monitor: &monitor::Monitor| {
+            format!(
+                "{}: {} ({}×{})",
+                index + 1,
+                monitor.name,
+                monitor.width,
+                monitor.height
+            )
+        };
+
+        let mut selected = origin;
+        egui::ComboBox::from_label(tr("Relative to"))
+            .selected_text(match selected {
+                Origin::Desktop => tr("Whole desktop"),
+                Origin::Monitor(id) => match monitors.iter().position(|m| m.id() == id) {
+                    Some(index) => name(index, &monitors[index]).into(),
+                    None => tr_args("{name}: disconnected", &[("name", &id.name())]).into(),
+                },
+                Origin::ActiveWindow => tr("Active window"),
+            })
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.selectable_value(&mut selected, Origin::Desktop, tr("Whole desktop"));
+                if monitors.len() > 1 || matches!(origin, Origin::Monitor(_)) {
+                    for (index, m) in monitors.iter().enumerate() {
+                        ui.selectable_value(&mut selected, Origin::Monitor(m.id()), name(index, m));
+                    }
+                }
+                ui.selectable_value(&mut selected, Origin::ActiveWindow, tr("Active window"))
+                    .on_hover_text(tr("The window that has the focus when the click happens"));
+            });
+        if selected != origin {
+            let (x, y) = match (origin, selected) {
+                (Origin::ActiveWindow, _) | (_, Origin::ActiveWindow) => (x, y),
+                _ => {
+                    let (global_x, global_y) = origin.to_global(x, y);
+                    selected.to_local(global_x, global_y)
+                }
+            };
+            self.click_position = ClickPosition::Custom {
+                x,
+                y,
+                origin: selected,
+            };
+            send_command(
+                &self.tx_command,
+                Config::ClickPosition(self.click_position).into(),
+            );
+        }
+    }
+
+    /// A button that starts dragging out the area the grid covers on
+    /// screen, and fits the grid to it once done. The clicker is shielded
+    /// off until then.
+    fn select_region_ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(selected) = region::take_result() {
+            self.update_shield();
+            if let (Some(region), ClickPosition::Grid(grid)) = (selected, &mut self.click_position)
+            {
+                grid.fit(region);
+                send_command(
+                    &self.tx_command,
+                    Config::ClickPosition(self.click_position).into(),
+                );
+            }
+        }
+
+        let selecting = region::is_selecting();
+        let text = if selecting {
+            tr("Selecting…")
+        } else {
+            tr("Select on screen")
+        };
+        let mut response = ui
+            .add_enabled(
+                overlay::is_available() && !self.is_picking(),
+                egui::Button::new(text),
+            )
+            .on_hover_text(tr(
+                "Drag a rectangle over the screen to spread the grid's cells over it.",
+            ));
+        if !overlay::is_available() {
+            response = response.on_disabled_hover_text(tr(
+                "Needs a transparent overlay, which this system does not support.",
+            ));
+        }
+        if response.clicked() {
+            region::start();
+            self.update_shield();
+        }
+    }
+
+    /// A button that starts picking the custom position, see
+    /// [`pick::start`]: the window steps aside and the next left click
+    /// anywhere on screen sets the position, a right click or Esc cancels.
+    /// The clicker is shielded off until then.
+    fn pick_position_ui(&mut self, ui: &mut egui::Ui) {
+        let origin = match self.click_position {
+            ClickPosition::Custom { origin, .. } => origin,
+            _ => Origin::Desktop,
+        };
+        if let Some(picked) = pick::take_result() {
+            self.update_shield();
+            if let Some((x, y)) = picked {
+                self.click_position = ClickPosition::Custom { x, y, origin };
+                send_command(
+                    &self.tx_command,
+                    Config::ClickPosition(self.click_position).into(),
+                );
+            }
+        }
+
+        let response = ui
+            .add_enabled(!self.is_picking(), egui::Button::new(tr("Pick on screen")))
+            .on_hover_text(tr(
+                "Hides this window until you click the target anywhere on screen. \
+                 Right-click or Esc cancels.",
+            ));
+        if response.clicked() {
+            pick::start(origin);
+            self.update_shield();
+        }
+    }
+
+    /// The app's own windows a run must not click into, by title, and
+    /// whether each is open. The sandbox is left out, as it is there to be
+    /// clicked.
+    fn dialogs(&self) -> [(&'static str, bool); 9] {
+        [
+            ("Quick setup", self.wizard.is_some()),
+            ("What's new", self.show_whats_new),
+            ("Settings", self.show_settings),
+            ("Help", self.show_help),
+            ("Click Sequence", self.show_sequence),
+            ("History", self.show_history),
+            ("Macro Library", self.show_library),
+            ("Jobs", self.show_jobs),
+            ("Script", self.show_script),
+        ]
+    }
+
+    /// Pauses a run while any of [`Self::dialogs`] is open, so the clicker
+    /// cannot click into them, and resumes it once they are all closed. A
+    /// run paused or stopped meanwhile by other means stays so.
+    fn pause_for_dialogs(&mut self) {
+        let open = self.dialogs().into_iter().find(|(_, open)| *open);
+        let dialog_open = open.is_some();
+        if let Some((title, _)) = open.filter(|_| !self.dialog_paused && clicker::is_running()) {
+            log::info!("Pausing while the {title} window is open");
+            send_command(&self.tx_command, Command::Pause);
+            self.dialog_paused = true;
+        } else if !dialog_open && self.dialog_paused {
+            self.dialog_paused = false;
+            if clicker::is_paused() {
+                send_command(&self.tx_command, Command::Resume);
+            }
+        }
+    }
+
+    fn is_picking(&self) -> bool {
+        region::is_selecting()
+            || pick::is_picking()
+            || self.picking_pixel.is_some()
+            || self.picking_job.is_some()
+    }
+
+    /// Keeps the clicker shielded off while anything is being picked on
+    /// screen.
+    fn update_shield(&self) {
+        clicker::set_shield(self.is_picking());
+    }
+
+    /// The pixel trigger, with an eyedropper that takes the position and
+    /// color from the next click on screen.
+    fn pixel_trigger_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        if let Some(picked) = self.picking_pixel.as_ref().and_then(poll_pick) {
+            self.picking_pixel = None;
+            self.update_shield();
+            if let Some((x, y)) = picked {
+                let (x, y) = (x.max(0) as usize, y.max(0) as usize);
+                let trigger = &mut self.click_options.pixel_trigger;
+                trigger.x = x;
+                trigger.y = y;
+                match screen::pixel(x, y) {
+                    Some(color) => trigger.color = color,
+                    None => log::error!("Could not read the screen at ({x}, {y})"),
+                }
+                send_command(
+                    &self.tx_command,
+                    Config::ClickOptions(Box::new(self.click_options)).into(),
+                );
+            }
+        }
+
+        let picking = self.picking_pixel.is_some();
+        let can_pick = !self.is_picking();
+        let trigger = &mut self.click_options.pixel_trigger;
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            changed |= ui
+                .checkbox(&mut trigger.enabled, tr("Only click while pixel at"))
+                .changed();
+            let label = ui.label(tr("X: "));
+            changed |= ui
+                .add_enabled(trigger.enabled, DragValue::new(&mut trigger.x))
+                .labelled_by(label.id)
+                .changed();
+            let label = ui.label(tr("Y: "));
+            changed |= ui
+                .add_enabled(trigger.enabled, DragValue::new(&mut trigger.y))
+                .labelled_by(label.id)
+                .changed();
+        });
+        row(ui, rtl, |ui| {
+            ui.add_enabled_ui(trigger.enabled, |ui| {
+                egui::ComboBox::from_id_source("pixel_condition")
+                    .selected_text(match trigger.condition {
+                        PixelCondition::Matches => tr("matches"),
+                        PixelCondition::Differs => tr("differs from"),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        changed |= ui
+                            .selectable_value(
+                                &mut trigger.condition,
+                                PixelCondition::Matches,
+                                tr("matches"),
+                            )
+                            .changed();
+                        changed |= ui
+                            .selectable_value(
+                                &mut trigger.condition,
+                                PixelCondition::Differs,
+                                tr("differs from"),
+                            )
+                            .changed();
+                    });
+                let response = ui.color_edit_button_srgb(&mut trigger.color);
+                changed |= accessible_name(ui, response, &tr("Pixel color")).changed();
+                let label = ui.label("±");
+                changed |= ui
+                    .add(DragValue::new(&mut trigger.tolerance))
+                    .labelled_by(label.id)
+                    .changed();
+
+                let text = if picking {
+                    tr("Click the pixel…")
+                } else {
+                    "🖊".into()
+                };
+                let response = ui
+                    .add_enabled(can_pick, egui::Button::new(text))
+                    .on_hover_text(tr(
+                        "Take the position and color from the next click on screen",
+                    ));
+                if accessible_name(ui, response, &tr("Eyedropper")).clicked() {
+                    self.picking_pixel = Some(listener::subscribe());
+                }
+            });
+        });
+        if trigger.enabled && !screen::is_supported() {
+            ui.label(tr("The screen cannot be read on this platform."));
+        } else if trigger.enabled && cfg!(target_os = "linux") {
+            ui.label(tr("Reading the screen needs ImageMagick's import."));
+        }
+
+        self.update_shield();
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::ClickOptions(Box::new(self.click_options)).into(),
+            );
+        }
+    }
+
+    /// Picks another application's window and a position in its client area.
+    fn target_window_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let is_window = matches!(self.click_position, ClickPosition::Window { .. });
+        if ui.radio(is_window, tr("Window")).clicked() && !is_window {
+            self.target_windows = target::list();
+            let id = self.target_windows.first().map_or(0, |window| window.id);
+            self.click_position = ClickPosition::Window { id, x: 0, y: 0 };
+            send_command(
+                &self.tx_command,
+                Config::ClickPosition(self.click_position).into(),
+            );
+        }
+        let ClickPosition::Window { id, x, y } = &mut self.click_position else {
+            return;
+        };
+
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            let selected = self
+                .target_windows
+                .iter()
+                .find(|window| window.id == *id)
+                .map_or_else(|| tr("No window"), |window| window.title.clone().into());
+            egui::ComboBox::from_id_source("target_window")
+                .selected_text(selected)
+                .width(220.0)
+                .show_ui(ui, |ui| {
+                    for window in &self.target_windows {
+                        changed |= ui.selectable_value(id, window.id, &window.title).changed();
+                    }
+                });
+            let refresh = ui.button("⟳").on_hover_text(tr("Refresh the window list"));
+            if accessible_name(ui, refresh, &tr("Refresh the window list")).clicked() {
+                self.target_windows = target::list();
+            }
+        });
+        row(ui, rtl, |ui| {
+            ui.label(tr("Inside the window at"));
+            let label = ui.label(tr("X: "));
+            changed |= ui.add(DragValue::new(x)).labelled_by(label.id).changed();
+            let label = ui.label(tr("Y: "));
+            changed |= ui.add(DragValue::new(y)).labelled_by(label.id).changed();
+        });
+        if self.target_windows.is_empty() {
+            ui.label(tr(
+                "No windows found. Linux needs xprop and xwininfo (x11-utils).",
+            ));
+        }
+
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::ClickPosition(self.click_position).into(),
+            );
+        }
+    }
+
+    /// A PNG to find on screen, clicked at its center or required to be
+    /// visible for the clicker to click.
+    fn image_target_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            let mut enabled = self.image_target.is_some();
+            if ui.checkbox(&mut enabled, tr("Find image")).changed() {
+                self.image_target = enabled.then(|| ImageTarget {
+                    path: self.image_path.trim().into(),
+                    ..ImageTarget::default()
+                });
+                changed = true;
+            }
+            let response = ui.add_enabled(
+                enabled,
+                egui::TextEdit::singleline(&mut self.image_path)
+                    .hint_text(tr("Path to a PNG"))
+                    .desired_width(200.0),
+            );
+            accessible_name(ui, response, &tr("Image path"));
+            if ui
+                .add_enabled(enabled, egui::Button::new(tr("Load")))
+                .clicked()
+            {
+                if let Some(target) = &mut self.image_target {
+                    target.path = self.image_path.trim().into();
+                    changed = true;
+                }
+            }
+        });
+
+        if let Some(target) = &mut self.image_target {
+            row(ui, rtl, |ui| {
+                egui::ComboBox::from_id_source("image_mode")
+                    .selected_text(match target.mode {
+                        ImageMode::ClickCenter => tr("Click its center"),
+                        ImageMode::WhileVisible => tr("Only click while visible"),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap = Some(false);
+                        changed |= ui
+                            .selectable_value(
+                                &mut target.mode,
+                                ImageMode::ClickCenter,
+                                tr("Click its center"),
+                            )
+                            .changed();
+                        changed |= ui
+                            .selectable_value(
+                                &mut target.mode,
+                                ImageMode::WhileVisible,
+                                tr("Only click while visible"),
+                            )
+                            .changed();
+                    });
+                let label = ui.label(tr("Confidence"));
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut target.threshold_pct)
+                            .clamp_range(1..=100)
+                            .suffix(" %"),
+                    )
+                    .labelled_by(label.id)
+                    .changed();
+            });
+            match template::last_match() {
+                Some(found) => ui.label(tr_args(
+                    "Found at ({x}, {y}), {score} % match",
+                    &[
+                        ("x", &found.x),
+                        ("y", &found.y),
+                        ("score", &format!("{:.0}", found.score * 100.0)),
+                    ],
+                )),
+                None if !screen::is_supported() => {
+                    ui.label(tr("The screen cannot be read on this platform."))
+                }
+                None => ui.label(tr("Not on screen")),
+            };
+        }
+
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::ImageTarget(self.image_target.clone()).into(),
+            );
+        }
+    }
+
+    /// Restricts clicking to while a chosen application is focused.
+    fn focus_guard_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            let mut enabled = self.focus_guard.is_some();
+            if ui
+                .checkbox(&mut enabled, tr("Only click while focused:"))
+                .changed()
+            {
+                self.focus_guard = enabled.then(String::new);
+                if enabled {
+                    self.target_windows = target::list();
+                }
+                changed = true;
+            }
+            let Some(title) = &mut self.focus_guard else {
+                return;
+            };
+            let response = ui.add(
+                egui::TextEdit::singleline(title)
+                    .hint_text(tr("Window title contains"))
+                    .desired_width(160.0),
+            );
+            changed |= accessible_name(ui, response, &tr("Focused window title")).changed();
+            egui::ComboBox::from_id_source("focus_guard_window")
+                .selected_text(tr("Pick"))
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    for window in &self.target_windows {
+                        if ui.selectable_label(false, &window.title).clicked() {
+                            title.clone_from(&window.title);
+                            changed = true;
+                        }
+                    }
+                });
+        });
+        if self.focus_guard.is_some() && !target::is_foreground_supported() {
+            ui.label(tr("The focused window cannot be checked on this platform."));
+        }
+
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::FocusGuard(self.focus_guard.clone()).into(),
+            );
+        }
+    }
+
+    fn humanize_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let humanize = &mut self.click_options.humanize;
+        let mut changed = false;
+        row(ui, rtl, |ui| {
+            changed |= ui.checkbox(&mut humanize.enabled, tr("Humanize")).changed();
+            let label = ui.label(tr("timing ±"));
+            let response = ui.add_enabled(
+                humanize.enabled,
+                egui::DragValue::new(&mut humanize.timing_jitter_pct)
+                    .clamp_range(0..=100)
+                    .suffix(" %"),
+            );
+            changed |= response.labelled_by(label.id).changed();
+            let label = ui.label(tr("position ±"));
+            let response = ui.add_enabled(
+                humanize.enabled,
+                egui::DragValue::new(&mut humanize.position_jitter_px).suffix(" px"),
+            );
+            changed |= response.labelled_by(label.id).changed();
+        });
+        row(ui, rtl, |ui| {
+            let response = ui.add_enabled(
+                humanize.enabled,
+                egui::DragValue::new(&mut humanize.pause_chance_pct)
+                    .clamp_range(0..=100)
+                    .suffix(" %"),
+            );
+            let label = ui.label(tr("chance of a pause of"));
+            changed |= response.labelled_by(label.id).changed();
+            let response = ui.add_enabled(
+                humanize.enabled,
+                egui::DragValue::new(&mut humanize.pause_ms).suffix(" ms"),
+            );
+            changed |= accessible_name(ui, response, &tr("Pause duration")).changed();
+        });
+        if changed {
+            send_command(
+                &self.tx_command,
+                Config::ClickOptions(Box::new(self.click_options)).into(),
+            );
+        }
+    }
+
+    /// Toggle for the keyboard auto-presser and the widget that captures the
+    /// key to repeat.
+    /// The key or mouse button for `activation`, captured from the next
+    /// press anywhere. Returns `true` if it was turned off.
+    /// The controller button bound to each action. Returns `true` if any
+    /// changed.
+    #[cfg(feature = "gamepad")]
+    fn gamepad_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let rtl = self.settings.is_right_to_left();
+        let mut changed = false;
+        let gamepad = &mut self.settings.gamepad;
+        for (id, label, button) in [
+            (
+                "gamepad_toggle",
+                tr("Controller: start/stop"),
+                &mut gamepad.toggle,
+            ),
+            ("gamepad_start", tr("Controller: start"), &mut gamepad.start),
+            ("gamepad_stop", tr("Controller: stop"), &mut gamepad.stop),
+            ("gamepad_pause", tr("Controller: pause"), &mut gamepad.pause),
+        ] {
+            row(ui, rtl, |ui| {
+                let before = button.clone();
+                let label = ui.label(label);
+                egui::ComboBox::from_id_source(id)
+                    .selected_text(button.clone().map_or_else(|| tr("None"), Cow::from))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(button, None, tr("None"));
+                        for (_, name) in crate::gamepad::BUTTONS {
+                            ui.selectable_value(button, Some(name.to_owned()), name);
+                        }
+                    })
+                    .response
+                    .labelled_by(label.id);
+                changed |= *button != before;
+            });
+        }
+        if changed {
+            crate::gamepad::configure(&self.settings.gamepad);
+        }
+        changed
+    }
+
+    fn trigger_ui(&mut self, ui: &mut egui::Ui, activation: Activation) -> bool {
+        let (text, hover, name) = match activation {
+            Activation::Hold => (
+                tr("Click only while holding"),
+                tr(
+                    "Starts the clicker while the key or mouse button is held down, \
+                 wherever the focus is, and stops it on release.",
+                ),
+                tr("Key or button to hold"),
+            ),
+            Activation::Toggle => (
+                tr("Start and stop with"),
+                tr(
+                    "Each press starts or stops the clicker, wherever the focus is. \
+                 A combination such as Ctrl+Alt+S stays clear of games, and a \
+                 spare mouse button, e.g. a side button, keeps your hand off \
+                 the keyboard.",
+                ),
+                tr("Key or button to toggle with"),
+            ),
+        };
+        let capturing = activation::capturing() == Some(activation);
+        let trigger = self.settings.trigger_mut(activation);
+        let mut enabled = trigger.is_some() || capturing;
+        let mut turned_off = false;
+        if ui
+            .checkbox(&mut enabled, text)
+            .on_hover_text(hover)
+            .changed()
+        {
+            if enabled {
+                activation::capture(activation);
+            } else {
+                *trigger = None;
+                activation::set_trigger(activation, None);
+                turned_off = true;
+            }
+        }
+        let text = match *trigger {
+            _ if capturing => tr("Press a key, combination or mouse button…"),
+            Some(trigger) => trigger.label().into(),
+            None => tr("None"),
+        };
+        let response = ui.add_enabled(enabled, egui::Button::new(text));
+        if accessible_name(ui, response, &name).clicked() {
+            activation::capture(activation);
+        }
+        if trigger.is_some() && ui.button(tr("Clear")).clicked() {
+            *trigger = None;
+            activation::set_trigger(activation, None);
+            turned_off = true;
+        }
+        if capturing {
+            ui.ctx().request_repaint();
+        } else if let Some(bound) = *trigger {
+            let mut clashes: Vec<Cow<str>> =
+                hotkeys::conflicts(bound, hotkeys::Action::Activation(activation))
+                    .into_iter()
+                    .map(hotkeys::Action::name)
+                    .collect();
+            if self.click_options.key.map(Input::Key) == Some(bound.input) {
+                clashes.push(tr("the key the clicker presses"));
+            }
+            if !clashes.is_empty() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    tr_args("⚠ Also {actions}", &[("actions", &clashes.join(", "))]),
+                )
+                .on_hover_text(tr(
+                    "The trigger takes the key over from the window and quick slot keys.",
+                ));
+            }
+        }
+        turned_off
+    }
+
+    fn key_ui(&mut self, ui: &mut egui::Ui) {
+        let mut use_key = self.click_options.key.is_some() || self.capturing_key;
+        if ui.checkbox(&mut use_key, tr("Press key instead")).changed() {
+            self.capturing_key = use_key;
+            if !use_key && self.click_options.key.take().is_some() {
+                send_command(
+                    &self.tx_command,
+                    Config::ClickOptions(Box::new(self.click_options)).into(),
+                );
+            }
+        }
+
+        let text = match self.click_options.key {
+            _ if self.capturing_key => tr("Press the key you want repeated…"),
+            Some(key) => keys::name(key).into(),
+            None => tr("None"),
+        };
+        let response = ui.add_enabled(use_key, egui::Button::new(text));
+        if accessible_name(ui, response, &tr("Key to repeat")).clicked() {
+            self.capturing_key = true;
+        }
+
+        if self.capturing_key {
+            let pressed = ui.input(|input| {
+                input.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key, pressed: true, ..
+                    } => keys::from_egui(*key),
+                    _ => None,
+                })
+            });
+            if let Some(key) = pressed {
+                self.capturing_key = false;
+                self.click_options.key = Some(key);
+                send_command(
+                    &self.tx_command,
+                    Config::ClickOptions(Box::new(self.click_options)).into(),
+                );
+            }
+        }
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context) {
+        let rtl = self.settings.is_right_to_left();
+        self.dwell.set_click_options(self.click_options);
+        overlay::set_target(match self.click_position {
+            // The active window would be looked up every frame.
+            ClickPosition::Custom { x, y, origin }
+                if self.settings.target_crosshair && origin != Origin::ActiveWindow =>
+            {
+                Some(origin.to_global(x, y))
+            }
+            _ => None,
+        });
+
+        if self.collapsed {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.label(taskbar::status().0);
+            });
+            return;
+        }
+
+        if self.permission_missing {
+            egui::CentralPanel::default().show(ctx, |ui| self.permission_ui(ui));
+            return;
+        }
+
+        self.settings_ui(ctx);
+        self.whats_new_ui(ctx);
+        self.help_ui(ctx);
+        self.confirm_start_ui(ctx);
+        self.wizard_ui(ctx);
+        self.pause_for_dialogs();
+        self.status_ui(ctx);
+        self.sandbox_ui(ctx);
+
+        if self.settings.simple_mode {
+            egui::CentralPanel::default().show(ctx, |ui| self.simple_ui(ui));
+            return;
+        }
+
+        self.sequence_ui(ctx);
+        self.script_ui(ctx);
+        self.jobs_ui(ctx);
+        self.library_ui(ctx);
+        self.history_ui(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // Scrolls when the window is resized smaller than the options.
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    column(ui, rtl, |ui| {
+                        self.help_hint_ui(ui);
+                        self.profiles_ui(ui);
+
+                        ui.group(|ui| {
+                            ui.heading(tr("Click Interval"));
+                            row(ui, rtl, |ui| {
+                                // Dragging or typing applies on release, so a run
+                                // never picks up a half-entered interval.
+                                let mut editing = false;
+                                let mut finished = false;
+                                for (value, unit, name) in [
+                                    (&mut self.click_interval.hours, HOUR, tr("Hours")),
+                                    (&mut self.click_interval.minutes, MINUTE, tr("Minutes")),
+                                    (&mut self.click_interval.seconds, SECOND, tr("Seconds")),
+                                    (
+                                        &mut self.click_interval.milliseconds,
+                                        MILLISECOND,
+                                        tr("Milliseconds"),
+                                    ),
+                                ] {
+                                    let response = ui.add(
+                                        egui::DragValue::new(value)
+                                            .custom_parser(duration::drag_value_parser(unit)),
+                                    );
+                                    let label = ui.label(name);
+                                    let response = response.labelled_by(label.id);
+                                    let active = response.dragged() || response.has_focus();
+                                    editing |= active;
+                                    finished |= response.drag_released()
+                                        || response.lost_focus()
+                                        || (response.changed() && !active);
+                                }
+                                if finished {
+                                    send_command(
+                                        &self.tx_command,
+                                        Config::ClickInterval(self.click_interval).into(),
+                                    );
+                                } else if editing && clicker::is_running() {
+                                    ui.weak(tr("Applies on release"));
+                                }
+                            });
+                            self.interval_presets_ui(ui);
+                            self.interval_preview_ui(ui);
+                            self.interval_text_ui(ui);
+                            row(ui, rtl, |ui| {
+                                if ui
+                                    .checkbox(
+                                        &mut self.click_interval.adaptive,
+                                        tr("Hold the rate"),
+                                    )
+                                    .on_hover_text(tr(
+                                        "Clicks that take longer than the interval are made up by \
+                                 starting the next ones right away, so a run keeps its clicks per \
+                                 second on average.",
+                                    ))
+                                    .changed()
+                                {
+                                    send_command(
+                                        &self.tx_command,
+                                        Config::ClickInterval(self.click_interval).into(),
+                                    );
+                                }
+                                if ui
+                                    .checkbox(
+                                        &mut self.click_interval.precise,
+                                        tr("Precision mode"),
+                                    )
+                                    .on_hover_text(tr(
+                                        "Spins through the end of each wait for accurate short \
+                                 intervals. Keeps a CPU core busy while clicking.",
+                                    ))
+                                    .changed()
+                                {
+                                    send_command(
+                                        &self.tx_command,
+                                        Config::ClickInterval(self.click_interval).into(),
+                                    );
+                                }
+                            });
+                            self.run_limit_ui(ui);
+                            self.start_schedule_ui(ui);
+                        });
+
+                        row(ui, rtl, |ui| {
+                            ui.group(|ui| {
+                                ui.set_width(ui.available_width());
+                                column(ui, rtl, |ui| {
+                                    ui.heading(tr("Click Options"));
+                                    egui::ComboBox::from_label(tr("Mouse Button"))
+                                        .selected_text(match self.click_options.mouse_button {
+                                            MouseButton::Left => tr("Left"),
+                                            MouseButton::Middle => tr("Middle"),
+                                            MouseButton::Right => tr("Right"),
+                                        })
+                                        .show_ui(ui, |ui| {
+                                            ui.style_mut().wrap = Some(false);
+                                            ui.set_min_width(60.0);
+                                            if ui
+                                                .selectable_value(
+                                                    &mut self.click_options.mouse_button,
+                                                    MouseButton::Left,
+                                                    tr("Left"),
+                                                )
+                                                .changed()
+                                            {
+                                                send_command(
+                                                    &self.tx_command,
+                                                    Config::ClickOptions(Box::new(self.click_options)).into(),
+                                                );
+                                            };
+                                            if ui
+                                                .selectable_value(
+                                                    &mut self.click_options.mouse_button,
+                                                    MouseButton::Middle,
+                                                    tr("Middle"),
+                                                )
+                                                .changed()
+                                            {
+                                                send_command(
+                                                    &self.tx_command,
+                                                    Config::ClickOptions(Box::new(self.click_options)).into(),
+                                                );
+                                            };
+                                            if ui
+                                                .selectable_value(
+                                                    &mut self.click_options.mouse_button,
+                                                    MouseButton::Right,
+                                                    tr("Right"),
+                                                )
+                                                .changed()
+                                            {
+                                                send_command(
+                                                    &self.tx_command,
+                                                    Config::ClickOptions(Box::new(self.click_options)).into(),
+                                                );
+                                            };
+                                        });
+                                    self.button_rotation_ui(ui);
+                                    self.button_weights_ui(ui);
+
+                                    if click_type_ui(
+                                        ui,
+                                        rtl,
+                                        egui::ComboBox::from_label(tr("Click Type")),
+                                        &mut self.click_options.click_type,
+                                    ) {
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickOptions(Box::new(self.click_options)).into(),
+                                        );
+                                    }
+
+                                    row(ui, rtl, |ui| {
+                                        let response = ui
+                                            .add(
+                                                egui::DragValue::new(
+                                                    &mut self.click_options.burst_delay_ms,
+                                                )
+                                                .suffix(" ms"),
+                                            )
+                                            .on_hover_text(tr(
+                                                "Some apps only take a double click with a gap \
+                                         between the clicks, e.g. 50 ms. It has to stay \
+                                         below the system's double-click time.",
+                                            ));
+                                        let label =
+                                            ui.label(tr("Gap between double-click presses"));
+                                        if response.labelled_by(label.id).changed() {
+                                            send_command(
+                                                &self.tx_command,
+                                                Config::ClickOptions(Box::new(self.click_options)).into(),
+                                            );
+                                        }
+                                    });
+
+                                    row(ui, rtl, |ui| {
+                                        let response = ui
+                                    .add(
+                                        egui::DragValue::new(&mut self.click_options.press_ms)
+                                            .clamp_range(0..=10_000)
+                                            .suffix(" ms"),
+                                    )
+                                    .on_hover_text(tr(
+                                        "Time between pressing and releasing the button, for \
+                                         apps that ignore instant clicks.",
+                                    ));
+                                        let label = ui.label(tr("Hold each click"));
+                                        if response.labelled_by(label.id).changed() {
+                                            send_command(
+                                                &self.tx_command,
+                                                Config::ClickOptions(Box::new(self.click_options)).into(),
+                                            );
+                                        }
+                                    });
+
+                                    if ui
+                                        .checkbox(
+                                            &mut self.click_options.wiggle,
+                                            tr("Wiggle the cursor before each click"),
+                                        )
+                                        .on_hover_text(tr(
+                                            "Moves the cursor 1-3 px away and back right before \
+                                     clicking, for apps that ignore clicks without motion.",
+                                        ))
+                                        .changed()
+                                    {
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickOptions(Box::new(self.click_options)).into(),
+                                        );
+                                    }
+
+                                    row(ui, rtl, |ui| {
+                                        let mut changed = ui
+                                            .checkbox(
+                                                &mut self.click_options.hold_mode,
+                                                tr("Hold button for"),
+                                            )
+                                            .changed();
+                                        let response = ui.add_enabled(
+                                            self.click_options.hold_mode,
+                                            egui::DragValue::new(&mut self.click_options.hold_ms)
+                                                .suffix(" ms"),
+                                        );
+                                        changed |=
+                                            accessible_name(ui, response, &tr("Hold duration"))
+                                                .changed();
+                                        ui.label(tr("(0 = until Stop)"));
+                                        if changed {
+                                            send_command(
+                                                &self.tx_command,
+                                                Config::ClickOptions(Box::new(self.click_options)).into(),
+                                            );
+                                        }
+                                    });
+
+                                    row(ui, rtl, |ui| self.key_ui(ui));
+                                    self.humanize_ui(ui);
+                                    self.pixel_trigger_ui(ui);
+                                    if drag::editor_ui(ui, &mut self.click_options.drag) {
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickOptions(Box::new(self.click_options)).into(),
+                                        );
+                                    }
+                                    if burst::editor_ui(ui, &mut self.click_options.burst) {
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickOptions(Box::new(self.click_options)).into(),
+                                        );
+                                    }
+                                    if ramp::editor_ui(ui, &mut self.click_options.ramp) {
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickOptions(Box::new(self.click_options)).into(),
+                                        );
+                                    }
+                                    if reminder::editor_ui(ui, &mut self.click_options.reminder) {
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickOptions(Box::new(self.click_options)).into(),
+                                        );
+                                    }
+                                });
+                            });
+                        });
+
+                        ui.group(|ui| {
+                            ui.set_width(ui.available_width());
+                            ui.heading(tr("Click Position"));
+
+                            if ui
+                                .radio_value(
+                                    &mut self.click_position,
+                                    ClickPosition::CurrentCursorPosition,
+                                    tr("Current Cursor Position"),
+                                )
+                                .changed()
+                            {
+                                send_command(
+                                    &self.tx_command,
+                                    Config::ClickPosition(self.click_position).into(),
+                                );
+                            };
+
+                            row(ui, rtl, |ui| {
+                                ui.radio_value(
+                                    &mut self.click_position,
+                                    ClickPosition::Custom {
+                                        x: 0,
+                                        y: 0,
+                                        origin: Origin::Desktop,
+                                    },
+                                    tr("Custom"),
+                                );
+                                if let ClickPosition::Custom { x, y, origin } =
+                                    &mut self.click_position.clone()
+                                {
+                                    let label = ui.label(tr("X: "));
+                                    if ui
+                                        .add(egui::DragValue::new(x))
+                                        .labelled_by(label.id)
+                                        .changed()
+                                    {
+                                        self.click_position = ClickPosition::Custom {
+                                            x: *x,
+                                            y: *y,
+                                            origin: *origin,
+                                        };
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickPosition(self.click_position).into(),
+                                        );
+                                    };
+                                    let label = ui.label(tr("Y: "));
+                                    if ui.add(DragValue::new(y)).labelled_by(label.id).changed() {
+                                        self.click_position = ClickPosition::Custom {
+                                            x: *x,
+                                            y: *y,
+                                            origin: *origin,
+                                        };
+                                        send_command(
+                                            &self.tx_command,
+                                            Config::ClickPosition(self.click_position).into(),
+                                        );
+                                    };
+                                } else {
+                                    let label = ui.label(tr("X: "));
+                                    ui.add_enabled(false, egui::DragValue::new(&mut 0))
+                                        .labelled_by(label.id);
+                                    let label = ui.label(tr("Y: "));
+                                    ui.add_enabled(false, DragValue::new(&mut 0))
+                                        .labelled_by(label.id);
+                                }
+
+                                self.pick_position_ui(ui);
+
+                                let sequence_label = tr_args(
+                                    "Sequence ({steps})",
+                                    &[("steps", &self.click_sequence.len())],
+                                );
+                                if ui.button(sequence_label).clicked() {
+                                    self.show_sequence = !self.show_sequence;
+                                }
+
+                                self.test_click_ui(ui);
+
+                                if ui
+                                    .button(tr("Sandbox"))
+                                    .on_hover_text(tr(
+                                        "Buttons and a text box to try the clicker on",
+                                    ))
+                                    .clicked()
+                                {
+                                    self.show_sandbox = !self.show_sandbox;
+                                }
+                            });
+                            self.origin_ui(ui);
+
+                            let is_grid = matches!(self.click_position, ClickPosition::Grid(_));
+                            if ui.radio(is_grid, tr("Grid")).clicked() && !is_grid {
+                                self.click_position = ClickPosition::Grid(GridPattern::default());
+                                send_command(
+                                    &self.tx_command,
+                                    Config::ClickPosition(self.click_position).into(),
+                                );
+                            }
+                            if let ClickPosition::Grid(grid) = &mut self.click_position {
+                                if pattern::editor_ui(ui, grid) {
+                                    send_command(
+                                        &self.tx_command,
+                                        Config::ClickPosition(self.click_position).into(),
+                                    );
+                                }
+                                self.select_region_ui(ui);
+                            }
+
+                            let is_path = matches!(self.click_position, ClickPosition::Path(_));
+                            if ui.radio(is_path, tr("Path")).clicked() && !is_path {
+                                self.click_position = ClickPosition::Path(PathPattern::default());
+                                send_command(
+                                    &self.tx_command,
+                                    Config::ClickPosition(self.click_position).into(),
+                                );
+                            }
+                            if let ClickPosition::Path(path) = &mut self.click_position {
+                                if path::editor_ui(ui, path) {
+                                    send_command(
+                                        &self.tx_command,
+                                        Config::ClickPosition(self.click_position).into(),
+                                    );
+                                }
+                            }
+
+                            self.target_window_ui(ui);
+                            self.image_target_ui(ui);
+                            self.focus_guard_ui(ui);
+                        });
+
+                        ui.group(|ui| {
+                            ui.set_width(ui.available_width());
+                            ui.heading(tr("Type Text"));
+                            if row(ui, rtl, |ui| typing::editor_ui(ui, &mut self.typing_text)) {
+                                send_command(
+                                    &self.tx_command,
+                                    Config::TypingText(self.typing_text.clone()).into(),
+                                );
+                            }
+                        });
+
+                        ui.group(|ui| {
+                            ui.set_width(ui.available_width());
+                            ui.heading(tr("Key Rotation"));
+                            if rotation::editor_ui(
+                                ui,
+                                &mut self.key_rotation,
+                                &mut self.capturing_rotation_key,
+                            ) {
+                                send_command(
+                                    &self.tx_command,
+                                    Config::KeyRotation(self.key_rotation.clone()).into(),
+                                );
+                            }
+                        });
+
+                        self.recorder_ui(ui);
+                        self.slots_ui(ui);
+                        self.watch_ui(ui);
+
+                        row(ui, rtl, |ui| {
+                            let mut preview = clicker::is_preview();
+                            if ui
+                                .checkbox(&mut preview, tr("Preview"))
+                                .on_hover_text(tr(
+                                    "Runs without clicking and marks on screen where and when each click would land",
+                                ))
+                                .changed()
+                            {
+                                clicker::set_preview(preview);
+                            }
+                        });
+                        row(ui, rtl, |ui| {
+                            if create_button(ui, &tr("Start (F6)")).clicked() {
+                                self.request_start(Command::Start);
+                            }
+                            ui.add_space(52.5);
+
+                            if create_button(ui, &tr("Stop (F7)")).clicked() {
+                                send_command(&self.tx_command, Command::Stop);
+                            }
+                            ui.add_space(52.5);
+
+                            if create_button(ui, &tr("Toggle (F8)")).clicked() {
+                                self.request_start(Command::Toggle);
+                            }
+                        });
+                        row(ui, rtl, |ui| {
+                            if clicker::is_paused() {
+                                if create_button(ui, &tr("Resume (F9)")).clicked() {
+                                    send_command(&self.tx_command, Command::Resume);
+                                }
+                                ui.label(tr("Paused, Stop ends the run"));
+                            } else {
+                                let response = ui.add_enabled_ui(clicker::is_running(), |ui| {
+                                    create_button(ui, &tr("Pause (F9)")).on_hover_text(tr(
+                                        "Halts clicking but keeps the clicks and time so far",
+                                    ))
+                                });
+                                if response.inner.clicked() {
+                                    send_command(&self.tx_command, Command::Pause);
+                                }
+                            }
+                        });
+
+                        if let Some(report) = accuracy::last_report() {
+                            ui.label(tr_args("Last run: {report}", &[("report", &report)]));
+                        }
+                    });
+                });
+        });
+    }
+}
+
+/// A click type combo box, with a click count field next to it when
+/// `Custom` is selected. Returns `true` if the click type changed.
+pub fn click_type_ui(
+    ui: &mut egui::Ui,
+    rtl: bool,
+    combo: egui::ComboBox,
+    click_type: &mut ClickType,
+) -> bool {
+    let mut changed = false;
+    row(ui, rtl, |ui| {
+        combo
+            .selected_text(match *click_type {
+                ClickType::Single => tr("Single"),
+                ClickType::Double => tr("Double"),
+                ClickType::Triple => tr("Triple"),
+                ClickType::Custom(_) => tr("Custom"),
+            })
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.set_min_width(60.0);
+                for (option, name) in [
+                    (ClickType::Single, tr("Single")),
+                    (ClickType::Double, tr("Double")),
+                    (ClickType::Triple, tr("Triple")),
+                ] {
+                    changed |= ui.selectable_value(click_type, option, name).changed();
+                }
+                let is_custom = matches!(click_type, ClickType::Custom(_));
+                if ui.selectable_label(is_custom, tr("Custom")).clicked() && !is_custom {
+                    *click_type = ClickType::Custom(click_type.clicks());
+                    changed = true;
                 }
             });
+
+        if let ClickType::Custom(clicks) = click_type {
+            let response = ui.add(
+                egui::DragValue::new(clicks)
+                    .clamp_range(1..=ClickType::MAX_CLICKS)
+                    .suffix(tr(" clicks")),
+            );
+            changed |= accessible_name(ui, response, &tr("Clicks per trigger")).changed();
+        }
+    });
+    changed
+}
+
+/// A strip of the engine's activity over the last minute, newest on the
+/// right.
+/// The app's latest messages, newest at the bottom, e.g. input that could
+/// not be sent.
+fn log_ui(ui: &mut egui::Ui) {
+    let entries = logging::entries();
+    ui.horizontal(|ui| {
+        if let Some(path) = logging::log_path() {
+            ui.weak(tr_args(
+                "Also written to {path}",
+                &[("path", &path.display())],
+            ));
+        }
+        if ui
+            .add_enabled(!entries.is_empty(), egui::Button::new(tr("Clear")))
+            .clicked()
+        {
+            logging::clear();
+        }
+    });
+    if entries.is_empty() {
+        ui.weak(tr("Nothing logged yet"));
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .max_height(150.0)
+        .stick_to_bottom(true)
+        .show(ui, |ui| {
+            for entry in entries {
+                let color = match entry.level {
+                    log::Level::Error => ui.visuals().error_fg_color,
+                    log::Level::Warn => ui.visuals().warn_fg_color,
+                    _ => ui.visuals().text_color(),
+                };
+                let time = humantime::format_rfc3339_seconds(entry.time).to_string();
+                // Only the time of day, in UTC like the log file.
+                let time = time.get(11..19).unwrap_or(&time);
+                ui.colored_label(color, format!("{time} {}", entry.message));
+            }
+        });
+}
+
+/// Runs the latency test and lists its reports, one per input backend.
+fn latency_ui(ui: &mut egui::Ui) {
+    ui.label(tr(
+        "Measures how long a click takes from being sent until the system reports it. \
+         The test clicks where the cursor is, so keep it over this window.",
+    ));
+    let running = latency::is_running();
+    if ui
+        .add_enabled(!running, egui::Button::new(tr("Test the current backend")))
+        .on_hover_text(tr(
+            "Switch backends, e.g. with the second pointer, and test again to compare",
+        ))
+        .clicked()
+    {
+        latency::start();
+    }
+    if running {
+        ui.spinner();
+        ui.ctx().request_repaint();
+    }
+    for report in latency::reports() {
+        ui.label(report.to_string());
+    }
+}
+
+fn timeline_ui(ui: &mut egui::Ui) {
+    let (rect, response) =
+        ui.allocate_exact_size(Vec2::new(ui.available_width(), 32.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+    let x_for = |ago: Duration| {
+        rect.right() - rect.width() * (ago.as_secs_f32() / timeline::WINDOW.as_secs_f32())
+    };
+    let mid = rect.center().y;
+    for (activity, ago) in timeline::recent() {
+        let x = x_for(ago);
+        match activity {
+            Activity::Click => painter.vline(
+                x,
+                (mid - 6.0)..=(mid + 6.0),
+                egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE),
+            ),
+            Activity::Trigger { met } => painter.circle_filled(
+                egui::pos2(x, rect.bottom() - 4.0),
+                2.0,
+                if met {
+                    egui::Color32::GREEN
+                } else {
+                    egui::Color32::GRAY
+                },
+            ),
+            Activity::Pause | Activity::Suspend => painter.vline(
+                x,
+                rect.y_range(),
+                egui::Stroke::new(2.0, egui::Color32::YELLOW),
+            ),
+            Activity::Resume => painter.vline(
+                x,
+                rect.y_range(),
+                egui::Stroke::new(1.0, egui::Color32::YELLOW),
+            ),
+            Activity::Start | Activity::Stop | Activity::Finish => painter.vline(
+                x,
+                rect.y_range(),
+                egui::Stroke::new(1.0, ui.visuals().text_color()),
+            ),
+            Activity::Safety | Activity::TargetClosed => painter.vline(
+                x,
+                rect.y_range(),
+                egui::Stroke::new(3.0, egui::Color32::RED),
+            ),
+            Activity::Reminder => painter.circle_filled(
+                egui::pos2(x, rect.top() + 4.0),
+                2.0,
+                ui.visuals().text_color(),
+            ),
+        }
+    }
+    accessible_name(ui, response, &tr("Activity timeline")).on_hover_text(tr(
+        "Last 60 s, newest on the right: clicks (blue), pauses (yellow), trigger \
+         checks (green when met), start and stop, safety stops (red), reminders \
+         (dots at the top).",
+    ));
+}
+
+/// Polls a pick armed with [`listener::subscribe`]: `Some(Some(position))`
+/// once the user left-clicks somewhere, `Some(None)` if another button
+/// cancelled it, `None` while it is still waiting.
+fn poll_pick(rx_events: &Receiver<rdev::Event>) -> Option<Option<(i32, i32)>> {
+    rx_events
+        .try_iter()
+        .find_map(|event| match event.event_type {
+            rdev::EventType::ButtonPress(rdev::Button::Left) => {
+                Some(listener::cursor_position().map(|(x, y)| (x as i32, y as i32)))
+            }
+            rdev::EventType::ButtonPress(_) => Some(None),
+            _ => None,
+        })
+}
+
+/// Lays widgets out in a row, starting from the right edge when `rtl` is set.
+fn row<R>(ui: &mut egui::Ui, rtl: bool, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> R {
+    if rtl {
+        ui.with_layout(
+            egui::Layout::right_to_left(egui::Align::Center),
+            add_contents,
+        )
+        .inner
+    } else {
+        ui.horizontal(add_contents).inner
+    }
+}
+
+/// Time left until `instant` as `MM:SS`, or `H:MM:SS` from an hour up.
+pub fn countdown(instant: Instant) -> String {
+    let left = instant.saturating_duration_since(Instant::now()).as_secs();
+    if left >= 3600 {
+        format!("{}:{:02}:{:02}", left / 3600, left / 60 % 60, left % 60)
+    } else {
+        format!("{:02}:{:02}", left / 60, left % 60)
+    }
+}
+
+/// Lays widgets out in a column, aligned to the right edge when `rtl` is set.
+fn column<R>(ui: &mut egui::Ui, rtl: bool, add_contents: impl FnOnce(&mut egui::Ui) -> R) -> R {
+    if rtl {
+        ui.with_layout(egui::Layout::top_down(egui::Align::Max), add_contents)
+            .inner
+    } else {
+        ui.vertical(add_contents).inner
+    }
+}
+
+/// Hands `command` to the clicker thread. If the thread is gone the command
+/// is dropped with a warning rather than taking the window down with it.
+/// What went wrong with the clicking loop, with the error under a
+/// collapsible header.
+fn failure_ui(ui: &mut egui::Ui, rtl: bool, failure: &Failure) {
+    let (summary, error) = match failure {
+        Failure::Restarted { error, restarts } => (
+            tr_n(
+                "The clicker failed and was restarted with the same settings ({restarts} \
+                 time so far). Start it again to keep clicking.",
+                "The clicker failed and was restarted with the same settings ({restarts} \
+                 times so far). Start it again to keep clicking.",
+                *restarts as u64,
+                &[("restarts", restarts)],
+            )
+            .into(),
+            error,
+        ),
+        Failure::Stopped { error } => (
+            tr("The clicker failed repeatedly and was left stopped."),
+            error,
+        ),
+        Failure::Input { error } => (
+            tr("Clicks and keys are not getting through. The app may lack the permission to send input."),
+            error,
+        ),
+    };
+    row(ui, rtl, |ui| {
+        ui.colored_label(ui.visuals().error_fg_color, summary);
+        if matches!(failure, Failure::Stopped { .. }) && ui.button(tr("Restart")).clicked() {
+            supervisor::request_restart();
+        }
+        if ui.button(tr("Dismiss")).clicked() {
+            supervisor::dismiss();
+        }
+    });
+    egui::CollapsingHeader::new(tr("Error details"))
+        .id_source("failure_details")
+        .show(ui, |ui| {
+            ui.label(error);
         });
+}
+
+fn send_command(tx_command: &Sender<Command>, command: Command) {
+    if tx_command.send(command).is_err() {
+        log::warn!("The clicker engine is not running");
     }
 }
 
+/// Overrides the name screen readers announce for `response`, for widgets
+/// whose visible text is an icon.
+pub fn accessible_name(ui: &egui::Ui, response: Response, name: &str) -> Response {
+    ui.ctx()
+        .accesskit_node_builder(response.id, |node| node.set_name(name));
+    response
+}
+
 fn create_button(ui: &mut egui::Ui, text: &str) -> Response {
     let mut button = egui::Button::new(text);
     button = button.min_size(Vec2 { x: 100.0, y: 40.0 });
@@ -0,0 +1,46 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{burst::Burst, i18n::tr};
+
+/// Editor for the burst size and both intervals. Returns `true` if the
+/// burst changed.
+pub fn editor_ui(ui: &mut egui::Ui, burst: &mut Burst) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut burst.enabled, tr("Click in bursts of"))
+            .on_hover_text(tr("Replaces the click interval while enabled"))
+            .changed();
+        ui.add_enabled_ui(burst.enabled, |ui| {
+            let size = ui.add(DragValue::new(&mut burst.size).clamp_range(1..=1000));
+            changed |= accessible_name(ui, size, &tr("Clicks per burst")).changed();
+            ui.label(tr("clicks"));
+        });
+    });
+    ui.add_enabled_ui(burst.enabled, |ui| {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("Between clicks"));
+            changed |= ui
+                .add(
+                    DragValue::new(&mut burst.inner_ms)
+                        .clamp_range(0..=60_000)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id)
+                .changed();
+            let label = ui.label(tr("between bursts"));
+            changed |= ui
+                .add(
+                    DragValue::new(&mut burst.outer_ms)
+                        .clamp_range(0..=3_600_000)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id)
+                .changed();
+        });
+    });
+
+    changed
+}
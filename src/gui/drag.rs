@@ -0,0 +1,41 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{drag::Drag, i18n::tr};
+
+/// Editor for the drag's points and duration. Returns `true` if the drag
+/// changed.
+pub fn editor_ui(ui: &mut egui::Ui, drag: &mut Drag) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        changed |= ui.checkbox(&mut drag.enabled, tr("Drag from")).changed();
+        ui.add_enabled_ui(drag.enabled, |ui| {
+            let from_x = ui.add(DragValue::new(&mut drag.from_x));
+            changed |= accessible_name(ui, from_x, &tr("Drag start X")).changed();
+            let from_y = ui.add(DragValue::new(&mut drag.from_y));
+            changed |= accessible_name(ui, from_y, &tr("Drag start Y")).changed();
+            ui.label(tr("to"));
+            let to_x = ui.add(DragValue::new(&mut drag.to_x));
+            changed |= accessible_name(ui, to_x, &tr("Drag end X")).changed();
+            let to_y = ui.add(DragValue::new(&mut drag.to_y));
+            changed |= accessible_name(ui, to_y, &tr("Drag end Y")).changed();
+        });
+    });
+    ui.add_enabled_ui(drag.enabled, |ui| {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("over"));
+            changed |= ui
+                .add(
+                    DragValue::new(&mut drag.duration_ms)
+                        .clamp_range(0..=60_000)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id)
+                .changed();
+            ui.label(tr("instead of clicking"));
+        });
+    });
+
+    changed
+}
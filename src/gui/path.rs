@@ -0,0 +1,64 @@
+use egui::{ComboBox, DragValue};
+
+use super::accessible_name;
+use crate::{
+    i18n::tr,
+    path::{PathKind, PathPattern},
+};
+
+/// Editor for the path's shape and step count. Returns `true` if the path
+/// changed.
+pub fn editor_ui(ui: &mut egui::Ui, path: &mut PathPattern) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ComboBox::from_id_source("path_kind")
+            .selected_text(match path.kind {
+                PathKind::Line => tr("Line"),
+                PathKind::Circle => tr("Circle"),
+            })
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                changed |= ui
+                    .selectable_value(&mut path.kind, PathKind::Line, tr("Line"))
+                    .changed();
+                changed |= ui
+                    .selectable_value(&mut path.kind, PathKind::Circle, tr("Circle"))
+                    .changed();
+            });
+
+        ui.label(match path.kind {
+            PathKind::Line => tr("from"),
+            PathKind::Circle => tr("around"),
+        });
+        let x = ui.add(DragValue::new(&mut path.x));
+        changed |= accessible_name(ui, x, &tr("Path X")).changed();
+        let y = ui.add(DragValue::new(&mut path.y));
+        changed |= accessible_name(ui, y, &tr("Path Y")).changed();
+
+        match path.kind {
+            PathKind::Line => {
+                ui.label(tr("to"));
+                let end_x = ui.add(DragValue::new(&mut path.end_x));
+                changed |= accessible_name(ui, end_x, &tr("Path end X")).changed();
+                let end_y = ui.add(DragValue::new(&mut path.end_y));
+                changed |= accessible_name(ui, end_y, &tr("Path end Y")).changed();
+            }
+            PathKind::Circle => {
+                ui.label(tr("radius"));
+                let radius = ui.add(DragValue::new(&mut path.radius).suffix(" px"));
+                changed |= accessible_name(ui, radius, &tr("Circle radius")).changed();
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        let label = ui.label(tr("Steps: "));
+        changed |= ui
+            .add(DragValue::new(&mut path.steps).clamp_range(1..=1000))
+            .labelled_by(label.id)
+            .changed();
+    });
+
+    changed
+}
@@ -0,0 +1,41 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{i18n::tr, pattern::GridPattern};
+
+/// Editor for the grid's origin, size and spacing. Returns `true` if the
+/// grid changed.
+pub fn editor_ui(ui: &mut egui::Ui, grid: &mut GridPattern) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label(tr("Top-left"));
+        let label = ui.label(tr("X: "));
+        changed |= ui
+            .add(DragValue::new(&mut grid.x))
+            .labelled_by(label.id)
+            .changed();
+        let label = ui.label(tr("Y: "));
+        changed |= ui
+            .add(DragValue::new(&mut grid.y))
+            .labelled_by(label.id)
+            .changed();
+    });
+
+    ui.horizontal(|ui| {
+        let columns = ui.add(DragValue::new(&mut grid.columns).clamp_range(1..=100));
+        changed |= accessible_name(ui, columns, &tr("Grid columns")).changed();
+        ui.label("×");
+        let rows = ui.add(DragValue::new(&mut grid.rows).clamp_range(1..=100));
+        changed |= accessible_name(ui, rows, &tr("Grid rows")).changed();
+        ui.label(tr("cells, spacing"));
+
+        let spacing_x = ui.add(DragValue::new(&mut grid.spacing_x).suffix(" px"));
+        changed |= accessible_name(ui, spacing_x, &tr("Horizontal cell spacing")).changed();
+        ui.label("×");
+        let spacing_y = ui.add(DragValue::new(&mut grid.spacing_y).suffix(" px"));
+        changed |= accessible_name(ui, spacing_y, &tr("Vertical cell spacing")).changed();
+    });
+
+    changed
+}
@@ -0,0 +1,59 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{
+    i18n::tr,
+    ramp::{Curve, Ramp},
+};
+
+/// Editor for the ramp's intervals, duration and curve. Returns `true` if
+/// the ramp changed.
+pub fn editor_ui(ui: &mut egui::Ui, ramp: &mut Ramp) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut ramp.enabled, tr("Ramp the interval from"))
+            .on_hover_text(tr("Replaces the click interval while enabled"))
+            .changed();
+        ui.add_enabled_ui(ramp.enabled, |ui| {
+            let from = ui.add(
+                DragValue::new(&mut ramp.from_ms)
+                    .clamp_range(0..=3_600_000)
+                    .suffix(" ms"),
+            );
+            changed |= accessible_name(ui, from, &tr("Start interval")).changed();
+            let label = ui.label(tr("to"));
+            changed |= ui
+                .add(
+                    DragValue::new(&mut ramp.to_ms)
+                        .clamp_range(0..=3_600_000)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id)
+                .changed();
+        });
+    });
+    ui.add_enabled_ui(ramp.enabled, |ui| {
+        ui.horizontal(|ui| {
+            let label = ui.label(tr("over"));
+            changed |= ui
+                .add(
+                    DragValue::new(&mut ramp.duration_secs)
+                        .clamp_range(0..=86_400)
+                        .suffix(" s"),
+                )
+                .labelled_by(label.id)
+                .changed();
+            changed |= ui
+                .radio_value(&mut ramp.curve, Curve::Linear, tr("linearly"))
+                .changed();
+            changed |= ui
+                .radio_value(&mut ramp.curve, Curve::Exponential, tr("exponentially"))
+                .changed();
+            ui.label(tr("then hold"));
+        });
+    });
+
+    changed
+}
@@ -0,0 +1,66 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{i18n::tr, reminder::Reminder};
+
+/// Editor for the reminder interval and quiet hours. Returns `true` if the
+/// reminder changed.
+pub fn editor_ui(ui: &mut egui::Ui, reminder: &mut Reminder) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut reminder.enabled, tr("Remind me it is running every"))
+            .on_hover_text(tr(
+                "As a notification, beep or announcement, whichever are turned on in the settings",
+            ))
+            .changed();
+        ui.add_enabled_ui(reminder.enabled, |ui| {
+            for minutes in [30, 60] {
+                changed |= ui
+                    .selectable_value(
+                        &mut reminder.every_minutes,
+                        minutes,
+                        format!("{minutes} min"),
+                    )
+                    .changed();
+            }
+            let response = ui.add(
+                DragValue::new(&mut reminder.every_minutes)
+                    .clamp_range(1..=24 * 60)
+                    .suffix(" min"),
+            );
+            changed |= accessible_name(ui, response, &tr("Minutes between reminders")).changed();
+        });
+    });
+    ui.add_enabled_ui(reminder.enabled, |ui| {
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut reminder.quiet_hours, tr("Quiet from"))
+                .changed();
+            ui.add_enabled_ui(reminder.quiet_hours, |ui| {
+                changed |= time_ui(ui, &mut reminder.quiet_from, &tr("Start of quiet hours"));
+                ui.label(tr("until"));
+                changed |= time_ui(ui, &mut reminder.quiet_until, &tr("End of quiet hours"));
+            });
+        });
+    });
+
+    changed
+}
+
+/// Hour and minute fields for `minutes` after midnight.
+fn time_ui(ui: &mut egui::Ui, minutes: &mut usize, name: &str) -> bool {
+    let (mut hour, mut minute) = (*minutes / 60 % 24, *minutes % 60);
+    let response = ui.add(DragValue::new(&mut hour).clamp_range(0..=23));
+    let mut changed = accessible_name(ui, response, name).changed();
+    ui.label(":");
+    let response = ui.add(
+        DragValue::new(&mut minute)
+            .clamp_range(0..=59)
+            .custom_formatter(|minute, _| format!("{minute:02}")),
+    );
+    changed |= accessible_name(ui, response, name).changed();
+    *minutes = hour * 60 + minute;
+    changed
+}
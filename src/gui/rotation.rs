@@ -0,0 +1,100 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{
+    i18n::{tr, tr_args},
+    keys,
+    rotation::{KeyRotation, KeyStep},
+};
+
+/// Editor for the rotation. `capturing` is the index of the key waiting to
+/// be pressed, the number of keys while one is being added. Returns `true`
+/// if anything changed.
+pub fn editor_ui(
+    ui: &mut egui::Ui,
+    rotation: &mut KeyRotation,
+    capturing: &mut Option<usize>,
+) -> bool {
+    let mut changed = false;
+    changed |= ui
+        .checkbox(&mut rotation.enabled, tr("Press these keys in turn"))
+        .on_hover_text(tr(
+            "One key per cycle instead of the key or button, starting over after the last",
+        ))
+        .changed();
+
+    let mut remove = None;
+    for (i, step) in rotation.steps.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}.", i + 1));
+            let text = match *capturing {
+                Some(index) if index == i => tr("Press a key…"),
+                _ => keys::name(step.key).into(),
+            };
+            let response = ui.button(text);
+            if accessible_name(
+                ui,
+                response,
+                &tr_args("Key {number}", &[("number", &(i + 1))]),
+            )
+            .clicked()
+            {
+                *capturing = Some(i);
+            }
+            let label = ui.label(tr("then wait"));
+            changed |= ui
+                .add(
+                    DragValue::new(&mut step.delay_ms)
+                        .clamp_range(0..=3_600_000)
+                        .suffix(" ms"),
+                )
+                .labelled_by(label.id)
+                .changed();
+            if ui.button(tr("Remove")).clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        rotation.steps.remove(i);
+        *capturing = None;
+        changed = true;
+    }
+
+    let adding = *capturing == Some(rotation.steps.len());
+    if ui
+        .button(if adding {
+            tr("Press a key…")
+        } else {
+            tr("Add key")
+        })
+        .clicked()
+    {
+        *capturing = Some(rotation.steps.len());
+    }
+
+    if let Some(i) = *capturing {
+        let pressed = ui.input(|input| {
+            input.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key, pressed: true, ..
+                } => keys::from_egui(*key),
+                _ => None,
+            })
+        });
+        if let Some(key) = pressed {
+            *capturing = None;
+            match rotation.steps.get_mut(i) {
+                Some(step) => step.key = key,
+                None => {
+                    // A new key waits as long as the one before it.
+                    let delay_ms = rotation.steps.last().map_or(1000, |step| step.delay_ms);
+                    rotation.steps.push(KeyStep { key, delay_ms });
+                }
+            }
+            changed = true;
+        }
+    }
+
+    changed
+}
@@ -0,0 +1,53 @@
+use egui::{ComboBox, DragValue};
+
+use super::accessible_name;
+use crate::{
+    i18n::{tr, tr_in},
+    schedule::{StartMode, StartSchedule},
+};
+
+/// Editor for the start schedule, laid out in the caller's row. Returns
+/// `true` if anything changed.
+pub fn editor_ui(ui: &mut egui::Ui, schedule: &mut StartSchedule) -> bool {
+    let mut changed = false;
+    let label = ui.label(tr_in("start schedule", "Start"));
+    ComboBox::from_id_source("start_mode")
+        .selected_text(match schedule.mode {
+            StartMode::Immediately => tr("immediately"),
+            StartMode::Countdown => tr("after"),
+            StartMode::At => tr("at"),
+        })
+        .width(90.0)
+        .show_ui(ui, |ui| {
+            for (mode, text) in [
+                (StartMode::Immediately, tr("immediately")),
+                (StartMode::Countdown, tr("after")),
+                (StartMode::At, tr("at")),
+            ] {
+                changed |= ui
+                    .selectable_value(&mut schedule.mode, mode, text)
+                    .changed();
+            }
+        })
+        .response
+        .labelled_by(label.id);
+
+    match schedule.mode {
+        StartMode::Immediately => {}
+        StartMode::Countdown => {
+            let response = ui.add(DragValue::new(&mut schedule.countdown_secs).suffix(" s"));
+            changed |= accessible_name(ui, response, &tr("Countdown in seconds")).changed();
+        }
+        StartMode::At => {
+            for (value, max, name) in [
+                (&mut schedule.hour, 23, tr("Hour")),
+                (&mut schedule.minute, 59, tr("Minute")),
+                (&mut schedule.second, 59, tr("Second")),
+            ] {
+                let response = ui.add(DragValue::new(value).clamp_range(0..=max));
+                changed |= accessible_name(ui, response, &name).changed();
+            }
+        }
+    }
+    changed
+}
@@ -0,0 +1,339 @@
+use std::{fmt, time::Duration};
+
+use egui::{ComboBox, DragValue, Grid};
+
+use super::{accessible_name, click_type_ui};
+use crate::{
+    engine::MouseButton,
+    i18n::{tr, tr_args},
+    keys,
+    sequence::{progress, ClickPoint, OnTimeout, SequenceLoop, StepAction},
+};
+
+/// Editor for the loop settings of a click sequence, with the loop and step
+/// the worker is at. Returns `true` if the settings changed.
+pub fn loop_ui(ui: &mut egui::Ui, sequence_loop: &mut SequenceLoop) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut sequence_loop.forever, tr("Loop forever"))
+            .changed();
+        let count = ui.add_enabled(
+            !sequence_loop.forever,
+            DragValue::new(&mut sequence_loop.count)
+                .clamp_range(1..=usize::MAX)
+                .suffix(tr(" times")),
+        );
+        changed |= accessible_name(ui, count, &tr("Number of loops")).changed();
+        ui.label(tr("Delay between loops"));
+        let delay = ui.add(DragValue::new(&mut sequence_loop.delay_ms).suffix(" ms"));
+        changed |= accessible_name(ui, delay, &tr("Delay between loops")).changed();
+    });
+
+    if let Some(progress) = progress() {
+        let args: [(&str, &dyn fmt::Display); 3] = [
+            ("loop", &progress.loop_number),
+            ("loops", &sequence_loop.count),
+            ("step", &progress.step),
+        ];
+        ui.label(if sequence_loop.forever {
+            tr_args("Loop {loop}, step {step}", &args)
+        } else {
+            tr_args("Loop {loop} of {loops}, step {step}", &args)
+        });
+        // Follow the worker while it runs.
+        ui.ctx().request_repaint_after(Duration::from_millis(100));
+    }
+    changed
+}
+
+/// Editor for the steps of a click sequence. Returns `true` if the list
+/// changed.
+pub fn editor_ui(ui: &mut egui::Ui, points: &mut Vec<ClickPoint>) -> bool {
+    let mut changed = false;
+    let mut move_up = None;
+    let mut move_down = None;
+    let mut remove = None;
+    // The step whose key button waits for a key press, kept between frames.
+    let capture_id = ui.id().with("sequence_capturing_key");
+    let mut capturing = ui.ctx().data_mut(|data| data.get_temp::<usize>(capture_id));
+
+    egui::ScrollArea::vertical()
+        .max_height(200.0)
+        .show(ui, |ui| {
+            Grid::new("click_sequence").striped(true).show(ui, |ui| {
+                ui.label("#");
+                ui.label(tr("Action"));
+                ui.label(tr("X"));
+                ui.label(tr("Y"));
+                ui.label(tr("Button, key or condition"));
+                ui.label(tr("Type or timeout"));
+                ui.label(tr("Repeat"));
+                ui.label(tr("Delay (ms)"));
+                ui.end_row();
+
+                let count = points.len();
+                for (i, point) in points.iter_mut().enumerate() {
+                    let step = i + 1;
+                    ui.label(step.to_string());
+
+                    ComboBox::from_id_source(("sequence_action", i))
+                        .selected_text(point.action.label())
+                        .width(60.0)
+                        .show_ui(ui, |ui| {
+                            for action in StepAction::ALL {
+                                changed |= ui
+                                    .selectable_value(&mut point.action, action, action.label())
+                                    .changed();
+                            }
+                        });
+
+                    let uses_point = point.action.uses_point();
+                    let x = ui.add_enabled(uses_point, DragValue::new(&mut point.x));
+                    changed |=
+                        accessible_name(ui, x, &tr_args("Step {step} X", &[("step", &step)]))
+                            .changed();
+                    let y = ui.add_enabled(uses_point, DragValue::new(&mut point.y));
+                    changed |=
+                        accessible_name(ui, y, &tr_args("Step {step} Y", &[("step", &step)]))
+                            .changed();
+
+                    match point.action {
+                        StepAction::Key => {
+                            let text = match point.click_options.key {
+                                _ if capturing == Some(i) => tr("Press a key…"),
+                                Some(key) => keys::name(key).into(),
+                                None => tr("None"),
+                            };
+                            let response = ui.button(text);
+                            if accessible_name(
+                                ui,
+                                response,
+                                &tr_args("Step {step} key", &[("step", &step)]),
+                            )
+                            .clicked()
+                            {
+                                capturing = Some(i);
+                            }
+                        }
+                        StepAction::WaitPixel => {
+                            let response = ui.color_edit_button_srgb(&mut point.color);
+                            changed |= accessible_name(
+                                ui,
+                                response,
+                                &tr_args("Step {step} color", &[("step", &step)]),
+                            )
+                            .changed();
+                        }
+                        StepAction::WaitWindow => {
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut point.text)
+                                    .hint_text(tr("Window title"))
+                                    .desired_width(100.0),
+                            );
+                            changed |= accessible_name(
+                                ui,
+                                response,
+                                &tr_args("Step {step} window title", &[("step", &step)]),
+                            )
+                            .changed();
+                        }
+                        StepAction::SetClipboard => {
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut point.text)
+                                    .hint_text(tr("Text to copy"))
+                                    .desired_width(100.0),
+                            );
+                            changed |= accessible_name(
+                                ui,
+                                response,
+                                &tr_args("Step {step} text", &[("step", &step)]),
+                            )
+                            .changed();
+                        }
+                        _ => {
+                            ui.add_enabled_ui(point.action == StepAction::Click, |ui| {
+                                ComboBox::from_id_source(("sequence_button", i))
+                                    .selected_text(match point.click_options.mouse_button {
+                                        MouseButton::Left => tr("Left"),
+                                        MouseButton::Middle => tr("Middle"),
+                                        MouseButton::Right => tr("Right"),
+                                    })
+                                    .width(70.0)
+                                    .show_ui(ui, |ui| {
+                                        for (button, label) in [
+                                            (MouseButton::Left, tr("Left")),
+                                            (MouseButton::Middle, tr("Middle")),
+                                            (MouseButton::Right, tr("Right")),
+                                        ] {
+                                            changed |= ui
+                                                .selectable_value(
+                                                    &mut point.click_options.mouse_button,
+                                                    button,
+                                                    label,
+                                                )
+                                                .changed();
+                                        }
+                                    });
+                            });
+                        }
+                    }
+
+                    if point.action.is_conditional() {
+                        ui.horizontal(|ui| {
+                            let timeout = ui.add(
+                                DragValue::new(&mut point.timeout_ms)
+                                    .suffix(" ms")
+                                    .speed(100),
+                            );
+                            changed |= accessible_name(
+                                ui,
+                                timeout,
+                                &tr_args(
+                                    "Step {step} timeout in milliseconds, 0 for none",
+                                    &[("step", &step)],
+                                ),
+                            )
+                            .changed();
+                            ComboBox::from_id_source(("sequence_timeout", i))
+                                .selected_text(point.on_timeout.label())
+                                .width(70.0)
+                                .show_ui(ui, |ui| {
+                                    for on_timeout in [OnTimeout::Skip, OnTimeout::Abort] {
+                                        changed |= ui
+                                            .selectable_value(
+                                                &mut point.on_timeout,
+                                                on_timeout,
+                                                on_timeout.label(),
+                                            )
+                                            .changed();
+                                    }
+                                });
+                        });
+                    } else {
+                        ui.add_enabled_ui(
+                            matches!(point.action, StepAction::Click | StepAction::Key),
+                            |ui| {
+                                changed |= click_type_ui(
+                                    ui,
+                                    false,
+                                    ComboBox::from_id_source(("sequence_type", i)).width(70.0),
+                                    &mut point.click_options.click_type,
+                                );
+                            },
+                        );
+                    }
+
+                    let repeat = ui.add(
+                        DragValue::new(&mut point.repeat)
+                            .clamp_range(1..=usize::MAX)
+                            .suffix("×"),
+                    );
+                    changed |= accessible_name(
+                        ui,
+                        repeat,
+                        &tr_args("Step {step} repeat count", &[("step", &step)]),
+                    )
+                    .changed();
+
+                    let delay = ui.add(DragValue::new(&mut point.delay_ms));
+                    changed |= accessible_name(
+                        ui,
+                        delay,
+                        &tr_args("Step {step} delay in milliseconds", &[("step", &step)]),
+                    )
+                    .changed();
+
+                    ui.horizontal(|ui| {
+                        let up = ui.add_enabled(i > 0, egui::Button::new("⏶"));
+                        if accessible_name(
+                            ui,
+                            up,
+                            &tr_args("Move step {step} up", &[("step", &step)]),
+                        )
+                        .clicked()
+                        {
+                            move_up = Some(i);
+                        }
+                        let down = ui.add_enabled(i + 1 < count, egui::Button::new("⏷"));
+                        if accessible_name(
+                            ui,
+                            down,
+                            &tr_args("Move step {step} down", &[("step", &step)]),
+                        )
+                        .clicked()
+                        {
+                            move_down = Some(i);
+                        }
+                        let delete = ui.button("✖");
+                        if accessible_name(
+                            ui,
+                            delete,
+                            &tr_args("Remove step {step}", &[("step", &step)]),
+                        )
+                        .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        });
+
+    if let Some(i) = capturing {
+        let pressed = ui.input(|input| {
+            input.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key, pressed: true, ..
+                } => keys::from_egui(*key),
+                _ => None,
+            })
+        });
+        if let Some(key) = pressed {
+            if let Some(point) = points.get_mut(i) {
+                point.click_options.key = Some(key);
+                changed = true;
+            }
+            capturing = None;
+        }
+    }
+
+    if let Some(i) = move_up {
+        points.swap(i, i - 1);
+        changed = true;
+    }
+    if let Some(i) = move_down {
+        points.swap(i, i + 1);
+        changed = true;
+    }
+    if let Some(i) = remove {
+        points.remove(i);
+        changed = true;
+    }
+    // The captured step may have moved or gone.
+    if move_up.is_some() || move_down.is_some() || remove.is_some() {
+        capturing = None;
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button(tr("Add step")).clicked() {
+            points.push(points.last().cloned().unwrap_or_default());
+            changed = true;
+        }
+        if ui
+            .add_enabled(!points.is_empty(), egui::Button::new(tr("Clear")))
+            .clicked()
+        {
+            points.clear();
+            changed = true;
+        }
+    });
+
+    ui.ctx().data_mut(|data| match capturing {
+        Some(i) => data.insert_temp(capture_id, i),
+        None => data.remove::<usize>(capture_id),
+    });
+
+    changed
+}
@@ -0,0 +1,63 @@
+use super::{row, MainApp};
+use crate::{
+    i18n::tr,
+    slots::{self, Slot, SLOT_COUNT},
+};
+
+impl MainApp {
+    /// The quick slots bar, binding each Ctrl+F hotkey to a profile or to
+    /// the current macro.
+    pub(super) fn slots_ui(&mut self, ui: &mut egui::Ui) {
+        let rtl = self.settings.is_right_to_left();
+        let mut changed = false;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            row(ui, rtl, |ui| {
+                for index in 0..SLOT_COUNT {
+                    let slot = &mut self.settings.slots[index];
+                    let selected = match slot {
+                        Slot::Empty => tr("Empty"),
+                        Slot::Profile(name) => name.clone().into(),
+                        Slot::Macro => tr("Macro"),
+                    };
+                    let mut bound = slot.clone();
+                    let label = ui.label(slots::hotkey_label(index));
+                    let response = egui::ComboBox::from_id_source(("slot", index))
+                        .width(60.0)
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.style_mut().wrap = Some(false);
+                            ui.selectable_value(&mut bound, Slot::Empty, tr("Empty"));
+                            if !self.recorder.is_empty() {
+                                ui.selectable_value(&mut bound, Slot::Macro, tr("Current macro"))
+                                    .on_hover_text(tr("Bind a copy of the current recording."));
+                            }
+                            for name in &self.profiles {
+                                ui.selectable_value(&mut bound, Slot::Profile(name.clone()), name);
+                            }
+                        })
+                        .response;
+                    response.labelled_by(label.id);
+
+                    if bound != *slot {
+                        if bound == Slot::Macro {
+                            if let Err(e) = slots::save_macro(index, &self.recorder.recording()) {
+                                log::error!("Could not save the macro of slot {}: {e}", index + 1);
+                                continue;
+                            }
+                        }
+                        *slot = bound;
+                        changed = true;
+                    }
+                }
+            });
+        });
+
+        if changed {
+            slots::set_slots(&self.settings.slots);
+            if let Err(e) = self.settings.save() {
+                log::error!("Could not save settings: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use egui::DragValue;
+
+use super::accessible_name;
+use crate::{i18n::tr, typing::TypingText};
+
+/// Editor for the text to type, laid out in the caller's row. Returns
+/// `true` if anything changed.
+pub fn editor_ui(ui: &mut egui::Ui, typing: &mut TypingText) -> bool {
+    let mut changed = false;
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut typing.text)
+            .hint_text(tr("Text to type instead of clicking"))
+            .desired_width(180.0),
+    );
+    changed |= accessible_name(ui, response, &tr("Text to type")).changed();
+
+    let response = ui.add(DragValue::new(&mut typing.char_delay_ms).suffix(" ms"));
+    let label = ui.label(tr("per character"));
+    changed |= response.labelled_by(label.id).changed();
+
+    changed |= ui.checkbox(&mut typing.repeat, tr("Repeat")).changed();
+    changed
+}
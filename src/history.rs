@@ -0,0 +1,148 @@
+//! Past runs kept across restarts in `<config dir>/history.json`, so a run
+//! that worked well can be looked up and its settings applied again.
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "runs": [{ "started": "2024-05-01T12:00:00Z", "ended": "2024-05-01T12:01:00Z",
+//!              "elapsed_ms": 60000, "clicks": 600, "target_cps": 10,
+//!              "config": "interval=100ms button=left clicks=1 position=cursor",
+//!              "profile": { "interval.milliseconds": 100, ... } }]
+//! }
+//! ```
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    config,
+    portable::{json_to_table, table_to_json},
+    profile::Profile,
+    stats::{self, RunRecord},
+};
+
+/// Schema version of the history file.
+const VERSION: u64 = 1;
+
+/// The oldest runs are dropped beyond this many.
+const MAX_ENTRIES: usize = 500;
+
+/// Starts a thread that appends every run to the history as it ends.
+pub fn spawn_recorder() {
+    let rx_runs = stats::subscribe_runs();
+    thread::spawn(move || {
+        for run in rx_runs {
+            if let Err(e) = append(&run) {
+                log::error!("Failed to save the run history: {e}");
+            }
+        }
+    });
+}
+
+/// The saved runs, oldest first. A missing or unreadable file reads as an
+/// empty history.
+pub fn load() -> Vec<RunRecord> {
+    let Some(path) = history_path() else {
+        return Vec::new();
+    };
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            log::error!("Failed to read {}: {e}", path.display());
+            return Vec::new();
+        }
+    };
+    match serde_json::from_str::<Value>(&text) {
+        Ok(root) => root
+            .get("runs")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(parse_run)
+            .collect(),
+        Err(e) => {
+            log::error!("Failed to read {}: {e}", path.display());
+            Vec::new()
+        }
+    }
+}
+
+/// Adds `run` to the end of the history.
+pub fn append(run: &RunRecord) -> io::Result<()> {
+    let mut runs = load();
+    runs.push(run.clone());
+    let excess = runs.len().saturating_sub(MAX_ENTRIES);
+    save(&runs[excess..])
+}
+
+/// Forgets all saved runs.
+pub fn clear() -> io::Result<()> {
+    match history_path().map(fs::remove_file) {
+        Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn save(runs: &[RunRecord]) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let root = json!({
+        "version": VERSION,
+        "runs": runs.iter().map(run_to_json).collect::<Vec<_>>(),
+    });
+    fs::write(path, format!("{root:#}\n"))
+}
+
+fn run_to_json(run: &RunRecord) -> Value {
+    let time = |time| humantime::format_rfc3339_seconds(time).to_string();
+    json!({
+        "started": time(run.started),
+        "ended": time(run.ended),
+        "elapsed_ms": u64::try_from(run.elapsed.as_millis()).unwrap_or(u64::MAX),
+        "clicks": run.clicks,
+        "target_cps": run.target_cps,
+        "config": run.config,
+        "profile": table_to_json(&run.profile.to_table()),
+    })
+}
+
+/// Reads one entry, skipping it if its times are missing or malformed.
+fn parse_run(value: &Value) -> Option<RunRecord> {
+    let time =
+        |key| -> Option<SystemTime> { humantime::parse_rfc3339(value.get(key)?.as_str()?).ok() };
+    Some(RunRecord {
+        started: time("started")?,
+        ended: time("ended")?,
+        elapsed: Duration::from_millis(
+            value
+                .get("elapsed_ms")
+                .and_then(Value::as_u64)
+                .unwrap_or_default(),
+        ),
+        clicks: value
+            .get("clicks")
+            .and_then(Value::as_u64)
+            .unwrap_or_default() as _,
+        target_cps: value.get("target_cps").and_then(Value::as_f64),
+        config: value
+            .get("config")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned(),
+        profile: Profile::from_table(&json_to_table(value.get("profile"))),
+    })
+}
+
+fn history_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("history.json"))
+}
@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+
+use rdev::Key;
+#[cfg(feature = "gui")]
+use winit::event::VirtualKeyCode;
+
+use crate::{
+    activation::{self, Activation, Input, Trigger},
+    i18n::{tr, tr_args},
+    jobs,
+    modifiers::Modifiers,
+    slots::{self, SLOT_COUNT},
+};
+
+/// What a hotkey does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// The built-in keys of the app window.
+    Start,
+    Stop,
+    Toggle,
+    PauseOrResume,
+    /// Launches the quick slot with this index.
+    Slot(usize),
+    /// A bound trigger, see [`crate::activation`].
+    Activation(Activation),
+}
+
+impl Action {
+    /// The action as shown in the GUI, in the current language.
+    pub fn name(self) -> Cow<'static, str> {
+        match self {
+            Action::Start => tr("Start in the window"),
+            Action::Stop => tr("Stop in the window"),
+            Action::Toggle => tr("Start/stop in the window"),
+            Action::PauseOrResume => tr("Pause in the window"),
+            Action::Slot(index) => {
+                tr_args("Quick slot {number}", &[("number", &(index + 1))]).into()
+            }
+            Action::Activation(Activation::Hold) => tr("Click only while holding"),
+            Action::Activation(Activation::Toggle) => tr("Start and stop"),
+        }
+    }
+}
+
+/// The built-in keys of the app window, pressed without modifiers.
+const WINDOW_KEYS: [(Action, Key); 4] = [
+    (Action::Start, Key::F6),
+    (Action::Stop, Key::F7),
+    (Action::Toggle, Key::F8),
+    (Action::PauseOrResume, Key::F9),
+];
+
+/// Every hotkey with what it does: the window keys, the quick slot keys and
+/// the bound triggers.
+pub fn bindings() -> Vec<(Action, Trigger)> {
+    let mut bindings: Vec<(Action, Trigger)> = WINDOW_KEYS
+        .iter()
+        .map(|&(action, key)| {
+            let trigger = Trigger {
+                modifiers: Modifiers::NONE,
+                input: Input::Key(key),
+            };
+            (action, trigger)
+        })
+        .collect();
+    bindings.extend((0..SLOT_COUNT).map(|index| (Action::Slot(index), slots::hotkey(index))));
+    for activation in [Activation::Hold, Activation::Toggle] {
+        if let Some(trigger) = activation::trigger(activation) {
+            bindings.push((Action::Activation(activation), trigger));
+        }
+    }
+    bindings
+}
+
+/// The actions besides `action` that `trigger` is bound to.
+pub fn conflicts(trigger: Trigger, action: Action) -> Vec<Action> {
+    bindings()
+        .into_iter()
+        .filter(|&(other, bound)| other != action && bound == trigger)
+        .map(|(other, _)| other)
+        .collect()
+}
+
+/// Whether `trigger` is bound as a trigger or a job's hotkey. Bound
+/// triggers take a key over from the window and quick slot keys, which then
+/// leave it alone, so one press never runs two actions.
+pub fn is_taken(trigger: Trigger) -> bool {
+    [Activation::Hold, Activation::Toggle]
+        .into_iter()
+        .any(|activation| activation::trigger(activation) == Some(trigger))
+        || jobs::is_hotkey(trigger)
+}
+
+/// The action of a window key pressed without modifiers, unless a trigger
+/// took the key over.
+#[cfg(feature = "gui")]
+pub fn window_action(key: VirtualKeyCode) -> Option<Action> {
+    let key = match key {
+        VirtualKeyCode::F6 => Key::F6,
+        VirtualKeyCode::F7 => Key::F7,
+        VirtualKeyCode::F8 => Key::F8,
+        VirtualKeyCode::F9 => Key::F9,
+        _ => return None,
+    };
+    let &(action, _) = WINDOW_KEYS
+        .iter()
+        .find(|(_, window_key)| *window_key == key)?;
+    let trigger = Trigger {
+        modifiers: Modifiers::NONE,
+        input: Input::Key(key),
+    };
+    (!is_taken(trigger)).then_some(action)
+}
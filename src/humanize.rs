@@ -0,0 +1,160 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Noise that makes automated clicking look less mechanical.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Humanize {
+    pub enabled: bool,
+    /// Standard deviation of the interval, in percent of it.
+    pub timing_jitter_pct: usize,
+    /// Standard deviation of custom click positions, in pixels.
+    pub position_jitter_px: usize,
+    /// Chance of an extra pause after a cycle, in percent.
+    pub pause_chance_pct: usize,
+    pub pause_ms: usize,
+}
+
+impl Default for Humanize {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timing_jitter_pct: 10,
+            position_jitter_px: 2,
+            pause_chance_pct: 2,
+            pause_ms: 1000,
+        }
+    }
+}
+
+impl Humanize {
+    /// The wait after a cycle: `interval` with Gaussian noise, sometimes
+    /// followed by a longer pause.
+    pub fn interval(&self, interval: Duration, rng: &mut Rng) -> Duration {
+        if !self.enabled {
+            return interval;
+        }
+
+        let std_dev = interval.as_secs_f64() * self.timing_jitter_pct as f64 / 100.0;
+        let secs = (interval.as_secs_f64() + rng.gaussian() * std_dev).max(0.0);
+        let mut wait = Duration::from_secs_f64(secs);
+        if rng.next_f64() * 100.0 < self.pause_chance_pct as f64 {
+            wait += Duration::from_millis(self.pause_ms as u64);
+        }
+        wait
+    }
+
+    /// `(x, y)` moved by a few pixels of Gaussian noise.
+    pub fn position(&self, x: i32, y: i32, rng: &mut Rng) -> (i32, i32) {
+        if !self.enabled {
+            return (x, y);
+        }
+
+        let std_dev = self.position_jitter_px as f64;
+        let jitter =
+            |value: i32, rng: &mut Rng| (value as f64 + rng.gaussian() * std_dev).round() as i32;
+        (jitter(x, rng), jitter(y, rng))
+    }
+}
+
+/// The seed of every run, `None` for a fresh one each run.
+static SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+/// The seed of the current or last run.
+static LAST_SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+/// Makes every run draw the same random numbers from `seed`, e.g. to
+/// reproduce a run while debugging, or a fresh seed each run for `None`.
+pub fn set_seed(seed: Option<u64>) {
+    *SEED.lock().unwrap() = seed;
+}
+
+/// The seed of the current or last run, `None` before the first.
+pub fn last_seed() -> Option<u64> {
+    *LAST_SEED.lock().unwrap()
+}
+
+/// A small xorshift generator; good enough for jitter and seeded
+/// differently in every process unless made with [`Rng::new`].
+#[derive(Debug)]
+pub struct Rng(u64);
+
+impl Default for Rng {
+    fn default() -> Self {
+        let seed = RandomState::new().build_hasher().finish();
+        Self(seed | 1)
+    }
+}
+
+impl Rng {
+    /// A generator that always gives the same numbers for `seed`.
+    pub fn new(seed: u64) -> Self {
+        // SplitMix64, so nearby seeds start far apart; xorshift is stuck at 0.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    /// The generator of a new run, from the seed set with [`set_seed`] or a
+    /// fresh one, which [`last_seed`] then reports. Fresh seeds fit in 32
+    /// bits, so they are short enough to note down.
+    pub fn for_run() -> Self {
+        let seed = SEED
+            .lock()
+            .unwrap()
+            .unwrap_or_else(|| RandomState::new().build_hasher().finish() >> 32);
+        *LAST_SEED.lock().unwrap() = Some(seed);
+        Self::new(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal, by the Box-Muller transform.
+    pub fn gaussian(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Noise for `seed`: a few intervals and positions, in turn.
+    fn draws(seed: u64) -> Vec<(Duration, (i32, i32))> {
+        let humanize = Humanize {
+            enabled: true,
+            pause_chance_pct: 50,
+            ..Humanize::default()
+        };
+        let mut rng = Rng::new(seed);
+        (0..100)
+            .map(|_| {
+                let wait = humanize.interval(Duration::from_millis(100), &mut rng);
+                (wait, humanize.position(500, 300, &mut rng))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_noise() {
+        assert_eq!(draws(42), draws(42));
+        assert_eq!(draws(0), draws(0));
+        assert_ne!(draws(42), draws(43));
+    }
+}
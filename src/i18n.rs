@@ -0,0 +1,734 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    env,
+    fmt::{self, Write},
+    fs, io,
+    path::PathBuf,
+    sync::RwLock,
+};
+
+use crate::config;
+
+/// Catalogs shipped with the app, as language code, name and the gettext
+/// PO source. English is the language of the source strings and needs
+/// none.
+const BUILT_IN: [(&str, &str, &str); 1] = [("de", "Deutsch", include_str!("../locales/de.po"))];
+
+/// Languages written from right to left, by code.
+const RIGHT_TO_LEFT: [&str; 9] = ["ar", "dv", "fa", "he", "ps", "sd", "ug", "ur", "yi"];
+
+/// A language the GUI can be shown in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    /// The code of the language, e.g. `de`, which is also the name of its
+    /// catalog file.
+    pub code: String,
+    /// The name of the language in itself, e.g. `Deutsch`.
+    pub name: String,
+}
+
+/// Separates a message's context from its source string in catalog keys,
+/// as in gettext's MO files.
+const CONTEXT_SEPARATOR: char = '\u{4}';
+
+/// The translations of one language.
+#[derive(Debug, Default)]
+struct Catalog {
+    /// Translated forms by [`key`]: one for most messages, one per plural
+    /// form for those with a `msgid_plural`.
+    messages: HashMap<String, Vec<String>>,
+    /// Picks the plural form for a count, from the header's `Plural-Forms`.
+    /// `n != 1` where there is none.
+    plural: Option<Plural>,
+}
+
+impl Catalog {
+    fn get(&self, context: Option<&str>, text: &str) -> Option<&[String]> {
+        self.messages
+            .get(key(context, text).as_ref())
+            .map(Vec::as_slice)
+    }
+
+    fn plural_form(&self, n: u64) -> usize {
+        match &self.plural {
+            Some(plural) => plural.eval(n) as usize,
+            None => usize::from(n != 1),
+        }
+    }
+}
+
+/// The catalog of the current language. `None` while the GUI is in
+/// English.
+static CATALOG: RwLock<Option<Catalog>> = RwLock::new(None);
+
+/// The code of the current language, see [`set_language`].
+static CODE: RwLock<String> = RwLock::new(String::new());
+
+/// The GUI string `text` in the current language, or `text` itself where
+/// the catalog has no translation.
+pub fn tr(text: &'static str) -> Cow<'static, str> {
+    translate(None, text)
+}
+
+/// Like [`tr`] for a source string that reads differently by `context`,
+/// the catalog's `msgctxt`.
+pub fn tr_in(context: &str, text: &'static str) -> Cow<'static, str> {
+    translate(Some(context), text)
+}
+
+fn translate(context: Option<&str>, text: &'static str) -> Cow<'static, str> {
+    let catalog = CATALOG.read().unwrap();
+    match catalog
+        .as_ref()
+        .and_then(|catalog| catalog.get(context, text))
+    {
+        Some([translation, ..]) if !translation.is_empty() => Cow::Owned(translation.clone()),
+        _ => Cow::Borrowed(text),
+    }
+}
+
+/// Like [`tr`], then fills the `{name}` placeholders of the translation
+/// with `args`, so translations can put them in any order.
+///
+/// ```text
+/// tr_args("Imported {path}", &[("path", &path.display())])
+/// ```
+pub fn tr_args(text: &'static str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    fill(&tr(text), args)
+}
+
+/// Like [`tr_args`] for a message that depends on the count `n`: the
+/// catalog's plural form for `n`, or `singular` or `plural` in English.
+///
+/// ```text
+/// tr_n("{count} click", "{count} clicks", count, &[("count", &count)])
+/// ```
+pub fn tr_n(
+    singular: &'static str,
+    plural: &'static str,
+    n: u64,
+    args: &[(&str, &dyn fmt::Display)],
+) -> String {
+    let catalog = CATALOG.read().unwrap();
+    let translation = catalog.as_ref().and_then(|catalog| {
+        let forms = catalog.get(None, singular)?;
+        forms
+            .get(catalog.plural_form(n))
+            .filter(|form| !form.is_empty())
+    });
+    match translation {
+        Some(translation) => fill(translation, args),
+        None => fill(if n == 1 { singular } else { plural }, args),
+    }
+}
+
+fn fill(template: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let arg = rest.find('}').and_then(|end| {
+            let (_, value) = args.iter().find(|(name, _)| *name == &rest[1..end])?;
+            Some((end, value))
+        });
+        match arg {
+            Some((end, value)) => {
+                write!(filled, "{value}").ok();
+                rest = &rest[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Switches the GUI to the language with `code`, or to the system language
+/// for `None`. Languages without a catalog show the English strings.
+///
+/// A catalog in `<config dir>/locales/<code>.po` is read on top of the
+/// built-in one, so translations can be corrected or added without a new
+/// release.
+pub fn set_language(code: Option<&str>) {
+    let code = code.map_or_else(system_language, str::to_owned);
+    let mut catalog = Catalog::default();
+    if let Some((_, _, source)) = BUILT_IN.iter().find(|(built_in, ..)| *built_in == code) {
+        parse(source, &mut catalog);
+    }
+    if let Some(path) = locales_dir().map(|dir| dir.join(format!("{code}.po"))) {
+        match fs::read_to_string(&path) {
+            Ok(source) => parse(&source, &mut catalog),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => log::error!("Could not read {}: {e}", path.display()),
+        }
+    }
+    *CATALOG.write().unwrap() = (!catalog.messages.is_empty()).then_some(catalog);
+    *CODE.write().unwrap() = code;
+}
+
+/// Whether the current language is written from right to left, which
+/// mirrors the layout unless the settings say otherwise.
+pub fn is_right_to_left() -> bool {
+    RIGHT_TO_LEFT.contains(&CODE.read().unwrap().as_str())
+}
+
+/// English, the built-in languages and those with a catalog in the
+/// locales directory, which are named by their code.
+pub fn languages() -> Vec<Language> {
+    let mut languages = vec![Language {
+        code: "en".to_owned(),
+        name: "English".to_owned(),
+    }];
+    languages.extend(BUILT_IN.iter().map(|(code, name, _)| Language {
+        code: (*code).to_owned(),
+        name: (*name).to_owned(),
+    }));
+
+    let entries = locales_dir().and_then(|dir| fs::read_dir(dir).ok());
+    let mut codes: Vec<String> = entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "po" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_owned())
+        })
+        .filter(|code| languages.iter().all(|language| language.code != *code))
+        .collect();
+    codes.sort();
+    languages.extend(codes.into_iter().map(|code| Language {
+        name: code.clone(),
+        code,
+    }));
+    languages
+}
+
+/// The language part of the locale in `LC_ALL`, `LC_MESSAGES` or `LANG`,
+/// e.g. `de` for `de_DE.UTF-8`. English where none is set.
+fn system_language() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .and_then(|locale| {
+            let code = locale.split(['_', '.', '@']).next()?.to_lowercase();
+            (!code.is_empty() && code != "c" && code != "posix").then_some(code)
+        })
+        .unwrap_or_else(|| "en".to_owned())
+}
+
+fn locales_dir() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("locales"))
+}
+
+/// The key of a message in [`Catalog::messages`].
+fn key<'a>(context: Option<&str>, text: &'a str) -> Cow<'a, str> {
+    match context {
+        Some(context) => Cow::Owned(format!("{context}{CONTEXT_SEPARATOR}{text}")),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Reads the messages of a gettext PO file into `catalog`, and the plural
+/// rule from its header. Comments, untranslated entries and entries flagged
+/// `fuzzy` are skipped, as `msgfmt` does.
+///
+/// ```text
+/// # Settings window
+/// msgid "Theme"
+/// msgstr "Design"
+///
+/// msgctxt "start schedule"
+/// msgid "Start"
+/// msgstr "Starten"
+///
+/// msgid "{clicks} click"
+/// msgid_plural "{clicks} clicks"
+/// msgstr[0] "{clicks} Klick"
+/// msgstr[1] "{clicks} Klicks"
+/// ```
+fn parse(source: &str, catalog: &mut Catalog) {
+    let mut entry = Entry::default();
+    for line in source.lines().map(str::trim) {
+        // Comments belong to the entry below them, so they end the one
+        // before.
+        let starts_entry =
+            line.starts_with('#') || line.starts_with("msgctxt ") || line.starts_with("msgid ");
+        if starts_entry && !entry.forms.is_empty() {
+            entry.add_to(catalog);
+        }
+        if let Some(flags) = line.strip_prefix("#,") {
+            entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+        } else if let Some(rest) = line.strip_prefix("msgctxt ") {
+            entry.context = Some(unquote(rest));
+            entry.field = Field::Context;
+        } else if line.starts_with("msgid_plural ") {
+            // Messages are looked up by their singular; the English plural
+            // comes from the call site.
+            entry.field = Field::Plural;
+        } else if let Some(rest) = line.strip_prefix("msgid ") {
+            entry.id = unquote(rest);
+            entry.field = Field::Id;
+        } else if let Some(rest) = line.strip_prefix("msgstr") {
+            let form = match rest.strip_prefix('[') {
+                Some(rest) => rest
+                    .split_once(']')
+                    .and_then(|(index, quoted)| Some((index.trim().parse().ok()?, quoted)))
+                    .filter(|&(index, _)| index < MAX_PLURAL_FORMS),
+                None => Some((0, rest)),
+            };
+            let Some((index, quoted)) = form else {
+                entry.field = Field::Skip;
+                continue;
+            };
+            // Forms can come in any order.
+            if entry.forms.len() <= index {
+                entry.forms.resize(index + 1, String::new());
+            }
+            entry.forms[index] = unquote(quoted.trim());
+            entry.field = Field::Str(index);
+        } else if line.starts_with('"') {
+            let part = unquote(line);
+            match entry.field {
+                Field::Context => entry
+                    .context
+                    .get_or_insert_with(String::new)
+                    .push_str(&part),
+                Field::Id => entry.id.push_str(&part),
+                Field::Str(index) => entry.forms[index].push_str(&part),
+                Field::Plural | Field::Skip => {}
+            }
+        }
+    }
+    entry.add_to(catalog);
+}
+
+/// More plural forms than any language has, to bound what a catalog can
+/// ask for.
+const MAX_PLURAL_FORMS: usize = 8;
+
+/// The PO entry being read.
+#[derive(Default)]
+struct Entry {
+    context: Option<String>,
+    id: String,
+    forms: Vec<String>,
+    field: Field,
+    fuzzy: bool,
+}
+
+/// Which of an entry's strings a continuation line adds to.
+#[derive(Default, Clone, Copy)]
+enum Field {
+    Context,
+    #[default]
+    Id,
+    Plural,
+    Str(usize),
+    Skip,
+}
+
+impl Entry {
+    /// Moves the entry into `catalog` and starts the next one. The header,
+    /// the entry without a source string, only sets the plural rule.
+    fn add_to(&mut self, catalog: &mut Catalog) {
+        let entry = std::mem::take(self);
+        if entry.id.is_empty() && entry.context.is_none() {
+            let header = entry.forms.first().map_or("", String::as_str);
+            let rule = header
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("Plural-Forms:"))
+                .and_then(|forms| {
+                    forms
+                        .split(';')
+                        .find_map(|part| part.trim().strip_prefix("plural="))
+                });
+            if let Some(rule) = rule {
+                match Plural::parse(rule) {
+                    Some(plural) => catalog.plural = Some(plural),
+                    None => log::warn!("Could not read the plural rule {rule:?}"),
+                }
+            }
+            return;
+        }
+        if entry.fuzzy || entry.forms.iter().all(String::is_empty) {
+            return;
+        }
+        catalog.messages.insert(
+            key(entry.context.as_deref(), &entry.id).into_owned(),
+            entry.forms,
+        );
+    }
+}
+
+/// A C expression in `n` from a catalog's `Plural-Forms` header, e.g.
+/// `(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2)`,
+/// that gives the plural form to use for `n`.
+#[derive(Debug, Clone, PartialEq)]
+enum Plural {
+    N,
+    Number(u64),
+    Not(Box<Plural>),
+    Binary(Op, Box<Plural>, Box<Plural>),
+    If(Box<Plural>, Box<Plural>, Box<Plural>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// Binary operators from the loosest to the tightest binding, with their
+/// spelling.
+const OPS: [&[(&str, Op)]; 6] = [
+    &[("||", Op::Or)],
+    &[("&&", Op::And)],
+    &[("==", Op::Eq), ("!=", Op::Ne)],
+    &[("<=", Op::Le), (">=", Op::Ge), ("<", Op::Lt), (">", Op::Gt)],
+    &[("+", Op::Add), ("-", Op::Sub)],
+    &[("*", Op::Mul), ("/", Op::Div), ("%", Op::Rem)],
+];
+
+impl Plural {
+    fn parse(rule: &str) -> Option<Self> {
+        let mut rest = rule.trim();
+        let plural = Self::parse_if(&mut rest)?;
+        rest.trim().is_empty().then_some(plural)
+    }
+
+    fn parse_if(rest: &mut &str) -> Option<Self> {
+        let condition = Self::parse_binary(rest, 0)?;
+        if !eat(rest, "?") {
+            return Some(condition);
+        }
+        let then = Self::parse_if(rest)?;
+        if !eat(rest, ":") {
+            return None;
+        }
+        let otherwise = Self::parse_if(rest)?;
+        Some(Self::If(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn parse_binary(rest: &mut &str, level: usize) -> Option<Self> {
+        let Some(ops) = OPS.get(level) else {
+            return Self::parse_unary(rest);
+        };
+        let mut left = Self::parse_binary(rest, level + 1)?;
+        while let Some(&(_, op)) = ops.iter().find(|(spelling, _)| eat(rest, spelling)) {
+            let right = Self::parse_binary(rest, level + 1)?;
+            left = Self::Binary(op, Box::new(left), Box::new(right));
+        }
+        Some(left)
+    }
+
+    fn parse_unary(rest: &mut &str) -> Option<Self> {
+        *rest = rest.trim_start();
+        if eat(rest, "!") {
+            return Some(Self::Not(Box::new(Self::parse_unary(rest)?)));
+        }
+        if eat(rest, "(") {
+            let inner = Self::parse_if(rest)?;
+            return eat(rest, ")").then_some(inner);
+        }
+        if eat(rest, "n") {
+            return Some(Self::N);
+        }
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number = rest[..digits].parse().ok()?;
+        *rest = &rest[digits..];
+        Some(Self::Number(number))
+    }
+
+    fn eval(&self, n: u64) -> u64 {
+        match self {
+            Self::N => n,
+            Self::Number(number) => *number,
+            Self::Not(inner) => u64::from(inner.eval(n) == 0),
+            Self::If(condition, then, otherwise) => {
+                if condition.eval(n) != 0 {
+                    then.eval(n)
+                } else {
+                    otherwise.eval(n)
+                }
+            }
+            Self::Binary(op, left, right) => {
+                let (a, b) = (left.eval(n), right.eval(n));
+                match op {
+                    Op::Or => u64::from(a != 0 || b != 0),
+                    Op::And => u64::from(a != 0 && b != 0),
+                    Op::Eq => u64::from(a == b),
+                    Op::Ne => u64::from(a != b),
+                    Op::Lt => u64::from(a < b),
+                    Op::Le => u64::from(a <= b),
+                    Op::Gt => u64::from(a > b),
+                    Op::Ge => u64::from(a >= b),
+                    Op::Add => a.wrapping_add(b),
+                    Op::Sub => a.wrapping_sub(b),
+                    Op::Mul => a.wrapping_mul(b),
+                    Op::Div => a.checked_div(b).unwrap_or(0),
+                    Op::Rem => a.checked_rem(b).unwrap_or(0),
+                }
+            }
+        }
+    }
+}
+
+/// Skips whitespace and then `token`, if `rest` starts with it. `!` is not
+/// taken from the front of `!=`.
+fn eat(rest: &mut &str, token: &str) -> bool {
+    let trimmed = rest.trim_start();
+    match trimmed.strip_prefix(token) {
+        Some(after) if !(token == "!" && after.starts_with('=')) => {
+            *rest = after;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The contents of a quoted PO string up to its closing quote, with its
+/// escapes resolved.
+fn unquote(quoted: &str) -> String {
+    let inner = quoted.strip_prefix('"').unwrap_or(quoted);
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some('r') => text.push('\r'),
+                Some(other) => text.push(other),
+                None => {}
+            },
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog(source: &str) -> Catalog {
+        let mut catalog = Catalog::default();
+        parse(source, &mut catalog);
+        catalog
+    }
+
+    fn lookup<'a>(catalog: &'a Catalog, context: Option<&str>, text: &str) -> Option<&'a str> {
+        catalog
+            .get(context, text)
+            .and_then(|forms| forms.first())
+            .map(String::as_str)
+    }
+
+    #[test]
+    fn reads_entries_across_continuation_lines() {
+        let catalog = catalog(
+            r#"
+# Settings window
+msgid "Theme"
+msgstr "Design"
+
+msgid ""
+"Imported {events} "
+"events"
+msgstr "{events} Ereignisse "
+"importiert"
+
+msgid "Untranslated"
+msgstr ""
+"#,
+        );
+        assert_eq!(lookup(&catalog, None, "Theme"), Some("Design"));
+        assert_eq!(
+            lookup(&catalog, None, "Imported {events} events"),
+            Some("{events} Ereignisse importiert")
+        );
+        assert_eq!(lookup(&catalog, None, "Untranslated"), None);
+    }
+
+    #[test]
+    fn resolves_escapes_in_ids_and_translations() {
+        let catalog = catalog(
+            r#"
+msgid "Press \"Start\"\n"
+"to begin"
+msgstr "Drücke \"Start\",\n"
+"um zu beginnen"
+
+msgid "C:\\Users\t{name}"
+msgstr "C:\\Benutzer\t{name}"
+"#,
+        );
+        assert_eq!(
+            lookup(&catalog, None, "Press \"Start\"\nto begin"),
+            Some("Drücke \"Start\",\num zu beginnen")
+        );
+        assert_eq!(
+            lookup(&catalog, None, "C:\\Users\t{name}"),
+            Some("C:\\Benutzer\t{name}")
+        );
+    }
+
+    #[test]
+    fn skips_fuzzy_entries() {
+        let catalog = catalog(
+            r#"
+msgid ""
+msgstr ""
+"Plural-Forms: nplurals=2; plural=(n != 1);\n"
+
+msgid "Theme"
+msgstr "Design"
+#, fuzzy
+msgid "Speed"
+msgstr "Tempo"
+
+#: src/gui.rs
+#, c-format, fuzzy
+msgctxt "start schedule"
+msgid "Start"
+msgstr "Starten"
+
+#, c-format
+msgid "Stop"
+msgstr "Anhalten"
+
+#, fuzzy
+msgid "{n} click"
+msgid_plural "{n} clicks"
+msgstr[0] "{n} Klick"
+msgstr[1] "{n} Klicks"
+"#,
+        );
+        assert_eq!(lookup(&catalog, None, "Theme"), Some("Design"));
+        assert_eq!(lookup(&catalog, None, "Speed"), None);
+        assert_eq!(lookup(&catalog, Some("start schedule"), "Start"), None);
+        assert_eq!(lookup(&catalog, None, "Stop"), Some("Anhalten"));
+        assert_eq!(lookup(&catalog, None, "{n} click"), None);
+        assert!(catalog.plural.is_some());
+    }
+
+    #[test]
+    fn keeps_messages_apart_by_context() {
+        let catalog = catalog(
+            r#"
+msgid "Start"
+msgstr "Start"
+
+msgctxt "start schedule"
+msgid "Start"
+msgstr "Starten"
+"#,
+        );
+        assert_eq!(lookup(&catalog, None, "Start"), Some("Start"));
+        assert_eq!(
+            lookup(&catalog, Some("start schedule"), "Start"),
+            Some("Starten")
+        );
+        assert_eq!(lookup(&catalog, Some("other"), "Start"), None);
+    }
+
+    #[test]
+    fn picks_plural_forms_by_the_header_rule() {
+        let catalog = catalog(
+            r#"
+msgid ""
+msgstr ""
+"Language: pl\n"
+"Plural-Forms: nplurals=3; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+msgid "{n} click"
+msgid_plural "{n} clicks"
+msgstr[0] "{n} kliknięcie"
+msgstr[2] "{n} kliknięć"
+msgstr[1] "{n} kliknięcia"
+"#,
+        );
+        let forms = catalog.get(None, "{n} click").unwrap();
+        let form = |n| forms[catalog.plural_form(n)].as_str();
+        assert_eq!(form(1), "{n} kliknięcie");
+        assert_eq!(form(3), "{n} kliknięcia");
+        assert_eq!(form(22), "{n} kliknięcia");
+        assert_eq!(form(5), "{n} kliknięć");
+        assert_eq!(form(12), "{n} kliknięć");
+    }
+
+    #[test]
+    fn plural_rule_defaults_to_one_and_many() {
+        let catalog = catalog("msgid \"Theme\"\nmsgstr \"Design\"\n");
+        assert_eq!(catalog.plural_form(1), 0);
+        assert_eq!(catalog.plural_form(0), 1);
+        assert_eq!(catalog.plural_form(2), 1);
+    }
+
+    #[test]
+    fn plural_rules_parse_like_c() {
+        let eval = |rule: &str, n| Plural::parse(rule).map(|plural| plural.eval(n));
+        assert_eq!(eval("n != 1", 1), Some(0));
+        assert_eq!(eval("n!=1", 7), Some(1));
+        assert_eq!(eval("0", 7), Some(0));
+        assert_eq!(eval("n>1", 1), Some(0));
+        assert_eq!(eval("!(n == 0)", 0), Some(0));
+        assert_eq!(eval("n == 0 ? 0 : n == 1 ? 1 : 2", 1), Some(1));
+        assert_eq!(eval("n % 0", 3), Some(0));
+        assert_eq!(eval("(n == 1", 1), None);
+        assert_eq!(eval("n = 1", 1), None);
+        assert_eq!(eval("n == 1 ?", 1), None);
+    }
+
+    #[test]
+    fn unquote_resolves_escapes_up_to_the_closing_quote() {
+        assert_eq!(unquote(r#""plain""#), "plain");
+        assert_eq!(unquote(r#""say \"hi\"""#), r#"say "hi""#);
+        assert_eq!(unquote(r#""ends with a quote\"""#), r#"ends with a quote""#);
+        assert_eq!(unquote(r#""C:\\""#), r"C:\");
+        assert_eq!(unquote(r#""\\\"""#), r#"\""#);
+        assert_eq!(
+            unquote(r#""two\nlines\tand a tab""#),
+            "two\nlines\tand a tab"
+        );
+        assert_eq!(unquote(r#""stops here" "not this""#), "stops here");
+    }
+
+    #[test]
+    fn built_in_catalogs_parse() {
+        for (code, _, source) in BUILT_IN {
+            let catalog = catalog(source);
+            assert!(catalog.plural.is_some(), "{code} has no plural rule");
+            assert!(
+                catalog.messages.values().all(|forms| !forms.is_empty()),
+                "{code} has an entry without translations"
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Clicking while the user is away: the clicker starts once no keyboard or
+//! mouse input has come in for a while and stops as soon as some does,
+//! e.g. to keep a session from timing out.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{RecvTimeoutError, Sender},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+use crate::{
+    clicker::{self, Command},
+    listener, source,
+};
+
+/// How often the watcher looks at the time since the last input.
+const POLL: Duration = Duration::from_millis(250);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How long the user has to be idle, in seconds.
+static IDLE_SECS: AtomicU64 = AtomicU64::new(60);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn set_idle_time(idle: Duration) {
+    IDLE_SECS.store(idle.as_secs(), Ordering::SeqCst);
+}
+
+/// Starts a thread that starts the clicker through `tx_command` once the
+/// user has been idle for the set time, while [`is_enabled`], and stops it
+/// at their next input. Input the clicker sent itself does not count, and
+/// runs the user started are left alone.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let mut last_input = Instant::now();
+        // Whether the running clicker was started by this watcher.
+        let mut started = false;
+        loop {
+            match rx_events.recv_timeout(POLL) {
+                Ok(event) => {
+                    if let EventType::MouseMove { x, y } = event.event_type {
+                        if source::is_own_move(x, y) {
+                            continue;
+                        }
+                    }
+                    last_input = Instant::now();
+                    if started {
+                        started = false;
+                        if clicker::is_running() {
+                            log::info!("Input after being idle, stopping the clicker");
+                            tx_command.send(Command::Stop).ok();
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let idle = Duration::from_secs(IDLE_SECS.load(Ordering::SeqCst));
+                    if is_enabled()
+                        && !started
+                        && !clicker::is_running()
+                        && !clicker::is_paused()
+                        && last_input.elapsed() >= idle
+                    {
+                        log::info!(
+                            "Idle for {}, starting the clicker",
+                            humantime::format_duration(idle)
+                        );
+                        started = tx_command.send(Command::Start).is_ok();
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+}
@@ -0,0 +1,525 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use rdev::{Button, EventType, Key};
+
+use crate::{
+    keys,
+    recorder::{CoordinateSpace, RecordedEvent, Recording},
+};
+
+/// Pause before every imported event, AutoHotkey's default mouse and key
+/// delay.
+const EVENT_DELAY: Duration = Duration::from_millis(10);
+
+/// A macro converted from another tool, and what of it could not be.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Imported {
+    pub recording: Recording,
+    /// Lines or records that were left out, to show to the user.
+    pub skipped: Vec<String>,
+}
+
+/// Converts the macro at `path` by its extension: `.rec` files are read as
+/// TinyTask recordings, anything else as an AutoHotkey script.
+pub fn import(path: &Path) -> io::Result<Imported> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("rec"))
+    {
+        from_tinytask(&fs::read(path)?)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+    } else {
+        Ok(from_ahk(&fs::read_to_string(path)?))
+    }
+}
+
+/// Converts the commands of an AutoHotkey script that map to input events:
+///
+/// - `Click [X, Y] [Left|Right|Middle] [Count] [Down|Up]`
+/// - `MouseMove X, Y`
+/// - `Sleep Milliseconds`
+/// - `Send Keys` (also `SendInput`, `SendEvent` and `SendPlay`), with the
+///   `^ ! + #` modifiers and `{Enter}`-style key names
+///
+/// Both the legacy comma syntax and function calls such as `Click(10, 20)`
+/// are read. Positions are taken as screen coordinates, as with
+/// `CoordMode "Mouse", "Screen"`. Comments, directives, labels and blocks
+/// are ignored; other commands are skipped and listed.
+pub fn from_ahk(script: &str) -> Imported {
+    let mut builder = Builder::default();
+    let mut skipped = Vec::new();
+
+    for line in script.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.ends_with("::")
+            || (line.ends_with(':') && !line.contains(' '))
+            || matches!(line, "{" | "}")
+            || line.eq_ignore_ascii_case("return")
+        {
+            continue;
+        }
+
+        let (command, args) = split_command(line);
+        let handled = match command.to_ascii_lowercase().as_str() {
+            "click" => builder.click(&args),
+            "mousemove" => match numbers(&args)[..] {
+                [x, y, ..] => {
+                    builder.push(EventType::MouseMove { x, y });
+                    true
+                }
+                _ => false,
+            },
+            "sleep" => match args.first().and_then(|ms| ms.parse().ok()) {
+                Some(ms) => {
+                    builder.wait(Duration::from_millis(ms));
+                    true
+                }
+                None => false,
+            },
+            "send" | "sendinput" | "sendevent" | "sendplay" => builder.send(&args.join(", ")),
+            _ => false,
+        };
+        if !handled {
+            skipped.push(line.to_owned());
+        }
+    }
+
+    Imported {
+        recording: builder.finish(),
+        skipped,
+    }
+}
+
+/// Converts a TinyTask `.rec` file: a list of Windows journal messages of
+/// five little-endian 32-bit fields each (message, two parameters, tick
+/// count and window). Mouse moves, button presses and key presses are
+/// read; other messages are skipped and counted.
+pub fn from_tinytask(bytes: &[u8]) -> Result<Imported, String> {
+    const RECORD: usize = 20;
+    if !bytes.len().is_multiple_of(RECORD) {
+        return Err("not a TinyTask recording: the size is not a multiple of 20 bytes".to_owned());
+    }
+
+    let mut builder = Builder {
+        event_delay: Duration::ZERO,
+        ..Builder::default()
+    };
+    let mut unknown = 0;
+    let mut last_time = None;
+    for record in bytes.chunks_exact(RECORD) {
+        let field =
+            |i: usize| u32::from_le_bytes(record[i * 4..i * 4 + 4].try_into().unwrap_or_default());
+        let (message, param_l, param_h, time) = (field(0), field(1), field(2), field(3));
+        if let Some(last_time) = last_time {
+            builder.wait(Duration::from_millis(time.wrapping_sub(last_time) as u64));
+        }
+        last_time = Some(time);
+
+        let (x, y) = (param_l as i32 as f64, param_h as i32 as f64);
+        let event_type = match message {
+            0x200 => Some(EventType::MouseMove { x, y }),
+            0x201 => Some(EventType::ButtonPress(Button::Left)),
+            0x202 => Some(EventType::ButtonRelease(Button::Left)),
+            0x204 => Some(EventType::ButtonPress(Button::Right)),
+            0x205 => Some(EventType::ButtonRelease(Button::Right)),
+            0x207 => Some(EventType::ButtonPress(Button::Middle)),
+            0x208 => Some(EventType::ButtonRelease(Button::Middle)),
+            0x100 | 0x104 => virtual_key(param_l & 0xff).map(EventType::KeyPress),
+            0x101 | 0x105 => virtual_key(param_l & 0xff).map(EventType::KeyRelease),
+            _ => None,
+        };
+        match event_type {
+            Some(event_type) => {
+                // Buttons happen where the cursor is; make sure it is there.
+                let is_button = matches!(
+                    event_type,
+                    EventType::ButtonPress(_) | EventType::ButtonRelease(_)
+                );
+                if is_button && builder.position != Some((x, y)) {
+                    builder.push(EventType::MouseMove { x, y });
+                }
+                builder.push(event_type);
+            }
+            None => unknown += 1,
+        }
+    }
+
+    let skipped = if unknown > 0 {
+        vec![format!("{unknown} unsupported messages")]
+    } else {
+        Vec::new()
+    };
+    Ok(Imported {
+        recording: builder.finish(),
+        skipped,
+    })
+}
+
+/// Collects events, carrying waits over to the next event.
+struct Builder {
+    events: Vec<RecordedEvent>,
+    pending: Duration,
+    event_delay: Duration,
+    position: Option<(f64, f64)>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            pending: Duration::ZERO,
+            event_delay: EVENT_DELAY,
+            position: None,
+        }
+    }
+}
+
+impl Builder {
+    fn wait(&mut self, delay: Duration) {
+        self.pending += delay;
+    }
+
+    fn push(&mut self, event_type: EventType) {
+        if let EventType::MouseMove { x, y } = event_type {
+            self.position = Some((x, y));
+        }
+        let delay = std::mem::take(&mut self.pending) + self.event_delay;
+        self.events.push(RecordedEvent { delay, event_type });
+    }
+
+    fn finish(self) -> Recording {
+        Recording {
+            space: CoordinateSpace::Absolute,
+            events: self.events,
+        }
+    }
+
+    /// `Click` with its arguments in any order, as AutoHotkey allows.
+    fn click(&mut self, args: &[String]) -> bool {
+        let mut button = Button::Left;
+        let mut position = Vec::new();
+        let mut count = None;
+        let (mut press, mut release) = (true, true);
+        for arg in args.iter().flat_map(|arg| arg.split_whitespace()) {
+            match arg.to_ascii_lowercase().as_str() {
+                "left" | "l" => button = Button::Left,
+                "right" | "r" => button = Button::Right,
+                "middle" | "m" => button = Button::Middle,
+                "down" | "d" => release = false,
+                "up" | "u" => press = false,
+                number => match number.parse::<f64>() {
+                    Ok(n) if position.len() < 2 => position.push(n),
+                    Ok(n) if count.is_none() => count = Some(n.max(0.0) as usize),
+                    _ => return false,
+                },
+            }
+        }
+
+        match position[..] {
+            [x, y] => self.push(EventType::MouseMove { x, y }),
+            [] => {}
+            // A single number is a click count.
+            [clicks] => count = Some(clicks.max(0.0) as usize),
+            _ => return false,
+        }
+        for _ in 0..count.unwrap_or(1) {
+            if press {
+                self.push(EventType::ButtonPress(button));
+            }
+            if release {
+                self.push(EventType::ButtonRelease(button));
+            }
+        }
+        true
+    }
+
+    /// `Send` keys: characters, `{Name}` keys and modifier prefixes.
+    fn send(&mut self, keys: &str) -> bool {
+        let mut modifiers = Vec::new();
+        let mut chars = keys.chars();
+        while let Some(c) = chars.next() {
+            let (key, shift) = match c {
+                '^' => {
+                    modifiers.push(Key::ControlLeft);
+                    continue;
+                }
+                '!' => {
+                    modifiers.push(Key::Alt);
+                    continue;
+                }
+                '+' => {
+                    modifiers.push(Key::ShiftLeft);
+                    continue;
+                }
+                '#' => {
+                    modifiers.push(Key::MetaLeft);
+                    continue;
+                }
+                '{' => {
+                    let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    match named_key(&name) {
+                        Some(key) => (key, false),
+                        None => return false,
+                    }
+                }
+                c => match keys::for_char(c) {
+                    Some(key) => key,
+                    None => return false,
+                },
+            };
+
+            if shift {
+                modifiers.push(Key::ShiftLeft);
+            }
+            for &modifier in &modifiers {
+                self.push(EventType::KeyPress(modifier));
+            }
+            self.push(EventType::KeyPress(key));
+            self.push(EventType::KeyRelease(key));
+            for &modifier in modifiers.iter().rev() {
+                self.push(EventType::KeyRelease(modifier));
+            }
+            modifiers.clear();
+        }
+        true
+    }
+}
+
+/// A line without its `;` comment.
+fn strip_comment(line: &str) -> &str {
+    if line.trim_start().starts_with(';') {
+        return "";
+    }
+    line.find(" ;")
+        .or_else(|| line.find("\t;"))
+        .map_or(line, |i| &line[..i])
+}
+
+/// The command of a line and its comma-separated arguments, for both
+/// `Sleep, 100` and `Sleep(100)`.
+fn split_command(line: &str) -> (&str, Vec<String>) {
+    let end = line
+        .find(|c: char| c == ',' || c == '(' || c.is_whitespace())
+        .unwrap_or(line.len());
+    let (command, rest) = line.split_at(end);
+    let rest = rest.trim_start();
+    let rest = match rest.strip_prefix('(') {
+        Some(inner) => inner.strip_suffix(')').unwrap_or(inner),
+        None => rest.strip_prefix(',').unwrap_or(rest),
+    };
+    let args = if command.to_ascii_lowercase().starts_with("send") {
+        // Keys may contain commas; keep them whole.
+        vec![rest.trim().trim_matches('"').to_owned()]
+    } else {
+        rest.split(',')
+            .map(|arg| arg.trim().trim_matches('"').to_owned())
+            .filter(|arg| !arg.is_empty())
+            .collect()
+    };
+    (command, args)
+}
+
+fn numbers(args: &[String]) -> Vec<f64> {
+    args.iter().filter_map(|arg| arg.parse().ok()).collect()
+}
+
+/// The key of an AutoHotkey key name, e.g. `Enter` or `F5`, or of a single
+/// character in braces, e.g. `{!}`.
+fn named_key(name: &str) -> Option<Key> {
+    const F_KEYS: [Key; 12] = [
+        Key::F1,
+        Key::F2,
+        Key::F3,
+        Key::F4,
+        Key::F5,
+        Key::F6,
+        Key::F7,
+        Key::F8,
+        Key::F9,
+        Key::F10,
+        Key::F11,
+        Key::F12,
+    ];
+
+    let key = match name.to_ascii_lowercase().as_str() {
+        "enter" => Key::Return,
+        "tab" => Key::Tab,
+        "space" => Key::Space,
+        "esc" | "escape" => Key::Escape,
+        "bs" | "backspace" => Key::Backspace,
+        "del" | "delete" => Key::Delete,
+        "ins" | "insert" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pgup" => Key::PageUp,
+        "pgdn" => Key::PageDown,
+        "up" => Key::UpArrow,
+        "down" => Key::DownArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        f if f.starts_with('f') && f.len() > 1 => {
+            let n: usize = f[1..].parse().ok()?;
+            *F_KEYS.get(n.checked_sub(1)?)?
+        }
+        _ => {
+            let mut chars = name.chars();
+            let (c, None) = (chars.next()?, chars.next()) else {
+                return None;
+            };
+            keys::for_char(c)?.0
+        }
+    };
+    Some(key)
+}
+
+/// The key of a Windows virtual-key code.
+fn virtual_key(code: u32) -> Option<Key> {
+    let key = match code {
+        0x08 => Key::Backspace,
+        0x09 => Key::Tab,
+        0x0d => Key::Return,
+        0x10 | 0xa0 => Key::ShiftLeft,
+        0xa1 => Key::ShiftRight,
+        0x11 | 0xa2 => Key::ControlLeft,
+        0xa3 => Key::ControlRight,
+        0x12 | 0xa4 => Key::Alt,
+        0xa5 => Key::AltGr,
+        0x1b => Key::Escape,
+        0x20 => Key::Space,
+        0x21 => Key::PageUp,
+        0x22 => Key::PageDown,
+        0x23 => Key::End,
+        0x24 => Key::Home,
+        0x25 => Key::LeftArrow,
+        0x26 => Key::UpArrow,
+        0x27 => Key::RightArrow,
+        0x28 => Key::DownArrow,
+        0x2d => Key::Insert,
+        0x2e => Key::Delete,
+        0x30..=0x39 | 0x41..=0x5a => keys::for_char(char::from_u32(code)?.to_ascii_lowercase())?.0,
+        0x70..=0x7b => named_key(&format!("F{}", code - 0x6f))?,
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_types(imported: &Imported) -> Vec<EventType> {
+        imported
+            .recording
+            .events
+            .iter()
+            .map(|event| event.event_type)
+            .collect()
+    }
+
+    #[test]
+    fn ahk_clicks_moves_and_sleeps() {
+        let imported = from_ahk("Click 100, 200\nSleep 50\nClick(right)\nMouseMove, 5, 6");
+        assert!(imported.skipped.is_empty());
+        assert_eq!(
+            event_types(&imported),
+            [
+                EventType::MouseMove { x: 100.0, y: 200.0 },
+                EventType::ButtonPress(Button::Left),
+                EventType::ButtonRelease(Button::Left),
+                EventType::ButtonPress(Button::Right),
+                EventType::ButtonRelease(Button::Right),
+                EventType::MouseMove { x: 5.0, y: 6.0 },
+            ]
+        );
+        // The sleep is carried over to the next event.
+        assert_eq!(
+            imported.recording.events[3].delay,
+            Duration::from_millis(50) + EVENT_DELAY
+        );
+        assert_eq!(imported.recording.space, CoordinateSpace::Absolute);
+    }
+
+    #[test]
+    fn ahk_click_counts_and_halves() {
+        let imported = from_ahk("Click 2\nClick Down\nClick Up");
+        assert_eq!(
+            event_types(&imported),
+            [
+                EventType::ButtonPress(Button::Left),
+                EventType::ButtonRelease(Button::Left),
+                EventType::ButtonPress(Button::Left),
+                EventType::ButtonRelease(Button::Left),
+                EventType::ButtonPress(Button::Left),
+                EventType::ButtonRelease(Button::Left),
+            ]
+        );
+    }
+
+    #[test]
+    fn ahk_send_with_modifiers_and_names() {
+        let imported = from_ahk("Send ^a{Enter}");
+        assert_eq!(
+            event_types(&imported),
+            [
+                EventType::KeyPress(Key::ControlLeft),
+                EventType::KeyPress(Key::KeyA),
+                EventType::KeyRelease(Key::KeyA),
+                EventType::KeyRelease(Key::ControlLeft),
+                EventType::KeyPress(Key::Return),
+                EventType::KeyRelease(Key::Return),
+            ]
+        );
+    }
+
+    #[test]
+    fn ahk_skips_what_it_cannot_convert() {
+        let script = "#NoEnv\n; a comment\nF1::\nMsgBox hi\nSleep, soon ; later\nreturn\n";
+        let imported = from_ahk(script);
+        assert!(imported.recording.events.is_empty());
+        assert_eq!(imported.skipped, ["MsgBox hi", "Sleep, soon"]);
+    }
+
+    fn record(message: u32, param_l: u32, param_h: u32, time: u32) -> Vec<u8> {
+        [message, param_l, param_h, time, 0]
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn tinytask_records() {
+        let bytes = [
+            record(0x200, 10, 20, 1000),
+            record(0x201, 10, 20, 1100),
+            record(0x202, 30, 40, 1150),
+            record(0x100, 0x41, 0, 1200),
+            record(0x0f, 0, 0, 1200),
+        ]
+        .concat();
+        let imported = from_tinytask(&bytes).unwrap();
+        assert_eq!(
+            event_types(&imported),
+            [
+                EventType::MouseMove { x: 10.0, y: 20.0 },
+                EventType::ButtonPress(Button::Left),
+                // The button went up elsewhere, so the cursor moves first.
+                EventType::MouseMove { x: 30.0, y: 40.0 },
+                EventType::ButtonRelease(Button::Left),
+                EventType::KeyPress(Key::KeyA),
+            ]
+        );
+        assert_eq!(
+            imported.recording.events[1].delay,
+            Duration::from_millis(100)
+        );
+        assert_eq!(imported.skipped, ["1 unsupported messages"]);
+    }
+
+    #[test]
+    fn tinytask_rejects_truncated_files() {
+        assert!(from_tinytask(&[0; 19]).is_err());
+        assert_eq!(from_tinytask(&[]), Ok(Imported::default()));
+    }
+}
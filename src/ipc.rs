@@ -0,0 +1,437 @@
+//! Local control of the running app through a Unix domain socket on Linux
+//! and macOS or a named pipe on Windows, for shell scripts and other tools
+//! on the same machine. Unlike [`crate::remote`] it needs no port and only
+//! the user running the app can connect.
+//!
+//! Clients write one command per line and get one line back, `ok`,
+//! `error: <reason>` or the status:
+//!
+//! - `start`, `stop`, `toggle`, `pause` and `resume`
+//! - `status`, answered as `key=value` pairs, e.g.
+//!   `running=true paused=false clicks=120 elapsed_ms=12000 clicks_per_second=10`
+//! - `set interval <DURATION>`, e.g. `set interval 100` or
+//!   `set interval 1.5s`; a bare number is in milliseconds
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    sync::mpsc::Sender,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    clicker::{self, Command, Config},
+    duration,
+    engine::ClickInterval,
+    stats,
+};
+
+/// Lines longer than this are refused.
+const MAX_LINE: u64 = 1024;
+
+/// Starts a thread that accepts local connections and drives the clicker
+/// through `tx_command`.
+pub fn spawn_server(tx_command: Sender<Command>) -> io::Result<()> {
+    let listener = platform::Listener::bind()?;
+    log::info!("Listening for local commands on {}", platform::address());
+    thread::spawn(move || loop {
+        match listener.accept() {
+            Ok(stream) => {
+                let tx_command = tx_command.clone();
+                thread::spawn(move || {
+                    if let Err(e) = serve(&stream, &tx_command) {
+                        log::error!("Local control connection failed: {e}");
+                    }
+                });
+            }
+            Err(e) => {
+                log::error!("Local control connection failed: {e}");
+                // Don't spin if the socket or pipe broke for good.
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Sends `command` to the running app and returns its reply.
+pub fn send(command: &str) -> io::Result<String> {
+    let stream = platform::connect()?;
+    writeln!(&stream, "{}", command.trim())?;
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    Ok(reply.trim_end().to_owned())
+}
+
+/// Where clients connect, for messages.
+pub fn address() -> String {
+    platform::address()
+}
+
+/// Answers the commands of one client until it disconnects.
+fn serve<S>(stream: &S, tx_command: &Sender<Command>) -> io::Result<()>
+where
+    for<'a> &'a S: Read + Write,
+{
+    let mut reader = BufReader::new(stream);
+    let mut writer = stream;
+    loop {
+        let mut line = String::new();
+        if (&mut reader).take(MAX_LINE).read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        if !line.ends_with('\n') && line.len() as u64 == MAX_LINE {
+            writeln!(writer, "error: line too long")?;
+            return Ok(());
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        writeln!(writer, "{}", control(line, tx_command))?;
+        writer.flush()?;
+    }
+}
+
+/// Carries out a command line, returning the reply.
+fn control(line: &str, tx_command: &Sender<Command>) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let command = match words.as_slice() {
+        ["start"] => Command::Start,
+        ["stop"] => Command::Stop,
+        ["toggle"] => Command::Toggle,
+        ["pause"] => Command::Pause,
+        ["resume"] => Command::Resume,
+        ["status"] => return status(),
+        ["set", "interval", value @ ..] if !value.is_empty() => {
+            let value = value.join(" ");
+            match duration::parse(&value, Duration::from_millis(1)) {
+                Some(interval) if interval >= Duration::from_millis(1) => {
+                    Config::ClickInterval(ClickInterval::from_millis(interval.as_millis() as usize))
+                        .into()
+                }
+                _ => return format!("error: invalid interval {value:?}"),
+            }
+        }
+        _ => return format!("error: unknown command {line:?}"),
+    };
+    if tx_command.send(command).is_ok() {
+        "ok".to_owned()
+    } else {
+        "error: the clicker has shut down".to_owned()
+    }
+}
+
+fn status() -> String {
+    let stats = stats::current();
+    format!(
+        "running={} paused={} clicks={} elapsed_ms={} clicks_per_second={:.2}",
+        clicker::is_running(),
+        clicker::is_paused(),
+        stats.clicks,
+        stats.elapsed().as_millis(),
+        stats.clicks_per_second()
+    )
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::{
+        env, fs, io,
+        os::unix::{
+            fs::{DirBuilderExt, MetadataExt},
+            net::{UnixListener, UnixStream},
+        },
+        path::{Path, PathBuf},
+    };
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        /// Binds the socket inside a directory only this user can enter, so
+        /// nobody else can connect from the moment it exists.
+        pub fn bind() -> io::Result<Self> {
+            private_dir(&base_dir())?;
+            let path = path();
+            let listener = match UnixListener::bind(&path) {
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+                    // Left behind by an instance that did not exit cleanly,
+                    // unless another one still answers on it.
+                    if UnixStream::connect(&path).is_ok() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AddrInUse,
+                            "another instance is listening already",
+                        ));
+                    }
+                    fs::remove_file(&path)?;
+                    UnixListener::bind(&path)?
+                }
+                result => result?,
+            };
+            Ok(Self(listener))
+        }
+
+        pub fn accept(&self) -> io::Result<UnixStream> {
+            self.0.accept().map(|(stream, _)| stream)
+        }
+    }
+
+    pub fn connect() -> io::Result<UnixStream> {
+        UnixStream::connect(path())
+    }
+
+    pub fn address() -> String {
+        path().display().to_string()
+    }
+
+    fn path() -> PathBuf {
+        base_dir().join("control.sock")
+    }
+
+    /// `auto-clicker` in the user's runtime directory where there is one;
+    /// otherwise in the temporary directory, named by user.
+    fn base_dir() -> PathBuf {
+        match env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir).join("auto-clicker"),
+            None => env::temp_dir().join(format!("auto-clicker-{}", uid())),
+        }
+    }
+
+    fn uid() -> u32 {
+        // SAFETY: getuid cannot fail.
+        unsafe { libc::getuid() }
+    }
+
+    /// Creates `dir` with mode 0700, or checks that the one there is a
+    /// directory of this user that nobody else can enter, rather than
+    /// something another user put in a shared temporary directory.
+    pub(super) fn private_dir(dir: &Path) -> io::Result<()> {
+        match fs::DirBuilder::new().mode(0o700).create(dir) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            result => result?,
+        }
+        let metadata = fs::symlink_metadata(dir)?;
+        if !metadata.is_dir() || metadata.uid() != uid() || metadata.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is not a directory only this user can access",
+                    dir.display()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{
+        fs::File,
+        io, iter,
+        os::windows::io::{FromRawHandle, RawHandle},
+        ptr,
+    };
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            name: *const u16,
+            open_mode: u32,
+            pipe_mode: u32,
+            max_instances: u32,
+            out_buffer_size: u32,
+            in_buffer_size: u32,
+            default_timeout: u32,
+            security_attributes: *const std::ffi::c_void,
+        ) -> isize;
+        fn ConnectNamedPipe(pipe: isize, overlapped: *mut std::ffi::c_void) -> i32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    const NAME: &str = r"\\.\pipe\auto-clicker";
+    const PIPE_ACCESS_DUPLEX: u32 = 0x3;
+    const FILE_FLAG_FIRST_PIPE_INSTANCE: u32 = 0x0008_0000;
+    const PIPE_REJECT_REMOTE_CLIENTS: u32 = 0x8;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const BUFFER_SIZE: u32 = 4096;
+    const INVALID_HANDLE_VALUE: isize = -1;
+    const ERROR_PIPE_CONNECTED: i32 = 535;
+
+    /// The next pipe instance to hand to a client. A named pipe takes one
+    /// client per instance, so each accepted client gets a fresh one.
+    pub struct Listener(std::sync::Mutex<isize>);
+
+    impl Listener {
+        pub fn bind() -> io::Result<Self> {
+            create(FILE_FLAG_FIRST_PIPE_INSTANCE).map(|pipe| Self(std::sync::Mutex::new(pipe)))
+        }
+
+        pub fn accept(&self) -> io::Result<File> {
+            let mut next = self.0.lock().unwrap();
+            let pipe = *next;
+            // SAFETY: `pipe` is an unconnected instance this listener owns.
+            if unsafe { ConnectNamedPipe(pipe, ptr::null_mut()) } == 0 {
+                let e = io::Error::last_os_error();
+                if e.raw_os_error() != Some(ERROR_PIPE_CONNECTED) {
+                    // The client went away already; start over with a
+                    // fresh instance.
+                    // SAFETY: the handle is not used again.
+                    unsafe { CloseHandle(pipe) };
+                    *next = create(0)?;
+                    return Err(e);
+                }
+            }
+            *next = create(0)?;
+            // SAFETY: the connected instance is handed over to the file,
+            // which closes it.
+            Ok(unsafe { File::from_raw_handle(pipe as RawHandle) })
+        }
+    }
+
+    fn create(flags: u32) -> io::Result<isize> {
+        let name: Vec<u16> = NAME.encode_utf16().chain(iter::once(0)).collect();
+        // SAFETY: the name is NUL-terminated and outlives the call. The
+        // default security lets only this user and administrators connect.
+        let pipe = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX | flags,
+                PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                ptr::null(),
+            )
+        };
+        if pipe == INVALID_HANDLE_VALUE {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(pipe)
+        }
+    }
+
+    pub fn connect() -> io::Result<File> {
+        File::options().read(true).write(true).open(NAME)
+    }
+
+    pub fn address() -> String {
+        NAME.to_owned()
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::{fs::File, io};
+
+    pub struct Listener;
+
+    impl Listener {
+        pub fn bind() -> io::Result<Self> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+
+        pub fn accept(&self) -> io::Result<File> {
+            Err(io::ErrorKind::Unsupported.into())
+        }
+    }
+
+    pub fn connect() -> io::Result<File> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn address() -> String {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn carries_out_commands() {
+        let (tx_command, rx_command) = mpsc::channel();
+        assert_eq!(control("start", &tx_command), "ok");
+        assert!(matches!(rx_command.try_recv(), Ok(Command::Start)));
+        assert_eq!(control("  toggle ", &tx_command), "ok");
+        assert!(matches!(rx_command.try_recv(), Ok(Command::Toggle)));
+
+        assert_eq!(control("set interval 1.5s", &tx_command), "ok");
+        assert!(matches!(
+            rx_command.try_recv(),
+            Ok(Command::UpdateConfig(Config::ClickInterval(interval)))
+                if interval.total_millis() == 1500
+        ));
+        assert_eq!(control("set interval 250", &tx_command), "ok");
+        assert!(matches!(
+            rx_command.try_recv(),
+            Ok(Command::UpdateConfig(Config::ClickInterval(interval)))
+                if interval.total_millis() == 250
+        ));
+
+        assert!(control("set interval 0", &tx_command).starts_with("error: invalid interval"));
+        assert!(control("set interval", &tx_command).starts_with("error: unknown command"));
+        assert!(control("start now", &tx_command).starts_with("error: unknown command"));
+        assert!(control("status", &tx_command).starts_with("running="));
+        assert!(rx_command.try_recv().is_err());
+
+        drop(rx_command);
+        assert_eq!(
+            control("stop", &tx_command),
+            "error: the clicker has shut down"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn answers_one_line_per_command_line() {
+        use std::os::unix::net::UnixStream;
+
+        let (client, server) = UnixStream::pair().unwrap();
+        let (tx_command, rx_command) = mpsc::channel();
+        let server = thread::spawn(move || serve(&server, &tx_command));
+
+        write!(&client, "start\n\n  \nbogus\r\nstop\n").unwrap();
+        writeln!(&client, "{}", "x".repeat(MAX_LINE as usize)).unwrap();
+        let mut replies = String::new();
+        BufReader::new(&client)
+            .read_to_string(&mut replies)
+            .unwrap();
+        assert_eq!(
+            replies,
+            "ok\nerror: unknown command \"bogus\"\nok\nerror: line too long\n"
+        );
+        server.join().unwrap().unwrap();
+        assert!(matches!(rx_command.try_recv(), Ok(Command::Start)));
+        assert!(matches!(rx_command.try_recv(), Ok(Command::Stop)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_the_socket_directory_private() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let base = std::env::temp_dir().join(format!("auto-clicker-ipc-{}", std::process::id()));
+        fs::create_dir_all(&base).unwrap();
+        let dir = base.join("private");
+        platform::private_dir(&dir).unwrap();
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // A second run finds it as it left it.
+        platform::private_dir(&dir).unwrap();
+
+        let shared = base.join("shared");
+        fs::create_dir(&shared).unwrap();
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(platform::private_dir(&shared).is_err());
+        let file = base.join("file");
+        fs::write(&file, "").unwrap();
+        assert!(platform::private_dir(&file).is_err());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,385 @@
+//! Click jobs: simple clickers of their own, each with an interval, button,
+//! position and hotkey, that run next to each other and next to the main
+//! clicker, e.g. a left click at one point every 100 ms and a right click
+//! at another every 2 s.
+//!
+//! Each running job has its own thread. Their events go out one click at a
+//! time under [`clicker::lock_sending`], so a job's move and click are
+//! never split by another job's or the clicking loop's.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+use crate::{
+    activation::{self, Activation, Trigger},
+    clicker,
+    config::Table,
+    engine::{ClickType, MouseButton},
+    listener,
+    modifiers::Modifiers,
+};
+
+/// How often a waiting job checks whether it was stopped.
+const STOP_POLL: Duration = Duration::from_millis(20);
+
+/// The shortest interval a job runs at.
+pub const MIN_INTERVAL_MS: usize = 1;
+
+/// One click job.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    /// Identifies the job while the app runs, whatever its place in the
+    /// list. Not saved.
+    pub id: u64,
+    pub name: String,
+    pub interval_ms: usize,
+    pub button: MouseButton,
+    pub click_type: ClickType,
+    /// Where to click on the desktop, `None` for wherever the cursor is.
+    pub position: Option<(i32, i32)>,
+    /// Starts and stops the job wherever the focus is.
+    pub hotkey: Option<Trigger>,
+}
+
+impl Job {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: next_id(),
+            name,
+            interval_ms: 1000,
+            button: MouseButton::Left,
+            click_type: ClickType::Single,
+            position: None,
+            hotkey: None,
+        }
+    }
+
+    /// Reads job `index` from the `jobs.<index>.*` keys of `table`.
+    pub fn from_table(table: &Table, index: usize) -> Option<Self> {
+        let key = |field: &str| format!("jobs.{index}.{field}");
+        let name = table.get_str(&key("name"))?;
+        let x = table.get::<i32>(&key("x"));
+        let y = table.get::<i32>(&key("y"));
+        Some(Self {
+            id: next_id(),
+            name,
+            interval_ms: table
+                .get::<usize>(&key("interval_ms"))
+                .unwrap_or(1000)
+                .max(MIN_INTERVAL_MS),
+            button: table
+                .get_str(&key("button"))
+                .and_then(|button| button.parse().ok())
+                .unwrap_or_default(),
+            click_type: ClickType::from_clicks(table.get(&key("clicks")).unwrap_or(1)),
+            position: x.zip(y),
+            hotkey: table
+                .get_str(&key("hotkey"))
+                .and_then(|hotkey| Trigger::parse(&hotkey)),
+        })
+    }
+
+    pub fn to_table(&self, table: &mut Table, index: usize) {
+        let key = |field: &str| format!("jobs.{index}.{field}");
+        table.set_str(&key("name"), &self.name);
+        table.set(&key("interval_ms"), self.interval_ms);
+        table.set_str(
+            &key("button"),
+            match self.button {
+                MouseButton::Left => "left",
+                MouseButton::Middle => "middle",
+                MouseButton::Right => "right",
+            },
+        );
+        table.set(&key("clicks"), self.click_type.clicks());
+        if let Some((x, y)) = self.position {
+            table.set(&key("x"), x);
+            table.set(&key("y"), y);
+        }
+        if let Some(hotkey) = self.hotkey {
+            table.set_str(&key("hotkey"), &hotkey.name());
+        }
+    }
+}
+
+fn next_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The jobs as last set, which running jobs read before every click.
+static JOBS: Mutex<Vec<Job>> = Mutex::new(Vec::new());
+
+/// The stop flags of the running jobs, by id.
+static RUNNING: Mutex<Option<HashMap<u64, Arc<AtomicBool>>>> = Mutex::new(None);
+
+/// Sets the jobs, e.g. after one was edited in the GUI. Running jobs pick
+/// up their changes before their next click; removed ones stop.
+pub fn set_jobs(jobs: &[Job]) {
+    *JOBS.lock().unwrap_or_else(PoisonError::into_inner) = jobs.to_vec();
+    let mut running = RUNNING.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(running) = running.as_mut() {
+        running.retain(|id, stop| {
+            let kept = jobs.iter().any(|job| job.id == *id);
+            if !kept {
+                stop.store(true, Ordering::SeqCst);
+            }
+            kept
+        });
+    }
+}
+
+fn job(id: u64) -> Option<Job> {
+    JOBS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|job| job.id == id)
+        .cloned()
+}
+
+pub fn is_running(id: u64) -> bool {
+    RUNNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .is_some_and(|running| running.contains_key(&id))
+}
+
+/// Whether any job is running.
+pub fn any_running() -> bool {
+    RUNNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .is_some_and(|running| !running.is_empty())
+}
+
+/// Starts job `id` on its own thread unless it is running already. It
+/// clicks right away and then every interval until stopped.
+pub fn start(id: u64) {
+    if clicker::is_shielded() || job(id).is_none() {
+        return;
+    }
+    let mut running = RUNNING.lock().unwrap_or_else(PoisonError::into_inner);
+    let running = running.get_or_insert_with(HashMap::new);
+    if running.contains_key(&id) {
+        return;
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    running.insert(id, Arc::clone(&stop));
+    thread::spawn(move || {
+        run(id, &stop);
+        if let Some(running) = RUNNING
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            // A restart may have put a new flag in its place.
+            if running
+                .get(&id)
+                .is_some_and(|flag| Arc::ptr_eq(flag, &stop))
+            {
+                running.remove(&id);
+            }
+        }
+    });
+}
+
+pub fn stop(id: u64) {
+    if let Some(running) = RUNNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        if let Some(stop) = running.remove(&id) {
+            stop.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+pub fn toggle(id: u64) {
+    if is_running(id) {
+        stop(id);
+    } else {
+        start(id);
+    }
+}
+
+pub fn start_all() {
+    let ids: Vec<u64> = JOBS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|job| job.id)
+        .collect();
+    ids.into_iter().for_each(start);
+}
+
+/// Stops every job, e.g. on the Stop hotkey or the fail-safe.
+pub fn stop_all() {
+    if let Some(running) = RUNNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        for (_, stop) in running.drain() {
+            stop.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+fn run(id: u64, stop: &AtomicBool) {
+    let mut next = Instant::now();
+    while !stop.load(Ordering::SeqCst) {
+        let Some(job) = job(id) else {
+            return;
+        };
+        if Instant::now() >= next {
+            click(&job);
+            next += Duration::from_millis(job.interval_ms.max(MIN_INTERVAL_MS) as u64);
+            // Don't rush to catch up after falling behind, e.g. while the
+            // clicks of other jobs went first.
+            next = next.max(Instant::now());
+        }
+        thread::sleep(
+            next.saturating_duration_since(Instant::now())
+                .min(STOP_POLL),
+        );
+    }
+}
+
+fn click(job: &Job) {
+    // Like the clicker, a job skips clicks on the app's own window.
+    if clicker::is_own_window(job.position) {
+        return;
+    }
+    let _sending = clicker::lock_sending();
+    if let Some((x, y)) = job.position {
+        clicker::move_to(x, y);
+    }
+    let button = clicker::rdev_button(job.button);
+    for _ in 0..job.click_type.clicks() {
+        clicker::send(&EventType::ButtonPress(button));
+        clicker::send(&EventType::ButtonRelease(button));
+    }
+}
+
+/// Whether a job starts and stops with `trigger`.
+pub fn is_hotkey(trigger: Trigger) -> bool {
+    JOBS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .any(|job| job.hotkey == Some(trigger))
+}
+
+/// Starts a thread that toggles a job whenever its hotkey is pressed,
+/// wherever the focus is. The hold and toggle triggers of the main clicker
+/// win over a job's hotkey.
+pub fn spawn_watcher() {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let mut held = Modifiers::NONE;
+        // Jobs whose hotkey is down; held keys repeat their presses.
+        let mut down = HashSet::new();
+        for event in rx_events {
+            let before = held;
+            let is_modifier = held.update(&event.event_type);
+            // A modifier hotkey is pressed with itself held.
+            let modifiers = if is_modifier { before } else { held };
+            let is_trigger = |hotkey| {
+                [Activation::Hold, Activation::Toggle]
+                    .into_iter()
+                    .any(|activation| activation::trigger(activation) == Some(hotkey))
+            };
+            let mut pressed = Vec::new();
+            for job in JOBS.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+                let Some(hotkey) = job.hotkey.filter(|&hotkey| !is_trigger(hotkey)) else {
+                    continue;
+                };
+                match hotkey.pressed(&event.event_type, modifiers) {
+                    Some(true) if down.insert(job.id) => pressed.push(job.id),
+                    Some(false) => {
+                        down.remove(&job.id);
+                    }
+                    _ => {}
+                }
+            }
+            pressed.into_iter().for_each(toggle);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use super::*;
+
+    /// The jobs are global, so the tests take turns.
+    static EXCLUSIVE: Mutex<()> = Mutex::new(());
+
+    /// A job whose clicks land on the app's own window and are skipped,
+    /// so running it sends no input.
+    fn quiet_job() -> Job {
+        clicker::set_own_window(Some((0, 0, 100, 100)));
+        Job {
+            position: Some((10, 10)),
+            ..Job::new("quiet".to_owned())
+        }
+    }
+
+    #[test]
+    fn start_stop_and_toggle() {
+        let _exclusive = EXCLUSIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        let job = quiet_job();
+        set_jobs(slice::from_ref(&job));
+
+        start(job.id);
+        start(job.id);
+        assert!(is_running(job.id));
+        assert!(any_running());
+        stop(job.id);
+        assert!(!is_running(job.id));
+
+        toggle(job.id);
+        assert!(is_running(job.id));
+        toggle(job.id);
+        assert!(!is_running(job.id));
+
+        // Only jobs that were set can start.
+        let unknown = quiet_job();
+        start(unknown.id);
+        assert!(!is_running(unknown.id));
+        assert!(!any_running());
+    }
+
+    #[test]
+    fn set_jobs_stops_removed_jobs_and_updates_the_rest() {
+        let _exclusive = EXCLUSIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        let (kept, removed) = (quiet_job(), quiet_job());
+        set_jobs(&[kept.clone(), removed.clone()]);
+        start_all();
+        assert!(is_running(kept.id) && is_running(removed.id));
+
+        let edited = Job {
+            interval_ms: 50,
+            ..kept.clone()
+        };
+        set_jobs(slice::from_ref(&edited));
+        assert!(is_running(kept.id));
+        assert!(!is_running(removed.id));
+        assert_eq!(job(kept.id), Some(edited));
+
+        stop_all();
+        assert!(!any_running());
+    }
+}
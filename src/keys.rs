@@ -0,0 +1,235 @@
+/// Keys the keyboard auto-presser can repeat, as sent by rdev. Only keys
+/// egui knows too are listed, see [`EGUI_KEYS`].
+const KEYS: [rdev::Key; KEY_COUNT] = [
+    rdev::Key::DownArrow,
+    rdev::Key::LeftArrow,
+    rdev::Key::RightArrow,
+    rdev::Key::UpArrow,
+    rdev::Key::Escape,
+    rdev::Key::Tab,
+    rdev::Key::Backspace,
+    rdev::Key::Return,
+    rdev::Key::Space,
+    rdev::Key::Insert,
+    rdev::Key::Delete,
+    rdev::Key::Home,
+    rdev::Key::End,
+    rdev::Key::PageUp,
+    rdev::Key::PageDown,
+    rdev::Key::Minus,
+    rdev::Key::Equal,
+    rdev::Key::Num0,
+    rdev::Key::Num1,
+    rdev::Key::Num2,
+    rdev::Key::Num3,
+    rdev::Key::Num4,
+    rdev::Key::Num5,
+    rdev::Key::Num6,
+    rdev::Key::Num7,
+    rdev::Key::Num8,
+    rdev::Key::Num9,
+    rdev::Key::KeyA,
+    rdev::Key::KeyB,
+    rdev::Key::KeyC,
+    rdev::Key::KeyD,
+    rdev::Key::KeyE,
+    rdev::Key::KeyF,
+    rdev::Key::KeyG,
+    rdev::Key::KeyH,
+    rdev::Key::KeyI,
+    rdev::Key::KeyJ,
+    rdev::Key::KeyK,
+    rdev::Key::KeyL,
+    rdev::Key::KeyM,
+    rdev::Key::KeyN,
+    rdev::Key::KeyO,
+    rdev::Key::KeyP,
+    rdev::Key::KeyQ,
+    rdev::Key::KeyR,
+    rdev::Key::KeyS,
+    rdev::Key::KeyT,
+    rdev::Key::KeyU,
+    rdev::Key::KeyV,
+    rdev::Key::KeyW,
+    rdev::Key::KeyX,
+    rdev::Key::KeyY,
+    rdev::Key::KeyZ,
+    rdev::Key::F1,
+    rdev::Key::F2,
+    rdev::Key::F3,
+    rdev::Key::F4,
+    rdev::Key::F5,
+    rdev::Key::F6,
+    rdev::Key::F7,
+    rdev::Key::F8,
+    rdev::Key::F9,
+    rdev::Key::F10,
+    rdev::Key::F11,
+    rdev::Key::F12,
+];
+
+const KEY_COUNT: usize = 65;
+
+/// The keys of [`KEYS`] in the same order, as reported by egui when the user
+/// presses them; modifiers never reach egui as key events.
+#[cfg(feature = "gui")]
+const EGUI_KEYS: [egui::Key; KEY_COUNT] = [
+    egui::Key::ArrowDown,
+    egui::Key::ArrowLeft,
+    egui::Key::ArrowRight,
+    egui::Key::ArrowUp,
+    egui::Key::Escape,
+    egui::Key::Tab,
+    egui::Key::Backspace,
+    egui::Key::Enter,
+    egui::Key::Space,
+    egui::Key::Insert,
+    egui::Key::Delete,
+    egui::Key::Home,
+    egui::Key::End,
+    egui::Key::PageUp,
+    egui::Key::PageDown,
+    egui::Key::Minus,
+    egui::Key::PlusEquals,
+    egui::Key::Num0,
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+    egui::Key::A,
+    egui::Key::B,
+    egui::Key::C,
+    egui::Key::D,
+    egui::Key::E,
+    egui::Key::F,
+    egui::Key::G,
+    egui::Key::H,
+    egui::Key::I,
+    egui::Key::J,
+    egui::Key::K,
+    egui::Key::L,
+    egui::Key::M,
+    egui::Key::N,
+    egui::Key::O,
+    egui::Key::P,
+    egui::Key::Q,
+    egui::Key::R,
+    egui::Key::S,
+    egui::Key::T,
+    egui::Key::U,
+    egui::Key::V,
+    egui::Key::W,
+    egui::Key::X,
+    egui::Key::Y,
+    egui::Key::Z,
+    egui::Key::F1,
+    egui::Key::F2,
+    egui::Key::F3,
+    egui::Key::F4,
+    egui::Key::F5,
+    egui::Key::F6,
+    egui::Key::F7,
+    egui::Key::F8,
+    egui::Key::F9,
+    egui::Key::F10,
+    egui::Key::F11,
+    egui::Key::F12,
+];
+
+/// The rdev key for a key pressed in the GUI, if it can be repeated.
+#[cfg(feature = "gui")]
+pub fn from_egui(key: egui::Key) -> Option<rdev::Key> {
+    let index = EGUI_KEYS.iter().position(|egui_key| *egui_key == key)?;
+    Some(KEYS[index])
+}
+
+/// The name a key is saved and shown under, e.g. `KeyA` or `Return`.
+pub fn name(key: rdev::Key) -> String {
+    format!("{key:?}")
+}
+
+/// The name a key is shown under in a combination, e.g. `S` for `KeyS`
+/// or `1` for `Num1`. [`parse`] reads it back.
+pub fn label(key: rdev::Key) -> String {
+    let name = name(key);
+    match name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Num"))
+    {
+        Some(short) if short.len() == 1 => short.to_owned(),
+        _ => name,
+    }
+}
+
+/// Parses a key name as written by [`name`], ignoring case. A single letter
+/// or digit is accepted as a shorthand for `KeyA` or `Num1`.
+pub fn parse(s: &str) -> Option<rdev::Key> {
+    let s = s.trim();
+    KEYS.into_iter().find(|key| {
+        let name = name(*key);
+        name.eq_ignore_ascii_case(s)
+            || name
+                .strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Num"))
+                .is_some_and(|short| short.eq_ignore_ascii_case(s))
+    })
+}
+
+/// The key that types `c` on a US keyboard layout and whether Shift has to
+/// be held for it.
+pub fn for_char(c: char) -> Option<(rdev::Key, bool)> {
+    use rdev::Key::*;
+
+    if c.is_ascii_alphabetic() {
+        let letter = c.to_ascii_uppercase().to_string();
+        return parse(&letter).map(|key| (key, c.is_ascii_uppercase()));
+    }
+    if c.is_ascii_digit() {
+        return parse(&c.to_string()).map(|key| (key, false));
+    }
+
+    let key = match c {
+        ' ' => (Space, false),
+        '\n' => (Return, false),
+        '\t' => (Tab, false),
+        '`' => (BackQuote, false),
+        '~' => (BackQuote, true),
+        '-' => (Minus, false),
+        '_' => (Minus, true),
+        '=' => (Equal, false),
+        '+' => (Equal, true),
+        '[' => (LeftBracket, false),
+        '{' => (LeftBracket, true),
+        ']' => (RightBracket, false),
+        '}' => (RightBracket, true),
+        '\\' => (BackSlash, false),
+        '|' => (BackSlash, true),
+        ';' => (SemiColon, false),
+        ':' => (SemiColon, true),
+        '\'' => (Quote, false),
+        '"' => (Quote, true),
+        ',' => (Comma, false),
+        '<' => (Comma, true),
+        '.' => (Dot, false),
+        '>' => (Dot, true),
+        '/' => (Slash, false),
+        '?' => (Slash, true),
+        '!' => (Num1, true),
+        '@' => (Num2, true),
+        '#' => (Num3, true),
+        '$' => (Num4, true),
+        '%' => (Num5, true),
+        '^' => (Num6, true),
+        '&' => (Num7, true),
+        '*' => (Num8, true),
+        '(' => (Num9, true),
+        ')' => (Num0, true),
+        _ => return None,
+    };
+    Some(key)
+}
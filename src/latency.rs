@@ -0,0 +1,134 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use rdev::{Button, EventType};
+
+use crate::{
+    listener, recorder,
+    source::{self, Backend},
+};
+
+/// Clicks sent per test.
+const CLICKS: usize = 20;
+
+/// Pause between the clicks of a test.
+const GAP: Duration = Duration::from_millis(50);
+
+/// How long the test waits after its last click for the listener to catch
+/// up before counting the rest as missed.
+const SETTLE: Duration = Duration::from_millis(250);
+
+static TESTING: AtomicBool = AtomicBool::new(false);
+
+/// What the listener saw of the current test so far.
+static SAMPLES: Mutex<Vec<Duration>> = Mutex::new(Vec::new());
+
+/// The last finished test of each backend.
+static REPORTS: Mutex<BTreeMap<Backend, LatencyReport>> = Mutex::new(BTreeMap::new());
+
+/// How long the events of one test took from being sent to being seen by
+/// the input listener.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyReport {
+    pub backend: Backend,
+    /// Presses and releases the listener saw.
+    pub seen: usize,
+    /// Presses and releases sent that it never saw.
+    pub missed: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl fmt::Display for LatencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            "{}: {:.2} ms mean, {:.2}-{:.2} ms, {} of {} seen",
+            self.backend,
+            ms(self.mean),
+            ms(self.min),
+            ms(self.max),
+            self.seen,
+            self.seen + self.missed
+        )
+    }
+}
+
+pub fn is_running() -> bool {
+    TESTING.load(Ordering::SeqCst)
+}
+
+/// The latest report of every backend tested so far.
+pub fn reports() -> Vec<LatencyReport> {
+    REPORTS.lock().unwrap().values().copied().collect()
+}
+
+/// Notes that the listener saw a sent event `latency` after it was sent.
+/// Ignored unless a test is running.
+pub fn observed(latency: Duration) {
+    if is_running() {
+        SAMPLES.lock().unwrap().push(latency);
+    }
+}
+
+/// Starts a test of the backend in use on its own thread, unless one is
+/// running. It left-clicks wherever the cursor is, and only while the
+/// cursor is over the app's window, so nothing else gets clicked.
+pub fn start() {
+    if TESTING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    listener::start();
+    SAMPLES.lock().unwrap().clear();
+
+    thread::spawn(|| {
+        let backend = source::backend();
+        let mut sent: usize = 0;
+        'clicks: for _ in 0..CLICKS {
+            if !recorder::is_own_window_hovered() {
+                break;
+            }
+            for event_type in [
+                EventType::ButtonPress(Button::Left),
+                EventType::ButtonRelease(Button::Left),
+            ] {
+                if let Err(e) = source::simulate(&event_type) {
+                    log::error!("Could not send a test click: {e:?}");
+                    break 'clicks;
+                }
+                sent += 1;
+            }
+            thread::sleep(GAP);
+        }
+        thread::sleep(SETTLE);
+
+        TESTING.store(false, Ordering::SeqCst);
+        let samples = std::mem::take(&mut *SAMPLES.lock().unwrap());
+        if samples.is_empty() {
+            if sent > 0 {
+                log::error!("The input listener saw none of the {sent} test events");
+            }
+            return;
+        }
+        let total: Duration = samples.iter().sum();
+        let report = LatencyReport {
+            backend,
+            seen: samples.len(),
+            missed: sent.saturating_sub(samples.len()),
+            mean: total / samples.len() as u32,
+            min: samples.iter().copied().min().unwrap_or_default(),
+            max: samples.iter().copied().max().unwrap_or_default(),
+        };
+        REPORTS.lock().unwrap().insert(backend, report);
+    });
+}
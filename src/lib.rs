@@ -0,0 +1,166 @@
+//! A portable auto clicker for Linux, macOS and Windows.
+//!
+//! Besides the app itself, the crate can be embedded: [`engine::ClickerEngine`]
+//! runs the clicking engine on its own thread without opening a window.
+
+// Much of the crate only backs the window, and goes unused without it.
+#![cfg_attr(not(feature = "gui"), allow(dead_code))]
+
+pub(crate) mod abort;
+pub(crate) mod accuracy;
+pub(crate) mod activation;
+pub(crate) mod alert;
+pub(crate) mod bundle;
+pub(crate) mod burst;
+pub(crate) mod calibration;
+pub(crate) mod changelog;
+pub(crate) mod cli;
+pub mod clicker;
+pub(crate) mod clipboard;
+pub(crate) mod config;
+pub(crate) mod display;
+pub(crate) mod dnd;
+#[cfg(feature = "gui")]
+pub(crate) mod dock;
+pub(crate) mod drag;
+pub(crate) mod duration;
+pub(crate) mod dwell;
+pub(crate) mod elevation;
+pub mod engine;
+pub(crate) mod failsafe;
+#[cfg(feature = "ffi")]
+pub(crate) mod ffi;
+#[cfg(feature = "gui")]
+pub(crate) mod fonts;
+#[cfg(feature = "gamepad")]
+pub(crate) mod gamepad;
+#[cfg(feature = "gui")]
+pub(crate) mod gui;
+pub(crate) mod history;
+pub(crate) mod hotkeys;
+pub(crate) mod humanize;
+pub(crate) mod i18n;
+pub(crate) mod idle;
+pub(crate) mod importer;
+pub(crate) mod ipc;
+pub(crate) mod jobs;
+pub(crate) mod keys;
+pub(crate) mod latency;
+pub(crate) mod library;
+pub(crate) mod listener;
+pub(crate) mod logging;
+pub(crate) mod modifiers;
+pub(crate) mod monitor;
+#[cfg(feature = "mqtt")]
+pub(crate) mod mqtt;
+#[cfg(feature = "notifications")]
+pub(crate) mod notification;
+pub(crate) mod overlay;
+pub(crate) mod path;
+pub(crate) mod pattern;
+pub(crate) mod permission;
+pub(crate) mod pick;
+pub(crate) mod pixel;
+pub(crate) mod pointer;
+pub(crate) mod portable;
+pub(crate) mod presets;
+pub(crate) mod priority;
+pub mod profile;
+pub(crate) mod ramp;
+pub(crate) mod ratelimit;
+pub(crate) mod recorder;
+pub(crate) mod region;
+pub(crate) mod reminder;
+#[cfg(feature = "remote")]
+pub(crate) mod remote;
+pub(crate) mod rotation;
+pub(crate) mod sandbox;
+pub(crate) mod schedule;
+pub(crate) mod screen;
+pub(crate) mod script;
+pub(crate) mod sendinput;
+pub(crate) mod sequence;
+pub(crate) mod service;
+pub(crate) mod session;
+pub(crate) mod settings;
+pub(crate) mod slots;
+#[cfg(feature = "sound")]
+pub(crate) mod sound;
+pub(crate) mod source;
+#[cfg(feature = "speech")]
+pub(crate) mod speech;
+pub(crate) mod stats;
+pub(crate) mod supervisor;
+pub(crate) mod takeover;
+pub(crate) mod target;
+#[cfg(feature = "gui")]
+pub(crate) mod taskbar;
+pub(crate) mod template;
+pub(crate) mod theme;
+pub(crate) mod timeline;
+pub(crate) mod trace;
+#[cfg(feature = "tray")]
+pub(crate) mod tray;
+pub(crate) mod turbo;
+pub(crate) mod typing;
+pub(crate) mod uinput;
+#[cfg(feature = "voice")]
+pub(crate) mod voice;
+pub(crate) mod vsync;
+pub(crate) mod watch;
+#[cfg(feature = "webhooks")]
+pub(crate) mod webhook;
+#[cfg(feature = "gui")]
+pub(crate) mod window;
+#[cfg(feature = "gui")]
+pub(crate) mod wizard;
+pub(crate) mod xtest;
+
+/// The app's entry point, for the `auto-clicker` binary.
+#[doc(hidden)]
+pub async fn run() {
+    logging::init();
+    match cli::parse(std::env::args().skip(1), &config::environment()) {
+        #[cfg(feature = "gui")]
+        Ok(cli::Command::Gui {
+            start_minimized,
+            autostart,
+            preset,
+        }) => window::run(start_minimized, autostart, preset).await,
+        #[cfg(not(feature = "gui"))]
+        Ok(cli::Command::Gui { .. }) => {
+            eprintln!("error: built without the GUI; see --help for the headless options");
+            std::process::exit(2);
+        }
+        Ok(cli::Command::Headless(args)) => cli::run_headless(*args),
+        Ok(cli::Command::Install(args)) => {
+            if let Err(e) = service::install(&args) {
+                eprintln!("Could not install the service: {e}");
+                std::process::exit(1);
+            }
+        }
+        Ok(cli::Command::Uninstall) => {
+            if let Err(e) = service::uninstall() {
+                eprintln!("Could not uninstall the service: {e}");
+                std::process::exit(1);
+            }
+        }
+        Ok(cli::Command::Send(command)) => match ipc::send(&command) {
+            Ok(reply) => {
+                println!("{reply}");
+                if reply.starts_with("error:") {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Could not reach the running app on {}: {e}", ipc::address());
+                std::process::exit(1);
+            }
+        },
+        Ok(cli::Command::Exit) => {}
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+    }
+}
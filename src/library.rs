@@ -0,0 +1,164 @@
+//! Saved macro recordings, stored as `<config dir>/macros/<name>.macro`:
+//! a few `# key value` lines of metadata, then the recording as written by
+//! [`Recording::to_text`].
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crate::{config, recorder::Recording};
+
+/// What the library shows of a saved recording without loading it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MacroInfo {
+    pub name: String,
+    /// When it was saved, if the file says.
+    pub saved: Option<SystemTime>,
+    /// How long it plays at its recorded speed.
+    pub duration: Duration,
+    pub events: usize,
+}
+
+impl MacroInfo {
+    fn of(name: &str, recording: &Recording, saved: SystemTime) -> Self {
+        Self {
+            name: name.to_owned(),
+            saved: Some(saved),
+            duration: recording.events.iter().map(|event| event.delay).sum(),
+            events: recording.events.len(),
+        }
+    }
+
+    fn to_header(&self) -> String {
+        let mut lines = vec![format!("# name {}", self.name)];
+        if let Some(saved) = self.saved {
+            lines.push(format!(
+                "# saved {}",
+                humantime::format_rfc3339_seconds(saved)
+            ));
+        }
+        lines.push(format!("# duration_us {}", self.duration.as_micros()));
+        lines.push(format!("# events {}", self.events));
+        lines.join("\n")
+    }
+
+    /// Reads the metadata of a saved file. Files without it, e.g. copied
+    /// from a quick slot, are measured from their events.
+    fn parse(name: &str, text: &str) -> Self {
+        let mut info = Self {
+            name: name.to_owned(),
+            saved: None,
+            duration: Duration::ZERO,
+            events: 0,
+        };
+        let mut has_header = false;
+        for line in text.lines() {
+            let Some((key, value)) = line
+                .strip_prefix("# ")
+                .and_then(|line| line.split_once(' '))
+            else {
+                continue;
+            };
+            has_header = true;
+            match key {
+                "saved" => info.saved = humantime::parse_rfc3339(value).ok(),
+                "duration_us" => {
+                    info.duration = Duration::from_micros(value.parse().unwrap_or_default());
+                }
+                "events" => info.events = value.parse().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        if !has_header {
+            let recording = Recording::parse(text);
+            info.duration = recording.events.iter().map(|event| event.delay).sum();
+            info.events = recording.events.len();
+        }
+        info
+    }
+}
+
+/// The saved recordings, sorted by name.
+pub fn list() -> Vec<MacroInfo> {
+    let Some(dir) = macros_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut macros: Vec<MacroInfo> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "macro" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?;
+            let text = fs::read_to_string(&path).ok()?;
+            Some(MacroInfo::parse(name, &text))
+        })
+        .collect();
+    macros.sort_by(|a, b| a.name.cmp(&b.name));
+    macros
+}
+
+/// Saves `recording` as `name`, replacing any recording of that name.
+pub fn save(name: &str, recording: &Recording) -> io::Result<()> {
+    let path = macro_path(name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let info = MacroInfo::of(name.trim(), recording, SystemTime::now());
+    fs::write(
+        path,
+        format!("{}\n{}\n", info.to_header(), recording.to_text()),
+    )
+}
+
+pub fn load(name: &str) -> io::Result<Recording> {
+    Ok(Recording::parse(&fs::read_to_string(macro_path(name)?)?))
+}
+
+/// Renames a saved recording. Fails rather than replace another one.
+pub fn rename(name: &str, new_name: &str) -> io::Result<()> {
+    let (from, to) = (macro_path(name)?, macro_path(new_name)?);
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("a macro named {:?} exists already", new_name.trim()),
+        ));
+    }
+    let text = fs::read_to_string(&from)?;
+    let mut info = MacroInfo::parse(name, &text);
+    info.name = new_name.trim().to_owned();
+    let recording = Recording::parse(&text);
+    fs::write(
+        &to,
+        format!("{}\n{}\n", info.to_header(), recording.to_text()),
+    )?;
+    fs::remove_file(from)
+}
+
+pub fn delete(name: &str) -> io::Result<()> {
+    fs::remove_file(macro_path(name)?)
+}
+
+fn macros_dir() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("macros"))
+}
+
+fn macro_path(name: &str) -> io::Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid macro name {name:?}"),
+        ));
+    }
+
+    let dir = macros_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+    Ok(dir.join(format!("{name}.macro")))
+}
@@ -0,0 +1,64 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    thread,
+};
+
+use rdev::{listen, Event, EventType};
+
+use crate::source;
+
+/// Subscribers of the global input listener. `rdev::listen` blocks forever
+/// and can only run once per process on some platforms, so every feature
+/// that watches real input shares this one listener thread.
+static SUBSCRIBERS: Mutex<Option<Vec<Sender<Event>>>> = Mutex::new(None);
+
+/// The last cursor position the listener saw.
+static CURSOR: Mutex<Option<(f64, f64)>> = Mutex::new(None);
+
+/// Starts the listener thread if it is not running yet.
+pub fn start() {
+    drop(subscribe());
+}
+
+/// Where the cursor was at the last mouse move, if the listener is running
+/// and has seen one.
+pub fn cursor_position() -> Option<(f64, f64)> {
+    *CURSOR.lock().unwrap()
+}
+
+/// Returns a receiver for every real global mouse and keyboard event from
+/// now on, starting the listener thread on first use. Button, key and wheel
+/// events the clicker sent itself are left out.
+pub fn subscribe() -> Receiver<Event> {
+    let (tx, rx) = mpsc::channel();
+
+    let mut subscribers = SUBSCRIBERS.lock().unwrap();
+    match subscribers.as_mut() {
+        Some(subscribers) => subscribers.push(tx),
+        None => {
+            *subscribers = Some(vec![tx]);
+            thread::spawn(|| {
+                let result = listen(|event| {
+                    if let EventType::MouseMove { x, y } = event.event_type {
+                        *CURSOR.lock().unwrap() = Some((x, y));
+                    }
+                    if source::is_synthesized(&event.event_type) {
+                        return;
+                    }
+                    if let Some(subscribers) = SUBSCRIBERS.lock().unwrap().as_mut() {
+                        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+                    }
+                });
+
+                if let Err(e) = result {
+                    log::error!("Could not listen for input events: {e:?}");
+                }
+            });
+        }
+    }
+
+    rx
+}
@@ -0,0 +1,163 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::config;
+
+/// The log file is rotated once it grows past this size.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Rotated log files kept besides the current one, `auto-clicker.1.log`
+/// being the newest.
+const KEPT_FILES: usize = 3;
+
+/// Entries kept for the log panel.
+const MAX_ENTRIES: usize = 500;
+
+/// One logged message.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub time: SystemTime,
+    pub level: Level,
+    /// The module that logged it, e.g. `auto_clicker::clicker`.
+    pub target: String,
+    pub message: String,
+}
+
+static ENTRIES: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+static FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Writes the app's messages to stderr, the log file and the log panel,
+/// and passes anything else `RUST_LOG` asks for on to `env_logger`.
+struct Logger {
+    env: env_logger::Logger,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        is_kept(metadata) || self.env.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !is_kept(record.metadata()) {
+            if self.env.matches(record) {
+                self.env.log(record);
+            }
+            return;
+        }
+        let entry = Entry {
+            time: SystemTime::now(),
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        };
+        eprintln!("{}", entry.message);
+        write(&entry);
+        let mut entries = ENTRIES.lock().unwrap();
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    fn flush(&self) {
+        self.env.flush();
+        if let Some(file) = FILE.lock().unwrap().as_mut() {
+            file.flush().ok();
+        }
+    }
+}
+
+/// Messages of the app itself from info up, and warnings and errors of its
+/// libraries, e.g. wgpu, are kept.
+fn is_kept(metadata: &Metadata) -> bool {
+    let own = metadata.target().starts_with(env!("CARGO_CRATE_NAME"));
+    metadata.level() <= if own { Level::Info } else { Level::Warn }
+}
+
+/// Installs the logger, opening the log file in [`log_path`]. Without a
+/// config directory messages only go to stderr and the log panel.
+pub fn init() {
+    let env = env_logger::Builder::from_default_env().build();
+    let max_level = env.filter().max(LevelFilter::Info);
+    if let Some(path) = log_path() {
+        *FILE.lock().unwrap() = open(&path);
+    }
+    if log::set_boxed_logger(Box::new(Logger { env })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// `<config dir>/logs/auto-clicker.log`.
+pub fn log_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("logs").join("auto-clicker.log"))
+}
+
+/// The latest messages, oldest first.
+pub fn entries() -> Vec<Entry> {
+    ENTRIES.lock().unwrap().iter().cloned().collect()
+}
+
+/// Empties the log panel; the log file keeps its messages.
+pub fn clear() {
+    ENTRIES.lock().unwrap().clear();
+}
+
+fn open(path: &Path) -> Option<File> {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| OpenOptions::new().create(true).append(true).open(path));
+    match result {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("Could not open the log file {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// Appends `entry` to the log file, rotating it first if it is full.
+fn write(entry: &Entry) {
+    let mut file = FILE.lock().unwrap();
+    let full = file
+        .as_ref()
+        .and_then(|file| file.metadata().ok())
+        .is_some_and(|metadata| metadata.len() >= MAX_FILE_SIZE);
+    if full {
+        if let Some(path) = log_path() {
+            *file = None;
+            rotate(&path);
+            *file = open(&path);
+        }
+    }
+    if let Some(file) = file.as_mut() {
+        writeln!(
+            file,
+            "{} {:<5} {}: {}",
+            humantime::format_rfc3339_seconds(entry.time),
+            entry.level,
+            entry.target,
+            entry.message
+        )
+        .ok();
+    }
+}
+
+/// Shifts `auto-clicker.log` to `auto-clicker.1.log`, that one to `.2` and
+/// so on, dropping the oldest.
+fn rotate(path: &Path) {
+    let numbered = |n: usize| path.with_extension(format!("{n}.log"));
+    fs::remove_file(numbered(KEPT_FILES)).ok();
+    for n in (1..KEPT_FILES).rev() {
+        fs::rename(numbered(n), numbered(n + 1)).ok();
+    }
+    fs::rename(path, numbered(1)).ok();
+}
@@ -1,7 +1,4 @@
-pub mod gui;
-pub mod window;
-
 #[tokio::main]
 async fn main() {
-    window::run().await;
+    auto_clicker::run().await;
 }
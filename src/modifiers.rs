@@ -0,0 +1,138 @@
+use std::{fmt, sync::Mutex, thread};
+
+use rdev::{EventType, Key};
+
+use crate::listener;
+
+const MODIFIERS: [Key; 8] = [
+    Key::Alt,
+    Key::AltGr,
+    Key::ControlLeft,
+    Key::ControlRight,
+    Key::MetaLeft,
+    Key::MetaRight,
+    Key::ShiftLeft,
+    Key::ShiftRight,
+];
+
+/// Modifier keys the user holds down right now.
+static HELD: Mutex<Vec<Key>> = Mutex::new(Vec::new());
+
+/// Starts a thread that follows which modifier keys are physically held,
+/// see [`held`].
+pub fn spawn_tracker() {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        for event in rx_events {
+            match event.event_type {
+                EventType::KeyPress(key) if MODIFIERS.contains(&key) => {
+                    let mut held = HELD.lock().unwrap();
+                    if !held.contains(&key) {
+                        held.push(key);
+                    }
+                }
+                EventType::KeyRelease(key) => HELD.lock().unwrap().retain(|&held| held != key),
+                _ => {}
+            }
+        }
+    });
+}
+
+/// The modifiers held down, e.g. Ctrl of a Ctrl+F1 hotkey that just
+/// started the clicker. Empty unless [`spawn_tracker`] runs.
+pub fn held() -> Vec<Key> {
+    HELD.lock().unwrap().clone()
+}
+
+/// Forgets `keys` after the clicker released them itself; the listener
+/// does not report its own key events.
+pub fn forget(keys: &[Key]) {
+    HELD.lock().unwrap().retain(|key| !keys.contains(key));
+}
+
+/// The modifier keys of a hotkey combination, e.g. Ctrl and Alt of
+/// Ctrl+Alt+S. Left and right keys count the same.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        ctrl: false,
+        alt: false,
+        shift: false,
+        meta: false,
+    };
+
+    pub fn is_modifier(key: Key) -> bool {
+        MODIFIERS.contains(&key)
+    }
+
+    /// Follows a press or release of a modifier key. Returns `false` for
+    /// other events. Each listener thread follows the modifiers of its own
+    /// events, so they are in order with the keys they modify.
+    pub fn update(&mut self, event_type: &EventType) -> bool {
+        let (key, down) = match *event_type {
+            EventType::KeyPress(key) => (key, true),
+            EventType::KeyRelease(key) => (key, false),
+            _ => return false,
+        };
+        match key {
+            Key::ControlLeft | Key::ControlRight => self.ctrl = down,
+            Key::Alt | Key::AltGr => self.alt = down,
+            Key::ShiftLeft | Key::ShiftRight => self.shift = down,
+            Key::MetaLeft | Key::MetaRight => self.meta = down,
+            _ => return false,
+        }
+        true
+    }
+
+    /// The modifiers of the app window's keyboard events.
+    #[cfg(feature = "gui")]
+    pub fn from_winit(state: winit::event::ModifiersState) -> Self {
+        Self {
+            ctrl: state.ctrl(),
+            alt: state.alt(),
+            shift: state.shift(),
+            meta: state.logo(),
+        }
+    }
+
+    /// Reads the `Ctrl+Alt+` prefix of a combination, returning the
+    /// modifiers and the rest.
+    pub fn parse_prefix(mut s: &str) -> (Self, &str) {
+        let mut modifiers = Self::NONE;
+        while let Some((name, rest)) = s.split_once('+') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "meta" | "win" | "super" | "cmd" => modifiers.meta = true,
+                _ => break,
+            }
+            s = rest;
+        }
+        (modifiers, s)
+    }
+}
+
+/// The `Ctrl+Alt+` prefix of a combination, empty without modifiers.
+impl fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.meta, "Meta"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        Ok(())
+    }
+}
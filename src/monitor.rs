@@ -0,0 +1,102 @@
+use std::sync::Mutex;
+
+#[cfg(feature = "gui")]
+use winit::monitor::MonitorHandle;
+
+/// A connected screen, in global desktop coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monitor {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Monitor {
+    pub fn id(&self) -> MonitorId {
+        MonitorId::of(&self.name)
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn from_handle(handle: &MonitorHandle) -> Self {
+        let position = handle.position();
+        let size = handle.size();
+        Self {
+            name: handle.name().unwrap_or_else(|| "Monitor".to_owned()),
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+/// Identifies a screen by its name, e.g. `DP-1` or `\\.\DISPLAY2`, which
+/// stays the same when the screens are rearranged or listed in another
+/// order, unlike its place in [`list`]. Cheap to copy; the name is kept in a
+/// table for the running app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MonitorId(usize);
+
+/// The names behind the [`MonitorId`]s handed out so far.
+static NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+impl MonitorId {
+    pub fn of(name: &str) -> Self {
+        let mut names = NAMES.lock().unwrap();
+        match names.iter().position(|known| known == name) {
+            Some(index) => Self(index),
+            None => {
+                names.push(name.to_owned());
+                Self(names.len() - 1)
+            }
+        }
+    }
+
+    pub fn name(self) -> String {
+        NAMES.lock().unwrap()[self.0].clone()
+    }
+}
+
+static MONITORS: Mutex<Vec<Monitor>> = Mutex::new(Vec::new());
+
+/// Records the connected monitors in the order winit lists them. Only the
+/// event loop can enumerate them, but the clicker thread translates
+/// positions with them, see [`to_global`].
+pub fn set_monitors(monitors: Vec<Monitor>) {
+    *MONITORS.lock().unwrap() = monitors;
+}
+
+pub fn list() -> Vec<Monitor> {
+    MONITORS.lock().unwrap().clone()
+}
+
+/// The connected monitor `id`, where it is now.
+pub fn get(id: MonitorId) -> Option<Monitor> {
+    MONITORS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|monitor| monitor.id() == id)
+        .cloned()
+}
+
+/// `(x, y)` relative to the top-left corner of monitor `id` as global
+/// coordinates, wherever it is now. Without a monitor, or one that is no
+/// longer connected, the position is taken as global already.
+pub fn to_global(id: Option<MonitorId>, x: i32, y: i32) -> (i32, i32) {
+    match id.and_then(get) {
+        Some(monitor) => (monitor.x + x, monitor.y + y),
+        None => (x, y),
+    }
+}
+
+/// The global position `(x, y)` relative to monitor `id`, the inverse of
+/// [`to_global`].
+pub fn to_local(id: Option<MonitorId>, x: i32, y: i32) -> (i32, i32) {
+    match id.and_then(get) {
+        Some(monitor) => (x - monitor.x, y - monitor.y),
+        None => (x, y),
+    }
+}
@@ -0,0 +1,98 @@
+use std::{sync::mpsc::Sender, thread, time::Duration};
+
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+
+use crate::{
+    clicker::{self, Command},
+    settings::MqttSettings,
+    timeline::{self, Activity},
+};
+
+/// How long to wait before reconnecting after the broker drops us.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Connects to the broker in `settings` and keeps the connection up until
+/// the process exits: messages on the command topic start and stop the
+/// clicker through `tx_command`, and the state topic gets a retained `ON`
+/// or `OFF` whenever a run starts or ends. Meant to be spawned as a task.
+pub async fn run(settings: MqttSettings, tx_command: Sender<Command>) {
+    let mut options = MqttOptions::new(&settings.client_id, &settings.host, settings.port);
+    options.set_keep_alive(KEEP_ALIVE);
+    if !settings.username.is_empty() {
+        options.set_credentials(&settings.username, &settings.password);
+    }
+    let (client, mut event_loop) = AsyncClient::new(options, 10);
+
+    // Timeline subscribers are plain channels, so the state is published
+    // from a thread of its own.
+    {
+        let client = client.clone();
+        let state_topic = settings.state_topic.clone();
+        let rx_activity = timeline::subscribe();
+        thread::spawn(move || {
+            for activity in rx_activity {
+                let state = match activity {
+                    Activity::Start | Activity::Resume => "ON",
+                    Activity::Stop
+                    | Activity::Finish
+                    | Activity::Safety
+                    | Activity::TargetClosed
+                    | Activity::Suspend => "OFF",
+                    _ => continue,
+                };
+                if let Err(e) = client.try_publish(&state_topic, QoS::AtLeastOnce, true, state) {
+                    log::error!("Could not publish the MQTT state: {e}");
+                }
+            }
+        });
+    }
+
+    loop {
+        match event_loop.poll().await {
+            // Subscriptions do not survive a reconnect, so subscribe on
+            // every connection.
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                if let Err(e) = client
+                    .subscribe(&settings.command_topic, QoS::AtLeastOnce)
+                    .await
+                {
+                    log::error!("Could not subscribe to {}: {e}", settings.command_topic);
+                }
+                let state = if clicker::is_running() { "ON" } else { "OFF" };
+                client
+                    .publish(&settings.state_topic, QoS::AtLeastOnce, true, state)
+                    .await
+                    .ok();
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                let message = String::from_utf8_lossy(&publish.payload);
+                match command(&message) {
+                    Some(command) => {
+                        if tx_command.send(command).is_err() {
+                            return;
+                        }
+                    }
+                    None => log::warn!("Unknown MQTT command {:?}", message.trim()),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("MQTT connection to {} failed: {e}", settings.host);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+fn command(message: &str) -> Option<Command> {
+    match message.trim().to_ascii_lowercase().as_str() {
+        "start" | "on" => Some(Command::Start),
+        "stop" | "off" => Some(Command::Stop),
+        "toggle" => Some(Command::Toggle),
+        "pause" => Some(Command::Pause),
+        "resume" => Some(Command::Resume),
+        _ => None,
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use notify_rust::Notification;
+
+use crate::{
+    dnd,
+    i18n::{tr, tr_n},
+    stats,
+    timeline::{self, RunEvent},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts a thread that shows a desktop notification when a run starts,
+/// stops, reaches its click count or run time, or is stopped by a safety
+/// check or its target window closing, while [`is_enabled`].
+pub fn spawn_notifier() {
+    let run_events = timeline::run_events();
+    thread::spawn(move || {
+        for event in run_events {
+            if !is_enabled() || dnd::holds_alerts() {
+                continue;
+            }
+            let clicks = stats::current().clicks;
+            let args: [(&str, &dyn fmt::Display); 1] = [("clicks", &clicks)];
+            let n = clicks as u64;
+            let body = match event {
+                RunEvent::Start => tr("Clicking started").into_owned(),
+                RunEvent::Stop => tr_n(
+                    "Stopped after {clicks} click",
+                    "Stopped after {clicks} clicks",
+                    n,
+                    &args,
+                ),
+                RunEvent::Finish => tr_n(
+                    "Run complete after {clicks} click",
+                    "Run complete after {clicks} clicks",
+                    n,
+                    &args,
+                ),
+                RunEvent::Safety => tr_n(
+                    "Stopped by a safety check after {clicks} click",
+                    "Stopped by a safety check after {clicks} clicks",
+                    n,
+                    &args,
+                ),
+                RunEvent::TargetClosed => tr_n(
+                    "Stopped after {clicks} click, the target window closed",
+                    "Stopped after {clicks} clicks, the target window closed",
+                    n,
+                    &args,
+                ),
+                RunEvent::Reminder => tr_n(
+                    "Still clicking, {clicks} click so far",
+                    "Still clicking, {clicks} clicks so far",
+                    n,
+                    &args,
+                ),
+            };
+            let result = Notification::new()
+                .appname("Auto Clicker")
+                .summary("Auto Clicker")
+                .body(&body)
+                .show();
+            if let Err(e) = result {
+                log::error!("Could not show a notification: {e}");
+            }
+        }
+    });
+}
@@ -0,0 +1,555 @@
+#[cfg(feature = "gui")]
+use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+#[cfg(feature = "gui")]
+use egui::{Align2, Color32, FontId, Pos2, Rect, Stroke};
+#[cfg(feature = "gui")]
+use egui_wgpu_backend::{RenderPass, ScreenDescriptor};
+#[cfg(feature = "gui")]
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    window::{CursorIcon, Window},
+};
+
+use crate::listener;
+#[cfg(feature = "gui")]
+use crate::{
+    i18n::tr,
+    monitor::{self, Monitor},
+    pick,
+    region::{self, Selection},
+};
+
+/// How long a click marker stays on screen.
+#[cfg(feature = "gui")]
+const MARK_LIFETIME: Duration = Duration::from_millis(600);
+
+/// How long a preview marker stays on screen, long enough to see the order
+/// of a few clicks.
+#[cfg(feature = "gui")]
+const PREVIEW_LIFETIME: Duration = Duration::from_millis(1500);
+
+/// How long the "AUTOMATION STOPPED" flash stays on screen.
+#[cfg(feature = "gui")]
+const STOP_FLASH_LIFETIME: Duration = Duration::from_millis(1500);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HALO_ENABLED: AtomicBool = AtomicBool::new(false);
+/// Positions in desktop pixels and when they were marked.
+static MARKS: Mutex<Vec<((f32, f32), Instant)>> = Mutex::new(Vec::new());
+static PREVIEW_MARKS: Mutex<Vec<((f32, f32), Instant)>> = Mutex::new(Vec::new());
+static STOP_FLASH: Mutex<Option<Instant>> = Mutex::new(None);
+/// The crosshair position in desktop pixels.
+static TARGET: Mutex<Option<(f32, f32)>> = Mutex::new(None);
+/// Set once an overlay could be created.
+static AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the overlay can be shown, e.g. for [`region`] selections. Not
+/// where the surface cannot be transparent or click-through.
+pub fn is_available() -> bool {
+    AVAILABLE.load(Ordering::SeqCst)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+    if enabled {
+        // The cursor position of each click comes from the listener.
+        listener::start();
+    } else {
+        MARKS.lock().unwrap().clear();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Draw a halo around the cursor for as long as the clicker is running.
+pub fn set_halo_enabled(enabled: bool) {
+    HALO_ENABLED.store(enabled, Ordering::SeqCst);
+    if enabled {
+        listener::start();
+    }
+}
+
+pub fn is_halo_enabled() -> bool {
+    HALO_ENABLED.load(Ordering::SeqCst)
+}
+
+/// Records a synthesized click at the current cursor position so the
+/// overlay can flash a marker there.
+pub fn mark_click() {
+    if !is_enabled() {
+        return;
+    }
+    if let Some((x, y)) = listener::cursor_position() {
+        MARKS
+            .lock()
+            .unwrap()
+            .push(((x as f32, y as f32), Instant::now()));
+    }
+}
+
+/// Records a click a preview run would have made at `(x, y)`, shown
+/// whether or not the click visualizer is on.
+pub fn mark_preview(x: i32, y: i32) {
+    PREVIEW_MARKS
+        .lock()
+        .unwrap()
+        .push(((x as f32, y as f32), Instant::now()));
+}
+
+/// Covers the screen with a fading "AUTOMATION STOPPED" banner, for when a
+/// safety trigger stops the clicker. Shown regardless of the visualizer
+/// settings, so it is noticed even over a fullscreen game.
+pub fn flash_stop() {
+    *STOP_FLASH.lock().unwrap() = Some(Instant::now());
+}
+
+/// Marks where the clicker will click with a crosshair, e.g. the custom
+/// click position, or removes the mark with `None`.
+pub fn set_target(target: Option<(i32, i32)>) {
+    *TARGET.lock().unwrap() = target.map(|(x, y)| (x as f32, y as f32));
+}
+
+/// Where a window covering all of `monitors` goes: the smallest rectangle
+/// around them, in desktop pixels. `None` without monitors.
+#[cfg(feature = "gui")]
+pub fn desktop(monitors: &[Monitor]) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    let left = monitors.iter().map(|m| m.x).min()?;
+    let top = monitors.iter().map(|m| m.y).min()?;
+    let right = monitors.iter().map(|m| m.x + m.width as i32).max()?;
+    let bottom = monitors.iter().map(|m| m.y + m.height as i32).max()?;
+    Some((
+        PhysicalPosition::new(left, top),
+        PhysicalSize::new((right - left) as u32, (bottom - top) as u32),
+    ))
+}
+
+/// A transparent, click-through, always-on-top window covering every
+/// monitor, see [`desktop`], used to draw a ripple wherever the clicker
+/// clicks, a halo around the cursor while it runs, the stop flash and the
+/// target crosshair.
+#[cfg(feature = "gui")]
+pub struct Overlay {
+    surface: wgpu::Surface,
+    config: wgpu::SurfaceConfiguration,
+    egui_rpass: RenderPass,
+    context: egui::Context,
+    visible: bool,
+    halo: Option<Pos2>,
+    /// The crosshair drawn last, so a static one is not redrawn every frame.
+    drawn_target: Option<Pos2>,
+    /// The region selection drawn last, see [`region::start`].
+    selection: Option<Selection>,
+    /// The cursor while a position is picked, see [`pick::start`].
+    pick_cursor: Option<Pos2>,
+    animating: bool,
+    window: Window,
+}
+
+#[cfg(feature = "gui")]
+impl Overlay {
+    /// Returns `None` if the window cannot be made transparent or
+    /// click-through; an opaque overlay would hide the whole screen.
+    pub fn new(
+        window: Window,
+        instance: &wgpu::Instance,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+    ) -> Option<Self> {
+        if let Err(e) = window.set_cursor_hittest(false) {
+            log::warn!("Click visualizer unavailable, overlay cannot be click-through: {e}");
+            return None;
+        }
+
+        // # Safety
+        //
+        // The surface needs to live as long as the window that created it.
+        // Overlay owns the window and drops the surface first.
+        let surface = unsafe { instance.create_surface(&window) }.ok()?;
+        let surface_caps = surface.get_capabilities(adapter);
+
+        let Some(alpha_mode) = surface_caps.alpha_modes.iter().copied().find(|mode| {
+            matches!(
+                mode,
+                wgpu::CompositeAlphaMode::PreMultiplied | wgpu::CompositeAlphaMode::PostMultiplied
+            )
+        }) else {
+            log::warn!("Click visualizer unavailable, the surface does not support transparency");
+            return None;
+        };
+
+        let format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.describe().srgb)
+            .unwrap_or(surface_caps.formats[0]);
+        let size = window.inner_size();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: surface_caps.present_modes[0],
+            alpha_mode,
+            view_formats: vec![],
+        };
+        surface.configure(device, &config);
+
+        AVAILABLE.store(true, Ordering::SeqCst);
+        Some(Self {
+            surface,
+            config,
+            egui_rpass: RenderPass::new(device, format, 1),
+            context: egui::Context::default(),
+            visible: false,
+            halo: None,
+            drawn_target: None,
+            selection: None,
+            pick_cursor: None,
+            animating: false,
+            window,
+        })
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    /// Shows the window while there are markers, a cursor halo, the stop
+    /// flash, a crosshair, a region selection or a pick to draw and asks
+    /// for a redraw when they change.
+    /// Returns `true` while anything is animating.
+    pub fn update(&mut self, running: bool) -> bool {
+        self.halo = (running && is_halo_enabled())
+            .then(listener::cursor_position)
+            .flatten()
+            .map(|(x, y)| Pos2::new(x as f32, y as f32));
+
+        let mut marks = MARKS.lock().unwrap();
+        marks.retain(|(_, at)| at.elapsed() < MARK_LIFETIME);
+        let mut preview_marks = PREVIEW_MARKS.lock().unwrap();
+        preview_marks.retain(|(_, at)| at.elapsed() < PREVIEW_LIFETIME);
+        let mut stop_flash = STOP_FLASH.lock().unwrap();
+        if stop_flash.is_some_and(|at| at.elapsed() >= STOP_FLASH_LIFETIME) {
+            *stop_flash = None;
+        }
+        let selection = region::selection();
+        if selection.is_some() != self.selection.is_some() {
+            // Catch the mouse while selecting, so the drag does not reach
+            // the windows below; the listener still sees it.
+            let selecting = selection.is_some();
+            if let Err(e) = self.window.set_cursor_hittest(selecting) {
+                log::warn!("Could not make the overlay catch the mouse: {e}");
+            }
+            self.window.set_cursor_icon(if selecting {
+                CursorIcon::Crosshair
+            } else {
+                CursorIcon::Default
+            });
+        }
+        self.selection = selection;
+        self.pick_cursor = pick::cursor().map(|(x, y)| Pos2::new(x as f32, y as f32));
+        let animating = !marks.is_empty()
+            || !preview_marks.is_empty()
+            || self.halo.is_some()
+            || stop_flash.is_some()
+            || selection.is_some()
+            || pick::is_picking();
+        let target = TARGET.lock().unwrap().map(Pos2::from);
+        let visible = animating || target.is_some();
+
+        if visible != self.visible {
+            self.visible = visible;
+            self.window.set_visible(visible);
+        }
+        // One more frame after an animation ends clears its last traces.
+        if animating || self.animating || target != self.drawn_target {
+            self.drawn_target = target;
+            self.window.request_redraw();
+        }
+        self.animating = animating;
+        animating
+    }
+
+    /// Stretches the window over `monitors` again if they were plugged in,
+    /// removed or rearranged. Its surface follows on
+    /// [`winit::event::WindowEvent::Resized`], see [`Self::resize`].
+    pub fn cover(&mut self, monitors: &[Monitor]) {
+        let Some((position, size)) = desktop(monitors) else {
+            return;
+        };
+        if self.window.outer_position().ok() != Some(position) {
+            self.window.set_outer_position(position);
+        }
+        if self.window.inner_size() != size {
+            self.window.set_inner_size(size);
+        }
+    }
+
+    /// Sizes the surface to the window's `size`, without which the old one
+    /// is stretched over it.
+    pub fn resize(&mut self, device: &wgpu::Device, size: PhysicalSize<u32>) {
+        if size.width > 0 && size.height > 0 {
+            self.config.width = size.width;
+            self.config.height = size.height;
+            self.surface.configure(device, &self.config);
+            self.window.request_redraw();
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let output = self.surface.get_current_texture()?;
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Draw in physical pixels, which is what the input listener reports.
+        let raw_input = egui::RawInput {
+            screen_rect: Some(Rect::from_min_size(
+                Pos2::ZERO,
+                egui::vec2(self.config.width as f32, self.config.height as f32),
+            )),
+            pixels_per_point: Some(1.0),
+            ..Default::default()
+        };
+        // Everything to draw is in desktop coordinates, while the window
+        // starts at the top-left of all screens, e.g. at a negative x with a
+        // screen to the left of the primary one.
+        let origin = self
+            .window
+            .outer_position()
+            .map(|position| egui::vec2(position.x as f32, position.y as f32))
+            .unwrap_or(egui::Vec2::ZERO);
+        let local = |pos: Pos2| pos - origin;
+        let marks: Vec<_> = MARKS
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|&(pos, at)| (local(pos.into()), at))
+            .collect();
+        let preview_marks: Vec<_> = PREVIEW_MARKS
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|&(pos, at)| (local(pos.into()), at))
+            .collect();
+        let halo = self.halo.map(local);
+        let stop_flash = *STOP_FLASH.lock().unwrap();
+        let target = self.drawn_target.map(local);
+        let selection = self.selection;
+        let pick_cursor = self
+            .pick_cursor
+            .map(|pos| (local(pos), format!("{}, {}", pos.x, pos.y)));
+        // Texts go on every screen rather than across the gaps between them.
+        let screens: Vec<Rect> = monitor::list()
+            .iter()
+            .map(|m| {
+                Rect::from_min_size(
+                    local(Pos2::new(m.x as f32, m.y as f32)),
+                    egui::vec2(m.width as f32, m.height as f32),
+                )
+            })
+            .collect();
+        let full_output = self.context.run(raw_input, |ctx| {
+            let painter = ctx.layer_painter(egui::LayerId::background());
+            if let Some(selection) = selection {
+                painter.rect_filled(ctx.screen_rect(), 0.0, Color32::from_black_alpha(110));
+                for screen in screens_or(&screens, ctx.screen_rect()) {
+                    painter.text(
+                        screen.center_top() + egui::vec2(0.0, 40.0),
+                        Align2::CENTER_TOP,
+                        tr("Drag over the area to select, Esc to cancel"),
+                        FontId::proportional(28.0),
+                        Color32::WHITE,
+                    );
+                }
+                let color = Color32::from_rgb(0, 200, 255);
+                let label = match selection.region() {
+                    Some(region) => {
+                        let rect = Rect::from_min_size(
+                            local(Pos2::new(region.x as f32, region.y as f32)),
+                            egui::vec2(region.width as f32, region.height as f32),
+                        );
+                        painter.rect(
+                            rect,
+                            0.0,
+                            Color32::from_white_alpha(30),
+                            Stroke::new(2.0, color),
+                        );
+                        format!(
+                            "{}, {}  {} × {}",
+                            region.x, region.y, region.width, region.height
+                        )
+                    }
+                    None => selection
+                        .cursor
+                        .map(|(x, y)| format!("{x}, {y}"))
+                        .unwrap_or_default(),
+                };
+                if let Some((x, y)) = selection.cursor {
+                    painter.text(
+                        local(Pos2::new(x as f32, y as f32)) + egui::vec2(16.0, 16.0),
+                        Align2::LEFT_TOP,
+                        label,
+                        FontId::monospace(16.0),
+                        color,
+                    );
+                }
+            }
+            if let Some(at) = stop_flash {
+                let t = at.elapsed().as_secs_f32() / STOP_FLASH_LIFETIME.as_secs_f32();
+                let fade = (1.0 - t).clamp(0.0, 1.0);
+                painter.rect_filled(
+                    ctx.screen_rect(),
+                    0.0,
+                    Color32::from_rgba_unmultiplied(200, 0, 0, (fade * 110.0) as u8),
+                );
+                for screen in screens_or(&screens, ctx.screen_rect()) {
+                    painter.text(
+                        screen.center(),
+                        Align2::CENTER_CENTER,
+                        "AUTOMATION STOPPED",
+                        FontId::proportional(96.0),
+                        Color32::from_rgba_unmultiplied(255, 255, 255, (fade * 255.0) as u8),
+                    );
+                }
+            }
+            if let Some((pos, label)) = pick_cursor {
+                // Lines across the whole screen, with a gap where the
+                // target is so it stays visible.
+                let screen = ctx.screen_rect();
+                let color = Color32::from_rgb(0, 200, 255);
+                let stroke = Stroke::new(1.0, color);
+                let gap = 6.0;
+                painter.line_segment(
+                    [Pos2::new(screen.left(), pos.y), pos - egui::vec2(gap, 0.0)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [pos + egui::vec2(gap, 0.0), Pos2::new(screen.right(), pos.y)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [Pos2::new(pos.x, screen.top()), pos - egui::vec2(0.0, gap)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [
+                        pos + egui::vec2(0.0, gap),
+                        Pos2::new(pos.x, screen.bottom()),
+                    ],
+                    stroke,
+                );
+                painter.text(
+                    pos + egui::vec2(16.0, 16.0),
+                    Align2::LEFT_TOP,
+                    label,
+                    FontId::monospace(16.0),
+                    color,
+                );
+            }
+            if let Some(pos) = target {
+                let color = Color32::from_rgb(0, 200, 255);
+                let stroke = Stroke::new(2.0, color);
+                painter.line_segment(
+                    [pos - egui::vec2(16.0, 0.0), pos - egui::vec2(4.0, 0.0)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [pos + egui::vec2(4.0, 0.0), pos + egui::vec2(16.0, 0.0)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [pos - egui::vec2(0.0, 16.0), pos - egui::vec2(0.0, 4.0)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [pos + egui::vec2(0.0, 4.0), pos + egui::vec2(0.0, 16.0)],
+                    stroke,
+                );
+                painter.circle_stroke(pos, 10.0, stroke);
+            }
+            if let Some(pos) = halo {
+                painter.circle(
+                    pos,
+                    24.0,
+                    Color32::from_rgba_unmultiplied(255, 200, 0, 40),
+                    Stroke::new(3.0, Color32::from_rgba_unmultiplied(255, 200, 0, 200)),
+                );
+            }
+            for (pos, at) in marks {
+                let t = at.elapsed().as_secs_f32() / MARK_LIFETIME.as_secs_f32();
+                let alpha = ((1.0 - t).clamp(0.0, 1.0) * 255.0) as u8;
+                let color = Color32::from_rgba_unmultiplied(255, 64, 64, alpha);
+                painter.circle_filled(pos, 4.0, color);
+                painter.circle_stroke(pos, 6.0 + 24.0 * t, Stroke::new(3.0, color));
+            }
+            // Preview clicks are joined in the order they would happen.
+            let mut previous: Option<Pos2> = None;
+            for (pos, at) in preview_marks {
+                let t = at.elapsed().as_secs_f32() / PREVIEW_LIFETIME.as_secs_f32();
+                let alpha = ((1.0 - t).clamp(0.0, 1.0) * 255.0) as u8;
+                let color = Color32::from_rgba_unmultiplied(64, 220, 96, alpha);
+                if let Some(previous) = previous {
+                    painter.line_segment([previous, pos], Stroke::new(1.0, color));
+                }
+                painter.circle_stroke(pos, 8.0, Stroke::new(2.0, color));
+                painter.circle_filled(pos, 3.0, color);
+                previous = Some(pos);
+            }
+        });
+        let paint_jobs = self.context.tessellate(full_output.shapes);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Overlay Encoder"),
+        });
+        let screen_descriptor = ScreenDescriptor {
+            physical_width: self.config.width,
+            physical_height: self.config.height,
+            scale_factor: 1.0,
+        };
+        self.egui_rpass
+            .add_textures(device, queue, &full_output.textures_delta)
+            .expect("add texture ok");
+        self.egui_rpass
+            .update_buffers(device, queue, &paint_jobs, &screen_descriptor);
+        self.egui_rpass
+            .execute(
+                &mut encoder,
+                &view,
+                &paint_jobs,
+                &screen_descriptor,
+                Some(wgpu::Color::TRANSPARENT),
+            )
+            .unwrap();
+
+        queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+
+        Ok(())
+    }
+}
+
+/// The monitors' rectangles, or the whole window while none are known.
+#[cfg(feature = "gui")]
+fn screens_or(screens: &[Rect], window: Rect) -> Vec<Rect> {
+    if screens.is_empty() {
+        vec![window]
+    } else {
+        screens.to_vec()
+    }
+}
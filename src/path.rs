@@ -0,0 +1,92 @@
+use std::f64::consts::TAU;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    /// From `(x, y)` to `(end_x, end_y)`.
+    #[default]
+    Line,
+    /// Around `(x, y)` at `radius`, clockwise from the right.
+    Circle,
+}
+
+/// Clicks along a line or circle, `steps` evenly spaced points per pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPattern {
+    pub kind: PathKind,
+    pub x: usize,
+    pub y: usize,
+    pub end_x: usize,
+    pub end_y: usize,
+    pub radius: usize,
+    pub steps: usize,
+}
+
+impl Default for PathPattern {
+    fn default() -> Self {
+        Self {
+            kind: PathKind::Line,
+            x: 0,
+            y: 0,
+            end_x: 200,
+            end_y: 0,
+            radius: 100,
+            steps: 10,
+        }
+    }
+}
+
+impl PathPattern {
+    /// Point `step` of `steps`. A line includes both ends, a circle does
+    /// not repeat its starting point.
+    pub fn point(&self, step: usize) -> (usize, usize) {
+        let (x, y) = (self.x as f64, self.y as f64);
+        let (px, py) = match self.kind {
+            PathKind::Line => {
+                let t = if self.steps > 1 {
+                    step as f64 / (self.steps - 1) as f64
+                } else {
+                    0.0
+                };
+                (
+                    x + (self.end_x as f64 - x) * t,
+                    y + (self.end_y as f64 - y) * t,
+                )
+            }
+            PathKind::Circle => {
+                let angle = TAU * step as f64 / self.steps.max(1) as f64;
+                let radius = self.radius as f64;
+                (x + radius * angle.cos(), y + radius * angle.sin())
+            }
+        };
+        (px.round().max(0.0) as usize, py.round().max(0.0) as usize)
+    }
+
+    /// Every point in order, starting over after the last one.
+    pub fn points(self) -> PathPoints {
+        PathPoints {
+            path: self,
+            next: 0,
+        }
+    }
+}
+
+/// Endless iterator over the points of a [`PathPattern`], see
+/// [`PathPattern::points`]. Yields nothing for a path without steps.
+#[derive(Debug, Clone)]
+pub struct PathPoints {
+    path: PathPattern,
+    next: usize,
+}
+
+impl Iterator for PathPoints {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.path.steps == 0 {
+            return None;
+        }
+        let point = self.path.point(self.next);
+        self.next = (self.next + 1) % self.path.steps;
+        Some(point)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::region::Region;
+
+/// A `columns` × `rows` grid of click targets. The top-left cell is at
+/// `(x, y)` and neighbouring cells are `spacing_x`/`spacing_y` pixels apart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridPattern {
+    pub x: usize,
+    pub y: usize,
+    pub columns: usize,
+    pub rows: usize,
+    pub spacing_x: usize,
+    pub spacing_y: usize,
+}
+
+impl Default for GridPattern {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            columns: 3,
+            rows: 3,
+            spacing_x: 50,
+            spacing_y: 50,
+        }
+    }
+}
+
+impl GridPattern {
+    pub fn len(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The screen position of cell `index`, counting row by row from the
+    /// top-left.
+    pub fn cell(&self, index: usize) -> (usize, usize) {
+        let columns = self.columns.max(1);
+        (
+            self.x + (index % columns) * self.spacing_x,
+            self.y + (index / columns) * self.spacing_y,
+        )
+    }
+
+    /// Spreads the cells evenly over `region`, with the outer ones on its
+    /// edges, or in its middle for a single column or row.
+    pub fn fit(&mut self, region: Region) {
+        let spread = |start: i32, length: u32, cells: usize| {
+            let start = start.max(0) as usize;
+            match cells {
+                0 | 1 => (start + length as usize / 2, 0),
+                cells => (start, length as usize / (cells - 1)),
+            }
+        };
+        (self.x, self.spacing_x) = spread(region.x, region.width, self.columns);
+        (self.y, self.spacing_y) = spread(region.y, region.height, self.rows);
+    }
+
+    /// Every cell in order, starting over after the last one.
+    pub fn cells(self) -> GridCells {
+        GridCells {
+            grid: self,
+            next: 0,
+        }
+    }
+}
+
+/// Endless iterator over the cells of a [`GridPattern`], see
+/// [`GridPattern::cells`]. Yields nothing for an empty grid.
+#[derive(Debug, Clone)]
+pub struct GridCells {
+    grid: GridPattern,
+    next: usize,
+}
+
+impl Iterator for GridCells {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.grid.is_empty() {
+            return None;
+        }
+        let cell = self.grid.cell(self.next);
+        self.next = (self.next + 1) % self.grid.len();
+        Some(cell)
+    }
+}
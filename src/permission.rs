@@ -0,0 +1,41 @@
+//! The Accessibility permission macOS requires before an app may send
+//! input. Without it the OS drops synthesized clicks and keys without an
+//! error, so the app asks for it up front.
+
+/// The Privacy & Security pane listing the apps allowed to control the
+/// computer.
+#[cfg(target_os = "macos")]
+const SETTINGS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
+
+/// Whether the app may send input. Always true where no permission is
+/// needed.
+#[cfg(target_os = "macos")]
+pub fn is_granted() -> bool {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+    // SAFETY: AXIsProcessTrusted has no preconditions.
+    unsafe { AXIsProcessTrusted() }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_granted() -> bool {
+    true
+}
+
+/// Opens System Settings at the Accessibility list, where the user can
+/// switch the app on.
+#[cfg(target_os = "macos")]
+pub fn open_settings() {
+    if let Err(e) = std::process::Command::new("open")
+        .arg(SETTINGS_URL)
+        .status()
+    {
+        log::error!("Could not open System Settings: {e}");
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn open_settings() {}
@@ -0,0 +1,68 @@
+//! Picking the custom click position by clicking it on screen. While a pick
+//! runs the main window is out of the way and the overlay draws a crosshair
+//! through the cursor with its coordinates.
+
+use std::{sync::Mutex, thread};
+
+use rdev::{Button, EventType, Key};
+
+use crate::{engine::Origin, listener};
+
+/// Where the cursor is during a pick, `Some(None)` before it has moved.
+static CURSOR: Mutex<Option<Option<(i32, i32)>>> = Mutex::new(None);
+
+/// How the last pick ended, until taken: the position measured from the
+/// origin it was started with, or `None` if it was cancelled.
+static RESULT: Mutex<Option<Option<(i32, i32)>>> = Mutex::new(None);
+
+/// Starts a pick unless one is running: the next left click sets the
+/// position, Esc or another button cancels. The click still reaches the
+/// window below, so for [`Origin::ActiveWindow`] it is measured from the
+/// window it focused. The result is collected with [`take_result`].
+pub fn start(origin: Origin) {
+    let mut cursor = CURSOR.lock().unwrap();
+    if cursor.is_some() {
+        return;
+    }
+    *cursor = Some(listener::cursor_position().map(|(x, y)| (x as i32, y as i32)));
+    // The press that started the pick came before the subscription, so
+    // only the next click is picked up.
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let mut pressed = None;
+        let mut result = None;
+        for event in rx_events {
+            match event.event_type {
+                EventType::MouseMove { x, y } => {
+                    *CURSOR.lock().unwrap() = Some(Some((x.round() as i32, y.round() as i32)));
+                }
+                EventType::ButtonPress(Button::Left) => {
+                    pressed = listener::cursor_position().map(|(x, y)| (x as i32, y as i32));
+                }
+                // The clicked window has the focus once the button is up.
+                EventType::ButtonRelease(Button::Left) if pressed.is_some() => {
+                    result = pressed.map(|(x, y)| origin.to_local(x, y));
+                    break;
+                }
+                EventType::ButtonPress(_) | EventType::KeyPress(Key::Escape) => break,
+                _ => {}
+            }
+        }
+        *RESULT.lock().unwrap() = Some(result);
+        *CURSOR.lock().unwrap() = None;
+    });
+}
+
+pub fn is_picking() -> bool {
+    CURSOR.lock().unwrap().is_some()
+}
+
+/// Where the cursor is while a pick runs, for the crosshair.
+pub fn cursor() -> Option<(i32, i32)> {
+    CURSOR.lock().unwrap().flatten()
+}
+
+/// How the last pick ended, once: `Some(None)` if it was cancelled.
+pub fn take_result() -> Option<Option<(i32, i32)>> {
+    RESULT.lock().unwrap().take()
+}
@@ -0,0 +1,73 @@
+use crate::screen::{self, Rgb};
+
+/// When a [`PixelTrigger`] lets the clicker fire.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PixelCondition {
+    /// While the pixel has the target color.
+    #[default]
+    Matches,
+    /// While the pixel has any other color.
+    Differs,
+}
+
+/// Holds the clicker back until the screen pixel at `(x, y)` matches, or
+/// stops matching, `color`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelTrigger {
+    pub enabled: bool,
+    pub x: usize,
+    pub y: usize,
+    pub color: Rgb,
+    /// How far each channel may be from `color` and still match.
+    pub tolerance: u8,
+    pub condition: PixelCondition,
+}
+
+impl Default for PixelTrigger {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            x: 0,
+            y: 0,
+            color: [255, 255, 255],
+            tolerance: 10,
+            condition: PixelCondition::Matches,
+        }
+    }
+}
+
+impl PixelTrigger {
+    /// Whether `color` is within the tolerance of the target color.
+    pub fn matches(&self, color: Rgb) -> bool {
+        color
+            .iter()
+            .zip(self.color)
+            .all(|(&a, b)| a.abs_diff(b) <= self.tolerance)
+    }
+
+    /// Reads the pixel and checks the condition. A pixel that cannot be
+    /// read never meets it.
+    pub fn is_met(&self) -> bool {
+        let Some(color) = screen::pixel(self.x, self.y) else {
+            return false;
+        };
+        match self.condition {
+            PixelCondition::Matches => self.matches(color),
+            PixelCondition::Differs => !self.matches(color),
+        }
+    }
+}
+
+/// Parses a color written as `RRGGBB`, with or without a leading `#`.
+pub fn parse_color(s: &str) -> Option<Rgb> {
+    let hex = s.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+pub fn format_color([r, g, b]: Rgb) -> String {
+    format!("#{r:02X}{g:02X}{b:02X}")
+}
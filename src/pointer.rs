@@ -0,0 +1,234 @@
+//! A second cursor for the clicker on X11, so clicks do not move the
+//! user's own pointer.
+//!
+//! XInput2 can have several master pointers, each with its own cursor and
+//! focus. `xinput create-master` adds one, and the X server gives every
+//! master an XTEST slave device that fake events can be sent through.
+
+#[cfg(not(target_os = "linux"))]
+use rdev::{EventType, SimulateError};
+
+/// Name of the master device; X appends " pointer" and " keyboard".
+#[cfg(target_os = "linux")]
+const MASTER: &str = "Auto Clicker";
+
+pub const fn is_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+#[cfg(target_os = "linux")]
+#[cfg_attr(not(feature = "gui"), allow(unused_imports))]
+pub use linux::{is_enabled, set_enabled, simulate};
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_enabled(_enabled: bool) {}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_enabled() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn simulate(_event_type: &EventType) -> Option<Result<(), SimulateError>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        ffi::{c_char, c_int, c_uint, c_ulong, c_void},
+        process::Command,
+        ptr,
+        sync::Mutex,
+    };
+
+    use rdev::{Button, EventType, SimulateError};
+
+    use super::MASTER;
+
+    #[link(name = "X11")]
+    extern "C" {
+        fn XOpenDisplay(name: *const c_char) -> *mut c_void;
+        fn XCloseDisplay(display: *mut c_void) -> c_int;
+        fn XFlush(display: *mut c_void) -> c_int;
+    }
+
+    #[link(name = "Xi")]
+    extern "C" {
+        fn XOpenDevice(display: *mut c_void, id: c_ulong) -> *mut c_void;
+        fn XCloseDevice(display: *mut c_void, device: *mut c_void) -> c_int;
+    }
+
+    #[link(name = "Xtst")]
+    extern "C" {
+        fn XTestFakeDeviceButtonEvent(
+            display: *mut c_void,
+            device: *mut c_void,
+            button: c_uint,
+            is_press: c_int,
+            axes: *const c_int,
+            axes_count: c_int,
+            delay: c_ulong,
+        ) -> c_int;
+        fn XTestFakeDeviceMotionEvent(
+            display: *mut c_void,
+            device: *mut c_void,
+            is_relative: c_int,
+            first_axis: c_int,
+            axes: *const c_int,
+            axes_count: c_int,
+            delay: c_ulong,
+        ) -> c_int;
+    }
+
+    /// The X connection and the opened XTEST device of our master pointer.
+    struct Pointer {
+        display: *mut c_void,
+        device: *mut c_void,
+    }
+
+    // SAFETY: the connection is only used while holding `POINTER`'s lock.
+    unsafe impl Send for Pointer {}
+
+    impl Drop for Pointer {
+        fn drop(&mut self) {
+            // SAFETY: both were opened in `open` and are closed once.
+            unsafe {
+                XCloseDevice(self.display, self.device);
+                XCloseDisplay(self.display);
+            }
+        }
+    }
+
+    static POINTER: Mutex<Option<Pointer>> = Mutex::new(None);
+
+    /// Creates the second master pointer and sends mouse events through it
+    /// from now on, or removes it again. The `xinput` tool does the
+    /// reconfiguring.
+    pub fn set_enabled(enabled: bool) {
+        let mut pointer = POINTER.lock().unwrap();
+        if enabled == pointer.is_some() {
+            return;
+        }
+        if enabled {
+            match open() {
+                Ok(opened) => *pointer = Some(opened),
+                Err(e) => log::error!("Could not create a second pointer: {e}"),
+            }
+        } else {
+            *pointer = None;
+            if let Err(e) = xinput(&["remove-master", &format!("{MASTER} pointer")]) {
+                log::error!("Could not remove the second pointer: {e}");
+            }
+        }
+    }
+
+    pub fn is_enabled() -> bool {
+        POINTER.lock().unwrap().is_some()
+    }
+
+    /// Sends mouse moves and buttons through the second pointer while it is
+    /// enabled. Returns `None` for everything else, which goes through rdev.
+    pub fn simulate(event_type: &EventType) -> Option<Result<(), SimulateError>> {
+        let pointer = POINTER.lock().unwrap();
+        let pointer = pointer.as_ref()?;
+        let sent = match *event_type {
+            EventType::MouseMove { x, y } => {
+                let axes = [x as c_int, y as c_int];
+                // SAFETY: the connection and device are open while in
+                // `POINTER`, and `axes` holds the two axes passed.
+                unsafe {
+                    XTestFakeDeviceMotionEvent(
+                        pointer.display,
+                        pointer.device,
+                        0,
+                        0,
+                        axes.as_ptr(),
+                        2,
+                        0,
+                    )
+                }
+            }
+            EventType::ButtonPress(button) | EventType::ButtonRelease(button) => {
+                let button = match button {
+                    Button::Left => 1,
+                    Button::Middle => 2,
+                    Button::Right => 3,
+                    Button::Unknown(button) => button as c_uint,
+                };
+                let press = matches!(event_type, EventType::ButtonPress(_)) as c_int;
+                // SAFETY: as above, with no axes.
+                unsafe {
+                    XTestFakeDeviceButtonEvent(
+                        pointer.display,
+                        pointer.device,
+                        button,
+                        press,
+                        ptr::null(),
+                        0,
+                        0,
+                    )
+                }
+            }
+            _ => return None,
+        };
+        // SAFETY: the connection is open.
+        unsafe { XFlush(pointer.display) };
+        Some(if sent != 0 {
+            Ok(())
+        } else {
+            Err(SimulateError)
+        })
+    }
+
+    /// Adds the master pointer unless it is left over from a previous run,
+    /// and opens its XTEST slave.
+    fn open() -> Result<Pointer, String> {
+        let xtest = format!("{MASTER} XTEST pointer");
+        let id = match device_id(&xtest) {
+            Some(id) => id,
+            None => {
+                xinput(&["create-master", MASTER])?;
+                device_id(&xtest).ok_or_else(|| format!("{xtest:?} did not appear"))?
+            }
+        };
+
+        // SAFETY: a null name opens `DISPLAY`; the device is only opened
+        // on a connection that succeeded, and both are closed on drop.
+        unsafe {
+            let display = XOpenDisplay(ptr::null());
+            if display.is_null() {
+                return Err("cannot open the X display".to_owned());
+            }
+            let device = XOpenDevice(display, id);
+            if device.is_null() {
+                XCloseDisplay(display);
+                return Err(format!("cannot open {xtest:?}"));
+            }
+            Ok(Pointer { display, device })
+        }
+    }
+
+    fn device_id(name: &str) -> Option<c_ulong> {
+        let output = Command::new("xinput")
+            .args(["list", "--id-only", name])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+
+    fn xinput(args: &[&str]) -> Result<(), String> {
+        let status = Command::new("xinput")
+            .args(args)
+            .status()
+            .map_err(|e| format!("cannot run xinput: {e}"))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("xinput {} failed with {status}", args.join(" ")))
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::{fs, io, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::{
+    config::Table,
+    pixel,
+    profile::Profile,
+    sequence::{ClickPoint, SequenceLoop},
+    settings::Settings,
+};
+
+/// Schema version written by [`export`].
+pub const VERSION: u64 = 1;
+
+/// Upgrades of older files, in order: `MIGRATIONS[n - 1]` turns a
+/// version `n` file into a version `n + 1` one.
+const MIGRATIONS: [fn(&mut Value); 0] = [];
+
+/// Settings that only make sense on the machine they were made on, left
+/// out of exports and kept on import.
+const MACHINE_KEYS: [&str; 8] = [
+    "font.path",
+    "watch.folder",
+    "window.x",
+    "window.y",
+    "window.width",
+    "window.height",
+    "input.display",
+    "app.last_seen_version",
+];
+
+/// Everything a config file holds: the click settings, the click
+/// sequence and the app settings, including the hotkeys.
+#[derive(Debug, Clone)]
+pub struct Portable {
+    pub profile: Profile,
+    pub sequence: Vec<ClickPoint>,
+    pub sequence_loop: SequenceLoop,
+    pub settings: Settings,
+}
+
+/// Writes `config` to `path` as JSON, for sharing between machines or
+/// keeping in dotfiles.
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "profile": { "interval.seconds": 1, "options.mouse_button": "Left", ... },
+///   "sequence": [{ "action": "click", "x": 10, "y": 20, "repeat": 1, "delay_ms": 0, "options": { ... } }],
+///   "sequence_loop": { "forever": false, "count": 3, "delay_ms": 500 },
+///   "settings": { "input.toggle_trigger": "F6", ... }
+/// }
+/// ```
+pub fn export(path: &Path, config: &Portable) -> io::Result<()> {
+    let mut settings = config.settings.to_table();
+    for key in MACHINE_KEYS {
+        settings.remove(key);
+    }
+    let sequence = config
+        .sequence
+        .iter()
+        .map(|point| {
+            json!({
+                "action": point.action.to_string(),
+                "x": point.x,
+                "y": point.y,
+                "repeat": point.repeat,
+                "delay_ms": point.delay_ms,
+                "color": pixel::format_color(point.color),
+                "text": point.text,
+                "timeout_ms": point.timeout_ms,
+                "on_timeout": point.on_timeout.to_string(),
+                "options": table_to_json(&options_table(point)),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let root = json!({
+        "version": VERSION,
+        "profile": table_to_json(&config.profile.to_table()),
+        "sequence": sequence,
+        "sequence_loop": {
+            "forever": config.sequence_loop.forever,
+            "count": config.sequence_loop.count,
+            "delay_ms": config.sequence_loop.delay_ms,
+        },
+        "settings": table_to_json(&settings),
+    });
+    fs::write(path, format!("{root:#}\n"))
+}
+
+/// Reads a file written by [`export`]. Files of older versions are
+/// migrated first. Files of newer versions are read as far as this
+/// version understands them: unknown keys are ignored and missing ones
+/// keep their defaults. Machine-specific settings are taken from
+/// `current`.
+pub fn import(path: &Path, current: &Settings) -> io::Result<Portable> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut root = serde_json::from_str::<Value>(&fs::read_to_string(path)?)
+        .map_err(|e| invalid(format!("{}: {e}", path.display())))?;
+    let version = root
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| invalid(format!("{} has no schema version", path.display())))?;
+    if version == 0 {
+        return Err(invalid(format!("unknown schema version {version}")));
+    }
+    if version > VERSION {
+        log::warn!(
+            "{} is from a newer version (schema {version}), settings it added are ignored",
+            path.display()
+        );
+    }
+    for migrate in MIGRATIONS.iter().skip(version as usize - 1) {
+        migrate(&mut root);
+    }
+
+    let profile = Profile::from_table(&json_to_table(root.get("profile")));
+    let sequence = root
+        .get("sequence")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|point| {
+            let number = |key| point.get(key).and_then(Value::as_u64).unwrap_or_default() as usize;
+            let string = |key| point.get(key).and_then(Value::as_str);
+            let defaults = ClickPoint::default();
+            ClickPoint {
+                action: string("action")
+                    .and_then(|action| action.parse().ok())
+                    .unwrap_or_default(),
+                x: number("x"),
+                y: number("y"),
+                click_options: Profile::from_table(&json_to_table(point.get("options")))
+                    .click_options,
+                repeat: point
+                    .get("repeat")
+                    .and_then(Value::as_u64)
+                    .map_or(1, |repeat| repeat.max(1) as usize),
+                delay_ms: number("delay_ms"),
+                color: string("color")
+                    .and_then(pixel::parse_color)
+                    .unwrap_or(defaults.color),
+                text: string("text").unwrap_or_default().to_owned(),
+                timeout_ms: point
+                    .get("timeout_ms")
+                    .and_then(Value::as_u64)
+                    .map_or(defaults.timeout_ms, |timeout| timeout as usize),
+                on_timeout: string("on_timeout")
+                    .and_then(|on_timeout| on_timeout.parse().ok())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    let sequence_loop = root.get("sequence_loop");
+    let loop_number = |key| {
+        sequence_loop
+            .and_then(|sequence_loop| sequence_loop.get(key))
+            .and_then(Value::as_u64)
+    };
+    let defaults = SequenceLoop::default();
+    let sequence_loop = SequenceLoop {
+        forever: sequence_loop
+            .and_then(|sequence_loop| sequence_loop.get("forever"))
+            .and_then(Value::as_bool)
+            .unwrap_or(defaults.forever),
+        count: loop_number("count").map_or(defaults.count, |count| count.max(1) as usize),
+        delay_ms: loop_number("delay_ms").map_or(defaults.delay_ms, |delay| delay as usize),
+    };
+
+    let mut settings = json_to_table(root.get("settings"));
+    let local = current.to_table();
+    for key in MACHINE_KEYS {
+        settings.remove(key);
+        if let Some((_, value)) = local.entries().find(|(k, _)| *k == key) {
+            settings.set(key, value);
+        }
+    }
+
+    Ok(Portable {
+        profile,
+        sequence,
+        sequence_loop,
+        settings: Settings::from_table(&settings),
+    })
+}
+
+/// The click option keys of `point`, as they appear in a profile.
+fn options_table(point: &ClickPoint) -> Table {
+    let profile = Profile {
+        click_options: point.click_options,
+        ..Profile::default()
+    };
+    let mut table = Table::default();
+    for (key, value) in profile.to_table().entries() {
+        if !key.starts_with("interval.") && !key.starts_with("position.") {
+            table.set(key, value);
+        }
+    }
+    table
+}
+
+/// Turns table values into JSON ones: strings, booleans and finite
+/// numbers stay what they are, anything else becomes its TOML text.
+pub fn table_to_json(table: &Table) -> Value {
+    let members = table
+        .entries()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::String(s) => Value::String(s.clone()),
+                toml::Value::Boolean(b) => Value::Bool(*b),
+                toml::Value::Integer(n) => Value::from(*n),
+                toml::Value::Float(n) if n.is_finite() => Value::from(*n),
+                other => Value::String(other.to_string()),
+            };
+            (key.to_owned(), value)
+        })
+        .collect();
+    Value::Object(members)
+}
+
+/// The reverse of [`table_to_json`]. Nested values have no table
+/// equivalent and are skipped.
+pub fn json_to_table(value: Option<&Value>) -> Table {
+    let mut table = Table::default();
+    let Some(Value::Object(members)) = value else {
+        return table;
+    };
+    for (key, value) in members {
+        match value {
+            Value::String(s) => table.set_str(key, s),
+            Value::Number(n) => table.set(key, n),
+            Value::Bool(b) => table.set(key, b),
+            _ => {}
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::{engine::MouseButton, sequence::StepAction};
+
+    const V1: &str = r#"{
+        "version": 1,
+        "profile": {
+            "interval.seconds": 2,
+            "options.mouse_button": "Right",
+            "options.hold_ms": 50
+        },
+        "sequence": [
+            { "action": "move", "x": 10, "y": 20, "repeat": 0, "delay_ms": 250 }
+        ],
+        "sequence_loop": { "forever": false, "count": 3, "delay_ms": 500 },
+        "settings": { "window.start_minimized": true, "window.x": 999 },
+        "added_later": [1, 2]
+    }"#;
+
+    #[test]
+    fn imports_a_version_1_file() {
+        let path = env::temp_dir().join(format!("auto-clicker-v1-{}.json", process::id()));
+        fs::write(&path, V1).unwrap();
+        let current = Settings {
+            window_position: Some((5, 6)),
+            ..Settings::default()
+        };
+        let imported = import(&path, &current);
+        fs::remove_file(&path).ok();
+        let imported = imported.unwrap();
+
+        assert_eq!(imported.profile.click_interval.seconds, 2);
+        assert_eq!(
+            imported.profile.click_options.mouse_button,
+            MouseButton::Right
+        );
+        assert_eq!(imported.profile.click_options.hold_ms, 50);
+
+        let [point] = imported.sequence.as_slice() else {
+            panic!("{:?}", imported.sequence);
+        };
+        assert_eq!(point.action, StepAction::Move);
+        assert_eq!((point.x, point.y), (10, 20));
+        assert_eq!(point.repeat, 1);
+        assert_eq!(point.delay_ms, 250);
+        assert_eq!(point.timeout_ms, ClickPoint::default().timeout_ms);
+
+        assert!(!imported.sequence_loop.forever);
+        assert_eq!(imported.sequence_loop.count, 3);
+        assert_eq!(imported.sequence_loop.delay_ms, 500);
+
+        assert!(imported.settings.start_minimized);
+        // The window position is this machine's, not the file's.
+        assert_eq!(imported.settings.window_position, Some((5, 6)));
+    }
+}
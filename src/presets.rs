@@ -0,0 +1,42 @@
+//! Interval presets to pick from next to the interval fields: common
+//! intervals and click rates, plus intervals the user saved under a name.
+
+use crate::config::Table;
+
+/// Built-in intervals, in milliseconds.
+pub const BUILT_IN_MS: [usize; 3] = [50, 100, 1000];
+
+/// Built-in click rates, in single clicks per second.
+pub const BUILT_IN_CPS: [usize; 3] = [10, 20, 50];
+
+/// An interval saved under a name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntervalPreset {
+    pub name: String,
+    pub interval_ms: usize,
+}
+
+impl IntervalPreset {
+    /// Reads the `index`th preset, counting from 1, if there is one.
+    pub fn from_table(table: &Table, index: usize) -> Option<Self> {
+        let key = |field: &str| format!("interval_presets.{index}.{field}");
+        Some(Self {
+            name: table.get_str(&key("name"))?,
+            interval_ms: table.get(&key("interval_ms")).unwrap_or_default(),
+        })
+    }
+
+    pub fn to_table(&self, table: &mut Table, index: usize) {
+        let key = |field: &str| format!("interval_presets.{index}.{field}");
+        table.set_str(&key("name"), &self.name);
+        table.set(&key("interval_ms"), self.interval_ms);
+    }
+}
+
+/// Adds `preset` to `presets`, replacing the one of the same name if any.
+pub fn save(presets: &mut Vec<IntervalPreset>, preset: IntervalPreset) {
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+}
@@ -0,0 +1,450 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    burst::Burst,
+    config::{self, Table},
+    drag::Drag,
+    engine::{ButtonRotation, ButtonWeights, ClickInterval, ClickOptions, ClickPosition, Origin},
+    humanize::Humanize,
+    keys,
+    monitor::{self, MonitorId},
+    path::{PathKind, PathPattern},
+    pattern::GridPattern,
+    pixel::{self, PixelCondition, PixelTrigger},
+    ramp::{Curve, Ramp},
+    reminder::Reminder,
+    target,
+};
+
+/// A named snapshot of the click settings, stored as
+/// `<config dir>/profiles/<name>.toml`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Profile {
+    pub click_interval: ClickInterval,
+    pub click_options: ClickOptions,
+    pub click_position: ClickPosition,
+}
+
+impl Profile {
+    /// Names of all saved profiles, sorted alphabetically.
+    pub fn list() -> Vec<String> {
+        let Some(dir) = profiles_dir() else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "toml" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_owned())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn load(name: &str) -> io::Result<Self> {
+        let source = fs::read_to_string(profile_path(name)?)?;
+        let table =
+            Table::parse(&source).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self::from_table(&table))
+    }
+
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        let path = profile_path(name)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_table().to_string())
+    }
+
+    pub fn delete(name: &str) -> io::Result<()> {
+        fs::remove_file(profile_path(name)?)
+    }
+
+    /// The click settings in use when the app last exited, see
+    /// [`Profile::save_last`].
+    pub fn load_last() -> Option<Self> {
+        let path = last_path()?;
+        let source = fs::read_to_string(&path).ok()?;
+        match Table::parse(&source) {
+            Ok(table) => Some(Self::from_table(&table)),
+            Err(e) => {
+                log::error!("Could not read {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Keeps these click settings for the next launch, outside the named
+    /// profiles.
+    pub fn save_last(&self) -> io::Result<()> {
+        let path = last_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_table().to_string())
+    }
+
+    pub(crate) fn from_table(table: &Table) -> Self {
+        let defaults = Self::default();
+
+        let click_interval = ClickInterval {
+            hours: table.get("interval.hours").unwrap_or_default(),
+            minutes: table.get("interval.minutes").unwrap_or_default(),
+            seconds: table.get("interval.seconds").unwrap_or_default(),
+            milliseconds: table.get("interval.milliseconds").unwrap_or_default(),
+            adaptive: table.get("interval.adaptive").unwrap_or_default(),
+            precise: table.get("interval.precise").unwrap_or_default(),
+        };
+
+        let click_options = ClickOptions {
+            mouse_button: table
+                .get_str("options.mouse_button")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.click_options.mouse_button),
+            button_rotation: {
+                let mut rotation = ButtonRotation {
+                    enabled: table.get("button_rotation.enabled").unwrap_or_default(),
+                    ..defaults.click_options.button_rotation
+                };
+                if let Some(buttons) = table.get_str("button_rotation.buttons") {
+                    rotation.set_buttons(&buttons);
+                }
+                rotation
+            },
+            button_weights: ButtonWeights {
+                enabled: table.get("button_weights.enabled").unwrap_or_default(),
+                left: table
+                    .get("button_weights.left")
+                    .unwrap_or(defaults.click_options.button_weights.left),
+                middle: table
+                    .get("button_weights.middle")
+                    .unwrap_or(defaults.click_options.button_weights.middle),
+                right: table
+                    .get("button_weights.right")
+                    .unwrap_or(defaults.click_options.button_weights.right),
+            },
+            click_type: table
+                .get_str("options.click_type")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.click_options.click_type),
+            burst_delay_ms: table.get("options.burst_delay_ms").unwrap_or_default(),
+            press_ms: table.get("options.press_ms").unwrap_or_default(),
+            wiggle: table.get("options.wiggle").unwrap_or_default(),
+            hold_mode: table.get("options.hold_mode").unwrap_or_default(),
+            hold_ms: table.get("options.hold_ms").unwrap_or_default(),
+            key: table.get_str("options.key").and_then(|s| keys::parse(&s)),
+            humanize: Humanize {
+                enabled: table.get("humanize.enabled").unwrap_or_default(),
+                timing_jitter_pct: table
+                    .get("humanize.timing_jitter_pct")
+                    .unwrap_or(defaults.click_options.humanize.timing_jitter_pct),
+                position_jitter_px: table
+                    .get("humanize.position_jitter_px")
+                    .unwrap_or(defaults.click_options.humanize.position_jitter_px),
+                pause_chance_pct: table
+                    .get("humanize.pause_chance_pct")
+                    .unwrap_or(defaults.click_options.humanize.pause_chance_pct),
+                pause_ms: table
+                    .get("humanize.pause_ms")
+                    .unwrap_or(defaults.click_options.humanize.pause_ms),
+            },
+            pixel_trigger: PixelTrigger {
+                enabled: table.get("pixel.enabled").unwrap_or_default(),
+                x: table.get("pixel.x").unwrap_or_default(),
+                y: table.get("pixel.y").unwrap_or_default(),
+                color: table
+                    .get_str("pixel.color")
+                    .and_then(|s| pixel::parse_color(&s))
+                    .unwrap_or(defaults.click_options.pixel_trigger.color),
+                tolerance: table
+                    .get("pixel.tolerance")
+                    .unwrap_or(defaults.click_options.pixel_trigger.tolerance),
+                condition: match table.get_str("pixel.condition").as_deref() {
+                    Some("Differs") => PixelCondition::Differs,
+                    _ => PixelCondition::Matches,
+                },
+            },
+            drag: Drag {
+                enabled: table.get("drag.enabled").unwrap_or_default(),
+                from_x: table.get("drag.from_x").unwrap_or_default(),
+                from_y: table.get("drag.from_y").unwrap_or_default(),
+                to_x: table
+                    .get("drag.to_x")
+                    .unwrap_or(defaults.click_options.drag.to_x),
+                to_y: table
+                    .get("drag.to_y")
+                    .unwrap_or(defaults.click_options.drag.to_y),
+                duration_ms: table
+                    .get("drag.duration_ms")
+                    .unwrap_or(defaults.click_options.drag.duration_ms),
+            },
+            burst: Burst {
+                enabled: table.get("burst.enabled").unwrap_or_default(),
+                size: table
+                    .get("burst.size")
+                    .unwrap_or(defaults.click_options.burst.size),
+                inner_ms: table
+                    .get("burst.inner_ms")
+                    .unwrap_or(defaults.click_options.burst.inner_ms),
+                outer_ms: table
+                    .get("burst.outer_ms")
+                    .unwrap_or(defaults.click_options.burst.outer_ms),
+            },
+            ramp: Ramp {
+                enabled: table.get("ramp.enabled").unwrap_or_default(),
+                from_ms: table
+                    .get("ramp.from_ms")
+                    .unwrap_or(defaults.click_options.ramp.from_ms),
+                to_ms: table
+                    .get("ramp.to_ms")
+                    .unwrap_or(defaults.click_options.ramp.to_ms),
+                duration_secs: table
+                    .get("ramp.duration_secs")
+                    .unwrap_or(defaults.click_options.ramp.duration_secs),
+                curve: match table.get_str("ramp.curve").as_deref() {
+                    Some("Exponential") => Curve::Exponential,
+                    _ => Curve::Linear,
+                },
+            },
+            reminder: Reminder {
+                enabled: table.get("reminder.enabled").unwrap_or_default(),
+                every_minutes: table
+                    .get("reminder.every_minutes")
+                    .unwrap_or(defaults.click_options.reminder.every_minutes),
+                quiet_hours: table.get("reminder.quiet_hours").unwrap_or_default(),
+                quiet_from: table
+                    .get("reminder.quiet_from")
+                    .unwrap_or(defaults.click_options.reminder.quiet_from),
+                quiet_until: table
+                    .get("reminder.quiet_until")
+                    .unwrap_or(defaults.click_options.reminder.quiet_until),
+            },
+        };
+
+        let click_position = match (
+            table.get::<i32>("position.x"),
+            table.get::<i32>("position.y"),
+            table.get("grid.columns"),
+        ) {
+            (Some(x), Some(y), Some(columns)) => {
+                let (x, y) = (x.max(0) as usize, y.max(0) as usize);
+                let defaults = GridPattern::default();
+                ClickPosition::Grid(GridPattern {
+                    x,
+                    y,
+                    columns,
+                    rows: table.get("grid.rows").unwrap_or(defaults.rows),
+                    spacing_x: table.get("grid.spacing_x").unwrap_or(defaults.spacing_x),
+                    spacing_y: table.get("grid.spacing_y").unwrap_or(defaults.spacing_y),
+                })
+            }
+            (Some(x), Some(y), None) if table.get_str("path.kind").is_some() => {
+                let (x, y) = (x.max(0) as usize, y.max(0) as usize);
+                let defaults = PathPattern::default();
+                ClickPosition::Path(PathPattern {
+                    kind: match table.get_str("path.kind").as_deref() {
+                        Some("Circle") => PathKind::Circle,
+                        _ => PathKind::Line,
+                    },
+                    x,
+                    y,
+                    end_x: table.get("path.end_x").unwrap_or(defaults.end_x),
+                    end_y: table.get("path.end_y").unwrap_or(defaults.end_y),
+                    radius: table.get("path.radius").unwrap_or(defaults.radius),
+                    steps: table.get("path.steps").unwrap_or(defaults.steps),
+                })
+            }
+            (Some(x), Some(y), None) if table.get_str("window.title").is_some() => {
+                // Window handles do not survive a restart, titles usually do.
+                let title = table.get_str("window.title");
+                let id = target::list()
+                    .into_iter()
+                    .find(|window| Some(&window.title) == title.as_ref())
+                    .map_or(0, |window| window.id);
+                ClickPosition::Window {
+                    id,
+                    x: x.max(0) as usize,
+                    y: y.max(0) as usize,
+                }
+            }
+            (Some(x), Some(y), None) => ClickPosition::Custom {
+                x,
+                y,
+                origin: match (
+                    table.get_str("position.origin").as_deref(),
+                    table.get_str("position.monitor"),
+                    // Profiles from before monitors were saved by name have
+                    // their place in the list.
+                    table.get::<usize>("position.monitor"),
+                ) {
+                    (Some("ActiveWindow"), _, _) => Origin::ActiveWindow,
+                    (_, Some(name), _) => Origin::Monitor(MonitorId::of(&name)),
+                    (_, None, Some(index)) => monitor::list()
+                        .get(index)
+                        .map_or(Origin::Desktop, |monitor| Origin::Monitor(monitor.id())),
+                    _ => Origin::Desktop,
+                },
+            },
+            _ => ClickPosition::CurrentCursorPosition,
+        };
+
+        Self {
+            click_interval,
+            click_options,
+            click_position,
+        }
+    }
+
+    pub(crate) fn to_table(self) -> Table {
+        let mut table = Table::default();
+
+        table.set("interval.hours", self.click_interval.hours);
+        table.set("interval.minutes", self.click_interval.minutes);
+        table.set("interval.seconds", self.click_interval.seconds);
+        table.set("interval.milliseconds", self.click_interval.milliseconds);
+        table.set("interval.adaptive", self.click_interval.adaptive);
+        table.set("interval.precise", self.click_interval.precise);
+
+        table.set_str(
+            "options.mouse_button",
+            &format!("{:?}", self.click_options.mouse_button),
+        );
+        let rotation = &self.click_options.button_rotation;
+        table.set("button_rotation.enabled", rotation.enabled);
+        let buttons: Vec<String> = rotation
+            .buttons()
+            .iter()
+            .map(|button| format!("{button:?}"))
+            .collect();
+        table.set_str("button_rotation.buttons", &buttons.join(","));
+        let weights = &self.click_options.button_weights;
+        table.set("button_weights.enabled", weights.enabled);
+        table.set("button_weights.left", weights.left);
+        table.set("button_weights.middle", weights.middle);
+        table.set("button_weights.right", weights.right);
+        table.set_str(
+            "options.click_type",
+            &self.click_options.click_type.clicks().to_string(),
+        );
+        table.set("options.burst_delay_ms", self.click_options.burst_delay_ms);
+        table.set("options.press_ms", self.click_options.press_ms);
+        table.set("options.wiggle", self.click_options.wiggle);
+        table.set("options.hold_mode", self.click_options.hold_mode);
+        if let Some(key) = self.click_options.key {
+            table.set_str("options.key", &keys::name(key));
+        }
+
+        let humanize = &self.click_options.humanize;
+        table.set("humanize.enabled", humanize.enabled);
+        table.set("humanize.timing_jitter_pct", humanize.timing_jitter_pct);
+        table.set("humanize.position_jitter_px", humanize.position_jitter_px);
+        table.set("humanize.pause_chance_pct", humanize.pause_chance_pct);
+        table.set("humanize.pause_ms", humanize.pause_ms);
+        table.set("options.hold_ms", self.click_options.hold_ms);
+
+        let pixel_trigger = &self.click_options.pixel_trigger;
+        table.set("pixel.enabled", pixel_trigger.enabled);
+        table.set("pixel.x", pixel_trigger.x);
+        table.set("pixel.y", pixel_trigger.y);
+        table.set_str("pixel.color", &pixel::format_color(pixel_trigger.color));
+        table.set("pixel.tolerance", pixel_trigger.tolerance);
+        table.set_str("pixel.condition", &format!("{:?}", pixel_trigger.condition));
+
+        let drag = &self.click_options.drag;
+        table.set("drag.enabled", drag.enabled);
+        table.set("drag.from_x", drag.from_x);
+        table.set("drag.from_y", drag.from_y);
+        table.set("drag.to_x", drag.to_x);
+        table.set("drag.to_y", drag.to_y);
+        table.set("drag.duration_ms", drag.duration_ms);
+
+        let burst = &self.click_options.burst;
+        table.set("burst.enabled", burst.enabled);
+        table.set("burst.size", burst.size);
+        table.set("burst.inner_ms", burst.inner_ms);
+        table.set("burst.outer_ms", burst.outer_ms);
+
+        let ramp = &self.click_options.ramp;
+        table.set("ramp.enabled", ramp.enabled);
+        table.set("ramp.from_ms", ramp.from_ms);
+        table.set("ramp.to_ms", ramp.to_ms);
+        table.set("ramp.duration_secs", ramp.duration_secs);
+        table.set_str("ramp.curve", &format!("{:?}", ramp.curve));
+
+        let reminder = &self.click_options.reminder;
+        table.set("reminder.enabled", reminder.enabled);
+        table.set("reminder.every_minutes", reminder.every_minutes);
+        table.set("reminder.quiet_hours", reminder.quiet_hours);
+        table.set("reminder.quiet_from", reminder.quiet_from);
+        table.set("reminder.quiet_until", reminder.quiet_until);
+
+        match self.click_position {
+            ClickPosition::CurrentCursorPosition => {}
+            ClickPosition::Custom { x, y, origin } => {
+                table.set("position.x", x);
+                table.set("position.y", y);
+                match origin {
+                    Origin::Desktop => {}
+                    Origin::Monitor(monitor) => table.set_str("position.monitor", &monitor.name()),
+                    Origin::ActiveWindow => table.set_str("position.origin", "ActiveWindow"),
+                }
+            }
+            ClickPosition::Grid(grid) => {
+                table.set("position.x", grid.x);
+                table.set("position.y", grid.y);
+                table.set("grid.columns", grid.columns);
+                table.set("grid.rows", grid.rows);
+                table.set("grid.spacing_x", grid.spacing_x);
+                table.set("grid.spacing_y", grid.spacing_y);
+            }
+            ClickPosition::Path(path) => {
+                table.set("position.x", path.x);
+                table.set("position.y", path.y);
+                table.set_str("path.kind", &format!("{:?}", path.kind));
+                table.set("path.end_x", path.end_x);
+                table.set("path.end_y", path.end_y);
+                table.set("path.radius", path.radius);
+                table.set("path.steps", path.steps);
+            }
+            ClickPosition::Window { id, x, y } => {
+                table.set("position.x", x);
+                table.set("position.y", y);
+                if let Some(window) = target::list().into_iter().find(|window| window.id == id) {
+                    table.set_str("window.title", &window.title);
+                }
+            }
+        }
+
+        table
+    }
+}
+
+fn last_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("last_profile.toml"))
+}
+
+fn profiles_dir() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("profiles"))
+}
+
+fn profile_path(name: &str) -> io::Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\', '.']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid profile name {name:?}"),
+        ));
+    }
+
+    let dir = profiles_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+    Ok(dir.join(format!("{name}.toml")))
+}
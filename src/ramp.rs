@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+/// How a [`Ramp`] moves from its start interval to its end interval.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    /// By the same number of milliseconds every second.
+    #[default]
+    Linear,
+    /// By the same factor every second, which feels even for rates.
+    Exponential,
+}
+
+/// Changing the interval over a run: from `from_ms` at the start to `to_ms`
+/// after `duration_secs`, then holding it. Replaces the click interval
+/// when enabled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ramp {
+    pub enabled: bool,
+    pub from_ms: usize,
+    pub to_ms: usize,
+    pub duration_secs: usize,
+    pub curve: Curve,
+}
+
+impl Default for Ramp {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            from_ms: 1000,
+            to_ms: 100,
+            duration_secs: 60,
+            curve: Curve::Linear,
+        }
+    }
+}
+
+impl Ramp {
+    /// The interval `elapsed` into a run.
+    pub fn interval_at(&self, elapsed: Duration) -> Duration {
+        let t = if self.duration_secs == 0 {
+            1.0
+        } else {
+            (elapsed.as_secs_f64() / self.duration_secs as f64).min(1.0)
+        };
+        let (from, to) = (self.from_ms as f64, self.to_ms as f64);
+        let ms = match self.curve {
+            // A zero end has no ratio to grow by.
+            Curve::Exponential if from > 0.0 && to > 0.0 => from * (to / from).powf(t),
+            _ => from + (to - from) * t,
+        };
+        Duration::from_secs_f64(ms / 1000.0)
+    }
+}
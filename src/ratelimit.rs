@@ -0,0 +1,219 @@
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+/// How much of a second's worth of presses may go out back to back, e.g.
+/// the clicks of a double click. Never less than one press.
+const BURST: f64 = 0.1;
+
+/// A token bucket of presses, refilled at the cap's rate.
+#[derive(Debug)]
+struct Bucket {
+    max_cps: Option<f64>,
+    /// Presses that may be sent right away. Below zero after a batch larger
+    /// than the bucket, which the following presses wait off.
+    tokens: f64,
+    refilled: Option<Instant>,
+}
+
+impl Bucket {
+    /// Takes `presses` from the bucket, refilled up to `now`, or returns
+    /// how long to wait before there are enough. Without a cap nothing is
+    /// taken.
+    fn take(&mut self, presses: f64, now: Instant) -> Option<Duration> {
+        let max_cps = self.max_cps?;
+        if let Some(refilled) = self.refilled {
+            let refill = now.saturating_duration_since(refilled).as_secs_f64() * max_cps;
+            self.tokens = (self.tokens + refill).min(capacity(max_cps));
+        }
+        self.refilled = Some(now);
+        // A batch larger than the bucket goes once the bucket is full and
+        // leaves it in debt.
+        let needed = presses.min(capacity(max_cps));
+        if self.tokens >= needed {
+            self.tokens -= presses;
+            return None;
+        }
+        Some(Duration::from_secs_f64((needed - self.tokens) / max_cps))
+    }
+}
+
+/// A cap on presses per second, shared by everything that sends through it.
+#[derive(Debug)]
+pub struct Limiter {
+    bucket: Mutex<Bucket>,
+}
+
+impl Limiter {
+    /// A limiter without a cap.
+    pub const fn new() -> Self {
+        Self {
+            bucket: Mutex::new(Bucket {
+                max_cps: None,
+                tokens: 0.0,
+                refilled: None,
+            }),
+        }
+    }
+
+    fn bucket(&self) -> MutexGuard<'_, Bucket> {
+        self.bucket.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Caps presses at `max_cps` per second. `None` lifts the cap.
+    pub fn set_max_cps(&self, max_cps: Option<f64>) {
+        let mut bucket = self.bucket();
+        bucket.max_cps = max_cps.filter(|cps| *cps > 0.0);
+        bucket.tokens = bucket.max_cps.map_or(0.0, capacity);
+        bucket.refilled = None;
+    }
+
+    pub fn max_cps(&self) -> Option<f64> {
+        self.bucket().max_cps
+    }
+
+    /// Waits until `events` can be sent without going over the cap, then
+    /// takes their presses from the bucket. Returns at once without a cap
+    /// or presses.
+    pub fn acquire(&self, events: &[EventType]) {
+        let presses = presses(events) as f64;
+        if presses == 0.0 {
+            return;
+        }
+        // Bound to a variable so the lock is released before sleeping.
+        loop {
+            let wait = self.bucket().take(presses, Instant::now());
+            match wait {
+                Some(wait) => thread::sleep(wait),
+                None => return,
+            }
+        }
+    }
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The limiter all sent input goes through.
+static LIMITER: Limiter = Limiter::new();
+
+/// Caps everything the app sends, runs, sequences, scripts and macro
+/// playback alike, at `max_cps` presses per second. `None` lifts the cap.
+pub fn set_max_cps(max_cps: Option<f64>) {
+    LIMITER.set_max_cps(max_cps);
+}
+
+pub fn max_cps() -> Option<f64> {
+    LIMITER.max_cps()
+}
+
+fn capacity(max_cps: f64) -> f64 {
+    (max_cps * BURST).max(1.0)
+}
+
+/// Mouse button and key presses among `events`, which count against the
+/// cap. Shift held for a typed capital is not a press of its own.
+fn presses(events: &[EventType]) -> usize {
+    events
+        .iter()
+        .filter(|event| match event {
+            EventType::ButtonPress(_) => true,
+            EventType::KeyPress(key) => *key != rdev::Key::ShiftLeft,
+            _ => false,
+        })
+        .count()
+}
+
+/// Waits until `events` fit under the app's cap, see [`Limiter::acquire`].
+pub fn acquire(events: &[EventType]) {
+    LIMITER.acquire(events);
+}
+
+#[cfg(test)]
+mod tests {
+    use rdev::Button;
+
+    use super::*;
+
+    fn bucket(max_cps: f64) -> Bucket {
+        Bucket {
+            max_cps: Some(max_cps),
+            tokens: capacity(max_cps),
+            refilled: None,
+        }
+    }
+
+    #[test]
+    fn a_full_bucket_lets_a_burst_through() {
+        let now = Instant::now();
+        // A tenth of a second's worth of 100 presses per second.
+        let mut bucket = bucket(100.0);
+        for _ in 0..10 {
+            assert_eq!(bucket.take(1.0, now), None);
+        }
+        assert_eq!(bucket.take(1.0, now), Some(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn the_burst_is_at_least_one_press() {
+        let now = Instant::now();
+        let mut bucket = bucket(2.0);
+        assert_eq!(bucket.take(1.0, now), None);
+        assert_eq!(bucket.take(1.0, now), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn refills_at_the_cap_up_to_capacity() {
+        let now = Instant::now();
+        let mut bucket = bucket(10.0);
+        assert_eq!(bucket.take(1.0, now), None);
+        assert_eq!(bucket.take(1.0, now), Some(Duration::from_millis(100)));
+        assert_eq!(bucket.take(1.0, now + Duration::from_millis(100)), None);
+        // A long pause does not save up more than the burst.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(bucket.take(1.0, later), None);
+        assert!(bucket.take(1.0, later).is_some());
+    }
+
+    #[test]
+    fn a_large_batch_leaves_the_bucket_in_debt() {
+        let now = Instant::now();
+        let mut bucket = bucket(10.0);
+        assert_eq!(bucket.take(3.0, now), None);
+        assert_eq!(bucket.take(1.0, now), Some(Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn no_cap_takes_nothing() {
+        let mut bucket = Bucket {
+            max_cps: None,
+            tokens: 0.0,
+            refilled: None,
+        };
+        assert_eq!(bucket.take(100.0, Instant::now()), None);
+    }
+
+    #[test]
+    fn acquire_blocks_until_the_cap_allows() {
+        let click = [
+            EventType::ButtonPress(Button::Left),
+            EventType::ButtonRelease(Button::Left),
+        ];
+        let limiter = Limiter::new();
+        limiter.set_max_cps(Some(20.0));
+        let started = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire(&click);
+        }
+        // The burst of two goes at once, the others a 20th of a second
+        // apart.
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,665 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use rdev::EventType;
+
+use crate::{abort, keys, listener, source::simulate, target};
+
+/// Whether the cursor is over, and the keyboard focus in, the app's own
+/// window, kept up to date from its window events.
+static OWN_WINDOW_HOVERED: AtomicBool = AtomicBool::new(false);
+static OWN_WINDOW_FOCUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_own_window_hovered(hovered: bool) {
+    OWN_WINDOW_HOVERED.store(hovered, Ordering::SeqCst);
+}
+
+/// Whether the cursor is over the app's own window.
+pub fn is_own_window_hovered() -> bool {
+    OWN_WINDOW_HOVERED.load(Ordering::SeqCst)
+}
+
+pub fn set_own_window_focused(focused: bool) {
+    OWN_WINDOW_FOCUSED.store(focused, Ordering::SeqCst);
+}
+
+/// Which input a recording keeps, to cut its size and noise.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecordFilter {
+    /// Keep only buttons, keys and the wheel.
+    pub ignore_moves: bool,
+    /// Snap moves to a grid of this many pixels and keep only those that
+    /// reach a new cell. 0 or 1 keeps every move.
+    pub move_grid: usize,
+    /// Drop input aimed at the app's own window.
+    pub ignore_own_window: bool,
+}
+
+impl RecordFilter {
+    /// What to record for `event_type`, given the last recorded cursor
+    /// position, or `None` to drop it.
+    fn apply(&self, event_type: EventType, last_move: Option<(f64, f64)>) -> Option<EventType> {
+        if self.ignore_own_window {
+            let own = match event_type {
+                EventType::KeyPress(_) | EventType::KeyRelease(_) => {
+                    OWN_WINDOW_FOCUSED.load(Ordering::SeqCst)
+                }
+                _ => OWN_WINDOW_HOVERED.load(Ordering::SeqCst),
+            };
+            if own {
+                return None;
+            }
+        }
+
+        let EventType::MouseMove { x, y } = event_type else {
+            return Some(event_type);
+        };
+        if self.ignore_moves {
+            return None;
+        }
+        if self.move_grid <= 1 {
+            return Some(event_type);
+        }
+        let grid = self.move_grid as f64;
+        let (x, y) = ((x / grid).round() * grid, (y / grid).round() * grid);
+        (last_move != Some((x, y))).then_some(EventType::MouseMove { x, y })
+    }
+}
+
+/// How a recording's timing changes when it is played.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Playback {
+    /// How many times as fast as recorded to play, from [`Playback::MIN_SPEED`]
+    /// to [`Playback::MAX_SPEED`].
+    pub speed: f64,
+    /// The shortest delay between two events, in milliseconds.
+    pub min_delay_ms: u64,
+    /// The longest delay between two events, in milliseconds; 0 for no
+    /// limit.
+    pub max_delay_ms: u64,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            min_delay_ms: 0,
+            max_delay_ms: 0,
+        }
+    }
+}
+
+impl Playback {
+    pub const MIN_SPEED: f64 = 0.25;
+    pub const MAX_SPEED: f64 = 10.0;
+
+    /// The recorded `delay` as played: scaled by the speed, then clamped to
+    /// the shortest and longest delay.
+    pub fn delay(&self, delay: Duration) -> Duration {
+        let speed = self.speed.clamp(Self::MIN_SPEED, Self::MAX_SPEED);
+        let delay = delay
+            .div_f64(speed)
+            .max(Duration::from_millis(self.min_delay_ms));
+        if self.max_delay_ms > 0 {
+            delay.min(Duration::from_millis(self.max_delay_ms))
+        } else {
+            delay
+        }
+    }
+}
+
+/// The timing every recording is played with, including those of quick
+/// slots and watched folders.
+static PLAYBACK: Mutex<Playback> = Mutex::new(Playback {
+    speed: 1.0,
+    min_delay_ms: 0,
+    max_delay_ms: 0,
+});
+
+/// Sets the timing of the playbacks that start from now on.
+pub fn set_playback(playback: Playback) {
+    *PLAYBACK.lock().unwrap() = playback;
+}
+
+/// A captured input event and how long after the previous one it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    pub delay: Duration,
+    pub event_type: EventType,
+}
+
+impl RecordedEvent {
+    /// The event as one line of a saved recording: the delay in
+    /// microseconds, then the event, e.g. `15000 press Left`,
+    /// `0 move 120.5 300` or `8000 key_press KeyA`.
+    pub fn to_line(&self) -> String {
+        let event = match self.event_type {
+            EventType::MouseMove { x, y } => format!("move {x} {y}"),
+            EventType::ButtonPress(button) => format!("press {}", button_name(button)),
+            EventType::ButtonRelease(button) => format!("release {}", button_name(button)),
+            EventType::KeyPress(key) => format!("key_press {}", keys::name(key)),
+            EventType::KeyRelease(key) => format!("key_release {}", keys::name(key)),
+            EventType::Wheel { delta_x, delta_y } => format!("wheel {delta_x} {delta_y}"),
+        };
+        format!("{} {event}", self.delay.as_micros())
+    }
+
+    /// Parses a line written by [`RecordedEvent::to_line`]. Keys the
+    /// auto-presser does not know are not read back.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let delay = Duration::from_micros(fields.next()?.parse().ok()?);
+        let kind = fields.next()?;
+        let mut arg = || fields.next();
+        let event_type = match kind {
+            "move" => EventType::MouseMove {
+                x: arg()?.parse().ok()?,
+                y: arg()?.parse().ok()?,
+            },
+            "press" => EventType::ButtonPress(parse_button(arg()?)?),
+            "release" => EventType::ButtonRelease(parse_button(arg()?)?),
+            "key_press" => EventType::KeyPress(keys::parse(arg()?)?),
+            "key_release" => EventType::KeyRelease(keys::parse(arg()?)?),
+            "wheel" => EventType::Wheel {
+                delta_x: arg()?.parse().ok()?,
+                delta_y: arg()?.parse().ok()?,
+            },
+            _ => return None,
+        };
+        Some(Self { delay, event_type })
+    }
+}
+
+/// What the mouse positions of a recording are measured from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum CoordinateSpace {
+    /// The top-left corner of the screen.
+    #[default]
+    Absolute,
+    /// The top-left corner of the client area of the window with this
+    /// title, wherever it is when the recording is played.
+    Window(String),
+}
+
+/// A recording and the space its mouse positions are in, as saved to a
+/// file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Recording {
+    pub space: CoordinateSpace,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    /// One event per line, see [`RecordedEvent::to_line`], after a
+    /// `window <title>` line for a window-relative recording.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::with_capacity(self.events.len() + 1);
+        if let CoordinateSpace::Window(title) = &self.space {
+            lines.push(format!("window {title}"));
+        }
+        lines.extend(self.events.iter().map(RecordedEvent::to_line));
+        lines.join("\n")
+    }
+
+    /// Reads text written by [`Recording::to_text`], skipping lines it
+    /// does not understand.
+    pub fn parse(text: &str) -> Self {
+        let mut recording = Self::default();
+        for line in text.lines() {
+            if let Some(title) = line.strip_prefix("window ") {
+                recording.space = CoordinateSpace::Window(title.to_owned());
+            } else if let Some(event) = RecordedEvent::parse_line(line) {
+                recording.events.push(event);
+            }
+        }
+        recording
+    }
+}
+
+/// How [`simplify`] cleans up a recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Simplify {
+    /// How far, in pixels, a simplified mouse path may stray from the
+    /// recorded one.
+    pub tolerance: f64,
+    /// Event times are rounded to multiples of this many milliseconds; 0
+    /// keeps them exact.
+    pub round_ms: u64,
+}
+
+impl Default for Simplify {
+    fn default() -> Self {
+        Self {
+            tolerance: 2.0,
+            round_ms: 10,
+        }
+    }
+}
+
+/// Cleans up a raw recording for editing: mouse paths are thinned out with
+/// the Douglas–Peucker algorithm, moves that go nowhere are merged and
+/// timings are rounded. The time of dropped events goes to the next kept
+/// one, so the recording keeps its overall pace.
+pub fn simplify(events: &[RecordedEvent], options: Simplify) -> Vec<RecordedEvent> {
+    // Work on times since the start of the recording, so rounding does not
+    // add up to drift.
+    let mut at = Duration::ZERO;
+    let timed: Vec<(Duration, EventType)> = events
+        .iter()
+        .map(|event| {
+            at += event.delay;
+            (at, event.event_type)
+        })
+        .collect();
+
+    let mut kept: Vec<(Duration, EventType)> = Vec::with_capacity(timed.len());
+    let mut last_move = None;
+    let mut i = 0;
+    while i < timed.len() {
+        let run_end = timed[i..]
+            .iter()
+            .position(|(_, event_type)| !matches!(event_type, EventType::MouseMove { .. }))
+            .map_or(timed.len(), |len| i + len);
+        if run_end == i {
+            kept.push(timed[i]);
+            i += 1;
+            continue;
+        }
+
+        let run = &timed[i..run_end];
+        let points: Vec<(f64, f64)> = run
+            .iter()
+            .map(|(_, event_type)| position(event_type))
+            .collect();
+        for index in douglas_peucker(&points, options.tolerance) {
+            if last_move != Some(points[index]) {
+                last_move = Some(points[index]);
+                kept.push(run[index]);
+            }
+        }
+        i = run_end;
+    }
+
+    let round = |at: Duration| match options.round_ms {
+        0 => at,
+        step => {
+            let step = step as u128 * 1000;
+            Duration::from_micros(((at.as_micros() + step / 2) / step * step) as u64)
+        }
+    };
+    let mut previous = Duration::ZERO;
+    kept.into_iter()
+        .map(|(at, event_type)| {
+            let at = round(at).max(previous);
+            let delay = at - previous;
+            previous = at;
+            RecordedEvent { delay, event_type }
+        })
+        .collect()
+}
+
+fn position(event_type: &EventType) -> (f64, f64) {
+    match *event_type {
+        EventType::MouseMove { x, y } => (x, y),
+        _ => (0.0, 0.0),
+    }
+}
+
+/// The indices of the points of a path that keep it within `tolerance`
+/// of the original, always including both ends.
+fn douglas_peucker(points: &[(f64, f64)], tolerance: f64) -> Vec<usize> {
+    let mut keep = vec![false; points.len()];
+    let last = points.len() - 1;
+    keep[0] = true;
+    keep[last] = true;
+
+    // An explicit stack instead of recursion: long drags can have
+    // thousands of points.
+    let mut stack = vec![(0, last)];
+    while let Some((first, last)) = stack.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| {
+                (
+                    i,
+                    distance_to_segment(points[i], points[first], points[last]),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((first, i));
+                stack.push((i, last));
+            }
+        }
+    }
+
+    (0..points.len()).filter(|&i| keep[i]).collect()
+}
+
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+    };
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
+
+fn button_name(button: rdev::Button) -> String {
+    match button {
+        rdev::Button::Unknown(code) => code.to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn parse_button(s: &str) -> Option<rdev::Button> {
+    match s {
+        "Left" => Some(rdev::Button::Left),
+        "Middle" => Some(rdev::Button::Middle),
+        "Right" => Some(rdev::Button::Right),
+        code => code.parse().ok().map(rdev::Button::Unknown),
+    }
+}
+
+/// The title of the window a recording is relative to, with its client
+/// origin.
+type Anchor = (String, (i32, i32));
+
+/// Records global mouse and keyboard input and plays it back.
+///
+/// A subscription to the shared input listener is taken on the first
+/// recording and simply ignores events while no recording is in progress.
+#[derive(Default)]
+pub struct Recorder {
+    recording: Arc<AtomicBool>,
+    playing: Arc<AtomicBool>,
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+    space: CoordinateSpace,
+    /// Record relative to the window that was focused, see
+    /// [`Recorder::set_relative`].
+    relative: bool,
+    /// The first window other than our own that had the focus during the
+    /// recording, with its client origin at the time.
+    anchor: Arc<Mutex<Option<Anchor>>>,
+    filter: Arc<Mutex<RecordFilter>>,
+    listener_started: bool,
+}
+
+impl Recorder {
+    pub fn start_recording(&mut self) {
+        if self.is_playing() {
+            return;
+        }
+        self.events.lock().unwrap().clear();
+        *self.anchor.lock().unwrap() = None;
+        self.space = CoordinateSpace::Absolute;
+        self.start_listener();
+        self.recording.store(true, Ordering::SeqCst);
+    }
+
+    pub fn stop_recording(&mut self) {
+        self.recording.store(false, Ordering::SeqCst);
+
+        // The clicks on our own Record and Stop Recording buttons end up at
+        // either end of the recording; drop them.
+        let mut events = self.events.lock().unwrap();
+        while matches!(
+            events.first().map(|e| &e.event_type),
+            Some(EventType::ButtonRelease(_))
+        ) {
+            events.remove(0);
+        }
+        while matches!(
+            events.last().map(|e| &e.event_type),
+            Some(EventType::ButtonPress(_) | EventType::ButtonRelease(_))
+        ) {
+            events.pop();
+        }
+
+        if !self.relative {
+            return;
+        }
+        let Some((title, (left, top))) = self.anchor.lock().unwrap().take() else {
+            log::info!("No window had the focus while recording, keeping screen coordinates");
+            return;
+        };
+        for event in events.iter_mut() {
+            if let EventType::MouseMove { x, y } = &mut event.event_type {
+                *x -= left as f64;
+                *y -= top as f64;
+            }
+        }
+        self.space = CoordinateSpace::Window(title);
+    }
+
+    /// Makes the next recordings relative to the first window other than
+    /// ours that is focused while recording, instead of to the screen.
+    pub fn set_relative(&mut self, relative: bool) {
+        self.relative = relative;
+    }
+
+    pub fn space(&self) -> &CoordinateSpace {
+        &self.space
+    }
+
+    /// Sets which input is kept, taking effect for the events that follow.
+    pub fn set_filter(&self, filter: RecordFilter) {
+        *self.filter.lock().unwrap() = filter;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::SeqCst)
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::SeqCst)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn recording(&self) -> Recording {
+        Recording {
+            space: self.space.clone(),
+            events: self.events.lock().unwrap().clone(),
+        }
+    }
+
+    /// Replaces the recording, e.g. with one loaded from disk. Ignored
+    /// while recording or playing.
+    pub fn set_recording(&mut self, recording: Recording) {
+        if !self.is_recording() && !self.is_playing() {
+            *self.events.lock().unwrap() = recording.events;
+            self.space = recording.space;
+        }
+    }
+
+    /// Runs [`simplify`] on the recording. Ignored while recording or
+    /// playing.
+    pub fn simplify(&self, options: Simplify) {
+        if !self.is_recording() && !self.is_playing() {
+            let mut events = self.events.lock().unwrap();
+            *events = simplify(&events, options);
+        }
+    }
+
+    /// Replays the recording on a background thread with its original
+    /// timing, changed as set with [`set_playback`]. A window-relative
+    /// recording is only played while its window is open.
+    pub fn play(&self) {
+        if self.is_recording() || self.is_playing() {
+            return;
+        }
+        let (left, top) = match &self.space {
+            CoordinateSpace::Absolute => (0, 0),
+            CoordinateSpace::Window(title) => {
+                let origin = target::list()
+                    .into_iter()
+                    .find(|window| window.title == *title)
+                    .and_then(|window| target::client_origin(window.id));
+                let Some(origin) = origin else {
+                    log::warn!("The window {title:?} is not open, not playing the recording");
+                    return;
+                };
+                origin
+            }
+        };
+        if self.playing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut events = self.events.lock().unwrap().clone();
+        for event in &mut events {
+            if let EventType::MouseMove { x, y } = &mut event.event_type {
+                *x += left as f64;
+                *y += top as f64;
+            }
+        }
+        let playing = self.playing.clone();
+        let playback = *PLAYBACK.lock().unwrap();
+        thread::spawn(move || {
+            // Each event is due a set time after the start, so oversleeping
+            // one delay does not push back all the events after it.
+            let mut due = Instant::now();
+            let since = abort::generation();
+            for event in events {
+                if !playing.load(Ordering::SeqCst) {
+                    break;
+                }
+                due += playback.delay(event.delay);
+                if !abort::sleep(due.saturating_duration_since(Instant::now()), since) {
+                    break;
+                }
+                if simulate(&event.event_type).is_err() {
+                    log::error!("We could not send {:?}", event.event_type);
+                }
+            }
+            playing.store(false, Ordering::SeqCst);
+        });
+    }
+
+    pub fn stop_playing(&self) {
+        self.playing.store(false, Ordering::SeqCst);
+    }
+
+    fn start_listener(&mut self) {
+        if self.listener_started {
+            return;
+        }
+        self.listener_started = true;
+
+        let recording = self.recording.clone();
+        let events = self.events.clone();
+        let anchor = self.anchor.clone();
+        let filter = self.filter.clone();
+        let rx_events = listener::subscribe();
+        thread::spawn(move || {
+            let mut last_event: Option<SystemTime> = None;
+            let mut last_move: Option<(f64, f64)> = None;
+            for event in rx_events {
+                if !recording.load(Ordering::SeqCst) {
+                    last_event = None;
+                    last_move = None;
+                    continue;
+                }
+
+                // Dropped events add their time to the next kept one, so
+                // playback keeps the original pace.
+                let filter = *filter.lock().unwrap();
+                let Some(event_type) = filter.apply(event.event_type, last_move) else {
+                    continue;
+                };
+                if let EventType::MouseMove { x, y } = event_type {
+                    last_move = Some((x, y));
+                }
+                if matches!(
+                    event_type,
+                    EventType::ButtonPress(_) | EventType::KeyPress(_)
+                ) {
+                    let mut anchor = anchor.lock().unwrap();
+                    if anchor.is_none() && !OWN_WINDOW_FOCUSED.load(Ordering::SeqCst) {
+                        *anchor = target::foreground().and_then(|window| {
+                            Some((window.title, target::client_origin(window.id)?))
+                        });
+                    }
+                }
+
+                // The time the OS hook saw the event, not when it got here.
+                let delay = last_event.map_or(Duration::ZERO, |last| {
+                    event.time.duration_since(last).unwrap_or_default()
+                });
+                last_event = Some(event.time);
+
+                events
+                    .lock()
+                    .unwrap()
+                    .push(RecordedEvent { delay, event_type });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(points: &[(f64, f64)]) -> Vec<RecordedEvent> {
+        points
+            .iter()
+            .map(|&(x, y)| RecordedEvent {
+                delay: Duration::from_millis(10),
+                event_type: EventType::MouseMove { x, y },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn collinear_points_are_removed() {
+        let points: Vec<_> = (0..=10)
+            .map(|i| (i as f64 * 10.0, i as f64 * 5.0))
+            .collect();
+        assert_eq!(douglas_peucker(&points, 0.5), [0, 10]);
+    }
+
+    #[test]
+    fn keeps_bumps_beyond_the_tolerance() {
+        let points = [(0.0, 0.0), (50.0, 3.0), (100.0, 0.0)];
+        assert_eq!(douglas_peucker(&points, 2.0), [0, 1, 2]);
+        assert_eq!(douglas_peucker(&points, 3.0), [0, 2]);
+        assert_eq!(douglas_peucker(&points, 5.0), [0, 2]);
+    }
+
+    #[test]
+    fn keeps_both_ends() {
+        assert_eq!(douglas_peucker(&[(4.0, 2.0)], 2.0), [0]);
+        assert_eq!(douglas_peucker(&[(0.0, 0.0), (0.0, 0.0)], 2.0), [0, 1]);
+
+        let events = moves(&[(0.0, 0.0), (1.0, 0.5), (2.0, 1.0), (3.0, 1.5)]);
+        let simplified = simplify(&events, Simplify::default());
+        let kept: Vec<_> = simplified.iter().map(|event| event.event_type).collect();
+        assert_eq!(
+            kept,
+            [
+                EventType::MouseMove { x: 0.0, y: 0.0 },
+                EventType::MouseMove { x: 3.0, y: 1.5 },
+            ]
+        );
+        // The time of the dropped moves goes to the last one.
+        let total: Duration = simplified.iter().map(|event| event.delay).sum();
+        assert_eq!(total, Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,107 @@
+//! Selecting a rectangle of the screen by dragging over it, e.g. the area a
+//! grid should cover. While a selection runs the overlay dims the screen,
+//! catches the mouse so the drag does not reach the windows below, and
+//! draws the rectangle with its coordinates.
+
+use std::{sync::Mutex, thread};
+
+use rdev::{Button, EventType, Key};
+
+use crate::listener;
+
+/// A rectangle on the desktop in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    /// The rectangle between two opposite corners.
+    pub fn between((x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> Self {
+        Self {
+            x: x1.min(x2),
+            y: y1.min(y2),
+            width: x1.abs_diff(x2),
+            height: y1.abs_diff(y2),
+        }
+    }
+}
+
+/// A selection in progress, as the overlay draws it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    /// Where the drag started, once the button is down.
+    pub anchor: Option<(i32, i32)>,
+    pub cursor: Option<(i32, i32)>,
+}
+
+impl Selection {
+    pub fn region(&self) -> Option<Region> {
+        Some(Region::between(self.anchor?, self.cursor?))
+    }
+}
+
+static SELECTION: Mutex<Option<Selection>> = Mutex::new(None);
+
+/// How the last selection ended, until taken: the region, or `None` if it
+/// was cancelled.
+static RESULT: Mutex<Option<Option<Region>>> = Mutex::new(None);
+
+/// Starts a selection unless one is running: the left button drags the
+/// rectangle, Esc or another button cancels. The result is collected with
+/// [`take_result`].
+pub fn start() {
+    let mut selection = SELECTION.lock().unwrap();
+    if selection.is_some() {
+        return;
+    }
+    let cursor = listener::cursor_position().map(|(x, y)| (x as i32, y as i32));
+    *selection = Some(Selection {
+        anchor: None,
+        cursor,
+    });
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let mut result = None;
+        for event in rx_events {
+            let mut selection = SELECTION.lock().unwrap();
+            let Some(selection) = selection.as_mut() else {
+                return;
+            };
+            match event.event_type {
+                EventType::MouseMove { x, y } => {
+                    selection.cursor = Some((x.round() as i32, y.round() as i32));
+                }
+                EventType::ButtonPress(Button::Left) => selection.anchor = selection.cursor,
+                EventType::ButtonRelease(Button::Left) if selection.anchor.is_some() => {
+                    // A click without a drag selects nothing.
+                    result = selection
+                        .region()
+                        .filter(|region| region.width > 0 && region.height > 0);
+                    break;
+                }
+                EventType::ButtonPress(_) | EventType::KeyPress(Key::Escape) => break,
+                _ => {}
+            }
+        }
+        *RESULT.lock().unwrap() = Some(result);
+        *SELECTION.lock().unwrap() = None;
+    });
+}
+
+/// The selection in progress, if any.
+pub fn selection() -> Option<Selection> {
+    *SELECTION.lock().unwrap()
+}
+
+pub fn is_selecting() -> bool {
+    SELECTION.lock().unwrap().is_some()
+}
+
+/// How the last selection ended, once: `Some(None)` if it was cancelled.
+pub fn take_result() -> Option<Option<Region>> {
+    RESULT.lock().unwrap().take()
+}
@@ -0,0 +1,90 @@
+use std::{sync::Mutex, thread, time::Duration};
+
+use crate::{
+    dnd, schedule, stats,
+    timeline::{self, Activity},
+};
+
+/// How often the run time is looked at.
+const POLL: Duration = Duration::from_secs(5);
+
+/// Reminders that a run is still going, for runs that might be forgotten.
+/// They come as the notifications, beeps and announcements turned on in
+/// the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reminder {
+    pub enabled: bool,
+    /// Run time between reminders; paused time does not count.
+    pub every_minutes: usize,
+    /// Hold reminders back from `quiet_from` until `quiet_until`, both in
+    /// minutes after local midnight. The span can reach past midnight.
+    pub quiet_hours: bool,
+    pub quiet_from: usize,
+    pub quiet_until: usize,
+}
+
+impl Default for Reminder {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            every_minutes: 30,
+            quiet_hours: false,
+            quiet_from: 22 * 60,
+            quiet_until: 7 * 60,
+        }
+    }
+}
+
+impl Reminder {
+    /// Whether `minute` after local midnight is within the quiet hours.
+    pub fn is_quiet(&self, minute: usize) -> bool {
+        if !self.quiet_hours {
+            false
+        } else if self.quiet_from <= self.quiet_until {
+            (self.quiet_from..self.quiet_until).contains(&minute)
+        } else {
+            minute >= self.quiet_from || minute < self.quiet_until
+        }
+    }
+}
+
+static REMINDER: Mutex<Option<Reminder>> = Mutex::new(None);
+
+/// Sets the reminders of the current run, e.g. when the clicker gets new
+/// click options.
+pub fn set_reminder(reminder: Reminder) {
+    *REMINDER.lock().unwrap() = Some(reminder);
+}
+
+/// Starts a thread that records an [`Activity::Reminder`] each time a run
+/// has gone on for another `every_minutes`, unless it falls in the quiet
+/// hours or the system is in do-not-disturb mode, see [`dnd`].
+pub fn spawn_watcher() {
+    thread::spawn(|| {
+        // Reminders due in the current run so far.
+        let mut reminded = 0;
+        loop {
+            thread::sleep(POLL);
+            let Some(reminder) = *REMINDER.lock().unwrap() else {
+                continue;
+            };
+            let stats = stats::current();
+            let every = reminder.every_minutes.max(1) as u64 * 60;
+            let due = stats.elapsed().as_secs() / every;
+            // A new run, or reminders just turned on, which start counting
+            // from here.
+            if due < reminded || !reminder.enabled {
+                reminded = due;
+                continue;
+            }
+            if !stats.is_running() || due == reminded {
+                continue;
+            }
+            reminded = due;
+            let minute = schedule::local_time_of_day().as_secs() as usize / 60;
+            if !reminder.is_quiet(minute) && !dnd::holds_alerts() {
+                timeline::record(Activity::Reminder);
+            }
+        }
+    });
+}
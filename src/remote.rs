@@ -0,0 +1,581 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use tungstenite::{handshake::derive_accept_key, protocol::Role, Message, WebSocket};
+
+use crate::{
+    clicker::{self, Command, Config},
+    config::Table,
+    profile::Profile,
+    stats,
+    timeline::{self, Activity},
+};
+
+/// A client that stops sending mid-request is dropped after this.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request bodies larger than this are refused.
+const MAX_BODY: usize = 64 * 1024;
+
+/// Request and header lines longer than this are refused.
+const MAX_LINE: usize = 8 * 1024;
+
+/// Requests with more headers than this are refused.
+const MAX_HEADERS: usize = 64;
+
+/// Connections beyond this many at once are closed right away.
+const MAX_CONNECTIONS: usize = 32;
+
+/// How often a WebSocket connection looks for new events between messages.
+const SOCKET_POLL: Duration = Duration::from_millis(50);
+
+/// WebSocket clients get a `clicks` event every this many clicks.
+const CLICK_MILESTONE: usize = 100;
+
+/// Who may use a server, see [`spawn_server`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Access {
+    /// Shared secret every request must carry, as `Authorization: Bearer
+    /// <token>` or, for browsers opening the WebSocket, a `token` query
+    /// parameter. Empty lets anyone in, which only a view-only server
+    /// allows.
+    pub token: String,
+    /// Origins of web pages, e.g. `https://dashboard.example`, that may send
+    /// requests from a browser. Requests with any other `Origin` header are
+    /// refused, so a page the user happens to visit cannot drive the
+    /// clicker.
+    pub allowed_origins: Vec<String>,
+}
+
+/// Starts a thread serving a small HTTP API on `address`, e.g.
+/// `127.0.0.1:7878`, that drives the clicker through `tx_command`:
+///
+/// - `POST /start`, `POST /stop`, `POST /toggle`, `POST /pause` and
+///   `POST /resume`
+/// - `PUT /config` with a profile file as the body, which replaces the
+///   interval, click options and click position
+/// - `GET /status`, answered as `key = value` lines
+/// - `GET /ws`, a WebSocket that streams `{"event": ...}` messages for
+///   `start`, `stop`, `finish`, `safety_stop`, `target_closed` and every
+///   [`CLICK_MILESTONE`] `clicks`, and takes `start`, `stop`, `toggle` and
+///   `status` as text messages
+///
+/// Every connection gets its own thread, so a slow client or an open
+/// WebSocket does not hold up the others, up to [`MAX_CONNECTIONS`] at
+/// once. Requests are checked against
+/// `access` first; the server refuses to start without a token when it can
+/// change the clicker.
+///
+/// Without `tx_command` the server is view-only: it answers `GET /status`
+/// and streams the WebSocket events, but refuses everything that would
+/// change the clicker, so a long run can be watched from another machine.
+pub fn spawn_server(
+    address: &str,
+    access: Access,
+    tx_command: Option<Sender<Command>>,
+) -> io::Result<()> {
+    if tx_command.is_some() && access.token.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the remote control needs a token",
+        ));
+    }
+    let listener = TcpListener::bind(address)?;
+    let access = Arc::new(access);
+    let connections = Arc::new(AtomicUsize::new(0));
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::error!("Remote control connection failed: {e}");
+                    continue;
+                }
+            };
+            let Some(connection) = Connection::open(&connections) else {
+                log::warn!("Remote control has {MAX_CONNECTIONS} connections, refusing another");
+                continue;
+            };
+            let access = Arc::clone(&access);
+            let tx_command = tx_command.clone();
+            thread::spawn(move || {
+                let _connection = connection;
+                if let Err(e) = handle(stream, &access, tx_command) {
+                    log::error!("Remote control request failed: {e}");
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Counts an open connection until dropped.
+struct Connection(Arc<AtomicUsize>);
+
+impl Connection {
+    /// `None` if [`MAX_CONNECTIONS`] are open already.
+    fn open(count: &Arc<AtomicUsize>) -> Option<Self> {
+        count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |open| {
+                (open < MAX_CONNECTIONS).then_some(open + 1)
+            })
+            .ok()?;
+        Some(Self(Arc::clone(count)))
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The parts of a request's head the server looks at.
+#[derive(Debug, Default)]
+struct Request {
+    method: String,
+    path: String,
+    /// The `token` query parameter.
+    query_token: Option<String>,
+    origin: Option<String>,
+    authorization: Option<String>,
+    websocket_key: Option<String>,
+    content_length: usize,
+}
+
+impl Request {
+    /// Reads the head up to the blank line. Lines over [`MAX_LINE`] and
+    /// more than [`MAX_HEADERS`] headers fail with
+    /// [`io::ErrorKind::InvalidData`].
+    fn read(reader: &mut impl BufRead) -> io::Result<Self> {
+        let mut request_line = String::new();
+        read_line(reader, &mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_owned();
+        let target = parts.next().unwrap_or_default();
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let mut request = Self {
+            method,
+            path: path.to_owned(),
+            query_token: query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("token="))
+                .map(percent_decode),
+            ..Self::default()
+        };
+
+        for count in 0.. {
+            let mut header = String::new();
+            if read_line(reader, &mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if count == MAX_HEADERS {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "too many headers",
+                ));
+            }
+            let Some((name, value)) = header.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().to_owned();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => request.content_length = value.parse().unwrap_or_default(),
+                "sec-websocket-key" => request.websocket_key = Some(value),
+                "origin" => request.origin = Some(value),
+                "authorization" => request.authorization = Some(value),
+                _ => {}
+            }
+        }
+        Ok(request)
+    }
+
+    /// Checks the request against `access`, returning the status and body
+    /// to refuse it with.
+    fn check(&self, access: &Access) -> Result<(), (&'static str, &'static str)> {
+        if let Some(origin) = &self.origin {
+            if !access
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed.trim_end_matches('/') == origin)
+            {
+                return Err(("403 Forbidden", "origin not allowed\n"));
+            }
+        }
+        if access.token.is_empty() {
+            return Ok(());
+        }
+        let token = self
+            .authorization
+            .as_deref()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or(self.query_token.as_deref());
+        if token.is_some_and(|token| same(token.trim().as_bytes(), access.token.as_bytes())) {
+            Ok(())
+        } else {
+            Err(("401 Unauthorized", "missing or wrong token\n"))
+        }
+    }
+}
+
+/// Reads one line of the head into `line`, failing on lines longer than
+/// [`MAX_LINE`] instead of buffering them whole.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+    let read = reader.by_ref().take(MAX_LINE as u64).read_line(line)?;
+    if read == MAX_LINE && !line.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+    }
+    Ok(read)
+}
+
+/// Resolves `%XX` escapes and `+` as space in a query value. Malformed
+/// escapes are kept as they are.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', None) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, None) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Compares `given` with the `secret` in time that depends only on the
+/// secret's length, so neither a wrong byte nor a wrong length shows in
+/// how long it takes.
+fn same(given: &[u8], secret: &[u8]) -> bool {
+    let mut diff = u8::from(given.len() != secret.len());
+    for (i, &byte) in secret.iter().enumerate() {
+        diff |= byte ^ given.get(i).copied().unwrap_or_default();
+    }
+    diff == 0
+}
+
+fn handle(
+    mut stream: TcpStream,
+    access: &Access,
+    tx_command: Option<Sender<Command>>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let request = match Request::read(&mut reader) {
+        Ok(request) => request,
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            return respond(
+                stream,
+                "431 Request Header Fields Too Large",
+                &format!("{e}\n"),
+            );
+        }
+        Err(e) => return Err(e),
+    };
+    if let Err((status, body)) = request.check(access) {
+        return respond(stream, status, body);
+    }
+    if request.content_length > MAX_BODY {
+        return respond(stream, "413 Payload Too Large", "body too large\n");
+    }
+    let mut body = vec![0; request.content_length];
+    reader.read_exact(&mut body)?;
+
+    let path = request.path.as_str();
+    if tx_command.is_none()
+        && matches!(
+            path,
+            "/start" | "/stop" | "/toggle" | "/pause" | "/resume" | "/config"
+        )
+    {
+        return respond(stream, "403 Forbidden", "this server is view-only\n");
+    }
+    let send = |command: Command| {
+        tx_command
+            .as_ref()
+            .is_some_and(|tx| tx.send(command).is_ok())
+    };
+    let sent = match (request.method.as_str(), path) {
+        ("POST", "/start") => send(Command::Start),
+        ("POST", "/stop") => send(Command::Stop),
+        ("POST", "/toggle") => send(Command::Toggle),
+        ("POST", "/pause") => send(Command::Pause),
+        ("POST", "/resume") => send(Command::Resume),
+        ("PUT", "/config") => {
+            let table = match Table::parse(&String::from_utf8_lossy(&body)) {
+                Ok(table) => table,
+                Err(e) => return respond(stream, "400 Bad Request", &format!("{e}\n")),
+            };
+            let profile = Profile::from_table(&table);
+            send(Config::ClickInterval(profile.click_interval).into())
+                && send(Config::ClickOptions(Box::new(profile.click_options)).into())
+                && send(Config::ClickPosition(profile.click_position).into())
+        }
+        ("GET", "/status") => return respond(stream, "200 OK", &status()),
+        ("GET", "/ws") => {
+            let Some(key) = request.websocket_key else {
+                return respond(stream, "400 Bad Request", "expected a WebSocket upgrade\n");
+            };
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                derive_accept_key(key.as_bytes())
+            )?;
+            if let Err(e) = serve_socket(stream, tx_command) {
+                log::error!("Remote control WebSocket failed: {e}");
+            }
+            return Ok(());
+        }
+        (
+            _,
+            "/start" | "/stop" | "/toggle" | "/pause" | "/resume" | "/config" | "/status" | "/ws",
+        ) => return respond(stream, "405 Method Not Allowed", "method not allowed\n"),
+        _ => return respond(stream, "404 Not Found", "not found\n"),
+    };
+
+    if sent {
+        respond(stream, "200 OK", "ok\n")
+    } else {
+        respond(
+            stream,
+            "503 Service Unavailable",
+            "the clicker has shut down\n",
+        )
+    }
+}
+
+/// Streams events to a WebSocket client and carries out its messages until
+/// it disconnects.
+fn serve_socket(
+    stream: TcpStream,
+    tx_command: Option<Sender<Command>>,
+) -> Result<(), Box<tungstenite::Error>> {
+    stream
+        .set_read_timeout(Some(SOCKET_POLL))
+        .map_err(tungstenite::Error::Io)?;
+    let rx_activity = timeline::subscribe();
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    let mut milestone = stats::current().clicks / CLICK_MILESTONE;
+
+    loop {
+        match socket.read_message() {
+            Ok(Message::Text(text)) => {
+                let reply = control(text.trim(), tx_command.as_ref());
+                socket.write_message(Message::Text(reply))?;
+            }
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(e) => return Err(Box::new(e)),
+        }
+
+        for activity in rx_activity.try_iter() {
+            if activity == Activity::Start {
+                milestone = 0;
+            }
+            if let Some(event) = activity.event_name() {
+                socket.write_message(Message::Text(format!(r#"{{"event":"{event}"}}"#)))?;
+            } else if activity == Activity::Click {
+                let clicks = stats::current().clicks;
+                if clicks / CLICK_MILESTONE > milestone {
+                    milestone = clicks / CLICK_MILESTONE;
+                    socket.write_message(Message::Text(format!(
+                        r#"{{"event":"clicks","clicks":{clicks}}}"#
+                    )))?;
+                }
+            }
+        }
+    }
+}
+
+/// Carries out a WebSocket control message, returning the reply.
+fn control(message: &str, tx_command: Option<&Sender<Command>>) -> String {
+    let command = match message {
+        "start" => Command::Start,
+        "stop" => Command::Stop,
+        "toggle" => Command::Toggle,
+        "pause" => Command::Pause,
+        "resume" => Command::Resume,
+        "status" => {
+            let stats = stats::current();
+            return format!(
+                r#"{{"event":"status","running":{},"clicks":{},"elapsed_ms":{}}}"#,
+                clicker::is_running(),
+                stats.clicks,
+                stats.elapsed().as_millis()
+            );
+        }
+        _ => return format!(r#"{{"error":"unknown command {message:?}"}}"#),
+    };
+    let Some(tx_command) = tx_command else {
+        return r#"{"error":"this server is view-only"}"#.to_owned();
+    };
+    if tx_command.send(command).is_ok() {
+        r#"{"ok":true}"#.to_owned()
+    } else {
+        r#"{"error":"the clicker has shut down"}"#.to_owned()
+    }
+}
+
+fn status() -> String {
+    let stats = stats::current();
+    let mut table = Table::default();
+    table.set("running", clicker::is_running());
+    table.set("paused", clicker::is_paused());
+    table.set("clicks", stats.clicks);
+    table.set("elapsed_ms", stats.elapsed().as_millis());
+    table.set("clicks_per_second", stats.clicks_per_second());
+    table.to_string()
+}
+
+fn respond(mut stream: TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(head: &str) -> Request {
+        Request::read(&mut head.replace('\n', "\r\n").as_bytes()).unwrap()
+    }
+
+    fn access(token: &str, allowed_origins: &[&str]) -> Access {
+        Access {
+            token: token.to_owned(),
+            allowed_origins: allowed_origins.iter().map(|&o| o.to_owned()).collect(),
+        }
+    }
+
+    #[test]
+    fn reads_the_head() {
+        let request = request(
+            "GET /ws?x=1&token=secret HTTP/1.1\nOrigin: http://a.test\nsec-websocket-key: k\n\n",
+        );
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/ws");
+        assert_eq!(request.query_token.as_deref(), Some("secret"));
+        assert_eq!(request.origin.as_deref(), Some("http://a.test"));
+        assert_eq!(request.websocket_key.as_deref(), Some("k"));
+    }
+
+    #[test]
+    fn requires_the_token() {
+        let access = access("secret", &[]);
+        assert!(request("POST /start HTTP/1.1\n\n").check(&access).is_err());
+        assert!(
+            request("POST /start HTTP/1.1\nAuthorization: Bearer wrong\n\n")
+                .check(&access)
+                .is_err()
+        );
+        assert!(
+            request("POST /start HTTP/1.1\nAuthorization: Bearer secret\n\n")
+                .check(&access)
+                .is_ok()
+        );
+        assert!(request("GET /ws?token=secret HTTP/1.1\n\n")
+            .check(&access)
+            .is_ok());
+    }
+
+    #[test]
+    fn refuses_browsers_from_other_origins() {
+        let head = "GET /ws?token=secret HTTP/1.1\nOrigin: https://evil.test\n\n";
+        assert_eq!(
+            request(head).check(&access("secret", &[])).unwrap_err().0,
+            "403 Forbidden"
+        );
+        assert!(request(head)
+            .check(&access("secret", &["https://evil.test/"]))
+            .is_ok());
+        // Without a token only the origin is checked.
+        assert!(request(head).check(&access("", &[])).is_err());
+        assert!(request("GET /status HTTP/1.1\n\n")
+            .check(&access("", &[]))
+            .is_ok());
+    }
+
+    #[test]
+    fn decodes_the_query_token() {
+        let request = request("GET /ws?token=a%2Bb%20c+d%zz%2 HTTP/1.1\n\n");
+        assert_eq!(request.query_token.as_deref(), Some("a+b c d%zz%2"));
+        assert!(request.check(&access("a+b c d%zz%2", &[])).is_ok());
+        assert_eq!(percent_decode("%C3%A9%e2%82%ac"), "é€");
+    }
+
+    #[test]
+    fn compares_tokens_in_full() {
+        assert!(same(b"secret", b"secret"));
+        assert!(!same(b"secreT", b"secret"));
+        assert!(!same(b"secre", b"secret"));
+        assert!(!same(b"secrets", b"secret"));
+        assert!(!same(b"", b"secret"));
+        assert!(same(b"", b""));
+    }
+
+    #[test]
+    fn refuses_oversized_heads() {
+        let read = |head: String| Request::read(&mut head.as_bytes()).map(|_| ());
+
+        let long_path = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+        assert_eq!(
+            read(long_path).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        let long_header = format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        assert!(read(long_header).is_err());
+
+        let headers = |count| {
+            let mut head = "GET / HTTP/1.1\r\n".to_owned();
+            head.push_str(&"X: 1\r\n".repeat(count));
+            head + "\r\n"
+        };
+        assert!(read(headers(MAX_HEADERS)).is_ok());
+        assert!(read(headers(MAX_HEADERS + 1)).is_err());
+    }
+
+    #[test]
+    fn caps_open_connections() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let open: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| Connection::open(&count).unwrap())
+            .collect();
+        assert!(Connection::open(&count).is_none());
+        drop(open);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        assert!(Connection::open(&count).is_some());
+    }
+}
@@ -0,0 +1,32 @@
+/// One key of a [`KeyRotation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyStep {
+    pub key: rdev::Key,
+    /// The wait after this key before the next, in place of the click
+    /// interval.
+    pub delay_ms: usize,
+}
+
+/// Keys the worker presses in turn instead of the key or button of the
+/// click options, one per cycle, starting over after the last, e.g. the
+/// skill keys 1 to 4 of a game.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KeyRotation {
+    pub enabled: bool,
+    pub steps: Vec<KeyStep>,
+}
+
+impl KeyRotation {
+    /// Whether the rotation replaces the key or button.
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.steps.is_empty()
+    }
+
+    /// The key of the `n`th press of a run, counting from 0.
+    pub fn step(&self, n: usize) -> Option<KeyStep> {
+        if !self.is_active() {
+            return None;
+        }
+        self.steps.get(n % self.steps.len()).copied()
+    }
+}
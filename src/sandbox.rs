@@ -0,0 +1,185 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+#[cfg(feature = "gui")]
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "gui")]
+use egui::{Pos2, Rect};
+
+#[cfg(feature = "gui")]
+use crate::{
+    gui::accessible_name,
+    i18n::{tr, tr_args},
+};
+
+/// Rows and columns of test buttons.
+#[cfg(feature = "gui")]
+const ROWS: usize = 3;
+#[cfg(feature = "gui")]
+const COLUMNS: usize = 4;
+
+/// Gaps between clicks the average interval is taken over.
+#[cfg(feature = "gui")]
+const GAPS: usize = 20;
+
+/// Where the app window's content starts on the desktop, in physical
+/// pixels.
+static WINDOW: Mutex<Option<(i32, i32)>> = Mutex::new(None);
+
+/// The left, top, right and bottom edges of the sandbox panel in the
+/// window's logical points and the pixels per point, while it is open.
+static AREA: Mutex<Option<([f32; 4], f32)>> = Mutex::new(None);
+static HOVERED: AtomicBool = AtomicBool::new(false);
+
+/// Notes where the window's content is, e.g. after it moved, so clicks can
+/// be matched with the sandbox.
+pub fn set_window(origin: Option<(i32, i32)>) {
+    *WINDOW.lock().unwrap() = origin;
+}
+
+/// Notes where the sandbox panel is and whether the cursor is over it, or
+/// `None` once it is closed.
+#[cfg(feature = "gui")]
+pub fn set_area(area: Option<Rect>, pixels_per_point: f32, hovered: bool) {
+    *AREA.lock().unwrap() = area.map(|area| {
+        let edges = [area.left(), area.top(), area.right(), area.bottom()];
+        (edges, pixels_per_point)
+    });
+    HOVERED.store(area.is_some() && hovered, Ordering::SeqCst);
+}
+
+/// Whether a click at `point`, or at the cursor without one, lands on the
+/// sandbox, which the clicker may click even though it is in the app's own
+/// window.
+pub fn contains(point: Option<(i32, i32)>) -> bool {
+    let Some((x, y)) = point else {
+        return HOVERED.load(Ordering::SeqCst);
+    };
+    let (Some((edges, scale)), Some((left, top))) =
+        (*AREA.lock().unwrap(), *WINDOW.lock().unwrap())
+    else {
+        return false;
+    };
+    let (x, y) = ((x - left) as f32 / scale, (y - top) as f32 / scale);
+    let [min_x, min_y, max_x, max_y] = edges;
+    (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y)
+}
+
+/// Where `pos`, in the window's logical points, is on the desktop, in the
+/// physical pixels click positions are entered in.
+#[cfg(feature = "gui")]
+fn to_screen(pos: Pos2, pixels_per_point: f32) -> Option<(i32, i32)> {
+    let (left, top) = (*WINDOW.lock().unwrap())?;
+    Some((
+        left + (pos.x * pixels_per_point).round() as i32,
+        top + (pos.y * pixels_per_point).round() as i32,
+    ))
+}
+
+/// A panel of buttons and a text box to point the clicker at, to check its
+/// position, interval and typing before it clicks anything real.
+#[cfg(feature = "gui")]
+#[derive(Debug, Default)]
+pub struct Sandbox {
+    /// Clicks on each button, row by row.
+    hits: [usize; ROWS * COLUMNS],
+    last_hit: Option<Instant>,
+    /// The latest gaps between clicks, newest last.
+    gaps: VecDeque<Duration>,
+    /// Where the last click landed on the desktop, and with which button.
+    last_click: Option<((i32, i32), Cow<'static, str>)>,
+    text: String,
+}
+
+#[cfg(feature = "gui")]
+impl Sandbox {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(tr(
+            "Point the clicker at these buttons or type into the box. Clicks here are counted instead of doing anything.",
+        ));
+
+        egui::Grid::new("sandbox_grid").show(ui, |ui| {
+            for row in 0..ROWS {
+                for column in 0..COLUMNS {
+                    let cell = row * COLUMNS + column;
+                    let button = egui::Button::new(self.hits[cell].to_string())
+                        .min_size(egui::vec2(64.0, 40.0));
+                    let response = ui.add(button);
+                    let response = accessible_name(
+                        ui,
+                        response,
+                        &tr_args(
+                            "Test button {row}, {column}",
+                            &[("row", &(row + 1)), ("column", &(column + 1))],
+                        ),
+                    );
+                    let button = if response.clicked() {
+                        Some(tr("left"))
+                    } else if response.secondary_clicked() {
+                        Some(tr("right"))
+                    } else if response.middle_clicked() {
+                        Some(tr("middle"))
+                    } else {
+                        None
+                    };
+                    if let Some(button) = button {
+                        let pos = response.interact_pointer_pos();
+                        let at = pos.and_then(|pos| to_screen(pos, ui.ctx().pixels_per_point()));
+                        self.hit(cell, at, button);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        let clicks: usize = self.hits.iter().sum();
+        ui.label(tr_args("Clicks: {clicks}", &[("clicks", &clicks)]));
+        if let Some(last) = self.gaps.back() {
+            let mean = self.gaps.iter().sum::<Duration>() / self.gaps.len() as u32;
+            ui.label(tr_args(
+                "Interval: {last} ms, {mean} ms on average",
+                &[("last", &last.as_millis()), ("mean", &mean.as_millis())],
+            ));
+        }
+        if let Some(((x, y), button)) = &self.last_click {
+            ui.label(tr_args(
+                "Last click: {button} at {x}, {y}",
+                &[("button", button), ("x", x), ("y", y)],
+            ));
+        }
+
+        let response = ui.add(
+            egui::TextEdit::multiline(&mut self.text)
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        );
+        accessible_name(ui, response, &tr("Typing test"));
+        ui.horizontal(|ui| {
+            ui.label(tr_args(
+                "{chars} characters",
+                &[("chars", &self.text.chars().count())],
+            ));
+            if ui.button(tr("Reset")).clicked() {
+                *self = Self::default();
+            }
+        });
+    }
+
+    fn hit(&mut self, cell: usize, at: Option<(i32, i32)>, button: Cow<'static, str>) {
+        self.hits[cell] += 1;
+        let now = Instant::now();
+        if let Some(last) = self.last_hit.replace(now) {
+            if self.gaps.len() == GAPS {
+                self.gaps.pop_front();
+            }
+            self.gaps.push_back(now - last);
+        }
+        self.last_click = at.map(|at| (at, button));
+    }
+}
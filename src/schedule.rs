@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum StartMode {
+    #[default]
+    Immediately,
+    /// After `countdown_secs` seconds.
+    Countdown,
+    /// At the next `hour:minute:second`, local time.
+    At,
+}
+
+/// When a run begins after Start is pressed. The worker waits for the
+/// schedule before the first click; Stop cancels the wait.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StartSchedule {
+    pub mode: StartMode,
+    pub countdown_secs: usize,
+    pub hour: usize,
+    pub minute: usize,
+    pub second: usize,
+}
+
+impl StartSchedule {
+    /// How long to wait from now. A time of day that has already passed
+    /// today means tomorrow.
+    pub fn wait(&self) -> Duration {
+        match self.mode {
+            StartMode::Immediately => Duration::ZERO,
+            StartMode::Countdown => Duration::from_secs(self.countdown_secs as u64),
+            StartMode::At => {
+                let target =
+                    Duration::from_secs((self.hour * 3600 + self.minute * 60 + self.second) as u64);
+                let now = local_time_of_day();
+                if target >= now {
+                    target - now
+                } else {
+                    DAY - now + target
+                }
+            }
+        }
+    }
+
+    /// Parses a local time of day written as `HH:MM` or `HH:MM:SS`.
+    pub fn parse_time(s: &str) -> Result<Self, String> {
+        let error = || format!("invalid time {s:?}, expected HH:MM or HH:MM:SS");
+        let parts = s
+            .split(':')
+            .map(|part| part.parse::<usize>().map_err(|_| error()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (hour, minute, second) = match parts[..] {
+            [hour, minute] => (hour, minute, 0),
+            [hour, minute, second] => (hour, minute, second),
+            _ => return Err(error()),
+        };
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(error());
+        }
+        Ok(Self {
+            mode: StartMode::At,
+            hour,
+            minute,
+            second,
+            ..Self::default()
+        })
+    }
+}
+
+/// Time since local midnight.
+#[cfg(unix)]
+pub fn local_time_of_day() -> Duration {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs() as libc::time_t;
+
+    // SAFETY: localtime_r only reads `secs` and writes to `tm`.
+    let tm = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&secs, &mut tm);
+        tm
+    };
+    Duration::from_secs((tm.tm_hour * 3600 + tm.tm_min * 60 + tm.tm_sec) as u64)
+        + Duration::from_nanos(now.subsec_nanos() as u64)
+}
+
+/// Time since local midnight.
+#[cfg(windows)]
+pub fn local_time_of_day() -> Duration {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemTime {
+        year: u16,
+        month: u16,
+        day_of_week: u16,
+        day: u16,
+        hour: u16,
+        minute: u16,
+        second: u16,
+        milliseconds: u16,
+    }
+
+    extern "system" {
+        fn GetLocalTime(time: *mut SystemTime);
+    }
+
+    let mut time = SystemTime::default();
+    // SAFETY: GetLocalTime only writes to `time`.
+    unsafe {
+        GetLocalTime(&mut time);
+    }
+    Duration::from_secs(time.hour as u64 * 3600 + time.minute as u64 * 60 + time.second as u64)
+        + Duration::from_millis(time.milliseconds as u64)
+}
@@ -0,0 +1,308 @@
+/// An RGB color, as read from the screen.
+pub type Rgb = [u8; 3];
+
+/// A screenshot, row by row from the top-left.
+#[derive(Debug, Clone)]
+pub struct Capture {
+    /// Where the top-left pixel is on screen.
+    pub left: i32,
+    pub top: i32,
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+/// Reads the color of the screen pixel at `(x, y)`, or `None` where the
+/// screen cannot be read.
+#[cfg(windows)]
+pub fn pixel(x: usize, y: usize) -> Option<Rgb> {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetDC(hwnd: isize) -> isize;
+        fn ReleaseDC(hwnd: isize, hdc: isize) -> i32;
+    }
+
+    #[link(name = "gdi32")]
+    extern "system" {
+        fn GetPixel(hdc: isize, x: i32, y: i32) -> u32;
+    }
+
+    const CLR_INVALID: u32 = 0xFFFF_FFFF;
+
+    // SAFETY: the screen DC is released before returning and only read.
+    let color = unsafe {
+        let hdc = GetDC(0);
+        if hdc == 0 {
+            return None;
+        }
+        let color = GetPixel(hdc, x as i32, y as i32);
+        ReleaseDC(0, hdc);
+        color
+    };
+    // COLORREF is 0x00BBGGRR.
+    (color != CLR_INVALID).then(|| [color as u8, (color >> 8) as u8, (color >> 16) as u8])
+}
+
+/// Grabs the pixel with ImageMagick's `import`, which reads the X root
+/// window.
+#[cfg(target_os = "linux")]
+pub fn pixel(x: usize, y: usize) -> Option<Rgb> {
+    let output = std::process::Command::new("import")
+        .args([
+            "-silent",
+            "-window",
+            "root",
+            "-crop",
+            &format!("1x1+{x}+{y}"),
+            "-depth",
+            "8",
+            "rgb:-",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    match output.stdout[..] {
+        [r, g, b, ..] => Some([r, g, b]),
+        _ => None,
+    }
+}
+
+/// Captures the whole virtual screen, every monitor included, or `None`
+/// where the screen cannot be read.
+#[cfg(windows)]
+pub fn capture() -> Option<Capture> {
+    #[repr(C)]
+    struct BitmapInfo {
+        size: u32,
+        width: i32,
+        height: i32,
+        planes: u16,
+        bit_count: u16,
+        compression: u32,
+        size_image: u32,
+        x_pels_per_meter: i32,
+        y_pels_per_meter: i32,
+        clr_used: u32,
+        clr_important: u32,
+        colors: [u32; 1],
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetDC(hwnd: isize) -> isize;
+        fn ReleaseDC(hwnd: isize, hdc: isize) -> i32;
+    }
+
+    #[link(name = "gdi32")]
+    extern "system" {
+        fn CreateCompatibleDC(hdc: isize) -> isize;
+        fn CreateCompatibleBitmap(hdc: isize, width: i32, height: i32) -> isize;
+        fn SelectObject(hdc: isize, object: isize) -> isize;
+        fn BitBlt(
+            dest: isize,
+            x: i32,
+            y: i32,
+            width: i32,
+            height: i32,
+            src: isize,
+            src_x: i32,
+            src_y: i32,
+            rop: u32,
+        ) -> i32;
+        fn GetDIBits(
+            hdc: isize,
+            bitmap: isize,
+            start: u32,
+            lines: u32,
+            bits: *mut u8,
+            info: *mut BitmapInfo,
+            usage: u32,
+        ) -> i32;
+        fn DeleteObject(object: isize) -> i32;
+        fn DeleteDC(hdc: isize) -> i32;
+    }
+
+    const SRCCOPY: u32 = 0x00CC_0020;
+    const DIB_RGB_COLORS: u32 = 0;
+
+    // SAFETY: every GDI object created here is released before returning,
+    // and GetDIBits writes at most `width * height` 32-bit pixels to `bgra`.
+    unsafe {
+        let (left, top, width, height) = bounds()?;
+        let screen = GetDC(0);
+        if screen == 0 {
+            return None;
+        }
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap);
+        let copied = BitBlt(memory, 0, 0, width, height, screen, left, top, SRCCOPY) != 0;
+        SelectObject(memory, previous);
+
+        let mut info = BitmapInfo {
+            size: std::mem::size_of::<BitmapInfo>() as u32 - 4,
+            width,
+            // Negative for rows from the top down.
+            height: -height,
+            planes: 1,
+            bit_count: 32,
+            compression: 0,
+            size_image: 0,
+            x_pels_per_meter: 0,
+            y_pels_per_meter: 0,
+            clr_used: 0,
+            clr_important: 0,
+            colors: [0],
+        };
+        let mut bgra = vec![0u8; width as usize * height as usize * 4];
+        let lines = if copied {
+            GetDIBits(
+                memory,
+                bitmap,
+                0,
+                height as u32,
+                bgra.as_mut_ptr(),
+                &mut info,
+                DIB_RGB_COLORS,
+            )
+        } else {
+            0
+        };
+
+        DeleteObject(bitmap);
+        DeleteDC(memory);
+        ReleaseDC(0, screen);
+
+        if lines != height {
+            return None;
+        }
+        Some(Capture {
+            left,
+            top,
+            width: width as usize,
+            height: height as usize,
+            pixels: bgra
+                .chunks_exact(4)
+                .map(|bgra| [bgra[2], bgra[1], bgra[0]])
+                .collect(),
+        })
+    }
+}
+
+/// Captures the X root window as a PPM with ImageMagick's `import`.
+#[cfg(target_os = "linux")]
+pub fn capture() -> Option<Capture> {
+    let output = std::process::Command::new("import")
+        .args(["-silent", "-window", "root", "-depth", "8", "ppm:-"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ppm(&output.stdout)
+}
+
+/// Parses a binary (P6) PPM with 8-bit channels.
+#[cfg(target_os = "linux")]
+fn parse_ppm(data: &[u8]) -> Option<Capture> {
+    // The header is "P6", width, height and max value, separated by
+    // whitespace, followed by a single whitespace byte and the pixels.
+    let mut fields = Vec::with_capacity(4);
+    let mut pos = 0;
+    while fields.len() < 4 {
+        while data.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        let start = pos;
+        while !data.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        fields.push(std::str::from_utf8(&data[start..pos]).ok()?);
+    }
+    let pixels = data.get(pos + 1..)?;
+
+    if fields[0] != "P6" || fields[3] != "255" {
+        return None;
+    }
+    let width: usize = fields[1].parse().ok()?;
+    let height: usize = fields[2].parse().ok()?;
+    let pixels = pixels.get(..width * height * 3)?;
+
+    Some(Capture {
+        left: 0,
+        top: 0,
+        width,
+        height,
+        pixels: pixels
+            .chunks_exact(3)
+            .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+            .collect(),
+    })
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn capture() -> Option<Capture> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn pixel(_x: usize, _y: usize) -> Option<Rgb> {
+    None
+}
+
+/// The virtual screen, every monitor included, as `(left, top, width,
+/// height)`.
+#[cfg(windows)]
+pub fn bounds() -> Option<(i32, i32, i32, i32)> {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetSystemMetrics(index: i32) -> i32;
+    }
+
+    const SM_XVIRTUALSCREEN: i32 = 76;
+    const SM_YVIRTUALSCREEN: i32 = 77;
+    const SM_CXVIRTUALSCREEN: i32 = 78;
+    const SM_CYVIRTUALSCREEN: i32 = 79;
+
+    // SAFETY: GetSystemMetrics only takes and returns plain values.
+    let [left, top, width, height] = [
+        SM_XVIRTUALSCREEN,
+        SM_YVIRTUALSCREEN,
+        SM_CXVIRTUALSCREEN,
+        SM_CYVIRTUALSCREEN,
+    ]
+    .map(|index| unsafe { GetSystemMetrics(index) });
+    (width > 0 && height > 0).then_some((left, top, width, height))
+}
+
+/// Asks `xwininfo` for the size of the X root window.
+#[cfg(target_os = "linux")]
+pub fn bounds() -> Option<(i32, i32, i32, i32)> {
+    let output = std::process::Command::new("xwininfo")
+        .arg("-root")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let info = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        info.lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some((0, 0, field("Width:")?, field("Height:")?))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn bounds() -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+/// Whether [`pixel`] and [`capture`] can read the screen here.
+pub const fn is_supported() -> bool {
+    cfg!(any(target_os = "linux", windows))
+}
@@ -0,0 +1,268 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope};
+
+use crate::{
+    abort, clicker, config,
+    engine::{ClickOptions, MouseButton},
+    keys,
+    priority::ThreadPriority,
+    turbo,
+};
+
+/// A wait is slept in steps of this, so Stop takes effect mid-wait.
+const WAIT_STEP: Duration = Duration::from_millis(10);
+
+/// Declares a script parameter, see [`params`].
+const PARAM_PREFIX: &str = "// @param ";
+
+/// Shown in the editor the first time it is opened.
+pub const EXAMPLE: &str = "\
+// Click at (x, y), wait 50 ms and press A, count times.
+// @param count = 5
+// @param x = 100
+// @param y = 200
+for i in 0..count {
+    click(x, y);
+    wait(50);
+    key(\"a\");
+}
+";
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The `running` flags of the scripts that are running, see [`stop_all`].
+static RUNNING: Mutex<Vec<Arc<AtomicBool>>> = Mutex::new(Vec::new());
+
+/// Whether any script is running.
+pub fn any_running() -> bool {
+    !RUNNING.lock().unwrap().is_empty()
+}
+
+/// Stops every script, e.g. on the fail-safe or a session change.
+pub fn stop_all() {
+    for running in RUNNING.lock().unwrap().iter() {
+        running.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Runs Rhai scripts that drive the mouse and keyboard, on a background
+/// thread.
+///
+/// Scripts get these functions on top of Rhai's own `loop`, `while` and
+/// `for`:
+///
+/// - `click()`, `click(x, y)` and `click(x, y, button)`, with `button` one
+///   of `"left"`, `"middle"` or `"right"`
+/// - `move_to(x, y)`
+/// - `key(name)`, `key_down(name)` and `key_up(name)`, e.g. `key("Return")`
+/// - `wait(ms)`
+///
+/// Parameters declared with [`params`] are set as variables before the
+/// script starts.
+#[derive(Default)]
+pub struct ScriptRunner {
+    running: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl ScriptRunner {
+    /// Runs `source` with `values` for its parameters; parameters without
+    /// a value get their default.
+    pub fn run(&self, source: String, values: Vec<(String, String)>) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        *self.error.lock().unwrap() = None;
+
+        let running = self.running.clone();
+        let error = self.error.clone();
+        RUNNING.lock().unwrap().push(running.clone());
+        thread::spawn(move || {
+            // Scripts click like the clicker thread, see [`clicker::run`].
+            let mut priority = ThreadPriority::default();
+            priority.update();
+            let mut timer = turbo::HighResTimer::default();
+            timer.update();
+            let result = scope(&source, &values)
+                .and_then(|mut scope| engine(running.clone()).run_with_scope(&mut scope, &source));
+            if let Err(e) = result {
+                // Stop ends the script with a termination error, which is
+                // not worth showing.
+                if !matches!(*e, EvalAltResult::ErrorTerminated(..)) {
+                    *error.lock().unwrap() = Some(e.to_string());
+                }
+            }
+            running.store(false, Ordering::SeqCst);
+            RUNNING
+                .lock()
+                .unwrap()
+                .retain(|other| !Arc::ptr_eq(other, &running));
+        });
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Why the last script failed, if it did.
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+}
+
+/// A Rhai engine with the script functions, which stops the script once
+/// `running` is cleared or on an emergency abort.
+fn engine(running: Arc<AtomicBool>) -> Engine {
+    let mut engine = Engine::new();
+    let since = abort::generation();
+    let running = move || running.load(Ordering::SeqCst) && !abort::is_aborted(since);
+
+    {
+        let running = running.clone();
+        engine.on_progress(move |_| (!running()).then_some(Dynamic::UNIT));
+    }
+
+    engine.register_fn("click", || clicker::trigger(&ClickOptions::default()));
+    engine.register_fn("click", |x: i64, y: i64| -> ScriptResult<()> {
+        clicker::move_to(coordinate(x)?, coordinate(y)?);
+        clicker::trigger(&ClickOptions::default());
+        Ok(())
+    });
+    engine.register_fn(
+        "click",
+        |x: i64, y: i64, button: &str| -> ScriptResult<()> {
+            let mouse_button: MouseButton = button.parse()?;
+            clicker::move_to(coordinate(x)?, coordinate(y)?);
+            clicker::trigger(&ClickOptions {
+                mouse_button,
+                ..ClickOptions::default()
+            });
+            Ok(())
+        },
+    );
+    engine.register_fn("move_to", |x: i64, y: i64| -> ScriptResult<()> {
+        clicker::move_to(coordinate(x)?, coordinate(y)?);
+        Ok(())
+    });
+    engine.register_fn("key", |name: &str| -> ScriptResult<()> {
+        let key = key(name)?;
+        clicker::send(&EventType::KeyPress(key));
+        clicker::send(&EventType::KeyRelease(key));
+        Ok(())
+    });
+    engine.register_fn("key_down", |name: &str| -> ScriptResult<()> {
+        clicker::send(&EventType::KeyPress(key(name)?));
+        Ok(())
+    });
+    engine.register_fn("key_up", |name: &str| -> ScriptResult<()> {
+        clicker::send(&EventType::KeyRelease(key(name)?));
+        Ok(())
+    });
+    engine.register_fn("wait", move |ms: i64| {
+        let until = Instant::now() + Duration::from_millis(ms.max(0) as u64);
+        while running() {
+            let left = until.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            thread::sleep(left.min(WAIT_STEP));
+        }
+    });
+
+    engine
+}
+
+/// A parameter declared by a `// @param name = default` line. The default
+/// may be left out, and the parameter must then be given a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub default: Option<String>,
+}
+
+/// The parameters `source` declares, in order.
+pub fn params(source: &str) -> Vec<Param> {
+    source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(PARAM_PREFIX))
+        .map(|declaration| match declaration.split_once('=') {
+            Some((name, default)) => Param {
+                name: name.trim().to_owned(),
+                default: Some(default.trim().to_owned()),
+            },
+            None => Param {
+                name: declaration.trim().to_owned(),
+                default: None,
+            },
+        })
+        .filter(|param| !param.name.is_empty())
+        .collect()
+}
+
+/// The variables of the parameters of `source`, from `values` or their
+/// defaults. Numbers and `true`/`false` become numbers and booleans, and
+/// everything else a string.
+fn scope(source: &str, values: &[(String, String)]) -> ScriptResult<Scope<'static>> {
+    let mut scope = Scope::new();
+    for param in params(source) {
+        let value = values
+            .iter()
+            .find(|(name, _)| *name == param.name)
+            .map(|(_, value)| value.clone())
+            .or(param.default)
+            .ok_or_else(|| format!("no value for the parameter {:?}", param.name))?;
+        let value = if let Ok(int) = value.parse::<i64>() {
+            Dynamic::from(int)
+        } else if let Ok(float) = value.parse::<f64>() {
+            Dynamic::from(float)
+        } else if let Ok(boolean) = value.parse::<bool>() {
+            Dynamic::from(boolean)
+        } else {
+            Dynamic::from(value)
+        };
+        scope.push_dynamic(param.name, value);
+    }
+    Ok(scope)
+}
+
+fn coordinate(value: i64) -> ScriptResult<i32> {
+    i32::try_from(value).map_err(|_| format!("{value} is not a screen coordinate").into())
+}
+
+fn key(name: &str) -> ScriptResult<rdev::Key> {
+    keys::parse(name).ok_or_else(|| format!("unknown key {name:?}").into())
+}
+
+/// Loads the script saved by [`save`], if there is one.
+pub fn load() -> Option<String> {
+    fs::read_to_string(script_path()?).ok()
+}
+
+/// Keeps the script in the config dir, so it is there next time.
+pub fn save(source: &str) -> io::Result<()> {
+    let path = script_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, source)
+}
+
+fn script_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("script.rhai"))
+}
@@ -0,0 +1,319 @@
+//! Input through `SendInput` on Windows for everything, not only mouse
+//! buttons: moves, wheel and keys too, any number of them in one call.
+//!
+//! rdev makes one `SendInput` call per event. Handing a click's press and
+//! release, or a whole double click, to a single call costs one syscall
+//! and keeps other input from landing in between, so the clicker does not
+//! need to leave a gap between events.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rdev::{EventType, SimulateError};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub const fn is_supported() -> bool {
+    cfg!(windows)
+}
+
+/// Sends all input through [`send`] from now on, see
+/// [`crate::source::BackendChoice::SendInput`].
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled && is_supported(), Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+#[cfg(windows)]
+pub use windows::{convert, send, Input};
+
+/// Converts `event_types` to `SendInput`'s INPUTs tagged with `tag`, or
+/// `None` if one of them has no equivalent.
+#[cfg(not(windows))]
+pub fn convert(_event_types: &[EventType], _tag: usize) -> Option<Vec<Input>> {
+    None
+}
+
+/// Sends `inputs` in a single `SendInput` call.
+#[cfg(not(windows))]
+pub fn send(_inputs: &[Input]) -> Result<(), SimulateError> {
+    Err(SimulateError)
+}
+
+#[cfg(not(windows))]
+pub enum Input {}
+
+#[cfg(windows)]
+mod windows {
+    use rdev::{Button, EventType, Key, SimulateError};
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct MouseInput {
+        dx: i32,
+        dy: i32,
+        mouse_data: u32,
+        flags: u32,
+        time: u32,
+        extra_info: usize,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct KeyboardInput {
+        vk: u16,
+        scan: u16,
+        flags: u32,
+        time: u32,
+        extra_info: usize,
+    }
+
+    #[repr(C)]
+    union InputData {
+        mouse: MouseInput,
+        keyboard: KeyboardInput,
+    }
+
+    /// One INPUT.
+    #[repr(C)]
+    pub struct Input {
+        kind: u32,
+        data: InputData,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn SendInput(count: u32, inputs: *const Input, size: i32) -> u32;
+        fn GetSystemMetrics(index: i32) -> i32;
+    }
+
+    const INPUT_MOUSE: u32 = 0;
+    const INPUT_KEYBOARD: u32 = 1;
+
+    const MOUSEEVENTF_MOVE: u32 = 0x0001;
+    const MOUSEEVENTF_LEFTDOWN: u32 = 0x0002;
+    const MOUSEEVENTF_LEFTUP: u32 = 0x0004;
+    const MOUSEEVENTF_RIGHTDOWN: u32 = 0x0008;
+    const MOUSEEVENTF_RIGHTUP: u32 = 0x0010;
+    const MOUSEEVENTF_MIDDLEDOWN: u32 = 0x0020;
+    const MOUSEEVENTF_MIDDLEUP: u32 = 0x0040;
+    const MOUSEEVENTF_WHEEL: u32 = 0x0800;
+    const MOUSEEVENTF_HWHEEL: u32 = 0x1000;
+    const MOUSEEVENTF_VIRTUALDESK: u32 = 0x4000;
+    const MOUSEEVENTF_ABSOLUTE: u32 = 0x8000;
+    const KEYEVENTF_EXTENDEDKEY: u32 = 0x0001;
+    const KEYEVENTF_KEYUP: u32 = 0x0002;
+    const WHEEL_DELTA: i64 = 120;
+
+    const SM_XVIRTUALSCREEN: i32 = 76;
+    const SM_YVIRTUALSCREEN: i32 = 77;
+    const SM_CXVIRTUALSCREEN: i32 = 78;
+    const SM_CYVIRTUALSCREEN: i32 = 79;
+
+    pub fn convert(event_types: &[EventType], tag: usize) -> Option<Vec<Input>> {
+        event_types
+            .iter()
+            .map(|event_type| convert_one(event_type, tag))
+            .collect()
+    }
+
+    fn convert_one(event_type: &EventType, tag: usize) -> Option<Input> {
+        let mouse = |dx, dy, mouse_data, flags| Input {
+            kind: INPUT_MOUSE,
+            data: InputData {
+                mouse: MouseInput {
+                    dx,
+                    dy,
+                    mouse_data,
+                    flags,
+                    time: 0,
+                    extra_info: tag,
+                },
+            },
+        };
+        Some(match *event_type {
+            EventType::MouseMove { x, y } => {
+                let (dx, dy) = normalize(x, y);
+                mouse(
+                    dx,
+                    dy,
+                    0,
+                    MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
+                )
+            }
+            EventType::ButtonPress(button) | EventType::ButtonRelease(button) => {
+                let press = matches!(event_type, EventType::ButtonPress(_));
+                let flags = match (button, press) {
+                    (Button::Left, true) => MOUSEEVENTF_LEFTDOWN,
+                    (Button::Left, false) => MOUSEEVENTF_LEFTUP,
+                    (Button::Right, true) => MOUSEEVENTF_RIGHTDOWN,
+                    (Button::Right, false) => MOUSEEVENTF_RIGHTUP,
+                    (Button::Middle, true) => MOUSEEVENTF_MIDDLEDOWN,
+                    (Button::Middle, false) => MOUSEEVENTF_MIDDLEUP,
+                    (Button::Unknown(_), _) => return None,
+                };
+                mouse(0, 0, 0, flags)
+            }
+            // One of the two axes per INPUT; rdev sends wheels one axis at
+            // a time.
+            EventType::Wheel {
+                delta_x: 0,
+                delta_y,
+            } => mouse(0, 0, (delta_y * WHEEL_DELTA) as u32, MOUSEEVENTF_WHEEL),
+            EventType::Wheel {
+                delta_x,
+                delta_y: 0,
+            } => mouse(0, 0, (delta_x * WHEEL_DELTA) as u32, MOUSEEVENTF_HWHEEL),
+            EventType::Wheel { .. } => return None,
+            EventType::KeyPress(key) | EventType::KeyRelease(key) => {
+                let (vk, extended) = virtual_key(key)?;
+                let mut flags = if extended { KEYEVENTF_EXTENDEDKEY } else { 0 };
+                if matches!(event_type, EventType::KeyRelease(_)) {
+                    flags |= KEYEVENTF_KEYUP;
+                }
+                Input {
+                    kind: INPUT_KEYBOARD,
+                    data: InputData {
+                        keyboard: KeyboardInput {
+                            vk,
+                            scan: 0,
+                            flags,
+                            time: 0,
+                            extra_info: tag,
+                        },
+                    },
+                }
+            }
+        })
+    }
+
+    pub fn send(inputs: &[Input]) -> Result<(), SimulateError> {
+        // SAFETY: `inputs` holds `inputs.len()` valid INPUTs of the size
+        // passed along with them.
+        let sent = unsafe {
+            SendInput(
+                inputs.len() as u32,
+                inputs.as_ptr(),
+                std::mem::size_of::<Input>() as i32,
+            )
+        };
+        if sent as usize == inputs.len() {
+            Ok(())
+        } else {
+            Err(SimulateError)
+        }
+    }
+
+    /// `x`, `y` in the 0 to 65535 range absolute moves take, spanning all
+    /// monitors.
+    fn normalize(x: f64, y: f64) -> (i32, i32) {
+        // SAFETY: GetSystemMetrics has no preconditions.
+        let (left, top, width, height) = unsafe {
+            (
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN).max(2),
+                GetSystemMetrics(SM_CYVIRTUALSCREEN).max(2),
+            )
+        };
+        let scale = |value: f64, origin: i32, size: i32| {
+            ((value - origin as f64) * 65535.0 / (size - 1) as f64).round() as i32
+        };
+        (scale(x, left, width), scale(y, top, height))
+    }
+
+    /// The virtual-key code of `key` and whether it is an extended key.
+    /// rdev passes other virtual keys on as `Key::Unknown`.
+    fn virtual_key(key: Key) -> Option<(u16, bool)> {
+        let letter = |c: u8| (c as u16, false);
+        Some(match key {
+            Key::KeyA => letter(b'A'),
+            Key::KeyB => letter(b'B'),
+            Key::KeyC => letter(b'C'),
+            Key::KeyD => letter(b'D'),
+            Key::KeyE => letter(b'E'),
+            Key::KeyF => letter(b'F'),
+            Key::KeyG => letter(b'G'),
+            Key::KeyH => letter(b'H'),
+            Key::KeyI => letter(b'I'),
+            Key::KeyJ => letter(b'J'),
+            Key::KeyK => letter(b'K'),
+            Key::KeyL => letter(b'L'),
+            Key::KeyM => letter(b'M'),
+            Key::KeyN => letter(b'N'),
+            Key::KeyO => letter(b'O'),
+            Key::KeyP => letter(b'P'),
+            Key::KeyQ => letter(b'Q'),
+            Key::KeyR => letter(b'R'),
+            Key::KeyS => letter(b'S'),
+            Key::KeyT => letter(b'T'),
+            Key::KeyU => letter(b'U'),
+            Key::KeyV => letter(b'V'),
+            Key::KeyW => letter(b'W'),
+            Key::KeyX => letter(b'X'),
+            Key::KeyY => letter(b'Y'),
+            Key::KeyZ => letter(b'Z'),
+            Key::Num0 => letter(b'0'),
+            Key::Num1 => letter(b'1'),
+            Key::Num2 => letter(b'2'),
+            Key::Num3 => letter(b'3'),
+            Key::Num4 => letter(b'4'),
+            Key::Num5 => letter(b'5'),
+            Key::Num6 => letter(b'6'),
+            Key::Num7 => letter(b'7'),
+            Key::Num8 => letter(b'8'),
+            Key::Num9 => letter(b'9'),
+            Key::F1 => (0x70, false),
+            Key::F2 => (0x71, false),
+            Key::F3 => (0x72, false),
+            Key::F4 => (0x73, false),
+            Key::F5 => (0x74, false),
+            Key::F6 => (0x75, false),
+            Key::F7 => (0x76, false),
+            Key::F8 => (0x77, false),
+            Key::F9 => (0x78, false),
+            Key::F10 => (0x79, false),
+            Key::F11 => (0x7A, false),
+            Key::F12 => (0x7B, false),
+            Key::Backspace => (0x08, false),
+            Key::Tab => (0x09, false),
+            Key::Return => (0x0D, false),
+            Key::CapsLock => (0x14, false),
+            Key::Escape => (0x1B, false),
+            Key::Space => (0x20, false),
+            Key::PageUp => (0x21, true),
+            Key::PageDown => (0x22, true),
+            Key::End => (0x23, true),
+            Key::Home => (0x24, true),
+            Key::LeftArrow => (0x25, true),
+            Key::UpArrow => (0x26, true),
+            Key::RightArrow => (0x27, true),
+            Key::DownArrow => (0x28, true),
+            Key::Insert => (0x2D, true),
+            Key::Delete => (0x2E, true),
+            Key::MetaLeft => (0x5B, true),
+            Key::MetaRight => (0x5C, true),
+            Key::ShiftLeft => (0xA0, false),
+            Key::ShiftRight => (0xA1, false),
+            Key::ControlLeft => (0xA2, false),
+            Key::ControlRight => (0xA3, true),
+            Key::Alt => (0xA4, false),
+            Key::AltGr => (0xA5, true),
+            Key::SemiColon => (0xBA, false),
+            Key::Equal => (0xBB, false),
+            Key::Comma => (0xBC, false),
+            Key::Minus => (0xBD, false),
+            Key::Dot => (0xBE, false),
+            Key::Slash => (0xBF, false),
+            Key::BackQuote => (0xC0, false),
+            Key::LeftBracket => (0xDB, false),
+            Key::BackSlash => (0xDC, false),
+            Key::RightBracket => (0xDD, false),
+            Key::Quote => (0xDE, false),
+            Key::Unknown(code) if code <= 0xFF => (code as u16, false),
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,247 @@
+use std::{borrow::Cow, fmt, str::FromStr, sync::Mutex};
+
+use crate::{engine::ClickOptions, i18n::tr, pixel::PixelTrigger, screen::Rgb, target};
+
+/// What a step of a click sequence does.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StepAction {
+    /// Move to the step's point and click there.
+    #[default]
+    Click,
+    /// Only move to the step's point.
+    Move,
+    /// Press the step's key where the cursor is.
+    Key,
+    /// Do nothing but wait the step's delay.
+    Wait,
+    /// Wait until the pixel at the step's point has the step's color.
+    WaitPixel,
+    /// Wait until a window whose title contains the step's title exists.
+    WaitWindow,
+    /// Put the step's text on the clipboard.
+    SetClipboard,
+    /// Press Ctrl+V, or Cmd+V on macOS.
+    Paste,
+}
+
+impl StepAction {
+    pub const ALL: [Self; 8] = [
+        Self::Click,
+        Self::Move,
+        Self::Key,
+        Self::Wait,
+        Self::WaitPixel,
+        Self::WaitWindow,
+        Self::SetClipboard,
+        Self::Paste,
+    ];
+
+    pub fn label(self) -> Cow<'static, str> {
+        match self {
+            Self::Click => tr("Click"),
+            Self::Move => tr("Move"),
+            Self::Key => tr("Key"),
+            Self::Wait => tr("Wait"),
+            Self::WaitPixel => tr("Wait for pixel"),
+            Self::WaitWindow => tr("Wait for window"),
+            Self::SetClipboard => tr("Copy text"),
+            Self::Paste => tr("Paste"),
+        }
+    }
+
+    /// Whether the step goes to its point first.
+    pub fn moves(self) -> bool {
+        matches!(self, Self::Click | Self::Move)
+    }
+
+    /// Whether the step moves the mouse or sends a click or keys, which
+    /// must not land on the app's own window, see
+    /// [`crate::clicker::is_own_window`].
+    pub fn sends_input(self) -> bool {
+        matches!(self, Self::Click | Self::Move | Self::Key | Self::Paste)
+    }
+
+    /// Whether the step's point is used, to go to or to look at.
+    pub fn uses_point(self) -> bool {
+        self.moves() || self == Self::WaitPixel
+    }
+
+    /// Whether the step waits for a condition, with a timeout.
+    pub fn is_conditional(self) -> bool {
+        matches!(self, Self::WaitPixel | Self::WaitWindow)
+    }
+}
+
+impl fmt::Display for StepAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Click => write!(f, "click"),
+            Self::Move => write!(f, "move"),
+            Self::Key => write!(f, "key"),
+            Self::Wait => write!(f, "wait"),
+            Self::WaitPixel => write!(f, "wait_pixel"),
+            Self::WaitWindow => write!(f, "wait_window"),
+            Self::SetClipboard => write!(f, "set_clipboard"),
+            Self::Paste => write!(f, "paste"),
+        }
+    }
+}
+
+impl FromStr for StepAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.to_string() == s)
+            .ok_or_else(|| format!("unknown step action {s:?}"))
+    }
+}
+
+/// What a conditional step does when its condition is not met in time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnTimeout {
+    /// Go on with the next step.
+    #[default]
+    Skip,
+    /// Stop the run.
+    Abort,
+}
+
+impl OnTimeout {
+    pub fn label(self) -> Cow<'static, str> {
+        match self {
+            Self::Skip => tr("then skip"),
+            Self::Abort => tr("then stop"),
+        }
+    }
+}
+
+impl fmt::Display for OnTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::Abort => write!(f, "abort"),
+        }
+    }
+}
+
+impl FromStr for OnTimeout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "abort" => Ok(Self::Abort),
+            _ => Err(format!("unknown timeout action {s:?}")),
+        }
+    }
+}
+
+/// One step of a click sequence. The worker does `action` at `(x, y)` with
+/// `click_options` and then waits `delay_ms`, `repeat` times over, before
+/// the next step. Key steps press `click_options.key`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClickPoint {
+    pub action: StepAction,
+    pub x: usize,
+    pub y: usize,
+    pub click_options: ClickOptions,
+    pub repeat: usize,
+    pub delay_ms: usize,
+    /// The color a pixel wait waits for at `(x, y)`.
+    pub color: Rgb,
+    /// The text a clipboard step copies, or the title, or part of it, a
+    /// window wait waits for.
+    pub text: String,
+    /// How long a conditional step waits; 0 waits until the run is stopped.
+    pub timeout_ms: usize,
+    pub on_timeout: OnTimeout,
+}
+
+impl ClickPoint {
+    /// Whether the condition of a conditional step holds right now.
+    /// Always true for other steps.
+    pub fn is_met(&self) -> bool {
+        match self.action {
+            StepAction::WaitPixel => PixelTrigger {
+                enabled: true,
+                x: self.x,
+                y: self.y,
+                color: self.color,
+                ..PixelTrigger::default()
+            }
+            .is_met(),
+            StepAction::WaitWindow => {
+                let title = self.text.to_lowercase();
+                target::list()
+                    .iter()
+                    .any(|window| window.title.to_lowercase().contains(&title))
+            }
+            _ => true,
+        }
+    }
+}
+
+impl Default for ClickPoint {
+    fn default() -> Self {
+        Self {
+            action: StepAction::default(),
+            x: 0,
+            y: 0,
+            click_options: ClickOptions::default(),
+            repeat: 1,
+            delay_ms: 0,
+            color: [255, 255, 255],
+            text: String::new(),
+            timeout_ms: 10_000,
+            on_timeout: OnTimeout::default(),
+        }
+    }
+}
+
+/// How often the whole sequence runs in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceLoop {
+    /// Run until stopped instead of `count` times.
+    pub forever: bool,
+    pub count: usize,
+    /// Extra wait between two runs of the sequence, on top of the click
+    /// interval.
+    pub delay_ms: usize,
+}
+
+impl Default for SequenceLoop {
+    fn default() -> Self {
+        Self {
+            forever: true,
+            count: 1,
+            delay_ms: 0,
+        }
+    }
+}
+
+impl SequenceLoop {
+    /// Whether the run is over after `loops` runs of the sequence.
+    pub fn is_done(&self, loops: usize) -> bool {
+        !self.forever && loops >= self.count
+    }
+}
+
+/// Where a running sequence is, both counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub loop_number: usize,
+    pub step: usize,
+}
+
+/// Where the worker is in the sequence, `None` while it runs none.
+static PROGRESS: Mutex<Option<Progress>> = Mutex::new(None);
+
+pub fn set_progress(progress: Option<Progress>) {
+    *PROGRESS.lock().unwrap() = progress;
+}
+
+pub fn progress() -> Option<Progress> {
+    *PROGRESS.lock().unwrap()
+}
@@ -0,0 +1,213 @@
+use std::{env, io};
+
+/// Name of the systemd user unit and the Windows scheduled task.
+const NAME: &str = "auto-clicker";
+
+/// Registers `auto-clicker <args>` to run headless at every login: as a
+/// systemd user service on Linux, or a scheduled task on Windows.
+pub fn install(args: &[String]) -> io::Result<()> {
+    let exe = env::current_exe()?;
+    platform::install(&exe.to_string_lossy(), args)
+}
+
+/// Removes what [`install`] registered.
+pub fn uninstall() -> io::Result<()> {
+    platform::uninstall()
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn run(command: &mut std::process::Command) -> io::Result<()> {
+    let status = command.status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "{command:?} failed with {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// `exe` and `args` as a Windows command line, each word quoted so that
+/// `CommandLineToArgvW` splits it back the same, for the task's `/TR`.
+#[cfg(any(windows, test))]
+fn command_line(exe: &str, args: &[String]) -> String {
+    std::iter::once(exe)
+        .chain(args.iter().map(String::as_str))
+        .map(|word| {
+            let mut quoted = String::from('"');
+            // Backslashes only escape when a quote follows them.
+            let mut backslashes = 0;
+            for c in word.chars() {
+                match c {
+                    '\\' => backslashes += 1,
+                    '"' => {
+                        quoted.push_str(&"\\".repeat(backslashes + 1));
+                        backslashes = 0;
+                    }
+                    _ => backslashes = 0,
+                }
+                quoted.push(c);
+            }
+            // Nor may they escape the closing quote.
+            quoted.push_str(&"\\".repeat(backslashes));
+            quoted.push('"');
+            quoted
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{fs, io, path::PathBuf, process::Command};
+
+    use super::{run, NAME};
+    use crate::config;
+
+    fn unit_path() -> io::Result<PathBuf> {
+        // systemd reads user units from the XDG config directory, which is
+        // the parent of the app's own config directory.
+        config::config_dir()
+            .and_then(|dir| dir.parent().map(|parent| parent.to_owned()))
+            .map(|dir| dir.join("systemd/user").join(format!("{NAME}.service")))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))
+    }
+
+    /// Quotes a word for `ExecStart=`, where `%` starts a specifier.
+    fn quote(word: &str) -> String {
+        let escaped = word
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('%', "%%");
+        format!("\"{escaped}\"")
+    }
+
+    /// The unit running `exe` with `args` and the display variables
+    /// `environment`. It starts and stops with the graphical session, since
+    /// it has nothing to click without one.
+    pub(super) fn unit(exe: &str, args: &[String], environment: &[(&str, String)]) -> String {
+        let exec_start = std::iter::once(exe)
+            .chain(args.iter().map(String::as_str))
+            .map(quote)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let environment: String = environment
+            .iter()
+            .map(|(var, value)| format!("Environment={}\n", quote(&format!("{var}={value}"))))
+            .collect();
+
+        format!(
+            "[Unit]\n\
+             Description=Auto Clicker\n\
+             PartOf=graphical-session.target\n\
+             After=graphical-session.target\n\
+             \n\
+             [Service]\n\
+             ExecStart={exec_start}\n\
+             {environment}\
+             Restart=on-failure\n\
+             \n\
+             [Install]\n\
+             WantedBy=graphical-session.target\n"
+        )
+    }
+
+    pub fn install(exe: &str, args: &[String]) -> io::Result<()> {
+        // Input is synthesized on the user's display, which a user service
+        // does not inherit.
+        let environment: Vec<_> = ["DISPLAY", "WAYLAND_DISPLAY", "XAUTHORITY"]
+            .into_iter()
+            .filter_map(|var| Some((var, std::env::var(var).ok()?)))
+            .collect();
+        let unit = unit(exe, args, &environment);
+
+        let path = unit_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, unit)?;
+        println!("Wrote {}", path.display());
+
+        run(Command::new("systemctl").args(["--user", "daemon-reload"]))?;
+        run(Command::new("systemctl").args(["--user", "enable", "--now", NAME]))
+    }
+
+    pub fn uninstall() -> io::Result<()> {
+        run(Command::new("systemctl").args(["--user", "disable", "--now", NAME]))?;
+        let path = unit_path()?;
+        fs::remove_file(&path)?;
+        println!("Removed {}", path.display());
+        run(Command::new("systemctl").args(["--user", "daemon-reload"]))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{io, process::Command};
+
+    use super::{command_line, run, NAME};
+
+    pub fn install(exe: &str, args: &[String]) -> io::Result<()> {
+        let task = command_line(exe, args);
+        run(Command::new("schtasks")
+            .args(["/Create", "/F", "/SC", "ONLOGON", "/TN", NAME, "/TR", &task]))
+    }
+
+    pub fn uninstall() -> io::Result<()> {
+        run(Command::new("schtasks").args(["/Delete", "/F", "/TN", NAME]))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use std::io;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "installing as a service is only supported on Linux (systemd) and Windows",
+        )
+    }
+
+    pub fn install(_exe: &str, _args: &[String]) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn uninstall() -> io::Result<()> {
+        Err(unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_task_words_for_windows() {
+        let args = [
+            "--interval".to_owned(),
+            "say \"hi\"".to_owned(),
+            r"C:\dir\".to_owned(),
+            r#"a\"b"#.to_owned(),
+            String::new(),
+        ];
+        assert_eq!(
+            command_line(r"C:\Program Files\auto-clicker.exe", &args),
+            r#""C:\Program Files\auto-clicker.exe" "--interval" "say \"hi\"" "C:\dir\\" "a\\\"b" """#
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn unit_follows_the_graphical_session() {
+        let unit = platform::unit(
+            "/usr/bin/auto-clicker",
+            &["--start-in".to_owned(), "50%".to_owned()],
+            &[("DISPLAY", ":0".to_owned())],
+        );
+        assert!(unit.contains("\nPartOf=graphical-session.target\n"));
+        assert!(unit.contains("\nAfter=graphical-session.target\n"));
+        assert!(unit.contains("\nWantedBy=graphical-session.target\n"));
+        assert!(unit.contains("\nExecStart=\"/usr/bin/auto-clicker\" \"--start-in\" \"50%%\"\n"));
+        assert!(unit.contains("\nEnvironment=\"DISPLAY=:0\"\n"));
+    }
+}
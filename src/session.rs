@@ -0,0 +1,87 @@
+use std::{sync::mpsc::Sender, thread, time::Duration};
+
+use crate::{
+    clicker::{self, Command},
+    overlay, script,
+    timeline::{self, Activity},
+};
+
+/// Starts a thread that polls the login session once a second and stops the
+/// clicker and scripts when it changes, e.g. on a fast user switch, a lock,
+/// or a remote desktop connecting or disconnecting. Synthesized input during
+/// a switch can land in the wrong session.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    thread::spawn(move || {
+        let mut last = state();
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let current = state();
+            if current != last {
+                last = current;
+                if clicker::is_running() || script::any_running() {
+                    log::info!("Session changed, stopping the clicker");
+                    tx_command.send(Command::Stop).ok();
+                    script::stop_all();
+                    overlay::flash_stop();
+                    timeline::record(Activity::Safety);
+                }
+            }
+        }
+    });
+}
+
+/// Whether the session is the active one on the console, as reported by
+/// systemd-logind. `None` without logind.
+#[cfg(target_os = "linux")]
+fn state() -> Option<String> {
+    let id = std::env::var("XDG_SESSION_ID").ok()?;
+    let output = std::process::Command::new("loginctl")
+        .args([
+            "show-session",
+            &id,
+            "--property=Active",
+            "--property=Remote",
+        ])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The console session, this process's session and whether it is a remote
+/// desktop session.
+#[cfg(windows)]
+fn state() -> Option<(u32, u32, i32)> {
+    const SM_REMOTESESSION: i32 = 0x1000;
+
+    extern "system" {
+        fn WTSGetActiveConsoleSessionId() -> u32;
+        fn GetCurrentProcessId() -> u32;
+        fn ProcessIdToSessionId(process_id: u32, session_id: *mut u32) -> i32;
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetSystemMetrics(index: i32) -> i32;
+    }
+
+    // SAFETY: the calls only take plain values and write to `session`.
+    unsafe {
+        let mut session = 0;
+        if ProcessIdToSessionId(GetCurrentProcessId(), &mut session) == 0 {
+            return None;
+        }
+        Some((
+            WTSGetActiveConsoleSessionId(),
+            session,
+            GetSystemMetrics(SM_REMOTESESSION),
+        ))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn state() -> Option<()> {
+    None
+}
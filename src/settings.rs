@@ -0,0 +1,720 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    activation::{Activation, Trigger},
+    clicker,
+    config::{self, Table},
+    i18n,
+    jobs::Job,
+    presets::IntervalPreset,
+    recorder::{Playback, RecordFilter},
+    slots::{Slot, SLOT_COUNT},
+    source,
+    theme::{self, Appearance},
+    watch::{WatchAction, WatchFolder},
+};
+
+/// The range of [`Settings::ui_scale`].
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+
+/// App-wide preferences, stored as `<config dir>/settings.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// A TTF/OTF file added as a fallback font, e.g. for CJK scripts.
+    pub font_path: Option<PathBuf>,
+    /// Mirror the layout (`Some(true)`) or not, `None` to follow the
+    /// language, see [`Settings::is_right_to_left`].
+    pub right_to_left: Option<bool>,
+    /// Show the reduced-input layout with large Start/Stop buttons.
+    pub simple_mode: bool,
+    /// Click automatically when the cursor rests for `dwell_ms`.
+    pub dwell_enabled: bool,
+    pub dwell_ms: usize,
+    /// Flash a marker on screen at every synthesized click.
+    pub click_visualizer: bool,
+    /// Draw a halo around the cursor while the clicker is running.
+    pub cursor_halo: bool,
+    /// Mark the custom click position on screen with a crosshair.
+    pub target_crosshair: bool,
+    /// Stop the clicker when the cursor is slammed into a screen corner, see
+    /// [`crate::failsafe::spawn_watcher`].
+    pub failsafe: bool,
+    /// Pause clicking while the user moves the mouse, see
+    /// [`crate::takeover`].
+    pub auto_pause: bool,
+    /// How long clicking stays paused after the last move.
+    pub auto_pause_ms: usize,
+    /// Start clicking once the user is idle, see [`crate::idle`].
+    pub idle_start: bool,
+    /// How long the user has to be idle.
+    pub idle_start_secs: usize,
+    /// Run the clicker thread at raised priority, see
+    /// [`crate::priority::set_high_priority`].
+    pub high_priority: bool,
+    /// The least time between sent events, see
+    /// [`crate::clicker::set_event_gap`].
+    pub event_gap_ms: usize,
+    /// High-rate clicking, see [`crate::turbo::set_enabled`].
+    pub turbo: bool,
+    /// How much earlier waits end, from a calibration, see
+    /// [`crate::clicker::set_wake_offset`].
+    pub wake_offset_us: usize,
+    /// Align clicks to the display refresh, see [`crate::vsync::set_enabled`].
+    pub frame_perfect: bool,
+    /// Which input macro recordings keep, see [`RecordFilter`].
+    pub record_filter: RecordFilter,
+    /// Record macros relative to the focused window, see
+    /// [`crate::recorder::Recorder::set_relative`].
+    pub record_relative: bool,
+    /// The speed and delay limits macros are played with.
+    pub playback: Playback,
+    /// Announce runs starting and stopping with text-to-speech, when built
+    /// with the `speech` feature.
+    pub speech: bool,
+    /// Desktop notifications of runs, when built with the `notifications`
+    /// feature.
+    pub notifications: bool,
+    /// Sound cues on start and stop, when built with the `sound` feature.
+    pub sound: SoundSettings,
+    /// Game controller buttons that run the clicker, when built with the
+    /// `gamepad` feature.
+    pub gamepad: GamepadSettings,
+    /// Name of the selected theme, `None` for the system look.
+    pub theme: Option<String>,
+    /// Light or dark regardless of the OS setting, or following it.
+    pub appearance: Appearance,
+    /// Color of selections and links, `None` for the default one.
+    pub accent: Option<[u8; 3]>,
+    /// Code of the language the GUI is shown in, e.g. `de`, `None` for the
+    /// system language, see [`crate::i18n`].
+    pub language: Option<String>,
+    /// Zoom of the GUI on top of the display's scale factor, 1 for none.
+    pub ui_scale: f32,
+    /// Snap the window to screen edges and collapse it to a bar there, see
+    /// [`crate::dock::Dock`].
+    pub dock: bool,
+    /// Where the main window was when the app last exited.
+    pub window_position: Option<(i32, i32)>,
+    /// The main window's size in logical pixels when the app last exited.
+    pub window_size: Option<(u32, u32)>,
+    /// Launch with the window minimized.
+    pub start_minimized: bool,
+    /// Closing the window hides it to the tray, or minimizes it without one,
+    /// and keeps the clicker running.
+    pub minimize_on_close: bool,
+    /// Hold the sounds, announcements and notifications of runs back while
+    /// the system is in do-not-disturb mode, see [`crate::dnd`].
+    pub respect_do_not_disturb: bool,
+    /// Marker attached to synthesized events, see [`crate::source::set_tag`].
+    pub event_tag: usize,
+    /// Seed of the randomness of every run, `None` for a fresh one each run,
+    /// see [`crate::humanize::set_seed`].
+    pub random_seed: Option<u64>,
+    /// Clicks per second no run goes over whatever its interval, `None` for
+    /// no cap, see [`crate::ratelimit::set_max_cps`].
+    pub max_cps: Option<f64>,
+    /// X display the input backend connects to, see
+    /// [`crate::display::set_input_display`]. Empty for the current one.
+    pub input_display: String,
+    /// Click with a second X pointer, see [`crate::pointer`].
+    pub second_pointer: bool,
+    /// rdev or a uinput device, see [`source::set_choice`].
+    pub input_backend: source::BackendChoice,
+    /// Run the clicker only while this key or button is held, see
+    /// [`crate::activation`].
+    pub hold_to_click: Option<Trigger>,
+    /// Start and stop the clicker with each press of this key or button.
+    pub toggle_trigger: Option<Trigger>,
+    /// Address the HTTP remote control listens on, e.g. `127.0.0.1:7878`,
+    /// when built with the `remote` feature. Empty to disable it.
+    pub remote_address: String,
+    /// Address of a view-only server with the same status API, for watching
+    /// runs from another machine. Empty to disable it.
+    pub spectator_address: String,
+    /// Secret every request to the remote control must carry. The remote
+    /// control does not start without one.
+    pub remote_token: String,
+    /// Secret for the view-only server. Empty lets anyone watch.
+    pub spectator_token: String,
+    /// Origins of web pages allowed to use both servers from a browser,
+    /// comma-separated in the file.
+    pub remote_origins: Vec<String>,
+    /// Take commands from local tools through a socket or named pipe, see
+    /// [`crate::ipc`].
+    pub ipc: bool,
+    /// What each quick slot launches, see [`crate::slots::spawn_watcher`].
+    pub slots: [Slot; SLOT_COUNT],
+    /// Click jobs that run besides the main clicker, see [`crate::jobs`].
+    pub jobs: Vec<Job>,
+    /// Intervals saved under a name, see [`crate::presets`].
+    pub interval_presets: Vec<IntervalPreset>,
+    /// What new files in a folder launch, see
+    /// [`crate::watch::spawn_watcher`].
+    pub watch: WatchFolder,
+    /// URL run events are POSTed to when built with the `webhooks` feature.
+    /// Empty to disable them.
+    pub webhook_url: String,
+    /// Directory of the Vosk model for voice commands, when built with the
+    /// `voice` feature. Empty to disable them.
+    pub voice_model: String,
+    /// Broker connection for the `mqtt` feature.
+    pub mqtt: MqttSettings,
+    /// The app version that last ran, to show what is new after an update.
+    pub last_seen_version: Option<String>,
+    /// Whether the hint pointing new users to the help was dismissed.
+    pub help_hint_seen: bool,
+}
+
+/// Whether and how a run starting and stopping is announced with sound,
+/// see [`crate::sound::spawn_player`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    /// From 0 to 1.
+    pub volume: f32,
+    /// Played instead of the start beep, e.g. a WAV, OGG or MP3 file.
+    pub start_file: Option<PathBuf>,
+    /// Played instead of the stop beep.
+    pub stop_file: Option<PathBuf>,
+}
+
+/// Game controller buttons, by the names in [`crate::gamepad::BUTTONS`],
+/// that run the clicker like the window keys F6 to F9, see
+/// [`crate::gamepad::spawn_watcher`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GamepadSettings {
+    pub toggle: Option<String>,
+    pub start: Option<String>,
+    pub stop: Option<String>,
+    pub pause: Option<String>,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 0.5,
+            start_file: None,
+            stop_file: None,
+        }
+    }
+}
+
+/// Where the MQTT client connects and which topics it uses, see
+/// [`crate::mqtt::run`]. The password is stored as plain text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttSettings {
+    /// Broker host name. Empty to disable MQTT.
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub client_id: String,
+    /// Topic `start`, `stop` and `toggle` (or `ON`, `OFF` and `TOGGLE`)
+    /// messages are read from.
+    pub command_topic: String,
+    /// Topic the running state is published to as a retained `ON` or `OFF`.
+    pub state_topic: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 1883,
+            username: String::new(),
+            password: String::new(),
+            client_id: "auto-clicker".to_owned(),
+            command_topic: "auto-clicker/command".to_owned(),
+            state_topic: "auto-clicker/state".to_owned(),
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            font_path: None,
+            right_to_left: None,
+            simple_mode: false,
+            dwell_enabled: false,
+            dwell_ms: 800,
+            click_visualizer: false,
+            cursor_halo: false,
+            target_crosshair: true,
+            failsafe: true,
+            auto_pause: false,
+            auto_pause_ms: 1000,
+            idle_start: false,
+            idle_start_secs: 60,
+            high_priority: false,
+            event_gap_ms: clicker::DEFAULT_EVENT_GAP_MS,
+            turbo: false,
+            wake_offset_us: 0,
+            frame_perfect: false,
+            record_filter: RecordFilter::default(),
+            record_relative: false,
+            playback: Playback::default(),
+            speech: false,
+            notifications: false,
+            sound: SoundSettings::default(),
+            gamepad: GamepadSettings::default(),
+            theme: None,
+            appearance: Appearance::System,
+            accent: None,
+            language: None,
+            ui_scale: 1.0,
+            dock: false,
+            window_position: None,
+            window_size: None,
+            start_minimized: false,
+            minimize_on_close: false,
+            respect_do_not_disturb: true,
+            event_tag: source::DEFAULT_TAG,
+            random_seed: None,
+            max_cps: None,
+            input_display: String::new(),
+            second_pointer: false,
+            input_backend: source::BackendChoice::Auto,
+            hold_to_click: None,
+            toggle_trigger: None,
+            remote_address: String::new(),
+            remote_token: String::new(),
+            spectator_token: String::new(),
+            remote_origins: Vec::new(),
+            spectator_address: String::new(),
+            ipc: false,
+            slots: Default::default(),
+            jobs: Vec::new(),
+            interval_presets: Vec::new(),
+            watch: WatchFolder::default(),
+            webhook_url: String::new(),
+            voice_model: String::new(),
+            mqtt: MqttSettings::default(),
+            last_seen_version: None,
+            help_hint_seen: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings file, falling back to defaults if it is missing
+    /// or unreadable. `AUTOCLICKER_*` environment variables override single
+    /// settings on top, see [`config::env_var`]. They end up in the file
+    /// once the app saves the settings.
+    pub fn load() -> Self {
+        let mut table = match settings_path() {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(source) => Table::parse(&source).unwrap_or_else(|e| {
+                    log::error!("Could not read {}: {e}", path.display());
+                    Table::default()
+                }),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Table::default(),
+                Err(e) => {
+                    log::error!("Could not read {}: {e}", path.display());
+                    Table::default()
+                }
+            },
+            None => Table::default(),
+        };
+        table.override_from_env(&Self::env_keys(), &config::environment());
+        Self::from_table(&table)
+    }
+
+    /// Whether the layout is mirrored: as set, or for a right-to-left
+    /// language.
+    pub fn is_right_to_left(&self) -> bool {
+        self.right_to_left.unwrap_or_else(i18n::is_right_to_left)
+    }
+
+    /// The setting that holds the trigger of `activation`.
+    pub fn trigger_mut(&mut self, activation: Activation) -> &mut Option<Trigger> {
+        match activation {
+            Activation::Hold => &mut self.hold_to_click,
+            Activation::Toggle => &mut self.toggle_trigger,
+        }
+    }
+
+    /// Every key of the file, with a value of the right kind: the defaults
+    /// plus the keys left out while unset.
+    fn env_keys() -> Table {
+        const OPTIONAL_STRINGS: [&str; 28] = [
+            "font.path",
+            "sound.start_file",
+            "sound.stop_file",
+            "gamepad.toggle",
+            "gamepad.start",
+            "gamepad.stop",
+            "gamepad.pause",
+            "theme.name",
+            "theme.accent",
+            "layout.language",
+            "layout.direction",
+            "input.display",
+            "input.hold_to_click",
+            "input.toggle_trigger",
+            "remote.address",
+            "remote.spectator_address",
+            "remote.token",
+            "remote.spectator_token",
+            "remote.allowed_origins",
+            "webhook.url",
+            "voice.model",
+            "mqtt.host",
+            "mqtt.username",
+            "mqtt.password",
+            "mqtt.client_id",
+            "mqtt.command_topic",
+            "mqtt.state_topic",
+            "app.last_seen_version",
+        ];
+
+        let mut keys = Self::default().to_table();
+        for key in OPTIONAL_STRINGS {
+            keys.set_str(key, "");
+        }
+        for i in 1..=SLOT_COUNT {
+            keys.set_str(&format!("slots.{i}"), "");
+        }
+        for key in [
+            "window.x",
+            "window.y",
+            "window.width",
+            "window.height",
+            "mqtt.port",
+            "input.random_seed",
+            "input.max_cps",
+        ] {
+            keys.set(key, 0);
+        }
+        keys
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = settings_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_table().to_string())
+    }
+
+    pub fn from_table(table: &Table) -> Self {
+        let defaults = Self::default();
+
+        Self {
+            font_path: table
+                .get_str("font.path")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            // Before the direction followed the language, `right_to_left`
+            // was always written, so only an enabled one is an override.
+            right_to_left: match table.get_str("layout.direction").as_deref() {
+                Some("rtl") => Some(true),
+                Some("ltr") => Some(false),
+                _ => table.get::<bool>("layout.right_to_left").filter(|&rtl| rtl),
+            },
+            simple_mode: table.get("layout.simple_mode").unwrap_or_default(),
+            dwell_enabled: table.get("dwell.enabled").unwrap_or_default(),
+            dwell_ms: table.get("dwell.ms").unwrap_or(defaults.dwell_ms),
+            click_visualizer: table.get("overlay.click_visualizer").unwrap_or_default(),
+            cursor_halo: table.get("overlay.cursor_halo").unwrap_or_default(),
+            target_crosshair: table
+                .get("overlay.target_crosshair")
+                .unwrap_or(defaults.target_crosshair),
+            failsafe: table.get("clicker.failsafe").unwrap_or(defaults.failsafe),
+            auto_pause: table.get("clicker.auto_pause").unwrap_or_default(),
+            auto_pause_ms: table
+                .get("clicker.auto_pause_ms")
+                .unwrap_or(defaults.auto_pause_ms),
+            idle_start: table.get("clicker.idle_start").unwrap_or_default(),
+            idle_start_secs: table
+                .get("clicker.idle_start_secs")
+                .unwrap_or(defaults.idle_start_secs),
+            high_priority: table.get("clicker.high_priority").unwrap_or_default(),
+            event_gap_ms: table
+                .get("clicker.event_gap_ms")
+                .unwrap_or(defaults.event_gap_ms),
+            turbo: table.get("clicker.turbo").unwrap_or_default(),
+            wake_offset_us: table.get("clicker.wake_offset_us").unwrap_or_default(),
+            frame_perfect: table.get("clicker.frame_perfect").unwrap_or_default(),
+            record_filter: RecordFilter {
+                ignore_moves: table.get("recording.ignore_moves").unwrap_or_default(),
+                move_grid: table.get("recording.move_grid").unwrap_or_default(),
+                ignore_own_window: table.get("recording.ignore_own_window").unwrap_or_default(),
+            },
+            record_relative: table.get("recording.relative").unwrap_or_default(),
+            playback: Playback {
+                speed: table
+                    .get::<f64>("playback.speed")
+                    .unwrap_or(defaults.playback.speed)
+                    .clamp(Playback::MIN_SPEED, Playback::MAX_SPEED),
+                min_delay_ms: table.get("playback.min_delay_ms").unwrap_or_default(),
+                max_delay_ms: table.get("playback.max_delay_ms").unwrap_or_default(),
+            },
+            speech: table.get("speech.enabled").unwrap_or_default(),
+            notifications: table.get("notifications.enabled").unwrap_or_default(),
+            sound: SoundSettings {
+                enabled: table.get("sound.enabled").unwrap_or_default(),
+                volume: table.get("sound.volume").unwrap_or(defaults.sound.volume),
+                start_file: table.get_str("sound.start_file").map(PathBuf::from),
+                stop_file: table.get_str("sound.stop_file").map(PathBuf::from),
+            },
+            gamepad: GamepadSettings {
+                toggle: table.get_str("gamepad.toggle"),
+                start: table.get_str("gamepad.start"),
+                stop: table.get_str("gamepad.stop"),
+                pause: table.get_str("gamepad.pause"),
+            },
+            theme: table.get_str("theme.name"),
+            appearance: table
+                .get_str("theme.appearance")
+                .map(|value| Appearance::from_config(&value))
+                .unwrap_or_default(),
+            accent: table
+                .get_str("theme.accent")
+                .and_then(|value| theme::parse_hex_color(&value))
+                .map(|[r, g, b, _]| [r, g, b]),
+            language: table.get_str("layout.language"),
+            ui_scale: table
+                .get::<f32>("layout.ui_scale")
+                .map_or(defaults.ui_scale, |scale| {
+                    scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+                }),
+            dock: table.get("window.dock").unwrap_or_default(),
+            window_position: table.get("window.x").zip(table.get("window.y")),
+            window_size: table.get("window.width").zip(table.get("window.height")),
+            start_minimized: table.get("window.start_minimized").unwrap_or_default(),
+            minimize_on_close: table.get("window.minimize_on_close").unwrap_or_default(),
+            respect_do_not_disturb: table
+                .get("alerts.respect_do_not_disturb")
+                .unwrap_or(defaults.respect_do_not_disturb),
+            event_tag: table.get("input.event_tag").unwrap_or(defaults.event_tag),
+            random_seed: table.get("input.random_seed"),
+            max_cps: table.get("input.max_cps").filter(|cps: &f64| *cps > 0.0),
+            input_display: table.get_str("input.display").unwrap_or_default(),
+            second_pointer: table.get("input.second_pointer").unwrap_or_default(),
+            input_backend: table
+                .get_str("input.backend")
+                .map(|value| source::BackendChoice::from_config(&value))
+                .unwrap_or_default(),
+            hold_to_click: table
+                .get_str("input.hold_to_click")
+                .and_then(|s| Trigger::parse(&s)),
+            toggle_trigger: table
+                .get_str("input.toggle_trigger")
+                .and_then(|s| Trigger::parse(&s)),
+            remote_address: table.get_str("remote.address").unwrap_or_default(),
+            spectator_address: table
+                .get_str("remote.spectator_address")
+                .unwrap_or_default(),
+            remote_token: table.get_str("remote.token").unwrap_or_default(),
+            spectator_token: table.get_str("remote.spectator_token").unwrap_or_default(),
+            remote_origins: table
+                .get_str("remote.allowed_origins")
+                .map(|origins| {
+                    origins
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|origin| !origin.is_empty())
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            ipc: table.get("ipc.enabled").unwrap_or_default(),
+            slots: std::array::from_fn(|i| {
+                table
+                    .get_str(&format!("slots.{}", i + 1))
+                    .map(|value| Slot::from_config(&value))
+                    .unwrap_or_default()
+            }),
+            jobs: (1..).map_while(|i| Job::from_table(table, i)).collect(),
+            interval_presets: (1..)
+                .map_while(|i| IntervalPreset::from_table(table, i))
+                .collect(),
+            watch: WatchFolder {
+                enabled: table.get("watch.enabled").unwrap_or_default(),
+                folder: table.get_str("watch.folder").unwrap_or_default(),
+                action: table
+                    .get_str("watch.action")
+                    .map(|value| WatchAction::from_config(&value))
+                    .unwrap_or_default(),
+                param: table.get_str("watch.param").unwrap_or_default(),
+            },
+            webhook_url: table.get_str("webhook.url").unwrap_or_default(),
+            voice_model: table.get_str("voice.model").unwrap_or_default(),
+            mqtt: MqttSettings {
+                host: table.get_str("mqtt.host").unwrap_or_default(),
+                port: table.get("mqtt.port").unwrap_or(defaults.mqtt.port),
+                username: table.get_str("mqtt.username").unwrap_or_default(),
+                password: table.get_str("mqtt.password").unwrap_or_default(),
+                client_id: table
+                    .get_str("mqtt.client_id")
+                    .unwrap_or_else(|| defaults.mqtt.client_id.clone()),
+                command_topic: table
+                    .get_str("mqtt.command_topic")
+                    .unwrap_or_else(|| defaults.mqtt.command_topic.clone()),
+                state_topic: table
+                    .get_str("mqtt.state_topic")
+                    .unwrap_or(defaults.mqtt.state_topic),
+            },
+            last_seen_version: table.get_str("app.last_seen_version"),
+            help_hint_seen: table.get("app.help_hint_seen").unwrap_or_default(),
+        }
+    }
+
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::default();
+
+        if let Some(path) = &self.font_path {
+            table.set_str("font.path", &path.to_string_lossy());
+        }
+        if let Some(rtl) = self.right_to_left {
+            table.set_str("layout.direction", if rtl { "rtl" } else { "ltr" });
+        }
+        table.set("layout.simple_mode", self.simple_mode);
+        table.set("dwell.enabled", self.dwell_enabled);
+        table.set("dwell.ms", self.dwell_ms);
+        table.set("overlay.click_visualizer", self.click_visualizer);
+        table.set("overlay.cursor_halo", self.cursor_halo);
+        table.set("overlay.target_crosshair", self.target_crosshair);
+        table.set("clicker.failsafe", self.failsafe);
+        table.set("clicker.auto_pause", self.auto_pause);
+        table.set("clicker.auto_pause_ms", self.auto_pause_ms);
+        table.set("clicker.idle_start", self.idle_start);
+        table.set("clicker.idle_start_secs", self.idle_start_secs);
+        table.set("clicker.high_priority", self.high_priority);
+        table.set("clicker.event_gap_ms", self.event_gap_ms);
+        table.set("clicker.turbo", self.turbo);
+        table.set("clicker.wake_offset_us", self.wake_offset_us);
+        table.set("clicker.frame_perfect", self.frame_perfect);
+        table.set("recording.ignore_moves", self.record_filter.ignore_moves);
+        table.set("recording.move_grid", self.record_filter.move_grid);
+        table.set(
+            "recording.ignore_own_window",
+            self.record_filter.ignore_own_window,
+        );
+        table.set("recording.relative", self.record_relative);
+        table.set("playback.speed", self.playback.speed);
+        table.set("playback.min_delay_ms", self.playback.min_delay_ms);
+        table.set("playback.max_delay_ms", self.playback.max_delay_ms);
+        table.set("speech.enabled", self.speech);
+        table.set("notifications.enabled", self.notifications);
+        table.set("sound.enabled", self.sound.enabled);
+        table.set("sound.volume", self.sound.volume);
+        if let Some(path) = &self.sound.start_file {
+            table.set_str("sound.start_file", &path.to_string_lossy());
+        }
+        if let Some(path) = &self.sound.stop_file {
+            table.set_str("sound.stop_file", &path.to_string_lossy());
+        }
+        for (key, button) in [
+            ("gamepad.toggle", &self.gamepad.toggle),
+            ("gamepad.start", &self.gamepad.start),
+            ("gamepad.stop", &self.gamepad.stop),
+            ("gamepad.pause", &self.gamepad.pause),
+        ] {
+            if let Some(button) = button {
+                table.set_str(key, button);
+            }
+        }
+        if let Some(theme) = &self.theme {
+            table.set_str("theme.name", theme);
+        }
+        table.set_str("theme.appearance", self.appearance.to_config());
+        if let Some(accent) = self.accent {
+            table.set_str("theme.accent", &theme::to_hex_color(accent));
+        }
+        if let Some(language) = &self.language {
+            table.set_str("layout.language", language);
+        }
+        table.set("layout.ui_scale", self.ui_scale);
+        table.set("window.dock", self.dock);
+        if let Some((x, y)) = self.window_position {
+            table.set("window.x", x);
+            table.set("window.y", y);
+        }
+        if let Some((width, height)) = self.window_size {
+            table.set("window.width", width);
+            table.set("window.height", height);
+        }
+        table.set("window.start_minimized", self.start_minimized);
+        table.set("window.minimize_on_close", self.minimize_on_close);
+        table.set("alerts.respect_do_not_disturb", self.respect_do_not_disturb);
+        table.set("input.event_tag", self.event_tag);
+        if let Some(seed) = self.random_seed {
+            table.set("input.random_seed", seed);
+        }
+        if let Some(cps) = self.max_cps {
+            table.set("input.max_cps", cps);
+        }
+        if !self.input_display.is_empty() {
+            table.set_str("input.display", &self.input_display);
+        }
+        table.set("input.second_pointer", self.second_pointer);
+        table.set_str("input.backend", self.input_backend.to_config());
+        if let Some(trigger) = self.hold_to_click {
+            table.set_str("input.hold_to_click", &trigger.name());
+        }
+        if let Some(trigger) = self.toggle_trigger {
+            table.set_str("input.toggle_trigger", &trigger.name());
+        }
+        if !self.remote_address.is_empty() {
+            table.set_str("remote.address", &self.remote_address);
+        }
+        if !self.spectator_address.is_empty() {
+            table.set_str("remote.spectator_address", &self.spectator_address);
+        }
+        if !self.remote_token.is_empty() {
+            table.set_str("remote.token", &self.remote_token);
+        }
+        if !self.spectator_token.is_empty() {
+            table.set_str("remote.spectator_token", &self.spectator_token);
+        }
+        if !self.remote_origins.is_empty() {
+            table.set_str("remote.allowed_origins", &self.remote_origins.join(", "));
+        }
+        table.set("ipc.enabled", self.ipc);
+        for (i, slot) in self.slots.iter().enumerate() {
+            if let Some(value) = slot.to_config() {
+                table.set_str(&format!("slots.{}", i + 1), &value);
+            }
+        }
+        for (i, job) in self.jobs.iter().enumerate() {
+            job.to_table(&mut table, i + 1);
+        }
+        for (i, preset) in self.interval_presets.iter().enumerate() {
+            preset.to_table(&mut table, i + 1);
+        }
+        table.set("watch.enabled", self.watch.enabled);
+        table.set_str("watch.folder", &self.watch.folder);
+        table.set_str("watch.action", &self.watch.action.to_config());
+        table.set_str("watch.param", &self.watch.param);
+        if !self.webhook_url.is_empty() {
+            table.set_str("webhook.url", &self.webhook_url);
+        }
+        if !self.voice_model.is_empty() {
+            table.set_str("voice.model", &self.voice_model);
+        }
+        if !self.mqtt.host.is_empty() {
+            table.set_str("mqtt.host", &self.mqtt.host);
+            table.set("mqtt.port", self.mqtt.port);
+            table.set_str("mqtt.username", &self.mqtt.username);
+            table.set_str("mqtt.password", &self.mqtt.password);
+            table.set_str("mqtt.client_id", &self.mqtt.client_id);
+            table.set_str("mqtt.command_topic", &self.mqtt.command_topic);
+            table.set_str("mqtt.state_topic", &self.mqtt.state_topic);
+        }
+        if let Some(version) = &self.last_seen_version {
+            table.set_str("app.last_seen_version", version);
+        }
+        table.set("app.help_hint_seen", self.help_hint_seen);
+
+        table
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("settings.toml"))
+}
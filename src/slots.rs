@@ -0,0 +1,157 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::{mpsc::Sender, Mutex},
+    thread,
+};
+
+use rdev::{EventType, Key};
+
+use crate::{
+    activation::{Input, Trigger},
+    clicker::{Command, Config},
+    config, hotkeys, listener,
+    modifiers::Modifiers,
+    profile::Profile,
+    recorder::{Recorder, Recording},
+};
+
+/// Number of quick slots, launched with Ctrl+F1 up to Ctrl+F4.
+pub const SLOT_COUNT: usize = 4;
+
+const SLOT_KEYS: [Key; SLOT_COUNT] = [Key::F1, Key::F2, Key::F3, Key::F4];
+
+/// Held with exactly these, so other combinations of the keys stay free.
+const SLOT_MODIFIERS: Modifiers = Modifiers {
+    ctrl: true,
+    ..Modifiers::NONE
+};
+
+/// What a quick slot launches.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Slot {
+    #[default]
+    Empty,
+    /// Apply this saved profile and toggle the clicker.
+    Profile(String),
+    /// Toggle playback of the macro bound with [`save_macro`].
+    Macro,
+}
+
+impl Slot {
+    /// The form kept in the settings file, `None` for an empty slot.
+    pub fn to_config(&self) -> Option<String> {
+        match self {
+            Slot::Empty => None,
+            Slot::Profile(name) => Some(format!("profile:{name}")),
+            Slot::Macro => Some("macro".to_owned()),
+        }
+    }
+
+    pub fn from_config(value: &str) -> Self {
+        match value.split_once(':') {
+            Some(("profile", name)) => Slot::Profile(name.to_owned()),
+            _ if value == "macro" => Slot::Macro,
+            _ => Slot::Empty,
+        }
+    }
+}
+
+static SLOTS: Mutex<[Slot; SLOT_COUNT]> =
+    Mutex::new([Slot::Empty, Slot::Empty, Slot::Empty, Slot::Empty]);
+
+/// Sets what the slots launch, e.g. after they are changed in the GUI.
+pub fn set_slots(slots: &[Slot; SLOT_COUNT]) {
+    *SLOTS.lock().unwrap() = slots.clone();
+}
+
+/// The hotkey that launches slot `index`.
+pub fn hotkey(index: usize) -> Trigger {
+    Trigger {
+        modifiers: SLOT_MODIFIERS,
+        input: Input::Key(SLOT_KEYS[index]),
+    }
+}
+
+/// The label of the hotkey that launches slot `index`.
+pub fn hotkey_label(index: usize) -> String {
+    hotkey(index).label()
+}
+
+/// Starts a thread that launches a slot whenever its hotkey is pressed,
+/// wherever the focus is.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        let mut player = Recorder::default();
+        let mut held = Modifiers::NONE;
+        for event in rx_events {
+            if held.update(&event.event_type) {
+                continue;
+            }
+            match event.event_type {
+                EventType::KeyPress(key) if held == SLOT_MODIFIERS => {
+                    let index = SLOT_KEYS.iter().position(|&k| k == key);
+                    if let Some(index) = index.filter(|&index| !hotkeys::is_taken(hotkey(index))) {
+                        let slot = SLOTS.lock().unwrap()[index].clone();
+                        launch(index, &slot, &tx_command, &mut player);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+fn launch(index: usize, slot: &Slot, tx_command: &Sender<Command>, player: &mut Recorder) {
+    match slot {
+        Slot::Empty => {}
+        Slot::Profile(name) => match Profile::load(name) {
+            Ok(profile) => {
+                let commands = [
+                    Config::ClickInterval(profile.click_interval).into(),
+                    Config::ClickOptions(Box::new(profile.click_options)).into(),
+                    Config::ClickPosition(profile.click_position).into(),
+                    Command::Toggle,
+                ];
+                for command in commands {
+                    tx_command.send(command).ok();
+                }
+            }
+            Err(e) => log::error!("Could not load profile {name:?}: {e}"),
+        },
+        Slot::Macro => {
+            if player.is_playing() {
+                player.stop_playing();
+                return;
+            }
+            match load_macro(index) {
+                Ok(recording) => {
+                    player.set_recording(recording);
+                    player.play();
+                }
+                Err(e) => log::error!("Could not load the macro of slot {}: {e}", index + 1),
+            }
+        }
+    }
+}
+
+/// Stores `recording` as the macro of slot `index`.
+pub fn save_macro(index: usize, recording: &Recording) -> io::Result<()> {
+    let path = macro_path(index)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, recording.to_text())
+}
+
+/// The macro bound to slot `index` with [`save_macro`].
+pub fn load_macro(index: usize) -> io::Result<Recording> {
+    Ok(Recording::parse(&fs::read_to_string(macro_path(index)?)?))
+}
+
+fn macro_path(index: usize) -> io::Result<PathBuf> {
+    config::config_dir()
+        .map(|dir| dir.join("slots").join(format!("{}.macro", index + 1)))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))
+}
@@ -0,0 +1,94 @@
+use std::{fs::File, io::BufReader, path::Path, sync::Mutex, thread, time::Duration};
+
+use rodio::{source::SineWave, Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+
+use crate::{
+    dnd,
+    settings::SoundSettings,
+    timeline::{self, RunEvent},
+};
+
+/// Length of the built-in beeps.
+const BEEP: Duration = Duration::from_millis(120);
+const START_PITCH: f32 = 880.0;
+const STOP_PITCH: f32 = 440.0;
+/// Between the two, so a reminder is not mistaken for a start or stop.
+const REMINDER_PITCH: f32 = 660.0;
+
+static SETTINGS: Mutex<Option<SoundSettings>> = Mutex::new(None);
+
+/// Sets whether, how loud and which cues are played, e.g. after they are
+/// changed in the GUI.
+pub fn configure(settings: &SoundSettings) {
+    *SETTINGS.lock().unwrap() = Some(settings.clone());
+}
+
+/// Starts a thread that plays a cue when a run starts and when it stops,
+/// a higher beep for the start and a lower one for the stop unless sound
+/// files are configured, and a beep in between as a reminder that a run
+/// is still going.
+pub fn spawn_player() {
+    let run_events = timeline::run_events();
+    thread::spawn(move || {
+        // The output stream cannot leave the thread that opened it.
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("Sound cues are not available: {e}");
+                return;
+            }
+        };
+        for event in run_events {
+            let Some(settings) = SETTINGS.lock().unwrap().clone() else {
+                continue;
+            };
+            if settings.enabled && !dnd::holds_alerts() {
+                play(&handle, &settings, event);
+            }
+        }
+    });
+}
+
+fn play(handle: &OutputStreamHandle, settings: &SoundSettings, event: RunEvent) {
+    let sink = match Sink::try_new(handle) {
+        Ok(sink) => sink,
+        Err(e) => {
+            log::error!("Could not play a sound cue: {e}");
+            return;
+        }
+    };
+    sink.set_volume(settings.volume);
+
+    let file = match event {
+        RunEvent::Start => settings.start_file.as_ref(),
+        RunEvent::Reminder => None,
+        RunEvent::Stop | RunEvent::Finish | RunEvent::Safety | RunEvent::TargetClosed => {
+            settings.stop_file.as_ref()
+        }
+    };
+    match file {
+        Some(path) => match decode(path) {
+            Ok(source) => sink.append(source),
+            Err(e) => {
+                log::error!("Could not play {}: {e}", path.display());
+                return;
+            }
+        },
+        None => {
+            let pitch = match event {
+                RunEvent::Start => START_PITCH,
+                RunEvent::Reminder => REMINDER_PITCH,
+                RunEvent::Stop | RunEvent::Finish | RunEvent::Safety | RunEvent::TargetClosed => {
+                    STOP_PITCH
+                }
+            };
+            sink.append(SineWave::new(pitch).take_duration(BEEP));
+        }
+    }
+    sink.detach();
+}
+
+fn decode(path: &Path) -> Result<Decoder<BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())
+}
@@ -0,0 +1,386 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use rdev::{EventType, SimulateError};
+
+use crate::{i18n::tr, latency, pointer, ratelimit, sendinput, uinput, xtest};
+
+/// "ACLK" in ASCII.
+pub const DEFAULT_TAG: usize = 0x4143_4C4B;
+
+/// How long a sent event waits for the listener to see it before it is
+/// forgotten.
+const PENDING_TIMEOUT: Duration = Duration::from_millis(200);
+
+static TAG: AtomicUsize = AtomicUsize::new(DEFAULT_TAG);
+
+/// Events sent recently that the listener has not matched yet.
+static PENDING: Mutex<VecDeque<(EventType, Instant)>> = Mutex::new(VecDeque::new());
+
+/// Where the clicker last moved the cursor, and when.
+static LAST_MOVE: Mutex<Option<((f64, f64), Instant)>> = Mutex::new(None);
+
+/// Sets the marker attached to synthesized events, so other programs can
+/// tell them apart from real input.
+pub fn set_tag(tag: usize) {
+    TAG.store(tag, Ordering::SeqCst);
+}
+
+pub fn tag() -> usize {
+    TAG.load(Ordering::SeqCst)
+}
+
+/// Whether the platform carries [`tag`] with the events it sends. Only
+/// Windows has a field for it, `dwExtraInfo`, and only mouse buttons are
+/// sent with it; keys, wheel and moves go through rdev untagged.
+pub const fn is_tagging_supported() -> bool {
+    cfg!(windows)
+}
+
+/// The way [`simulate`] sends mouse buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Backend {
+    /// rdev's own simulation.
+    Rdev,
+    /// Tagged `SendInput` calls on Windows.
+    SendInput,
+    /// The second XInput2 pointer on Linux, see [`pointer`].
+    SecondPointer,
+    /// A virtual uinput device on Linux, see [`uinput`].
+    Uinput,
+    /// One XTEST connection on X11, see [`xtest`].
+    Xtest,
+    /// `SendInput` on Windows for every event, in batches, see
+    /// [`sendinput`].
+    NativeSendInput,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&match self {
+            Backend::Rdev => tr("rdev"),
+            Backend::SendInput => tr("SendInput"),
+            Backend::SecondPointer => tr("Second pointer"),
+            Backend::Uinput => tr("uinput"),
+            Backend::Xtest => tr("XTest"),
+            Backend::NativeSendInput => tr("Native SendInput"),
+        })
+    }
+}
+
+/// A way of sending input to the system. [`simulate`] goes through the one
+/// [`input`] picks.
+pub trait Input: Sync {
+    fn backend(&self) -> Backend;
+
+    /// Sends `event_type` right away.
+    fn simulate(&self, event_type: &EventType) -> Result<(), SimulateError>;
+
+    /// Sends all of `event_types` at once, in order, or returns `None`
+    /// without sending anything where this backend cannot.
+    fn simulate_batch(&self, _event_types: &[EventType]) -> Option<Result<(), SimulateError>> {
+        None
+    }
+
+    /// Whether the OS needs a moment between events sent this way, see
+    /// [`crate::clicker`]'s event gap.
+    fn needs_event_gap(&self) -> bool {
+        true
+    }
+}
+
+struct RdevInput;
+
+impl Input for RdevInput {
+    fn backend(&self) -> Backend {
+        if cfg!(windows) {
+            Backend::SendInput
+        } else {
+            Backend::Rdev
+        }
+    }
+
+    fn simulate(&self, event_type: &EventType) -> Result<(), SimulateError> {
+        #[cfg(windows)]
+        if let Some(result) = send_tagged_buttons(std::slice::from_ref(event_type), tag()) {
+            return result;
+        }
+        remember(std::slice::from_ref(event_type));
+        rdev::simulate(event_type)
+    }
+
+    #[cfg(windows)]
+    fn simulate_batch(&self, event_types: &[EventType]) -> Option<Result<(), SimulateError>> {
+        send_tagged_buttons(event_types, tag())
+    }
+}
+
+/// Mouse events through the second pointer, keys through rdev.
+struct SecondPointerInput;
+
+impl Input for SecondPointerInput {
+    fn backend(&self) -> Backend {
+        Backend::SecondPointer
+    }
+
+    fn simulate(&self, event_type: &EventType) -> Result<(), SimulateError> {
+        remember(std::slice::from_ref(event_type));
+        pointer::simulate(event_type).unwrap_or_else(|| rdev::simulate(event_type))
+    }
+}
+
+/// Everything through the uinput device, keys it has no code for through
+/// rdev.
+struct UinputInput;
+
+impl Input for UinputInput {
+    fn backend(&self) -> Backend {
+        Backend::Uinput
+    }
+
+    fn simulate(&self, event_type: &EventType) -> Result<(), SimulateError> {
+        remember(std::slice::from_ref(event_type));
+        uinput::simulate(event_type).unwrap_or_else(|| rdev::simulate(event_type))
+    }
+}
+
+/// Everything through the XTEST connection, keys without a keycode through
+/// rdev.
+struct XtestInput;
+
+impl Input for XtestInput {
+    fn backend(&self) -> Backend {
+        Backend::Xtest
+    }
+
+    fn simulate(&self, event_type: &EventType) -> Result<(), SimulateError> {
+        remember(std::slice::from_ref(event_type));
+        xtest::simulate(event_type).unwrap_or_else(|| rdev::simulate(event_type))
+    }
+
+    /// The X server takes the events off the connection in order.
+    fn needs_event_gap(&self) -> bool {
+        false
+    }
+}
+
+/// Everything through `SendInput`, batches in one call.
+struct SendInputInput;
+
+impl Input for SendInputInput {
+    fn backend(&self) -> Backend {
+        Backend::NativeSendInput
+    }
+
+    fn simulate(&self, event_type: &EventType) -> Result<(), SimulateError> {
+        self.simulate_batch(std::slice::from_ref(event_type))
+            .unwrap_or_else(|| {
+                remember(std::slice::from_ref(event_type));
+                rdev::simulate(event_type)
+            })
+    }
+
+    fn simulate_batch(&self, event_types: &[EventType]) -> Option<Result<(), SimulateError>> {
+        let inputs = sendinput::convert(event_types, tag())?;
+        remember(event_types);
+        Some(sendinput::send(&inputs))
+    }
+
+    /// The whole batch goes into the input queue at once.
+    fn needs_event_gap(&self) -> bool {
+        false
+    }
+}
+
+/// The input backend the user asked for, see [`set_choice`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendChoice {
+    /// uinput in Wayland sessions, rdev everywhere else.
+    #[default]
+    Auto,
+    Rdev,
+    Uinput,
+    Xtest,
+    SendInput,
+}
+
+impl BackendChoice {
+    pub fn to_config(self) -> &'static str {
+        match self {
+            BackendChoice::Auto => "auto",
+            BackendChoice::Rdev => "rdev",
+            BackendChoice::Uinput => "uinput",
+            BackendChoice::Xtest => "xtest",
+            BackendChoice::SendInput => "sendinput",
+        }
+    }
+
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "rdev" => BackendChoice::Rdev,
+            "uinput" => BackendChoice::Uinput,
+            "xtest" => BackendChoice::Xtest,
+            "sendinput" => BackendChoice::SendInput,
+            _ => BackendChoice::Auto,
+        }
+    }
+
+    /// Whether this choice means the uinput device in this session.
+    pub fn uses_uinput(self) -> bool {
+        match self {
+            BackendChoice::Auto => uinput::is_supported() && uinput::is_wayland_session(),
+            BackendChoice::Rdev | BackendChoice::Xtest | BackendChoice::SendInput => false,
+            BackendChoice::Uinput => uinput::is_supported(),
+        }
+    }
+
+    pub fn uses_xtest(self) -> bool {
+        self == BackendChoice::Xtest && xtest::is_supported()
+    }
+
+    pub fn uses_sendinput(self) -> bool {
+        self == BackendChoice::SendInput && sendinput::is_supported()
+    }
+
+    /// The choices that work on this platform.
+    pub fn available() -> Vec<BackendChoice> {
+        let mut choices = vec![BackendChoice::Auto, BackendChoice::Rdev];
+        if uinput::is_supported() {
+            choices.push(BackendChoice::Uinput);
+        }
+        if xtest::is_supported() {
+            choices.push(BackendChoice::Xtest);
+        }
+        if sendinput::is_supported() {
+            choices.push(BackendChoice::SendInput);
+        }
+        choices
+    }
+}
+
+/// Creates or removes the uinput device and XTEST connection as `choice`
+/// asks. Failing to create them, e.g. without access to `/dev/uinput`,
+/// leaves input on rdev.
+pub fn set_choice(choice: BackendChoice) {
+    uinput::set_enabled(choice.uses_uinput());
+    xtest::set_enabled(choice.uses_xtest());
+    sendinput::set_enabled(choice.uses_sendinput());
+}
+
+/// The input backend [`simulate`] sends through right now: the second
+/// pointer while it is enabled, then the uinput device or XTEST, then rdev.
+pub fn input() -> &'static dyn Input {
+    if pointer::is_enabled() {
+        &SecondPointerInput
+    } else if uinput::is_enabled() {
+        &UinputInput
+    } else if xtest::is_enabled() {
+        &XtestInput
+    } else if sendinput::is_enabled() {
+        &SendInputInput
+    } else {
+        &RdevInput
+    }
+}
+
+/// The backend mouse buttons go through right now.
+pub fn backend() -> Backend {
+    input().backend()
+}
+
+/// Sends `event_type` like [`rdev::simulate`], tagged where the platform
+/// allows, and remembers it so [`is_synthesized`] recognizes it. Waits
+/// first if a press would go over the rate cap, see [`ratelimit`].
+pub fn simulate(event_type: &EventType) -> Result<(), SimulateError> {
+    ratelimit::acquire(std::slice::from_ref(event_type));
+    input().simulate(event_type)
+}
+
+/// Sends all of `event_types` at once, in order, where the backend can:
+/// Windows takes a burst of mouse buttons in a single `SendInput` call, and
+/// the native `SendInput` backend any events. Returns `None`, without
+/// sending anything, where it cannot; the caller then sends them one by one
+/// with [`simulate`].
+pub fn simulate_batch(event_types: &[EventType]) -> Option<Result<(), SimulateError>> {
+    if !cfg!(windows) {
+        return None;
+    }
+    let result = input().simulate_batch(event_types)?;
+    // Counted once sent, so a batch the backend turned down is not counted
+    // again by `simulate`; any wait falls before the next send.
+    ratelimit::acquire(event_types);
+    Some(result)
+}
+
+/// Notes sent events for [`is_synthesized`]. This happens before sending:
+/// the listener hook can see an event before the send call returns.
+fn remember(event_types: &[EventType]) {
+    let mut pending = PENDING.lock().unwrap();
+    pending.retain(|(_, at)| at.elapsed() < PENDING_TIMEOUT);
+    for event_type in event_types {
+        match *event_type {
+            EventType::MouseMove { x, y } => {
+                *LAST_MOVE.lock().unwrap() = Some(((x, y), Instant::now()));
+            }
+            event_type => pending.push_back((event_type, Instant::now())),
+        }
+    }
+}
+
+/// Whether a cursor position seen by the listener is probably where the
+/// clicker just moved it: within a few pixels of its last move, shortly
+/// after it.
+pub fn is_own_move(x: f64, y: f64) -> bool {
+    LAST_MOVE
+        .lock()
+        .unwrap()
+        .is_some_and(|((sent_x, sent_y), at)| {
+            at.elapsed() < PENDING_TIMEOUT && (x - sent_x).hypot(y - sent_y) <= 2.0
+        })
+}
+
+/// Whether an event seen by the input listener is one the clicker just
+/// sent. Each sent event matches once. Cursor moves are never matched, the
+/// reported position can differ from the one requested.
+pub fn is_synthesized(event_type: &EventType) -> bool {
+    let mut pending = PENDING.lock().unwrap();
+    pending.retain(|(_, at)| at.elapsed() < PENDING_TIMEOUT);
+    match pending.iter().position(|(sent, _)| sent == event_type) {
+        Some(i) => {
+            if let Some((_, sent_at)) = pending.remove(i) {
+                latency::observed(sent_at.elapsed());
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sends mouse button events through one `SendInput` call, so a burst
+/// costs a single syscall and cannot be interleaved with other input.
+/// Returns `None` if any of the events is not a plain mouse button.
+#[cfg(windows)]
+fn send_tagged_buttons(event_types: &[EventType], tag: usize) -> Option<Result<(), SimulateError>> {
+    use rdev::Button;
+
+    let is_button = |event_type: &EventType| {
+        matches!(
+            event_type,
+            EventType::ButtonPress(Button::Left | Button::Right | Button::Middle)
+                | EventType::ButtonRelease(Button::Left | Button::Right | Button::Middle)
+        )
+    };
+    if !event_types.iter().all(is_button) {
+        return None;
+    }
+    let inputs = sendinput::convert(event_types, tag)?;
+    remember(event_types);
+    Some(sendinput::send(&inputs))
+}
@@ -0,0 +1,53 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use tts::Tts;
+
+use crate::{
+    dnd,
+    i18n::tr,
+    timeline::{self, RunEvent},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts a thread that announces runs starting, completing and stopping
+/// with the system's text-to-speech voice while [`is_enabled`].
+pub fn spawn_announcer() {
+    let run_events = timeline::run_events();
+    thread::spawn(move || {
+        // Some backends must be used from the thread that created them.
+        let mut tts = match Tts::default() {
+            Ok(tts) => tts,
+            Err(e) => {
+                log::warn!("Text-to-speech is not available: {e}");
+                return;
+            }
+        };
+        for event in run_events {
+            let text = match event {
+                RunEvent::Start => tr("Clicker started"),
+                RunEvent::Finish => tr("Run complete"),
+                RunEvent::Stop => tr("Clicker stopped"),
+                RunEvent::Safety => tr("Stopped: failsafe"),
+                RunEvent::TargetClosed => tr("Stopped: target window closed"),
+                RunEvent::Reminder => tr("Still clicking"),
+            };
+            if is_enabled() && !dnd::holds_alerts() {
+                if let Err(e) = tts.speak(&*text, true) {
+                    log::error!("Could not speak {text:?}: {e}");
+                }
+            }
+        }
+    });
+}
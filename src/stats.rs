@@ -0,0 +1,291 @@
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::Path,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex, PoisonError,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::profile::Profile;
+
+/// How many seconds of click rates the history keeps.
+const HISTORY_SECS: usize = 120;
+
+/// How many finished runs [`runs`] keeps.
+const MAX_RUNS: usize = 1000;
+
+static STATS: Mutex<RunStats> = Mutex::new(RunStats {
+    clicks: 0,
+    started: None,
+    finished: None,
+});
+static HISTORY: Mutex<RateHistory> = Mutex::new(RateHistory {
+    second_start: None,
+    current: 0,
+    closed: 0,
+    rates: VecDeque::new(),
+});
+
+/// The record of the current run, completed when it stops.
+static RUN: Mutex<Option<RunRecord>> = Mutex::new(None);
+static RUNS: Mutex<VecDeque<RunRecord>> = Mutex::new(VecDeque::new());
+static RUN_SUBSCRIBERS: Mutex<Vec<Sender<RunRecord>>> = Mutex::new(Vec::new());
+
+/// Clicks per whole second of the current or last run, oldest first.
+#[derive(Debug)]
+struct RateHistory {
+    second_start: Option<Instant>,
+    current: usize,
+    /// Seconds closed since the start, including those dropped from `rates`.
+    closed: usize,
+    rates: VecDeque<usize>,
+}
+
+impl RateHistory {
+    /// Closes every second that ended before `now`, including ones without
+    /// clicks.
+    fn roll(&mut self, now: Instant) {
+        let Some(second_start) = &mut self.second_start else {
+            return;
+        };
+        while now.duration_since(*second_start) >= Duration::from_secs(1) {
+            *second_start += Duration::from_secs(1);
+            if self.rates.len() == HISTORY_SECS {
+                self.rates.pop_front();
+            }
+            self.rates.push_back(self.current);
+            self.current = 0;
+            self.closed += 1;
+        }
+    }
+}
+
+/// Live figures for the current or last run, updated by the clicker thread.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunStats {
+    /// Button and key presses sent.
+    pub clicks: usize,
+    started: Option<Instant>,
+    /// How long the run lasted, once it has stopped.
+    finished: Option<Duration>,
+}
+
+impl RunStats {
+    pub fn is_running(&self) -> bool {
+        self.started.is_some() && self.finished.is_none()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match (self.started, self.finished) {
+            (_, Some(elapsed)) => elapsed,
+            (Some(started), None) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    /// Effective clicks per second over the whole run.
+    pub fn clicks_per_second(&self) -> f64 {
+        let secs = self.elapsed().as_secs_f64();
+        if secs > 0.0 {
+            self.clicks as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A run that has ended, see [`runs`].
+#[derive(Debug, Clone)]
+pub struct RunRecord {
+    pub started: SystemTime,
+    pub ended: SystemTime,
+    /// The time spent clicking, without pauses.
+    pub elapsed: Duration,
+    pub clicks: usize,
+    /// The clicks per second the settings aim for, if they set a steady
+    /// rate.
+    pub target_cps: Option<f64>,
+    /// The settings the run clicked with, e.g.
+    /// `interval=100ms button=left clicks=1 position=cursor`.
+    pub config: String,
+    /// The click settings the run used, to run with them again.
+    pub profile: Profile,
+}
+
+impl RunRecord {
+    /// Effective clicks per second over the whole run.
+    pub fn clicks_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.clicks as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn current() -> RunStats {
+    *STATS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Clicks in each of the last completed seconds of the run, oldest first,
+/// as `[seconds since start, clicks]` points.
+pub fn rate_history() -> Vec<[f64; 2]> {
+    let running = current().is_running();
+    let mut history = HISTORY.lock().unwrap_or_else(PoisonError::into_inner);
+    if running {
+        history.roll(Instant::now());
+    }
+    let first = history.closed - history.rates.len();
+    history
+        .rates
+        .iter()
+        .enumerate()
+        .map(|(i, &clicks)| [(first + i) as f64, clicks as f64])
+        .collect()
+}
+
+/// Resets the figures at the start of a run.
+pub fn start() {
+    let now = Instant::now();
+    *STATS.lock().unwrap_or_else(PoisonError::into_inner) = RunStats {
+        started: Some(now),
+        ..RunStats::default()
+    };
+    *RUN.lock().unwrap_or_else(PoisonError::into_inner) = Some(RunRecord {
+        started: SystemTime::now(),
+        ended: SystemTime::now(),
+        elapsed: Duration::ZERO,
+        clicks: 0,
+        target_cps: None,
+        config: String::new(),
+        profile: Profile::default(),
+    });
+    let mut history = HISTORY.lock().unwrap_or_else(PoisonError::into_inner);
+    history.second_start = Some(now);
+    history.current = 0;
+    history.closed = 0;
+    history.rates.clear();
+}
+
+/// Notes the settings of the current run for its record, see [`runs`].
+pub fn describe_run(target_cps: Option<f64>, config: String, profile: Profile) {
+    if let Some(run) = RUN.lock().unwrap_or_else(PoisonError::into_inner).as_mut() {
+        run.target_cps = target_cps;
+        run.config = config;
+        run.profile = profile;
+    }
+}
+
+/// Freezes the elapsed time at the end of a run and records the run.
+pub fn stop() {
+    pause();
+    let stats = current();
+    let Some(mut run) = RUN.lock().unwrap_or_else(PoisonError::into_inner).take() else {
+        return;
+    };
+    run.ended = SystemTime::now();
+    run.elapsed = stats.elapsed();
+    run.clicks = stats.clicks;
+    RUN_SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|tx| tx.send(run.clone()).is_ok());
+    let mut runs = RUNS.lock().unwrap_or_else(PoisonError::into_inner);
+    if runs.len() == MAX_RUNS {
+        runs.pop_front();
+    }
+    runs.push_back(run);
+}
+
+/// Freezes the figures while a run is paused, see [`resume`].
+pub fn pause() {
+    let mut stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    if stats.is_running() {
+        stats.finished = Some(stats.elapsed());
+        HISTORY
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .roll(Instant::now());
+    }
+}
+
+/// Continues the figures of a paused run, leaving out the time it was
+/// paused.
+pub fn resume() {
+    let mut stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    let (Some(started), Some(elapsed)) = (stats.started, stats.finished.take()) else {
+        return;
+    };
+    let now = Instant::now();
+    let paused = now.saturating_duration_since(started + elapsed);
+    stats.started = Some(now - elapsed);
+    let mut history = HISTORY.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(second_start) = &mut history.second_start {
+        *second_start += paused;
+    }
+}
+
+/// Counts a press sent during a run.
+pub fn count_click() {
+    let mut stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    if stats.is_running() {
+        stats.clicks += 1;
+        let mut history = HISTORY.lock().unwrap_or_else(PoisonError::into_inner);
+        history.roll(Instant::now());
+        history.current += 1;
+    }
+}
+
+/// The runs that ended since the app started, oldest first.
+pub fn runs() -> Vec<RunRecord> {
+    RUNS.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Returns a receiver for every run that ends from now on.
+pub fn subscribe_runs() -> Receiver<RunRecord> {
+    let (tx, rx) = mpsc::channel();
+    RUN_SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(tx);
+    rx
+}
+
+/// Writes `runs` to `path` as CSV, one line per run after a header, for
+/// spreadsheets and scripts.
+pub fn write_csv(path: &Path, runs: &[RunRecord]) -> io::Result<()> {
+    let mut csv = String::from("started,ended,elapsed_s,clicks,average_cps,target_cps,config\n");
+    for run in runs {
+        let fields = [
+            humantime::format_rfc3339_seconds(run.started).to_string(),
+            humantime::format_rfc3339_seconds(run.ended).to_string(),
+            format!("{:.3}", run.elapsed.as_secs_f64()),
+            run.clicks.to_string(),
+            format!("{:.3}", run.clicks_per_second()),
+            run.target_cps
+                .map_or_else(String::new, |cps| format!("{cps:.3}")),
+            run.config.clone(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    fs::write(path, csv)
+}
+
+/// Quotes a field that holds a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
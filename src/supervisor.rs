@@ -0,0 +1,320 @@
+//! Supervision of the clicking loop's thread: if it panics it is started
+//! again with the settings it was last sent, and what went wrong is kept for
+//! the status bar, as is input that keeps failing to send, e.g. for lack of
+//! permissions.
+
+use std::{
+    any::Any,
+    mem,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::clicker::{self, Command, Config};
+
+/// A thread that panics more often than this within [`RESTART_WINDOW`] is
+/// left stopped.
+const MAX_RESTARTS: usize = 3;
+
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+/// Failed sends in a row after which input counts as failing.
+const FAILING_SENDS: usize = 10;
+
+/// What went wrong with the clicking loop, until dismissed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+    /// The thread panicked and was started again, `restarts` times so far.
+    Restarted { error: String, restarts: usize },
+    /// The thread panicked too often and was left stopped, see
+    /// [`request_restart`].
+    Stopped { error: String },
+    /// Sending input failed [`FAILING_SENDS`] times in a row.
+    Input { error: String },
+}
+
+static FAILURE: Mutex<Option<Failure>> = Mutex::new(None);
+
+static SEND_FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+/// Reaches the running supervisor, see [`Event`].
+static EVENTS: Mutex<Option<Sender<Event>>> = Mutex::new(None);
+
+/// What the supervisor waits for, all on one channel so it sleeps until
+/// one of them happens.
+enum Event {
+    Command(Command),
+    /// The clicking loop's thread ended, usually by panicking.
+    WorkerExited,
+    /// See [`request_restart`].
+    RestartRequested,
+}
+
+pub fn failure() -> Option<Failure> {
+    FAILURE.lock().unwrap().clone()
+}
+
+pub fn dismiss() {
+    *FAILURE.lock().unwrap() = None;
+}
+
+/// Starts a thread left stopped by [`Failure::Stopped`] again.
+pub fn request_restart() {
+    if let Some(tx_event) = EVENTS.lock().unwrap().as_ref() {
+        tx_event.send(Event::RestartRequested).ok();
+    }
+}
+
+/// Counts a failed send; called by the clicking loop.
+pub fn send_failed(error: &str) {
+    if SEND_FAILURES.fetch_add(1, Ordering::SeqCst) + 1 == FAILING_SENDS {
+        log::error!("Sending input keeps failing: {error}");
+        *FAILURE.lock().unwrap() = Some(Failure::Input {
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Ends a run of failed sends; called by the clicking loop.
+pub fn send_succeeded() {
+    if SEND_FAILURES.swap(0, Ordering::SeqCst) >= FAILING_SENDS {
+        let mut failure = FAILURE.lock().unwrap();
+        if matches!(*failure, Some(Failure::Input { .. })) {
+            *failure = None;
+        }
+    }
+}
+
+/// Runs the clicking loop on a thread of its own and passes it the commands
+/// from `rx_command` until [`Command::Shutdown`], starting it again whenever
+/// it panics.
+pub fn run(rx_command: Receiver<Command>) {
+    supervise(rx_command, |rx_command| {
+        clicker::run(rx_command, None, false)
+    });
+}
+
+/// [`run`] with `run_loop` as the clicking loop.
+fn supervise(rx_command: Receiver<Command>, run_loop: fn(Receiver<Command>)) {
+    let (tx_event, rx_event) = mpsc::channel();
+    *EVENTS.lock().unwrap() = Some(tx_event.clone());
+
+    // Commands are passed on to the event channel, so one `recv` waits for
+    // them and for the thread to end alike.
+    {
+        let tx_event = tx_event.clone();
+        thread::spawn(move || {
+            for command in rx_command {
+                if tx_event.send(Event::Command(command)).is_err() {
+                    return;
+                }
+            }
+            tx_event.send(Event::Command(Command::Shutdown)).ok();
+        });
+    }
+
+    let mut supervisor = Supervisor {
+        configs: Vec::new(),
+        worker: None,
+        restarts: Vec::new(),
+        total_restarts: 0,
+        tx_event,
+        run_loop,
+    };
+    supervisor.worker = Some(supervisor.spawn_worker());
+    loop {
+        match rx_event.recv() {
+            Ok(Event::Command(Command::Shutdown)) | Err(_) => {
+                *EVENTS.lock().unwrap() = None;
+                if let Some(worker) = supervisor.worker.take() {
+                    worker.tx_command.send(Command::Shutdown).ok();
+                    worker.thread.join().ok();
+                }
+                return;
+            }
+            Ok(Event::Command(command)) => supervisor.forward(command),
+            Ok(Event::WorkerExited) => supervisor.worker_exited(),
+            Ok(Event::RestartRequested) => supervisor.restart_requested(),
+        }
+    }
+}
+
+/// A running clicking loop.
+struct Worker {
+    tx_command: Sender<Command>,
+    thread: JoinHandle<()>,
+}
+
+/// Sends [`Event::WorkerExited`] when dropped.
+struct ExitNotice(Sender<Event>);
+
+impl Drop for ExitNotice {
+    fn drop(&mut self) {
+        self.0.send(Event::WorkerExited).ok();
+    }
+}
+
+struct Supervisor {
+    /// The last of each kind of setting, to start a new loop with.
+    configs: Vec<Config>,
+    /// `None` once left stopped.
+    worker: Option<Worker>,
+    /// When the loop was started again within [`RESTART_WINDOW`].
+    restarts: Vec<Instant>,
+    total_restarts: usize,
+    tx_event: Sender<Event>,
+    run_loop: fn(Receiver<Command>),
+}
+
+impl Supervisor {
+    /// Starts a clicking loop, stopped and with the last settings, which
+    /// sends [`Event::WorkerExited`] when it ends.
+    fn spawn_worker(&self) -> Worker {
+        let (tx_command, rx_command) = mpsc::channel();
+        for config in &self.configs {
+            tx_command.send(config.clone().into()).ok();
+        }
+        let exited = ExitNotice(self.tx_event.clone());
+        let run_loop = self.run_loop;
+        let thread = thread::spawn(move || {
+            // Dropped when the loop returns or while a panic unwinds.
+            let _exited = exited;
+            run_loop(rx_command);
+        });
+        Worker { tx_command, thread }
+    }
+
+    /// Starts the loop again if asked to after it was left stopped.
+    fn restart_requested(&mut self) {
+        if self.worker.is_some() {
+            return;
+        }
+        log::info!("Starting the clicker thread again");
+        self.restarts.clear();
+        dismiss();
+        self.worker = Some(self.spawn_worker());
+    }
+
+    /// Starts the loop again after its thread ended, unless it did so too
+    /// often.
+    fn worker_exited(&mut self) {
+        let Some(worker) = self.worker.take() else {
+            return;
+        };
+        let error = match worker.thread.join() {
+            Ok(()) => "The clicker thread exited".to_string(),
+            Err(payload) => panic_message(payload),
+        };
+        log::error!("The clicker thread stopped: {error}");
+        clicker::reset();
+
+        let now = Instant::now();
+        self.restarts
+            .retain(|restart| now.duration_since(*restart) < RESTART_WINDOW);
+        if self.restarts.len() >= MAX_RESTARTS {
+            log::error!(
+                "The clicker thread stopped {} times within {}, so it is left stopped",
+                self.restarts.len() + 1,
+                humantime::format_duration(RESTART_WINDOW)
+            );
+            *FAILURE.lock().unwrap() = Some(Failure::Stopped { error });
+            return;
+        }
+        self.restarts.push(now);
+        self.total_restarts += 1;
+        *FAILURE.lock().unwrap() = Some(Failure::Restarted {
+            error,
+            restarts: self.total_restarts,
+        });
+        self.worker = Some(self.spawn_worker());
+    }
+
+    fn forward(&mut self, command: Command) {
+        if let Command::UpdateConfig(config) = &command {
+            let kind = mem::discriminant(config);
+            match self
+                .configs
+                .iter_mut()
+                .find(|known| mem::discriminant(*known) == kind)
+            {
+                Some(known) => *known = config.clone(),
+                None => self.configs.push(config.clone()),
+            }
+        }
+        let sent = self
+            .worker
+            .as_ref()
+            .is_some_and(|worker| worker.tx_command.send(command).is_ok());
+        if !sent {
+            log::warn!("The clicker thread is stopped; the command was dropped");
+        }
+    }
+}
+
+/// The message a thread panicked with.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "The clicker thread panicked".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+    use crate::schedule::{StartMode, StartSchedule};
+
+    static FAILED: AtomicBool = AtomicBool::new(false);
+
+    /// Fails the first time, then runs the real loop.
+    fn flaky_loop(rx_command: Receiver<Command>) {
+        if !FAILED.swap(true, Ordering::SeqCst) {
+            clicker::panic_holding_locks();
+        }
+        clicker::run(rx_command, None, false);
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let since = Instant::now();
+        while !condition() {
+            assert!(since.elapsed() < Duration::from_secs(5), "timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn restarted_loop_runs() {
+        let (tx_command, rx_command) = mpsc::channel();
+        let supervisor = thread::spawn(move || supervise(rx_command, flaky_loop));
+        wait_until(|| matches!(failure(), Some(Failure::Restarted { restarts: 1, .. })));
+
+        // The countdown keeps the run from clicking, and needs the locks the
+        // failed loop poisoned.
+        let schedule = StartSchedule {
+            mode: StartMode::Countdown,
+            countdown_secs: 60,
+            ..StartSchedule::default()
+        };
+        tx_command
+            .send(Config::StartSchedule(schedule).into())
+            .unwrap();
+        tx_command.send(Command::Start).unwrap();
+        wait_until(|| clicker::scheduled_start().is_some());
+        assert!(clicker::is_running());
+
+        tx_command.send(Command::Stop).unwrap();
+        wait_until(|| !clicker::is_running() && clicker::scheduled_start().is_none());
+        tx_command.send(Command::Shutdown).unwrap();
+        supervisor.join().unwrap();
+    }
+}
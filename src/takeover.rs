@@ -0,0 +1,66 @@
+//! Pausing the clicker while the user moves the mouse, so they can take
+//! over for a moment without reaching for a hotkey. Clicking carries on
+//! once the mouse has been left alone for the grace period.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rdev::EventType;
+
+use crate::{listener, source};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How long clicking stays paused after the last move, in milliseconds.
+static GRACE_MS: AtomicU64 = AtomicU64::new(1000);
+
+/// When the user last moved the mouse.
+static LAST_MOVE: Mutex<Option<Instant>> = Mutex::new(None);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn set_grace(grace: Duration) {
+    GRACE_MS.store(grace.as_millis() as u64, Ordering::SeqCst);
+}
+
+/// Starts a thread that notes when the user moves the mouse. Cursor moves
+/// the clicker made itself are ignored.
+pub fn spawn_watcher() {
+    let rx_events = listener::subscribe();
+    thread::spawn(move || {
+        for event in rx_events {
+            let EventType::MouseMove { x, y } = event.event_type else {
+                continue;
+            };
+            if is_enabled() && !source::is_own_move(x, y) {
+                *LAST_MOVE.lock().unwrap() = Some(Instant::now());
+            }
+        }
+    });
+}
+
+/// Until when clicking waits because the user has the mouse, or `None` if
+/// it need not.
+pub fn paused_until() -> Option<Instant> {
+    if !is_enabled() {
+        return None;
+    }
+    let grace = Duration::from_millis(GRACE_MS.load(Ordering::SeqCst));
+    LAST_MOVE
+        .lock()
+        .unwrap()
+        .map(|moved| moved + grace)
+        .filter(|&until| Instant::now() < until)
+}
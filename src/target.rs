@@ -0,0 +1,211 @@
+/// A top-level window of another application that clicks can be aimed at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// The platform's handle: an `HWND` on Windows, an X window id on Linux.
+    pub id: u64,
+    pub title: String,
+}
+
+/// The visible, titled top-level windows, in the window manager's order.
+/// Empty where enumerating windows is not supported.
+#[cfg(windows)]
+pub fn list() -> Vec<WindowInfo> {
+    #[link(name = "user32")]
+    extern "system" {
+        fn EnumWindows(
+            callback: unsafe extern "system" fn(isize, isize) -> i32,
+            param: isize,
+        ) -> i32;
+        fn IsWindowVisible(hwnd: isize) -> i32;
+        fn GetWindowTextW(hwnd: isize, text: *mut u16, max_count: i32) -> i32;
+    }
+
+    unsafe extern "system" fn collect(hwnd: isize, param: isize) -> i32 {
+        // SAFETY: `param` is the `Vec` passed to EnumWindows below, which
+        // outlives the enumeration.
+        let windows = &mut *(param as *mut Vec<WindowInfo>);
+        if IsWindowVisible(hwnd) != 0 {
+            let mut text = [0u16; 256];
+            let len = GetWindowTextW(hwnd, text.as_mut_ptr(), text.len() as i32);
+            if len > 0 {
+                windows.push(WindowInfo {
+                    id: hwnd as u64,
+                    title: String::from_utf16_lossy(&text[..len as usize]),
+                });
+            }
+        }
+        1
+    }
+
+    let mut windows = Vec::new();
+    // SAFETY: the callback only writes to `windows`.
+    unsafe {
+        EnumWindows(collect, &mut windows as *mut Vec<WindowInfo> as isize);
+    }
+    windows
+}
+
+/// Whether [`foreground`] can tell which window is focused here.
+pub const fn is_foreground_supported() -> bool {
+    cfg!(any(target_os = "linux", windows))
+}
+
+/// The focused window if its title contains `title`, ignoring case.
+pub fn focused_matching(title: &str) -> Option<WindowInfo> {
+    foreground().filter(|window| window.title.to_lowercase().contains(&title.to_lowercase()))
+}
+
+/// Whether the window is still open.
+pub fn is_open(id: u64) -> bool {
+    client_origin(id).is_some()
+}
+
+/// The window that has the keyboard focus, or `None` if there is none or
+/// it cannot be queried.
+#[cfg(windows)]
+pub fn foreground() -> Option<WindowInfo> {
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> isize;
+        fn GetWindowTextW(hwnd: isize, text: *mut u16, max_count: i32) -> i32;
+    }
+
+    // SAFETY: the calls only take a window handle and write to `text`.
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd == 0 {
+            return None;
+        }
+        let mut text = [0u16; 256];
+        let len = GetWindowTextW(hwnd, text.as_mut_ptr(), text.len() as i32);
+        Some(WindowInfo {
+            id: hwnd as u64,
+            title: String::from_utf16_lossy(&text[..len.max(0) as usize]),
+        })
+    }
+}
+
+/// Where the top-left corner of the window's client area currently is on
+/// screen, or `None` once the window is gone.
+#[cfg(windows)]
+pub fn client_origin(id: u64) -> Option<(i32, i32)> {
+    #[repr(C)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn IsWindow(hwnd: isize) -> i32;
+        fn ClientToScreen(hwnd: isize, point: *mut Point) -> i32;
+    }
+
+    let hwnd = id as isize;
+    let mut point = Point { x: 0, y: 0 };
+    // SAFETY: the calls only take a window handle, which they validate,
+    // and write to `point`.
+    unsafe {
+        if IsWindow(hwnd) == 0 || ClientToScreen(hwnd, &mut point) == 0 {
+            return None;
+        }
+    }
+    Some((point.x, point.y))
+}
+
+/// Lists the window manager's client windows with `xprop`, from the
+/// x11-utils package.
+#[cfg(target_os = "linux")]
+pub fn list() -> Vec<WindowInfo> {
+    let Some(clients) = xprop(&["-root", "_NET_CLIENT_LIST"]) else {
+        return Vec::new();
+    };
+    // _NET_CLIENT_LIST(WINDOW): window id # 0x1e00003, 0x2200003
+    let Some((_, ids)) = clients.split_once('#') else {
+        return Vec::new();
+    };
+
+    ids.split(',')
+        .filter_map(parse_id)
+        .filter_map(|id| {
+            let title = title(id)?;
+            (!title.is_empty()).then_some(WindowInfo { id, title })
+        })
+        .collect()
+}
+
+/// Asks `xprop` for the window manager's active window.
+#[cfg(target_os = "linux")]
+pub fn foreground() -> Option<WindowInfo> {
+    // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x1e00003
+    let active = xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+    let (_, id) = active.split_once('#')?;
+    let id = parse_id(id).filter(|&id| id != 0)?;
+    Some(WindowInfo {
+        id,
+        title: title(id).unwrap_or_default(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn parse_id(id: &str) -> Option<u64> {
+    u64::from_str_radix(id.trim().strip_prefix("0x")?, 16).ok()
+}
+
+#[cfg(target_os = "linux")]
+fn title(id: u64) -> Option<String> {
+    // _NET_WM_NAME(UTF8_STRING) = "Title"
+    let name = xprop(&["-id", &id.to_string(), "_NET_WM_NAME"])?;
+    let (_, title) = name.split_once(" = ")?;
+    Some(title.trim().trim_matches('"').replace("\\\"", "\""))
+}
+
+/// Asks `xwininfo` for the window's absolute position.
+#[cfg(target_os = "linux")]
+pub fn client_origin(id: u64) -> Option<(i32, i32)> {
+    let output = std::process::Command::new("xwininfo")
+        .args(["-id", &id.to_string()])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let info = String::from_utf8_lossy(&output.stdout);
+    let field = |name: &str| {
+        info.lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some((
+        field("Absolute upper-left X:")?,
+        field("Absolute upper-left Y:")?,
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn xprop(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("xprop")
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn list() -> Vec<WindowInfo> {
+    Vec::new()
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn foreground() -> Option<WindowInfo> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn client_origin(_id: u64) -> Option<(i32, i32)> {
+    None
+}
@@ -0,0 +1,209 @@
+use std::time::Instant;
+
+use winit::window::Window;
+
+use crate::{clicker, gui, stats};
+
+/// What the window's taskbar button shows besides the title.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    None,
+    /// Busy without a known end.
+    Indeterminate,
+    /// This far through the run, from 0 to 1.
+    Fraction(f64),
+}
+
+/// The window title and taskbar progress for the clicker's state, e.g.
+/// `Auto Clicker — RUNNING 12.4 CPS`.
+pub fn status() -> (String, Progress) {
+    if let Some(start) = clicker::scheduled_start() {
+        return (
+            format!("Auto Clicker — STARTING IN {}", gui::countdown(start)),
+            Progress::Indeterminate,
+        );
+    }
+    if !clicker::is_running() {
+        return ("Auto Clicker".to_owned(), Progress::None);
+    }
+
+    let stats = stats::current();
+    let title = format!(
+        "Auto Clicker — RUNNING {:.1} CPS",
+        stats.clicks_per_second()
+    );
+    match clicker::run_deadline() {
+        Some(deadline) => {
+            let elapsed = stats.elapsed().as_secs_f64();
+            let left = deadline
+                .saturating_duration_since(Instant::now())
+                .as_secs_f64();
+            let fraction = if elapsed + left > 0.0 {
+                elapsed / (elapsed + left)
+            } else {
+                1.0
+            };
+            (
+                format!("{title}, {} left", gui::countdown(deadline)),
+                Progress::Fraction(fraction),
+            )
+        }
+        None => (title, Progress::Indeterminate),
+    }
+}
+
+/// Shows [`Progress`] on the taskbar button where the platform has one:
+/// the progress bar of the Windows taskbar. Elsewhere only the title shows
+/// the state.
+#[derive(Default)]
+pub struct Taskbar {
+    #[cfg(windows)]
+    list: Option<windows::TaskbarList>,
+    /// What was shown last, so unchanged progress is not sent again.
+    shown: Option<Progress>,
+}
+
+impl Taskbar {
+    pub fn set_progress(&mut self, window: &Window, progress: Progress) {
+        if self.shown == Some(progress) {
+            return;
+        }
+        self.shown = Some(progress);
+
+        #[cfg(windows)]
+        {
+            use winit::platform::windows::WindowExtWindows;
+
+            if self.list.is_none() {
+                self.list = windows::TaskbarList::new();
+            }
+            if let Some(list) = &self.list {
+                list.set_progress(window.hwnd(), progress);
+            }
+        }
+        #[cfg(not(windows))]
+        let _ = window;
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::{ffi::c_void, ptr};
+
+    use super::Progress;
+
+    #[repr(C)]
+    struct Guid(u32, u16, u16, [u8; 8]);
+
+    const CLSID_TASKBAR_LIST: Guid = Guid(
+        0x56FD_F344,
+        0xFD6D,
+        0x11D0,
+        [0x95, 0x8A, 0x00, 0x60, 0x97, 0xC9, 0xA0, 0x90],
+    );
+    const IID_ITASKBAR_LIST3: Guid = Guid(
+        0xEA1A_FB91,
+        0x9E28,
+        0x4B86,
+        [0x90, 0xE9, 0x9E, 0x9F, 0x8A, 0x5E, 0xEF, 0xAF],
+    );
+
+    const COINIT_APARTMENTTHREADED: u32 = 0x2;
+    const CLSCTX_INPROC_SERVER: u32 = 0x1;
+    const TBPF_NOPROGRESS: u32 = 0x0;
+    const TBPF_INDETERMINATE: u32 = 0x1;
+    const TBPF_NORMAL: u32 = 0x2;
+    /// Steps of the progress value passed to the taskbar.
+    const PROGRESS_STEPS: u64 = 1000;
+
+    #[link(name = "ole32")]
+    extern "system" {
+        fn CoInitializeEx(reserved: *mut c_void, co_init: u32) -> i32;
+        fn CoCreateInstance(
+            clsid: *const Guid,
+            outer: *mut c_void,
+            context: u32,
+            iid: *const Guid,
+            object: *mut *mut c_void,
+        ) -> i32;
+    }
+
+    /// The start of the `ITaskbarList3` vtable, up to the methods used.
+    #[repr(C)]
+    struct Vtbl {
+        query_interface: usize,
+        add_ref: usize,
+        release: unsafe extern "system" fn(*mut Object) -> u32,
+        hr_init: unsafe extern "system" fn(*mut Object) -> i32,
+        add_tab: usize,
+        delete_tab: usize,
+        activate_tab: usize,
+        set_active_alt: usize,
+        mark_fullscreen_window: usize,
+        set_progress_value: unsafe extern "system" fn(*mut Object, isize, u64, u64) -> i32,
+        set_progress_state: unsafe extern "system" fn(*mut Object, isize, u32) -> i32,
+    }
+
+    #[repr(C)]
+    struct Object {
+        vtbl: *const Vtbl,
+    }
+
+    /// An `ITaskbarList3`, used from the event loop thread only.
+    pub struct TaskbarList(*mut Object);
+
+    impl TaskbarList {
+        pub fn new() -> Option<Self> {
+            let mut object: *mut c_void = ptr::null_mut();
+            // SAFETY: COM may already be initialized on this thread, which
+            // is fine to ignore; the object is only used through its vtable
+            // and released on drop.
+            unsafe {
+                CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED);
+                let result = CoCreateInstance(
+                    &CLSID_TASKBAR_LIST,
+                    ptr::null_mut(),
+                    CLSCTX_INPROC_SERVER,
+                    &IID_ITASKBAR_LIST3,
+                    &mut object,
+                );
+                if result < 0 || object.is_null() {
+                    return None;
+                }
+                let list = Self(object.cast());
+                ((*(*list.0).vtbl).hr_init)(list.0);
+                Some(list)
+            }
+        }
+
+        pub fn set_progress(&self, hwnd: isize, progress: Progress) {
+            // SAFETY: the object is alive until drop and `hwnd` is our own
+            // window.
+            unsafe {
+                let vtbl = &*(*self.0).vtbl;
+                match progress {
+                    Progress::None => {
+                        (vtbl.set_progress_state)(self.0, hwnd, TBPF_NOPROGRESS);
+                    }
+                    Progress::Indeterminate => {
+                        (vtbl.set_progress_state)(self.0, hwnd, TBPF_INDETERMINATE);
+                    }
+                    Progress::Fraction(fraction) => {
+                        let done = (fraction.clamp(0.0, 1.0) * PROGRESS_STEPS as f64) as u64;
+                        (vtbl.set_progress_state)(self.0, hwnd, TBPF_NORMAL);
+                        (vtbl.set_progress_value)(self.0, hwnd, done, PROGRESS_STEPS);
+                    }
+                }
+            }
+        }
+    }
+
+    impl Drop for TaskbarList {
+        fn drop(&mut self) {
+            // SAFETY: releases the reference taken by CoCreateInstance.
+            unsafe {
+                ((*(*self.0).vtbl).release)(self.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    screen::{self, Capture},
+    timeline::{self, Activity},
+};
+
+/// How long the watcher rests between screenshots.
+const POLL: Duration = Duration::from_millis(500);
+
+/// The coarse pass works on images shrunk by up to this factor.
+const MAX_SCALE: usize = 4;
+
+/// Candidates from the coarse pass that are refined at full size.
+const CANDIDATES: usize = 5;
+
+/// Where the template was last seen on screen, for the GUI.
+static LAST_MATCH: Mutex<Option<Match>> = Mutex::new(None);
+
+/// What the clicker does with an image it looks for.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ImageMode {
+    /// Click the center of the image wherever it is found.
+    #[default]
+    ClickCenter,
+    /// Click as usual, but only while the image is on screen.
+    WhileVisible,
+}
+
+/// A PNG to look for on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageTarget {
+    pub path: PathBuf,
+    /// Minimum normalized cross-correlation, in percent, for a match.
+    pub threshold_pct: usize,
+    pub mode: ImageMode,
+}
+
+impl Default for ImageTarget {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::new(),
+            threshold_pct: 90,
+            mode: ImageMode::default(),
+        }
+    }
+}
+
+/// A sighting of the template.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    /// The screen position of the template's center.
+    pub x: usize,
+    pub y: usize,
+    /// Normalized cross-correlation, from -1 to 1.
+    pub score: f32,
+}
+
+pub fn last_match() -> Option<Match> {
+    *LAST_MATCH.lock().unwrap()
+}
+
+/// A grayscale image.
+#[derive(Debug, Clone)]
+pub struct Gray {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+}
+
+impl Gray {
+    fn from_rgb(width: usize, height: usize, pixels: impl Iterator<Item = [u8; 3]>) -> Self {
+        Self {
+            width,
+            height,
+            values: pixels
+                .map(|[r, g, b]| 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32)
+                .collect(),
+        }
+    }
+
+    fn from_capture(capture: &Capture) -> Self {
+        Self::from_rgb(
+            capture.width,
+            capture.height,
+            capture.pixels.iter().copied(),
+        )
+    }
+
+    /// Loads a PNG, flattening transparency onto black.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |e: png::DecodingError| io::Error::new(io::ErrorKind::InvalidData, e);
+
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(invalid)?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(invalid)?;
+        let buf = &buf[..info.buffer_size()];
+        let (width, height) = (info.width as usize, info.height as usize);
+
+        let alpha = |value: u8, a: u8| (value as u16 * a as u16 / 255) as u8;
+        let image = match info.color_type {
+            png::ColorType::Grayscale => {
+                Self::from_rgb(width, height, buf.iter().map(|&v| [v, v, v]))
+            }
+            png::ColorType::GrayscaleAlpha => Self::from_rgb(
+                width,
+                height,
+                buf.chunks_exact(2).map(|p| {
+                    let v = alpha(p[0], p[1]);
+                    [v, v, v]
+                }),
+            ),
+            png::ColorType::Rgb => Self::from_rgb(
+                width,
+                height,
+                buf.chunks_exact(3).map(|p| [p[0], p[1], p[2]]),
+            ),
+            png::ColorType::Rgba => Self::from_rgb(
+                width,
+                height,
+                buf.chunks_exact(4)
+                    .map(|p| [alpha(p[0], p[3]), alpha(p[1], p[3]), alpha(p[2], p[3])]),
+            ),
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unexpanded palette image",
+                ))
+            }
+        };
+        if image.width == 0 || image.height == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "empty image"));
+        }
+        Ok(image)
+    }
+
+    /// Shrinks the image by `factor`, averaging each `factor`² block.
+    fn downscale(&self, factor: usize) -> Self {
+        if factor <= 1 {
+            return self.clone();
+        }
+        let width = self.width / factor;
+        let height = self.height / factor;
+        let mut values = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for dy in 0..factor {
+                    let row = (y * factor + dy) * self.width + x * factor;
+                    sum += self.values[row..row + factor].iter().sum::<f32>();
+                }
+                values.push(sum / (factor * factor) as f32);
+            }
+        }
+        Self {
+            width,
+            height,
+            values,
+        }
+    }
+
+    /// Normalized cross-correlation of `template` placed with its top-left
+    /// corner at `(x, y)`. A flat window or template scores 0.
+    fn ncc(&self, template: &Gray, x: usize, y: usize) -> f32 {
+        let n = (template.width * template.height) as f32;
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        let mut sum_t = 0.0;
+        let mut sum_t_sq = 0.0;
+        let mut sum_it = 0.0;
+        for ty in 0..template.height {
+            let row = (y + ty) * self.width + x;
+            let image_row = &self.values[row..row + template.width];
+            let template_row = &template.values[ty * template.width..(ty + 1) * template.width];
+            for (&i, &t) in image_row.iter().zip(template_row) {
+                sum += i;
+                sum_sq += i * i;
+                sum_t += t;
+                sum_t_sq += t * t;
+                sum_it += i * t;
+            }
+        }
+        let covariance = sum_it - sum * sum_t / n;
+        let variance = (sum_sq - sum * sum / n) * (sum_t_sq - sum_t * sum_t / n);
+        if variance <= f32::EPSILON {
+            0.0
+        } else {
+            covariance / variance.sqrt()
+        }
+    }
+}
+
+/// Finds the best match of `template` in `image`, as its top-left corner
+/// and score.
+///
+/// A full-size search is far too slow for a whole screen, so both images
+/// are first shrunk and searched everywhere, and only the best few coarse
+/// positions are refined at full size.
+pub fn find(image: &Gray, template: &Gray) -> Option<(usize, usize, f32)> {
+    if template.width > image.width || template.height > image.height {
+        return None;
+    }
+
+    // Keep at least 4 pixels of template on each side after shrinking.
+    let scale = (template.width.min(template.height) / 4).clamp(1, MAX_SCALE);
+    let small_image = image.downscale(scale);
+    let small_template = template.downscale(scale);
+
+    let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+    for y in 0..=small_image.height - small_template.height {
+        for x in 0..=small_image.width - small_template.width {
+            let score = small_image.ncc(&small_template, x, y);
+            if candidates.len() < CANDIDATES || score > candidates[CANDIDATES - 1].0 {
+                candidates.push((score, x, y));
+                candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+                candidates.truncate(CANDIDATES);
+            }
+        }
+    }
+
+    let max_x = image.width - template.width;
+    let max_y = image.height - template.height;
+    candidates
+        .into_iter()
+        .flat_map(|(_, cx, cy)| {
+            let xs = (cx * scale).saturating_sub(scale)..=(cx * scale + scale).min(max_x);
+            let ys = (cy * scale).saturating_sub(scale)..=(cy * scale + scale).min(max_y);
+            ys.flat_map(move |y| xs.clone().map(move |x| (x, y)))
+        })
+        .map(|(x, y)| (x, y, image.ncc(template, x, y)))
+        .max_by(|a, b| a.2.total_cmp(&b.2))
+}
+
+/// Screenshots the display in the background and looks for an image in
+/// each one. Stops when dropped.
+pub struct ImageWatch {
+    target: ImageTarget,
+    found: Arc<Mutex<Option<Match>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ImageWatch {
+    pub fn start(target: ImageTarget) -> io::Result<Self> {
+        let template = Gray::load(&target.path)?;
+        let threshold = target.threshold_pct as f32 / 100.0;
+        let found = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+        *LAST_MATCH.lock().unwrap() = None;
+
+        {
+            let found = found.clone();
+            let stop = stop.clone();
+            thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    let sighting = screen::capture().and_then(|capture| {
+                        let (x, y, score) = find(&Gray::from_capture(&capture), &template)?;
+                        (score >= threshold).then(|| Match {
+                            x: (capture.left + (x + template.width / 2) as i32).max(0) as usize,
+                            y: (capture.top + (y + template.height / 2) as i32).max(0) as usize,
+                            score,
+                        })
+                    });
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    timeline::record(Activity::Trigger {
+                        met: sighting.is_some(),
+                    });
+                    *found.lock().unwrap() = sighting;
+                    *LAST_MATCH.lock().unwrap() = sighting;
+                    thread::sleep(POLL);
+                }
+            });
+        }
+
+        Ok(Self {
+            target,
+            found,
+            stop,
+        })
+    }
+
+    pub fn mode(&self) -> ImageMode {
+        self.target.mode
+    }
+
+    /// Where the image was in the latest screenshot, if it was there.
+    pub fn found(&self) -> Option<Match> {
+        *self.found.lock().unwrap()
+    }
+}
+
+impl Drop for ImageWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        *LAST_MATCH.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An image of pseudo-random noise, the same on every run.
+    fn noise(width: usize, height: usize, seed: u32) -> Gray {
+        let mut state = seed;
+        let values = (0..width * height)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as f32
+            })
+            .collect();
+        Gray {
+            width,
+            height,
+            values,
+        }
+    }
+
+    fn crop(image: &Gray, x: usize, y: usize, width: usize, height: usize) -> Gray {
+        let values = (y..y + height)
+            .flat_map(|row| {
+                let start = row * image.width + x;
+                image.values[start..start + width].iter().copied()
+            })
+            .collect();
+        Gray {
+            width,
+            height,
+            values,
+        }
+    }
+
+    #[test]
+    fn finds_an_exact_match() {
+        let image = noise(64, 48, 1);
+        let template = crop(&image, 37, 21, 16, 12);
+        let (x, y, score) = find(&image, &template).unwrap();
+        assert_eq!((x, y), (37, 21));
+        assert!(score > 0.999, "{score}");
+    }
+
+    #[test]
+    fn an_absent_template_scores_below_the_threshold() {
+        let image = noise(64, 48, 1);
+        let template = noise(16, 12, 2);
+        let (_, _, score) = find(&image, &template).unwrap();
+        let threshold = ImageTarget::default().threshold_pct as f32 / 100.0;
+        assert!(score < threshold, "{score}");
+    }
+
+    #[test]
+    fn a_flat_template_scores_zero() {
+        let image = noise(64, 48, 1);
+        let template = Gray {
+            width: 16,
+            height: 12,
+            values: vec![128.0; 16 * 12],
+        };
+        assert_eq!(image.ncc(&template, 10, 10), 0.0);
+        let (_, _, score) = find(&image, &template).unwrap();
+        assert_eq!(score, 0.0);
+    }
+}
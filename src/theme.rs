@@ -0,0 +1,239 @@
+use std::{fs, io, path::PathBuf};
+
+#[cfg(feature = "gui")]
+use egui::{Color32, FontId, Rounding, Style, TextStyle, Visuals};
+
+use crate::config::{self, Table};
+
+/// Style overrides loaded from `<config dir>/themes/<name>.toml`.
+///
+/// Every key is optional; anything not set keeps the value of the base
+/// visuals (`base = "dark"` or `base = "light"`).
+///
+/// ```toml
+/// base = "dark"
+///
+/// [colors]
+/// accent = "#3d85c6"
+/// panel = "#1e1e1e"
+///
+/// [rounding]
+/// widget = 4.0
+///
+/// [spacing]
+/// item_x = 8.0
+///
+/// [fonts]
+/// heading = 20.0
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    table: Table,
+}
+
+impl Theme {
+    /// Names of all theme files found in the themes directory.
+    pub fn list() -> Vec<String> {
+        let Some(dir) = themes_dir() else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "toml" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_owned())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn load(name: &str) -> io::Result<Self> {
+        let dir = themes_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no configuration directory"))?;
+        let source = fs::read_to_string(dir.join(format!("{name}.toml")))?;
+
+        Ok(Self {
+            name: name.to_owned(),
+            table: Table::parse(&source)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        })
+    }
+
+    /// Builds the full egui style for this theme on top of `style`.
+    #[cfg(feature = "gui")]
+    pub fn style(&self, style: &Style) -> Style {
+        let mut style = style.clone();
+        let table = &self.table;
+
+        match table.get_str("base").as_deref() {
+            Some("light") => style.visuals = Visuals::light(),
+            Some("dark") => style.visuals = Visuals::dark(),
+            _ => {}
+        }
+
+        let visuals = &mut style.visuals;
+        if let Some(color) = self.color("colors.text") {
+            visuals.override_text_color = Some(color);
+        }
+        if let Some(color) = self.color("colors.panel") {
+            visuals.panel_fill = color;
+        }
+        if let Some(color) = self.color("colors.window") {
+            visuals.window_fill = color;
+        }
+        if let Some(color) = self.color("colors.extreme") {
+            visuals.extreme_bg_color = color;
+        }
+        if let Some(color) = self.color("colors.accent") {
+            set_accent(visuals, color);
+        }
+        if let Some(color) = self.color("colors.widget") {
+            visuals.widgets.inactive.bg_fill = color;
+            visuals.widgets.inactive.weak_bg_fill = color;
+        }
+        if let Some(color) = self.color("colors.widget_hovered") {
+            visuals.widgets.hovered.bg_fill = color;
+            visuals.widgets.hovered.weak_bg_fill = color;
+        }
+        if let Some(color) = self.color("colors.widget_active") {
+            visuals.widgets.active.bg_fill = color;
+            visuals.widgets.active.weak_bg_fill = color;
+        }
+
+        if let Some(radius) = table.get::<f32>("rounding.window") {
+            visuals.window_rounding = Rounding::same(radius);
+        }
+        if let Some(radius) = table.get::<f32>("rounding.widget") {
+            let widgets = &mut visuals.widgets;
+            for widget in [
+                &mut widgets.noninteractive,
+                &mut widgets.inactive,
+                &mut widgets.hovered,
+                &mut widgets.active,
+                &mut widgets.open,
+            ] {
+                widget.rounding = Rounding::same(radius);
+            }
+        }
+
+        let spacing = &mut style.spacing;
+        if let Some(x) = table.get("spacing.item_x") {
+            spacing.item_spacing.x = x;
+        }
+        if let Some(y) = table.get("spacing.item_y") {
+            spacing.item_spacing.y = y;
+        }
+        if let Some(x) = table.get("spacing.button_x") {
+            spacing.button_padding.x = x;
+        }
+        if let Some(y) = table.get("spacing.button_y") {
+            spacing.button_padding.y = y;
+        }
+
+        for (key, text_style) in [
+            ("fonts.small", TextStyle::Small),
+            ("fonts.body", TextStyle::Body),
+            ("fonts.button", TextStyle::Button),
+            ("fonts.heading", TextStyle::Heading),
+            ("fonts.monospace", TextStyle::Monospace),
+        ] {
+            if let Some(size) = table.get::<f32>(key) {
+                let family = style
+                    .text_styles
+                    .get(&text_style)
+                    .map(|font| font.family.clone())
+                    .unwrap_or_default();
+                style
+                    .text_styles
+                    .insert(text_style, FontId::new(size, family));
+            }
+        }
+
+        style
+    }
+
+    #[cfg(feature = "gui")]
+    fn color(&self, key: &str) -> Option<Color32> {
+        let value = self.table.get_str(key)?;
+        let Some([r, g, b, a]) = parse_hex_color(&value) else {
+            log::warn!("Theme {:?}: invalid color {value:?} for {key}", self.name);
+            return None;
+        };
+        Some(Color32::from_rgba_unmultiplied(r, g, b, a))
+    }
+}
+
+/// Whether the app is light or dark. A theme's `base` takes precedence.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Appearance {
+    /// Follow the OS light/dark setting.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    /// The visuals to start from, `system` for [`Appearance::System`].
+    #[cfg(feature = "gui")]
+    pub fn visuals(self, system: &Visuals) -> Visuals {
+        match self {
+            Appearance::System => system.clone(),
+            Appearance::Light => Visuals::light(),
+            Appearance::Dark => Visuals::dark(),
+        }
+    }
+
+    pub fn to_config(self) -> &'static str {
+        match self {
+            Appearance::System => "system",
+            Appearance::Light => "light",
+            Appearance::Dark => "dark",
+        }
+    }
+
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "light" => Appearance::Light,
+            "dark" => Appearance::Dark,
+            _ => Appearance::System,
+        }
+    }
+}
+
+/// Colors selections and links with `color`.
+#[cfg(feature = "gui")]
+pub fn set_accent(visuals: &mut Visuals, color: Color32) {
+    visuals.selection.bg_fill = color;
+    visuals.hyperlink_color = color;
+}
+
+/// Formats red, green and blue as `#rrggbb`.
+pub fn to_hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa` into red, green, blue and alpha, opaque
+/// without an alpha.
+pub fn parse_hex_color(value: &str) -> Option<[u8; 4]> {
+    let hex = value.strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    match hex.len() {
+        6 => Some([channel(0)?, channel(2)?, channel(4)?, 255]),
+        8 => Some([channel(0)?, channel(2)?, channel(4)?, channel(6)?]),
+        _ => None,
+    }
+}
+
+fn themes_dir() -> Option<PathBuf> {
+    config::config_dir().map(|dir| dir.join("themes"))
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+/// How far back the timeline reaches.
+pub const WINDOW: Duration = Duration::from_secs(60);
+
+/// Upper bound on kept entries, so a very fast clicker cannot grow the
+/// timeline without limit within its window.
+const MAX_ENTRIES: usize = 10_000;
+
+static ENTRIES: Mutex<VecDeque<(Activity, Instant)>> = Mutex::new(VecDeque::new());
+
+/// Receivers of every recorded activity, see [`subscribe`].
+static SUBSCRIBERS: Mutex<Vec<Sender<Activity>>> = Mutex::new(Vec::new());
+
+/// Something the engine did, as shown on the activity timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    Start,
+    Stop,
+    /// The run reached its click count or run time.
+    Finish,
+    /// A button or key press was sent.
+    Click,
+    /// Clicking paused for a guard or trigger.
+    Pause,
+    /// A trigger condition was checked.
+    Trigger {
+        met: bool,
+    },
+    /// A safety mechanism stopped the clicker.
+    Safety,
+    /// The window the run was aimed at closed, which stopped the clicker.
+    TargetClosed,
+    /// The user paused the run, see [`crate::clicker::Command::Pause`].
+    Suspend,
+    /// The paused run carries on.
+    Resume,
+    /// The run has gone on for another reminder interval.
+    Reminder,
+}
+
+impl Activity {
+    /// The name a start, stop, finish, safety stop, pause or resume is
+    /// reported under to webhooks and remote clients; `None` for the other
+    /// activities.
+    pub fn event_name(self) -> Option<&'static str> {
+        match self {
+            Activity::Start => Some("start"),
+            Activity::Stop => Some("stop"),
+            Activity::Finish => Some("finish"),
+            Activity::Safety => Some("safety_stop"),
+            Activity::TargetClosed => Some("target_closed"),
+            Activity::Suspend => Some("pause"),
+            Activity::Resume => Some("resume"),
+            Activity::Click | Activity::Pause | Activity::Trigger { .. } | Activity::Reminder => {
+                None
+            }
+        }
+    }
+}
+
+/// Adds `activity` to the timeline, dropping entries older than [`WINDOW`].
+pub fn record(activity: Activity) {
+    let now = Instant::now();
+    let mut entries = ENTRIES.lock().unwrap_or_else(PoisonError::into_inner);
+    while entries
+        .front()
+        .is_some_and(|(_, at)| now.duration_since(*at) > WINDOW)
+        || entries.len() >= MAX_ENTRIES
+    {
+        entries.pop_front();
+    }
+    entries.push_back((activity, now));
+    drop(entries);
+
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|tx| tx.send(activity).is_ok());
+}
+
+/// Returns a receiver for every activity recorded from now on.
+pub fn subscribe() -> Receiver<Activity> {
+    let (tx, rx) = mpsc::channel();
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(tx);
+    rx
+}
+
+/// A run starting or ending, see [`run_events`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunEvent {
+    Start,
+    Stop,
+    Finish,
+    Safety,
+    TargetClosed,
+    /// The run is still going, see [`crate::reminder`].
+    Reminder,
+}
+
+/// The runs starting and ending from now on, with reminders that they are
+/// still going, for announcing them. The
+/// plain stop a safety stop causes is left out, so each end is reported
+/// once.
+pub fn run_events() -> impl Iterator<Item = RunEvent> {
+    let mut after_safety = false;
+    subscribe()
+        .into_iter()
+        .filter_map(move |activity| match activity {
+            Activity::Start => Some(RunEvent::Start),
+            Activity::Stop if after_safety => {
+                after_safety = false;
+                None
+            }
+            Activity::Stop => Some(RunEvent::Stop),
+            Activity::Finish => Some(RunEvent::Finish),
+            Activity::Safety => {
+                after_safety = true;
+                Some(RunEvent::Safety)
+            }
+            Activity::TargetClosed => {
+                after_safety = true;
+                Some(RunEvent::TargetClosed)
+            }
+            Activity::Reminder => Some(RunEvent::Reminder),
+            Activity::Click
+            | Activity::Pause
+            | Activity::Trigger { .. }
+            | Activity::Suspend
+            | Activity::Resume => None,
+        })
+}
+
+/// The entries of the last [`WINDOW`], oldest first, with how long ago
+/// each happened.
+pub fn recent() -> Vec<(Activity, Duration)> {
+    let now = Instant::now();
+    ENTRIES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|&(activity, at)| (activity, now.duration_since(at)))
+        .filter(|(_, ago)| *ago <= WINDOW)
+        .collect()
+}
@@ -0,0 +1,109 @@
+use std::{
+    cell::Cell,
+    fmt::Write as _,
+    fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Instant,
+};
+
+/// Recording stops at this many events, about 15 minutes of clicking at
+/// 100 clicks per second, to bound memory use when tracing is left on.
+const MAX_EVENTS: usize = 500_000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(Vec::new());
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD_ID: Cell<u64> = Cell::new(NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed));
+}
+
+/// One timed phase, in microseconds since the first recorded event.
+#[derive(Debug, Clone, Copy)]
+struct TraceEvent {
+    name: &'static str,
+    start_us: u64,
+    duration_us: u64,
+    thread_id: u64,
+}
+
+/// Starts or stops recording the phases of the clicking engine.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Number of recorded events.
+pub fn len() -> usize {
+    EVENTS.lock().unwrap().len()
+}
+
+pub fn clear() {
+    EVENTS.lock().unwrap().clear();
+}
+
+/// Times the phase `name` until the returned guard is dropped. Does nothing
+/// while tracing is off.
+pub fn span(name: &'static str) -> Span {
+    Span {
+        name,
+        start: is_enabled().then(Instant::now),
+    }
+}
+
+pub struct Span {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let epoch = *EPOCH.get_or_init(|| start);
+        let event = TraceEvent {
+            name: self.name,
+            start_us: start.saturating_duration_since(epoch).as_micros() as u64,
+            duration_us: start.elapsed().as_micros() as u64,
+            thread_id: THREAD_ID.with(Cell::get),
+        };
+
+        let mut events = EVENTS.lock().unwrap();
+        if events.len() < MAX_EVENTS {
+            events.push(event);
+        }
+    }
+}
+
+/// Writes the recorded events as a Chrome trace, which can be opened in
+/// `chrome://tracing`, Perfetto or speedscope.
+pub fn export(path: &Path) -> io::Result<()> {
+    let events = EVENTS.lock().unwrap();
+    let mut json = String::from("{\"traceEvents\":[");
+    for (i, event) in events.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        // Phase names are fixed identifiers and need no escaping.
+        let _ = write!(
+            json,
+            "\n{{\"name\":\"{}\",\"cat\":\"engine\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":{}}}",
+            event.name, event.start_us, event.duration_us, event.thread_id
+        );
+    }
+    json.push_str("\n]}\n");
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, json)
+}
@@ -0,0 +1,156 @@
+//! The icon in the system tray, or the menu bar on macOS, with a menu to
+//! start, stop and toggle the clicker, bring back the window and quit. On
+//! Linux it needs a desktop with StatusNotifierItem/AppIndicator support.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use tray_icon::{
+    icon::Icon,
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    TrayIcon, TrayIconBuilder,
+};
+
+use crate::i18n::tr;
+
+/// The size of the generated icon, in pixels.
+const ICON_SIZE: u32 = 32;
+
+static AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+/// An entry of the tray menu, handed to the window to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    Start,
+    Stop,
+    Toggle,
+    ShowWindow,
+    Quit,
+}
+
+/// Keeps the tray icon alive; it is removed when dropped.
+pub struct Tray {
+    #[cfg(not(target_os = "linux"))]
+    _icon: TrayIcon,
+}
+
+/// Whether the tray icon is shown, so the window can be hidden to it.
+pub fn is_available() -> bool {
+    AVAILABLE.load(Ordering::Relaxed)
+}
+
+/// Puts the icon in the tray. What is picked from its menu is handed to
+/// `on_action` from a thread of its own. `None` where the platform has no
+/// tray.
+///
+/// On Windows and macOS this must run on the main thread, which the
+/// window's event loop then keeps serving the icon. On Linux the icon lives
+/// on a GTK thread of its own.
+pub fn spawn(on_action: impl Fn(TrayAction) + Send + 'static) -> Option<Tray> {
+    #[cfg(not(target_os = "linux"))]
+    {
+        let icon = build(on_action)
+            .map_err(|e| log::warn!("No tray icon: {e}"))
+            .ok()?;
+        AVAILABLE.store(true, Ordering::Relaxed);
+        Some(Tray { _icon: icon })
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let (tx_built, rx_built) = std::sync::mpsc::sync_channel(1);
+        thread::spawn(move || {
+            if let Err(e) = gtk::init() {
+                tx_built.send(Err(e.to_string())).ok();
+                return;
+            }
+            // Dropping the icon would remove it again.
+            let _icon = match build(on_action) {
+                Ok(icon) => icon,
+                Err(e) => {
+                    tx_built.send(Err(e)).ok();
+                    return;
+                }
+            };
+            tx_built.send(Ok(())).ok();
+            gtk::main();
+        });
+        match rx_built.recv() {
+            Ok(Ok(())) => {
+                AVAILABLE.store(true, Ordering::Relaxed);
+                Some(Tray {})
+            }
+            Ok(Err(e)) => {
+                log::warn!("No tray icon: {e}");
+                None
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+/// Creates the icon and its menu, and a thread that passes on what is
+/// picked from the menu.
+fn build(on_action: impl Fn(TrayAction) + Send + 'static) -> Result<TrayIcon, String> {
+    let start = MenuItem::new(tr("Start"), true, None);
+    let stop = MenuItem::new(tr("Stop"), true, None);
+    let toggle = MenuItem::new(tr("Toggle"), true, None);
+    let show = MenuItem::new(tr("Show window"), true, None);
+    let quit = MenuItem::new(tr("Quit"), true, None);
+    let menu = Menu::new();
+    menu.append_items(&[
+        &start,
+        &stop,
+        &toggle,
+        &PredefinedMenuItem::separator(),
+        &show,
+        &PredefinedMenuItem::separator(),
+        &quit,
+    ]);
+
+    let icon = TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_tooltip("Auto Clicker")
+        .with_icon(icon()?)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let entries = [
+        (start.id(), TrayAction::Start),
+        (stop.id(), TrayAction::Stop),
+        (toggle.id(), TrayAction::Toggle),
+        (show.id(), TrayAction::ShowWindow),
+        (quit.id(), TrayAction::Quit),
+    ];
+    thread::spawn(move || {
+        while let Ok(event) = MenuEvent::receiver().recv() {
+            if let Some(&(_, action)) = entries.iter().find(|(id, _)| *id == event.id) {
+                on_action(action);
+            }
+        }
+    });
+    Ok(icon)
+}
+
+/// A round target mark, drawn rather than loaded so no image file has to
+/// ship with the app.
+fn icon() -> Result<Icon, String> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            let pixel = if distance < 4.0 {
+                [255, 255, 255, 255]
+            } else if distance < 15.0 {
+                [0, 140, 220, 255]
+            } else {
+                [0, 0, 0, 0]
+            };
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).map_err(|e| e.to_string())
+}
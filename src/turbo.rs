@@ -0,0 +1,182 @@
+//! Turbo mode, for rates of several hundred to over a thousand clicks per
+//! second: the clicker thread gets the platform's high-resolution timing,
+//! waits are spun, the gap between sent events is skipped and clicks due
+//! within the same millisecond go out as one batch.
+//!
+//! A CPS cap, see [`crate::ratelimit::set_max_cps`], still applies and holds
+//! turbo runs to its rate. Many apps and games drop input this fast anyway.
+
+use std::{
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::engine::ClickOptions;
+
+/// How far ahead due clicks are gathered into one batch.
+const BATCH_WINDOW: Duration = Duration::from_millis(1);
+
+/// The most triggers sent in one batch.
+pub const MAX_BATCH: usize = 16;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns turbo mode on or off for the clicker thread. Like precision mode it
+/// keeps a CPU core busy while clicking, and on Windows it sets a 1 ms
+/// system timer resolution, which applies to the whole system.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// How many triggers of `click_options` one cycle sends at `interval`: all
+/// that are due within [`BATCH_WINDOW`] in turbo mode, otherwise 1. Only
+/// plain clicks are batched; keys, holds, drags, press times, burst delays,
+/// wiggles and changing buttons go one at a time.
+pub fn batch(interval: Duration, click_options: &ClickOptions) -> usize {
+    let plain = click_options.key.is_none()
+        && !click_options.hold_mode
+        && !click_options.drag.enabled
+        && !click_options.wiggle
+        && !click_options.button_rotation.enabled
+        && !click_options.button_weights.enabled
+        && click_options.press_ms == 0
+        && click_options.burst_delay_ms == 0;
+    if !is_enabled() || !plain {
+        return 1;
+    }
+    if interval.is_zero() {
+        return MAX_BATCH;
+    }
+    (BATCH_WINDOW.as_nanos() / interval.as_nanos()).clamp(1, MAX_BATCH as u128) as usize
+}
+
+/// Tracks whether the thread that owns it has high-resolution timing, so
+/// the clicker can apply [`set_enabled`] on its own thread, like
+/// [`crate::priority::ThreadPriority`].
+#[derive(Debug, Default)]
+pub struct HighResTimer {
+    active: bool,
+}
+
+impl HighResTimer {
+    /// Requests or releases high-resolution timing for the calling thread
+    /// if turbo mode was turned on or off.
+    pub fn update(&mut self) {
+        let wanted = is_enabled();
+        if wanted != self.active {
+            self.active = wanted;
+            if let Err(e) = set_current_thread(wanted) {
+                log::error!("Could not change the clicker thread's timer resolution: {e}");
+            }
+        }
+    }
+}
+
+impl Drop for HighResTimer {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = set_current_thread(false);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn set_current_thread(high_res: bool) -> io::Result<()> {
+    const TIMERR_NOERROR: u32 = 0;
+
+    #[link(name = "winmm")]
+    extern "system" {
+        fn timeBeginPeriod(period: u32) -> u32;
+        fn timeEndPeriod(period: u32) -> u32;
+    }
+
+    // SAFETY: these calls only take a plain value. Requests are counted by
+    // the system, so this pairs with high-priority clicking's own request.
+    let result = unsafe {
+        if high_res {
+            timeBeginPeriod(1)
+        } else {
+            timeEndPeriod(1)
+        }
+    };
+    if result != TIMERR_NOERROR {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("the 1 ms timer resolution was refused ({result})"),
+        ));
+    }
+    Ok(())
+}
+
+/// Makes the thread a Mach time-constraint thread, which the scheduler
+/// wakes on time rather than when it gets round to it.
+#[cfg(target_os = "macos")]
+fn set_current_thread(high_res: bool) -> io::Result<()> {
+    const THREAD_STANDARD_POLICY: u32 = 1;
+    const THREAD_TIME_CONSTRAINT_POLICY: u32 = 2;
+    const KERN_SUCCESS: i32 = 0;
+
+    #[repr(C)]
+    struct TimebaseInfo {
+        numer: u32,
+        denom: u32,
+    }
+
+    #[repr(C)]
+    struct TimeConstraintPolicy {
+        period: u32,
+        computation: u32,
+        constraint: u32,
+        preemptible: i32,
+    }
+
+    extern "C" {
+        fn mach_timebase_info(info: *mut TimebaseInfo) -> i32;
+        fn pthread_mach_thread_np(thread: libc::pthread_t) -> u32;
+        fn thread_policy_set(thread: u32, flavor: u32, policy: *const i32, count: u32) -> i32;
+    }
+
+    // SAFETY: the pointers are to live, properly sized values and the
+    // thread port is the calling thread's own.
+    let result = unsafe {
+        let thread = pthread_mach_thread_np(libc::pthread_self());
+        if high_res {
+            let mut timebase = TimebaseInfo { numer: 0, denom: 0 };
+            mach_timebase_info(&mut timebase);
+            let ticks = |nanos: u64| (nanos * timebase.denom as u64 / timebase.numer as u64) as u32;
+            let policy = TimeConstraintPolicy {
+                period: ticks(1_000_000),
+                computation: ticks(200_000),
+                constraint: ticks(1_000_000),
+                preemptible: 1,
+            };
+            thread_policy_set(
+                thread,
+                THREAD_TIME_CONSTRAINT_POLICY,
+                &policy as *const _ as *const i32,
+                4,
+            )
+        } else {
+            thread_policy_set(thread, THREAD_STANDARD_POLICY, std::ptr::null(), 0)
+        }
+    };
+    if result != KERN_SUCCESS {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("thread_policy_set failed ({result})"),
+        ));
+    }
+    Ok(())
+}
+
+/// Linux timers already have microsecond resolution; spinning the waits is
+/// all turbo mode needs there.
+#[cfg(not(any(windows, target_os = "macos")))]
+fn set_current_thread(_high_res: bool) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,16 @@
+/// Text the worker types instead of clicking. Each cycle types the whole
+/// text once; without `repeat` the clicker stops after the first cycle.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TypingText {
+    pub text: String,
+    /// Pause after each typed character.
+    pub char_delay_ms: usize,
+    pub repeat: bool,
+}
+
+impl TypingText {
+    /// Whether typing replaces clicking.
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty()
+    }
+}
@@ -0,0 +1,365 @@
+//! Input through a virtual device on Linux, for Wayland sessions where
+//! rdev's X11 simulation does nothing.
+//!
+//! The kernel's uinput module turns events written to `/dev/uinput` into a
+//! device every compositor reads like a real mouse and keyboard. The
+//! device reports absolute positions spanning the whole desktop, so moves
+//! land where asked without knowing where the cursor is. Writing to
+//! `/dev/uinput` usually needs membership in the `input` group or a udev
+//! rule.
+
+#[cfg(not(target_os = "linux"))]
+use rdev::{EventType, SimulateError};
+
+/// Name the device shows up under, e.g. in `libinput list-devices`.
+#[cfg(target_os = "linux")]
+const DEVICE_NAME: &str = "Auto Clicker";
+
+pub const fn is_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Whether the app runs in a Wayland session, going by the variables the
+/// session sets.
+pub fn is_wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session == "wayland")
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::{is_enabled, set_enabled, simulate};
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_enabled(_enabled: bool) {}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_enabled() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn simulate(_event_type: &EventType) -> Option<Result<(), SimulateError>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        ffi::{c_int, c_ulong},
+        fs::{File, OpenOptions},
+        io::{self, Write},
+        os::{fd::AsRawFd, unix::fs::OpenOptionsExt},
+        sync::Mutex,
+    };
+
+    use rdev::{Button, EventType, Key, SimulateError};
+
+    use super::DEVICE_NAME;
+    use crate::monitor;
+
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const EV_REL: u16 = 0x02;
+    const EV_ABS: u16 = 0x03;
+    const SYN_REPORT: u16 = 0x00;
+    const REL_HWHEEL: u16 = 0x06;
+    const REL_WHEEL: u16 = 0x08;
+    const ABS_X: u16 = 0x00;
+    const ABS_Y: u16 = 0x01;
+    const BTN_LEFT: u16 = 0x110;
+    const BTN_RIGHT: u16 = 0x111;
+    const BTN_MIDDLE: u16 = 0x112;
+    const BTN_SIDE: u16 = 0x113;
+    const BTN_EXTRA: u16 = 0x114;
+    /// Keyboard keys registered with the device, the standard PC keyboard.
+    const KEYS: std::ops::RangeInclusive<u16> = 1..=127;
+    const BUS_VIRTUAL: u16 = 0x06;
+
+    const UI_DEV_CREATE: c_ulong = 0x5501;
+    const UI_DEV_DESTROY: c_ulong = 0x5502;
+    const UI_SET_EVBIT: c_ulong = 0x4004_5564;
+    const UI_SET_KEYBIT: c_ulong = 0x4004_5565;
+    const UI_SET_RELBIT: c_ulong = 0x4004_5566;
+    const UI_SET_ABSBIT: c_ulong = 0x4004_5567;
+
+    const ABS_CNT: usize = 0x40;
+
+    /// `struct uinput_user_dev`, the legacy setup every kernel takes.
+    #[repr(C)]
+    struct UserDev {
+        name: [u8; 80],
+        bustype: u16,
+        vendor: u16,
+        product: u16,
+        version: u16,
+        ff_effects_max: u32,
+        absmax: [i32; ABS_CNT],
+        absmin: [i32; ABS_CNT],
+        absfuzz: [i32; ABS_CNT],
+        absflat: [i32; ABS_CNT],
+    }
+
+    /// The virtual device and the desktop area its absolute axes span.
+    struct Device {
+        file: File,
+        origin: (i32, i32),
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            // SAFETY: the descriptor is open for as long as `file`.
+            unsafe { libc::ioctl(self.file.as_raw_fd(), UI_DEV_DESTROY) };
+        }
+    }
+
+    static DEVICE: Mutex<Option<Device>> = Mutex::new(None);
+
+    /// Creates the virtual device and sends input through it from now on,
+    /// or removes it again.
+    pub fn set_enabled(enabled: bool) {
+        let mut device = DEVICE.lock().unwrap();
+        if enabled == device.is_some() {
+            return;
+        }
+        if enabled {
+            match open() {
+                Ok(opened) => *device = Some(opened),
+                Err(e) => log::error!("Could not create the uinput device: {e}"),
+            }
+        } else {
+            *device = None;
+        }
+    }
+
+    pub fn is_enabled() -> bool {
+        DEVICE.lock().unwrap().is_some()
+    }
+
+    /// Sends moves, buttons, wheel and keys through the device while it is
+    /// enabled. Returns `None` for keys it has no code for, which go
+    /// through rdev.
+    pub fn simulate(event_type: &EventType) -> Option<Result<(), SimulateError>> {
+        let mut device = DEVICE.lock().unwrap();
+        let device = device.as_mut()?;
+        let events = match *event_type {
+            EventType::MouseMove { x, y } => vec![
+                (EV_ABS, ABS_X, x.round() as i32 - device.origin.0),
+                (EV_ABS, ABS_Y, y.round() as i32 - device.origin.1),
+            ],
+            EventType::ButtonPress(button) | EventType::ButtonRelease(button) => {
+                let press = matches!(event_type, EventType::ButtonPress(_)) as i32;
+                vec![(EV_KEY, button_code(button)?, press)]
+            }
+            EventType::Wheel { delta_x, delta_y } => vec![
+                (EV_REL, REL_HWHEEL, delta_x as i32),
+                (EV_REL, REL_WHEEL, delta_y as i32),
+            ],
+            EventType::KeyPress(key) => vec![(EV_KEY, key_code(key)?, 1)],
+            EventType::KeyRelease(key) => vec![(EV_KEY, key_code(key)?, 0)],
+        };
+        Some(write(&mut device.file, &events).map_err(|e| {
+            log::error!("Could not write to the uinput device: {e}");
+            SimulateError
+        }))
+    }
+
+    /// Writes `events` followed by a report that ends the frame.
+    fn write(file: &mut File, events: &[(u16, u16, i32)]) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        for &(kind, code, value) in events.iter().chain([&(EV_SYN, SYN_REPORT, 0)]) {
+            let event = libc::input_event {
+                time: libc::timeval {
+                    tv_sec: 0,
+                    tv_usec: 0,
+                },
+                type_: kind,
+                code,
+                value,
+            };
+            // SAFETY: `input_event` is plain old data, read as its bytes.
+            bytes.extend_from_slice(unsafe {
+                std::slice::from_raw_parts(
+                    &event as *const libc::input_event as *const u8,
+                    std::mem::size_of::<libc::input_event>(),
+                )
+            });
+        }
+        file.write_all(&bytes)
+    }
+
+    /// Opens `/dev/uinput` and creates a device with the mouse buttons,
+    /// wheels, keyboard keys and absolute axes covering every monitor.
+    fn open() -> io::Result<Device> {
+        let (origin, size) = desktop().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "the screen size is not known")
+        })?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")?;
+        let fd = file.as_raw_fd();
+
+        let set = |request: c_ulong, value: u16| -> io::Result<()> {
+            // SAFETY: the `UI_SET_*BIT` requests take an int by value.
+            if unsafe { libc::ioctl(fd, request, value as c_int) } < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(())
+            }
+        };
+        for kind in [EV_KEY, EV_REL, EV_ABS] {
+            set(UI_SET_EVBIT, kind)?;
+        }
+        for code in KEYS.chain([BTN_LEFT, BTN_RIGHT, BTN_MIDDLE, BTN_SIDE, BTN_EXTRA]) {
+            set(UI_SET_KEYBIT, code)?;
+        }
+        for code in [REL_WHEEL, REL_HWHEEL] {
+            set(UI_SET_RELBIT, code)?;
+        }
+        for code in [ABS_X, ABS_Y] {
+            set(UI_SET_ABSBIT, code)?;
+        }
+
+        let mut setup = UserDev {
+            name: [0; 80],
+            bustype: BUS_VIRTUAL,
+            vendor: 0,
+            product: 0,
+            version: 1,
+            ff_effects_max: 0,
+            absmax: [0; ABS_CNT],
+            absmin: [0; ABS_CNT],
+            absfuzz: [0; ABS_CNT],
+            absflat: [0; ABS_CNT],
+        };
+        setup.name[..DEVICE_NAME.len()].copy_from_slice(DEVICE_NAME.as_bytes());
+        setup.absmax[ABS_X as usize] = size.0 - 1;
+        setup.absmax[ABS_Y as usize] = size.1 - 1;
+        // SAFETY: `UserDev` is plain old data, written as its bytes.
+        file.write_all(unsafe {
+            std::slice::from_raw_parts(
+                &setup as *const UserDev as *const u8,
+                std::mem::size_of::<UserDev>(),
+            )
+        })?;
+        // SAFETY: `UI_DEV_CREATE` takes no argument.
+        if unsafe { libc::ioctl(fd, UI_DEV_CREATE) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Device { file, origin })
+    }
+
+    /// The top left corner and size of the box around all monitors.
+    fn desktop() -> Option<((i32, i32), (i32, i32))> {
+        let monitors = monitor::list();
+        let left = monitors.iter().map(|m| m.x).min()?;
+        let top = monitors.iter().map(|m| m.y).min()?;
+        let right = monitors.iter().map(|m| m.x + m.width as i32).max()?;
+        let bottom = monitors.iter().map(|m| m.y + m.height as i32).max()?;
+        Some(((left, top), (right - left, bottom - top)))
+    }
+
+    fn button_code(button: Button) -> Option<u16> {
+        Some(match button {
+            Button::Left => BTN_LEFT,
+            Button::Right => BTN_RIGHT,
+            Button::Middle => BTN_MIDDLE,
+            // X numbers the side buttons 8 and 9, after the wheel's.
+            Button::Unknown(8) => BTN_SIDE,
+            Button::Unknown(9) => BTN_EXTRA,
+            Button::Unknown(_) => return None,
+        })
+    }
+
+    /// The evdev code of `key`. X keycodes, which rdev passes on as
+    /// `Key::Unknown`, are evdev codes plus 8.
+    fn key_code(key: Key) -> Option<u16> {
+        Some(match key {
+            Key::Escape => 1,
+            Key::Num1 => 2,
+            Key::Num2 => 3,
+            Key::Num3 => 4,
+            Key::Num4 => 5,
+            Key::Num5 => 6,
+            Key::Num6 => 7,
+            Key::Num7 => 8,
+            Key::Num8 => 9,
+            Key::Num9 => 10,
+            Key::Num0 => 11,
+            Key::Minus => 12,
+            Key::Equal => 13,
+            Key::Backspace => 14,
+            Key::Tab => 15,
+            Key::KeyQ => 16,
+            Key::KeyW => 17,
+            Key::KeyE => 18,
+            Key::KeyR => 19,
+            Key::KeyT => 20,
+            Key::KeyY => 21,
+            Key::KeyU => 22,
+            Key::KeyI => 23,
+            Key::KeyO => 24,
+            Key::KeyP => 25,
+            Key::LeftBracket => 26,
+            Key::RightBracket => 27,
+            Key::Return => 28,
+            Key::ControlLeft => 29,
+            Key::KeyA => 30,
+            Key::KeyS => 31,
+            Key::KeyD => 32,
+            Key::KeyF => 33,
+            Key::KeyG => 34,
+            Key::KeyH => 35,
+            Key::KeyJ => 36,
+            Key::KeyK => 37,
+            Key::KeyL => 38,
+            Key::SemiColon => 39,
+            Key::Quote => 40,
+            Key::BackQuote => 41,
+            Key::ShiftLeft => 42,
+            Key::BackSlash => 43,
+            Key::KeyZ => 44,
+            Key::KeyX => 45,
+            Key::KeyC => 46,
+            Key::KeyV => 47,
+            Key::KeyB => 48,
+            Key::KeyN => 49,
+            Key::KeyM => 50,
+            Key::Comma => 51,
+            Key::Dot => 52,
+            Key::Slash => 53,
+            Key::ShiftRight => 54,
+            Key::Alt => 56,
+            Key::Space => 57,
+            Key::CapsLock => 58,
+            Key::F1 => 59,
+            Key::F2 => 60,
+            Key::F3 => 61,
+            Key::F4 => 62,
+            Key::F5 => 63,
+            Key::F6 => 64,
+            Key::F7 => 65,
+            Key::F8 => 66,
+            Key::F9 => 67,
+            Key::F10 => 68,
+            Key::F11 => 87,
+            Key::F12 => 88,
+            Key::ControlRight => 97,
+            Key::AltGr => 100,
+            Key::Home => 102,
+            Key::UpArrow => 103,
+            Key::PageUp => 104,
+            Key::LeftArrow => 105,
+            Key::RightArrow => 106,
+            Key::End => 107,
+            Key::DownArrow => 108,
+            Key::PageDown => 109,
+            Key::Insert => 110,
+            Key::Delete => 111,
+            Key::MetaLeft => 125,
+            Key::MetaRight => 126,
+            Key::Unknown(code) if (9..=135).contains(&code) => code as u16 - 8,
+            _ => return None,
+        })
+    }
+}
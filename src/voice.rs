@@ -0,0 +1,100 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    SampleFormat, Stream,
+};
+use vosk::{DecodingState, Model, Recognizer};
+
+use crate::clicker::Command;
+
+/// Everything the recognizer listens for; anything else is `[unk]`.
+const GRAMMAR: [&str; 4] = ["start clicking", "stop clicking", "stop", "[unk]"];
+
+/// Starts a thread that listens on the default microphone for "start
+/// clicking" and "stop" (or "stop clicking") and sends the matching command.
+///
+/// `model` is the directory of a Vosk model, e.g. `vosk-model-small-en-us`.
+/// Recognition runs offline; nothing is recorded or sent anywhere.
+pub fn spawn_listener(model: PathBuf, tx_command: Sender<Command>) {
+    thread::spawn(move || {
+        if let Err(e) = listen(&model, &tx_command) {
+            log::warn!("Voice commands are not available: {e}");
+        }
+    });
+}
+
+fn listen(model: &Path, tx_command: &Sender<Command>) -> Result<(), String> {
+    let model = Model::new(model.to_string_lossy())
+        .ok_or_else(|| format!("could not load the model {}", model.display()))?;
+
+    let (tx_samples, rx_samples) = mpsc::channel();
+    let (_stream, sample_rate) = open_microphone(tx_samples)?;
+    let mut recognizer = Recognizer::new_with_grammar(&model, sample_rate as f32, &GRAMMAR[..])
+        .ok_or("could not create the recognizer")?;
+
+    for samples in rx_samples {
+        if !matches!(
+            recognizer.accept_waveform(&samples),
+            DecodingState::Finalized
+        ) {
+            continue;
+        }
+        let Some(result) = recognizer.result().single() else {
+            continue;
+        };
+        let command = match result.text {
+            "start clicking" => Command::Start,
+            "stop" | "stop clicking" => Command::Stop,
+            _ => continue,
+        };
+        if tx_command.send(command).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Starts capturing the default input device, sending its audio as mono
+/// 16-bit samples. The capture stops when the returned stream is dropped.
+fn open_microphone(tx_samples: Sender<Vec<i16>>) -> Result<(Stream, u32), String> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .ok_or("no microphone found")?;
+    let config = device.default_input_config().map_err(|e| e.to_string())?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+    let on_error = |e| log::error!("Microphone error: {e}");
+
+    let stream = match config.sample_format() {
+        SampleFormat::I16 => device.build_input_stream(
+            &config.into(),
+            move |data: &[i16], _: &_| {
+                let mono = data.chunks(channels).map(|frame| frame[0]).collect();
+                tx_samples.send(mono).ok();
+            },
+            on_error,
+            None,
+        ),
+        SampleFormat::F32 => device.build_input_stream(
+            &config.into(),
+            move |data: &[f32], _: &_| {
+                let mono = data
+                    .chunks(channels)
+                    .map(|frame| (frame[0].clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                    .collect();
+                tx_samples.send(mono).ok();
+            },
+            on_error,
+            None,
+        ),
+        format => return Err(format!("unsupported sample format {format:?}")),
+    }
+    .map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+    Ok((stream, sample_rate))
+}
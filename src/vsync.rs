@@ -0,0 +1,64 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Frame period assumed until the monitor's refresh rate is known.
+const DEFAULT_PERIOD: Duration = Duration::from_micros(16_667);
+
+/// A frame older than this no longer tells where the vblanks are, e.g.
+/// because the window stopped drawing.
+const STALE: Duration = Duration::from_millis(250);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// When the last frame was handed a buffer, and the frame period.
+static LAST_FRAME: Mutex<Option<(Instant, Duration)>> = Mutex::new(None);
+
+/// Experimental: move each click onto the next display refresh, for rhythm
+/// and timing games where sub-frame jitter matters. Needs the window to keep
+/// drawing while the clicker runs, see [`frame_presented`].
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        *LAST_FRAME.lock().unwrap() = None;
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Notes that the window's surface just got its next buffer. With FIFO
+/// presentation that happens at a vblank, so the frames mark the display's
+/// refresh. `refresh_millihertz` is the monitor's refresh rate, if known.
+pub fn frame_presented(refresh_millihertz: Option<u32>) {
+    if !is_enabled() {
+        return;
+    }
+    let period = refresh_millihertz
+        .filter(|&rate| rate > 0)
+        .map_or(DEFAULT_PERIOD, |rate| {
+            Duration::from_secs_f64(1000.0 / rate as f64)
+        });
+    *LAST_FRAME.lock().unwrap() = Some((Instant::now(), period));
+}
+
+/// The first predicted vblank at or after `at`, or `at` itself when
+/// frame-perfect mode is off or no recent frame tells where the vblanks are.
+pub fn align(at: Instant) -> Instant {
+    if !is_enabled() {
+        return at;
+    }
+    let Some((frame, period)) = *LAST_FRAME.lock().unwrap() else {
+        return at;
+    };
+    if frame.elapsed() > STALE || at <= frame {
+        return at;
+    }
+    let frames = (at - frame).as_secs_f64() / period.as_secs_f64();
+    frame + period.mul_f64(frames.ceil())
+}
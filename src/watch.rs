@@ -0,0 +1,186 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc::Sender, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    clicker::{self, Command, Config},
+    profile::Profile,
+    recorder::Recorder,
+    script::{self, ScriptRunner},
+    slots,
+};
+
+/// How often the watched folder is listed.
+const POLL: Duration = Duration::from_secs(1);
+
+/// What a new file in the watched folder launches.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum WatchAction {
+    /// Run the saved script.
+    #[default]
+    Script,
+    /// Apply this saved profile and start a run.
+    Profile(String),
+    /// Play the macro bound to the quick slot with this index.
+    SlotMacro(usize),
+}
+
+impl WatchAction {
+    pub fn to_config(&self) -> String {
+        match self {
+            WatchAction::Script => "script".to_owned(),
+            WatchAction::Profile(name) => format!("profile:{name}"),
+            WatchAction::SlotMacro(index) => format!("slot:{}", index + 1),
+        }
+    }
+
+    pub fn from_config(value: &str) -> Self {
+        match value.split_once(':') {
+            Some(("profile", name)) => WatchAction::Profile(name.to_owned()),
+            Some(("slot", number)) => number
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+                .filter(|&index| index < slots::SLOT_COUNT)
+                .map_or(WatchAction::Script, WatchAction::SlotMacro),
+            _ => WatchAction::Script,
+        }
+    }
+}
+
+/// Launching an action for each file that appears in a folder, e.g. to
+/// process every exported report.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WatchFolder {
+    pub enabled: bool,
+    pub folder: String,
+    pub action: WatchAction,
+    /// The script parameter that gets the new file's path. Empty to pass
+    /// none.
+    pub param: String,
+}
+
+static WATCH: Mutex<Option<WatchFolder>> = Mutex::new(None);
+
+/// Sets the folder to watch and what its new files launch, e.g. after they
+/// are changed in the GUI.
+pub fn set_watch(watch: &WatchFolder) {
+    *WATCH.lock().unwrap() = Some(watch.clone());
+}
+
+/// Starts a thread that polls the watched folder and launches the action
+/// for each new file, one at a time: the next waits until the clicker,
+/// script and macro of the last have finished. Files that were there when
+/// watching began are left alone, and a file counts as new once its size
+/// stays the same between two polls, so it is not picked up half-written.
+pub fn spawn_watcher(tx_command: Sender<Command>) {
+    thread::spawn(move || {
+        let runner = ScriptRunner::default();
+        let mut player = Recorder::default();
+        let mut watching: Option<(String, HashSet<PathBuf>)> = None;
+        let mut growing: HashMap<PathBuf, u64> = HashMap::new();
+        let mut queue = VecDeque::new();
+        loop {
+            thread::sleep(POLL);
+            let watch = WATCH.lock().unwrap().clone();
+            let Some(watch) = watch.filter(|watch| watch.enabled && !watch.folder.is_empty())
+            else {
+                watching = None;
+                continue;
+            };
+
+            let files = list(Path::new(&watch.folder));
+            let seen = match &mut watching {
+                Some((folder, seen)) if *folder == watch.folder => seen,
+                _ => {
+                    watching = Some((watch.folder.clone(), files.into_keys().collect()));
+                    growing.clear();
+                    queue.clear();
+                    continue;
+                }
+            };
+            for (path, size) in files {
+                if seen.contains(&path) {
+                    continue;
+                }
+                if growing.get(&path) == Some(&size) {
+                    growing.remove(&path);
+                    seen.insert(path.clone());
+                    queue.push_back(path);
+                } else {
+                    growing.insert(path, size);
+                }
+            }
+
+            if clicker::is_running() || runner.is_running() || player.is_playing() {
+                continue;
+            }
+            if let Some(path) = queue.pop_front() {
+                launch(&watch, &path, &tx_command, &runner, &mut player);
+            }
+        }
+    });
+}
+
+/// The files directly in `folder` with their sizes, empty if it cannot be
+/// read.
+fn list(folder: &Path) -> HashMap<PathBuf, u64> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata.len()))
+        })
+        .collect()
+}
+
+fn launch(
+    watch: &WatchFolder,
+    path: &Path,
+    tx_command: &Sender<Command>,
+    runner: &ScriptRunner,
+    player: &mut Recorder,
+) {
+    match &watch.action {
+        WatchAction::Script => {
+            let Some(source) = script::load() else {
+                log::warn!("No saved script to run for {}", path.display());
+                return;
+            };
+            let mut values = Vec::new();
+            if !watch.param.is_empty() {
+                values.push((watch.param.clone(), path.display().to_string()));
+            }
+            runner.run(source, values);
+        }
+        WatchAction::Profile(name) => match Profile::load(name) {
+            Ok(profile) => {
+                let commands = [
+                    Config::ClickInterval(profile.click_interval).into(),
+                    Config::ClickOptions(Box::new(profile.click_options)).into(),
+                    Config::ClickPosition(profile.click_position).into(),
+                    Command::Start,
+                ];
+                for command in commands {
+                    tx_command.send(command).ok();
+                }
+            }
+            Err(e) => log::error!("Could not load profile {name:?}: {e}"),
+        },
+        WatchAction::SlotMacro(index) => match slots::load_macro(*index) {
+            Ok(recording) => {
+                player.set_recording(recording);
+                player.play();
+            }
+            Err(e) => log::error!("Could not load the macro of slot {}: {e}", index + 1),
+        },
+    }
+}
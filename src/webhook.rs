@@ -0,0 +1,59 @@
+use std::{
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{stats, timeline};
+
+/// A webhook that does not answer within this is given up on.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts a thread that POSTs a JSON payload to `url` whenever a run
+/// starts, is stopped, finishes or is stopped by a safety mechanism.
+///
+/// Besides `event`, `timestamp`, `clicks` and `elapsed_ms`, the payload has
+/// the same message as `text` and `content`, so it can go straight to a
+/// Slack or Discord webhook.
+pub fn spawn_notifier(url: String) {
+    let rx_activity = timeline::subscribe();
+    thread::spawn(move || {
+        for activity in rx_activity {
+            let Some(event) = activity.event_name() else {
+                continue;
+            };
+            let body = payload(event);
+            let result = ureq::post(&url)
+                .timeout(TIMEOUT)
+                .set("Content-Type", "application/json")
+                .send_string(&body);
+            if let Err(e) = result {
+                log::error!("Could not send the {event} webhook: {e}");
+            }
+        }
+    });
+}
+
+fn payload(event: &str) -> String {
+    let stats = stats::current();
+    let message = match event {
+        "start" => "Auto Clicker started".to_owned(),
+        "finish" => format!("Auto Clicker finished after {} clicks", stats.clicks),
+        "safety_stop" => "Auto Clicker was stopped by a safety check".to_owned(),
+        "target_closed" => format!(
+            "Auto Clicker stopped after {} clicks, the target window closed",
+            stats.clicks
+        ),
+        "pause" => format!("Auto Clicker paused after {} clicks", stats.clicks),
+        "resume" => "Auto Clicker resumed".to_owned(),
+        _ => format!("Auto Clicker stopped after {} clicks", stats.clicks),
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    format!(
+        r#"{{"event":"{event}","timestamp":{timestamp},"clicks":{},"elapsed_ms":{},"text":"{message}","content":"{message}"}}"#,
+        stats.clicks,
+        stats.elapsed().as_millis(),
+    )
+}
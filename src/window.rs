@@ -1,101 +1,162 @@
 use std::{
-    sync::{
-        mpsc::{self, Sender},
-        Arc, Mutex,
-    },
-    thread::{self, sleep},
-    time::Duration,
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
 };
 
-use egui::{FontDefinitions, Style};
+use accesskit_winit::ActionRequestEvent;
+use egui::Style;
 use egui_wgpu_backend::{RenderPass, ScreenDescriptor};
 use egui_winit_platform::{Platform, PlatformDescriptor};
 
-use rdev::{simulate, EventType};
 use wgpu::Dx12Compiler;
 use winit::{
-    dpi::{LogicalSize, Size},
-    event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::{Window, WindowBuilder, WindowButtons},
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size},
+    event::{ElementState, KeyboardInput, StartCause, VirtualKeyCode, WindowEvent},
+    event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget},
+    window::{Window, WindowBuilder, WindowLevel},
 };
 
-use crate::gui::{self, ClickInterval, ClickOptions, ClickPosition, ClickType, MouseButton};
+use crate::{
+    abort, activation, alert,
+    cli::Args,
+    clicker::{self, Command},
+    display,
+    dock::Dock,
+    engine::ClickerEngine,
+    failsafe, fonts, gui, history,
+    hotkeys::{self, Action},
+    idle, jobs,
+    modifiers::{self, Modifiers},
+    monitor::{self, Monitor},
+    overlay::{self, Overlay},
+    pick, pointer, recorder, reminder, sandbox, session,
+    settings::Settings,
+    slots, takeover,
+    taskbar::{self, Taskbar},
+    vsync, watch,
+};
+
+/// The size of the main window in logical pixels until it is resized.
+const DEFAULT_SIZE: (u32, u32) = (437, 870);
+
+/// How often the screens are listed again, for those plugged in while the
+/// window is in the background, where [`WindowEvent::Focused`] does not
+/// tell.
+const MONITOR_CHECK_INTERVAL: Duration = Duration::from_secs(2);
 
 /// A custom event type for the winit app.
-enum Event {
+enum UserEvent {
+    #[allow(dead_code)]
     RequestRedraw,
+    /// A screen reader asks to act on a widget, e.g. to press a button.
+    AccessKit(ActionRequestEvent),
+    /// An entry of the tray icon's menu was picked.
+    #[cfg(feature = "tray")]
+    Tray(crate::tray::TrayAction),
+}
+
+impl From<ActionRequestEvent> for UserEvent {
+    fn from(event: ActionRequestEvent) -> Self {
+        Self::AccessKit(event)
+    }
 }
 
 /// This is the repaint signal type that egui needs for requesting a repaint from another thread.
 /// It sends the custom `RequestRedraw` event to the winit event loop.
-struct ExampleRepaintSignal(std::sync::Mutex<winit::event_loop::EventLoopProxy<Event>>);
+#[allow(dead_code)]
+struct ExampleRepaintSignal(std::sync::Mutex<EventLoopProxy<UserEvent>>);
 
 impl epi::backend::RepaintSignal for ExampleRepaintSignal {
     fn request_repaint(&self) {
-        self.0.lock().unwrap().send_event(Event::RequestRedraw).ok();
+        self.0
+            .lock()
+            .unwrap()
+            .send_event(UserEvent::RequestRedraw)
+            .ok();
     }
 }
 
-struct State {
-    app_gui: gui::MainApp,
+/// The ways to draw the window, tried in turn: the native graphics API,
+/// then OpenGL, then a software adapter, for machines without a suitable
+/// GPU or driver.
+const GPU_ATTEMPTS: [(&str, wgpu::Backends, bool); 3] = [
+    ("Vulkan/Metal/DX12", wgpu::Backends::PRIMARY, false),
+    ("OpenGL", wgpu::Backends::GL, false),
+    ("software", wgpu::Backends::all(), true),
+];
+
+/// What the window is drawn with.
+struct Gpu {
+    instance: wgpu::Instance,
     surface: wgpu::Surface,
+    adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
-    window: Window,
-    egui_rpass: RenderPass,
-    platform: Platform,
 }
 
-impl State {
-    // Creating some of the wgpu types requires async code
-    async fn new(
-        window: Window,
-        is_running: Arc<Mutex<bool>>,
-        tx_click_interval: Sender<ClickInterval>,
-        tx_click_options: Sender<ClickOptions>,
-        tx_click_position: Sender<ClickPosition>,
-    ) -> State {
-        let size = window.inner_size();
-
-        let app_gui = gui::MainApp::new(
-            is_running,
-            tx_click_interval,
-            tx_click_options,
-            tx_click_position,
-        );
+impl Gpu {
+    /// Sets up drawing to `window` with the first of [`GPU_ATTEMPTS`] that
+    /// works. The error says why each one failed.
+    async fn request(window: &Window) -> Result<Self, String> {
+        let mut failures = Vec::new();
+        for (name, backends, force_fallback_adapter) in GPU_ATTEMPTS {
+            match Self::request_with(window, backends, force_fallback_adapter).await {
+                Ok(gpu) => {
+                    if !failures.is_empty() {
+                        log::warn!(
+                            "Drawing with {name} ({}) after: {}",
+                            gpu.adapter.get_info().name,
+                            failures.join("; ")
+                        );
+                    }
+                    return Ok(gpu);
+                }
+                Err(e) => failures.push(format!("{name}: {e}")),
+            }
+        }
+        Err(failures.join("\n"))
+    }
 
+    async fn request_with(
+        window: &Window,
+        backends: wgpu::Backends,
+        force_fallback_adapter: bool,
+    ) -> Result<Self, String> {
         // The instance is a handle to our GPU
-        // Backends::all => Vulkan + Metal + DX12 + Browser WebGPU
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
+            backends,
             dx12_shader_compiler: Dx12Compiler::default(),
         });
 
         // # Safety
         //
         // The surface needs to live as long as the window that created it.
-        // State owns the window so this should be safe.
-        let surface = unsafe { instance.create_surface(&window) }.unwrap();
+        // State owns the window and the surface so this should be safe.
+        let surface = unsafe { instance.create_surface(window) }.map_err(|e| e.to_string())?;
 
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::LowPower,
                 compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+                force_fallback_adapter,
             })
             .await
-            .unwrap();
+            .ok_or("no compatible adapter")?;
+        if surface.get_capabilities(&adapter).formats.is_empty() {
+            return Err(format!(
+                "{} cannot draw to the window",
+                adapter.get_info().name
+            ));
+        }
 
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     features: wgpu::Features::empty(),
-                    // WebGL doesn't support all of wgpu's features, so if
-                    // we're building for the web we'll have to disable some.
-                    limits: if cfg!(target_arch = "wasm32") {
-                        wgpu::Limits::downlevel_webgl2_defaults()
+                    // WebGL, OpenGL and software adapters don't support all
+                    // of wgpu's limits, so ask only for what they have.
+                    limits: if cfg!(target_arch = "wasm32") || backends != wgpu::Backends::PRIMARY {
+                        wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits())
                     } else {
                         wgpu::Limits::default()
                     },
@@ -104,8 +165,60 @@ impl State {
                 None, // Trace path
             )
             .await
-            .unwrap();
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            instance,
+            surface,
+            adapter,
+            device,
+            queue,
+        })
+    }
+}
+
+struct State {
+    app_gui: gui::MainApp,
+    surface: wgpu::Surface,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration,
+    window: Window,
+    egui_rpass: RenderPass,
+    platform: Platform,
+    /// Hands the widget tree egui builds to the platform's screen reader
+    /// interface.
+    accesskit: accesskit_winit::Adapter,
+    overlay: Option<Overlay>,
+    /// The zoom the GUI is drawn at, see [`Settings::ui_scale`].
+    ui_scale: f32,
+}
+
+impl State {
+    // Creating some of the wgpu types requires async code. Fails if no
+    // graphics adapter can draw the window.
+    async fn new(
+        window: Window,
+        overlay_window: Window,
+        tx_command: Sender<Command>,
+        proxy: EventLoopProxy<UserEvent>,
+    ) -> Result<State, String> {
+        let size = window.inner_size();
+        let settings = Settings::load();
+        let font_definitions = fonts::font_definitions(settings.font_path.as_deref());
+        let ui_scale = settings.ui_scale;
+
+        let mut app_gui = gui::MainApp::new(tx_command, settings);
+
+        let Gpu {
+            instance,
+            surface,
+            adapter,
+            device,
+            queue,
+        } = Gpu::request(&window).await?;
         let surface_caps = surface.get_capabilities(&adapter);
+        let overlay = Overlay::new(overlay_window, &instance, &adapter, &device);
 
         // Shader code in this tutorial assumes an sRGB surface texture. Using a different
         // one will result all the colors coming out darker. If you want to support non
@@ -121,7 +234,8 @@ impl State {
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: surface_caps.present_modes[0],
+            // Waits for vblank, which frame-perfect clicking goes by.
+            present_mode: wgpu::PresentMode::Fifo,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
         };
@@ -131,23 +245,43 @@ impl State {
         let platform = Platform::new(PlatformDescriptor {
             physical_width: size.width,
             physical_height: size.height,
-            scale_factor: window.scale_factor(),
-            font_definitions: FontDefinitions::default(),
+            scale_factor: window.scale_factor() * ui_scale as f64,
+            font_definitions,
             style: Style::default(),
         });
 
+        // egui builds the AccessKit tree once a screen reader asks for it,
+        // and every frame from then on. The window must still be hidden
+        // while the adapter is set up.
+        let context = platform.context();
+        let accesskit = accesskit_winit::Adapter::new(
+            &window,
+            move || {
+                context.enable_accesskit();
+                context.request_repaint();
+                context.accesskit_placeholder_tree_update()
+            },
+            proxy,
+        );
+        window.set_visible(true);
+
         // We use the egui_wgpu_backend crate as the render backend.
         let egui_rpass = RenderPass::new(&device, surface_format, 1);
 
-        if let Some(theme) = window.theme() {
+        // Also applies the saved theme, so it runs without a system
+        // preference too, keeping egui's dark default then.
+        {
             use egui::Visuals;
-            platform.context().set_visuals(match theme {
-                winit::window::Theme::Light => Visuals::light(),
-                winit::window::Theme::Dark => Visuals::dark(),
-            });
+            app_gui.set_system_visuals(
+                &platform.context(),
+                match window.theme() {
+                    Some(winit::window::Theme::Light) => Visuals::light(),
+                    _ => Visuals::dark(),
+                },
+            );
         }
 
-        Self {
+        Ok(Self {
             app_gui,
             surface,
             device,
@@ -156,7 +290,10 @@ impl State {
             window,
             egui_rpass,
             platform,
-        }
+            accesskit,
+            overlay,
+            ui_scale,
+        })
     }
 
     pub fn window(&self) -> &Window {
@@ -165,18 +302,71 @@ impl State {
 
     fn update(&mut self) {}
 
+    /// The display's scale factor times the UI scale, which egui draws at.
+    fn pixels_per_point(&self) -> f64 {
+        self.window.scale_factor() * self.ui_scale as f64
+    }
+
+    /// Tells egui the scale to draw at, e.g. after the window moved to a
+    /// screen with another scale factor, which it otherwise takes as is.
+    fn rescale(&mut self) {
+        let mut size = self.window.inner_size();
+        self.platform
+            .handle_event(&winit::event::Event::WindowEvent::<()> {
+                window_id: self.window.id(),
+                event: WindowEvent::ScaleFactorChanged {
+                    scale_factor: self.pixels_per_point(),
+                    new_inner_size: &mut size,
+                },
+            });
+    }
+
+    /// Zooms to the UI scale in the settings if it changed, growing or
+    /// shrinking the window along so the layout keeps fitting.
+    fn apply_ui_scale(&mut self) {
+        let ui_scale = self.app_gui.settings().ui_scale;
+        if ui_scale == self.ui_scale {
+            return;
+        }
+        let size = self.window.inner_size();
+        let factor = ui_scale / self.ui_scale;
+        self.ui_scale = ui_scale;
+        self.window.set_inner_size(PhysicalSize::new(
+            (size.width as f32 * factor).round() as u32,
+            (size.height as f32 * factor).round() as u32,
+        ));
+        self.rescale();
+    }
+
+    fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
+        if size.width > 0 && size.height > 0 {
+            self.config.width = size.width;
+            self.config.height = size.height;
+            self.surface.configure(&self.device, &self.config);
+        }
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
+        vsync::frame_presented(
+            self.window
+                .current_monitor()
+                .and_then(|monitor| monitor.refresh_rate_millihertz()),
+        );
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
+        self.apply_ui_scale();
         self.platform.begin_frame();
 
         self.app_gui.update(&self.platform.context());
 
         // End the UI frame. We could now handle the output and draw the UI with the backend.
-        let full_output = self.platform.end_frame(Some(&self.window));
+        let mut full_output = self.platform.end_frame(Some(&self.window));
+        if let Some(update) = full_output.platform_output.accesskit_update.take() {
+            self.accesskit.update_if_active(|| update);
+        }
         let paint_jobs = self.platform.context().tessellate(full_output.shapes);
 
         let mut encoder = self
@@ -189,12 +379,17 @@ impl State {
             let screen_descriptor = ScreenDescriptor {
                 physical_width: self.config.width,
                 physical_height: self.config.height,
-                scale_factor: self.window().scale_factor() as f32,
+                scale_factor: self.pixels_per_point() as f32,
             };
             let tdelta: egui::TexturesDelta = full_output.textures_delta;
-            self.egui_rpass
+            // A frame that fails is skipped; the next one tries again.
+            if let Err(e) = self
+                .egui_rpass
                 .add_textures(&self.device, &self.queue, &tdelta)
-                .expect("add texture ok");
+            {
+                log::error!("Could not upload the window's textures: {e:?}");
+                return Ok(());
+            }
             self.egui_rpass.update_buffers(
                 &self.device,
                 &self.queue,
@@ -203,15 +398,16 @@ impl State {
             );
 
             // Record all render passes.
-            self.egui_rpass
-                .execute(
-                    &mut encoder,
-                    &view,
-                    &paint_jobs,
-                    &screen_descriptor,
-                    Some(wgpu::Color::BLACK),
-                )
-                .unwrap();
+            if let Err(e) = self.egui_rpass.execute(
+                &mut encoder,
+                &view,
+                &paint_jobs,
+                &screen_descriptor,
+                Some(wgpu::Color::BLACK),
+            ) {
+                log::error!("Could not draw the window: {e:?}");
+                return Ok(());
+            }
         }
 
         // submit will accept anything that implements IntoIter
@@ -222,105 +418,264 @@ impl State {
     }
 }
 
-pub async fn run() {
-    env_logger::init();
-    let event_loop = EventLoop::new();
-    let window = WindowBuilder::new()
-        .with_enabled_buttons(WindowButtons::all().difference(WindowButtons::MAXIMIZE))
-        .with_resizable(false)
-        .with_inner_size(Size::Logical(LogicalSize {
-            width: 437.0,
-            height: 265.0,
-        }))
-        .with_title("Auto Clicker")
-        .build(&event_loop)
-        .unwrap();
-
-    let (tx_click_interval, rx_click_interval) = mpsc::channel::<ClickInterval>();
-    let (tx_click_options, rx_click_options) = mpsc::channel::<ClickOptions>();
-    let (tx_click_position, rx_click_position) = mpsc::channel::<ClickPosition>();
-
-    let is_running = Arc::new(Mutex::new(false));
-    let is_running_autoclick_thread = is_running.clone();
-    let is_running_state_thread = is_running.clone();
-    thread::spawn(move || {
-        let mut is_running = false;
-        let mut delay = Duration::from_secs(0);
-        let mut mouse_button = rdev::Button::Left;
-        let mut click_position = ClickPosition::default();
-        let mut click_type = ClickType::default();
-
-        loop {
-            if let Ok(value) = is_running_autoclick_thread.lock() {
-                is_running = *value;
-            }
-
-            if let Ok(click_interval) = rx_click_interval.try_recv() {
-                delay = convert_time_to_duration(
-                    click_interval.hours,
-                    click_interval.minutes,
-                    click_interval.seconds,
-                    click_interval.milliseconds,
-                );
-            }
+/// Tells the clicker where our window is, so it never clicks it.
+fn note_own_window(window: &Window) {
+    let rect = window.outer_position().ok().map(|position| {
+        let size = window.outer_size();
+        (position.x, position.y, size.width, size.height)
+    });
+    clicker::set_own_window(rect);
+    let origin = window
+        .inner_position()
+        .ok()
+        .map(|position| (position.x, position.y));
+    sandbox::set_window(origin);
+}
 
-            if let Ok(click_options) = rx_click_options.try_recv() {
-                mouse_button = match click_options.mouse_button {
-                    MouseButton::Left => rdev::Button::Left,
-                    MouseButton::Middle => rdev::Button::Middle,
-                    MouseButton::Right => rdev::Button::Right,
-                };
+/// Lists the connected screens again, for the clicker and the overlay,
+/// which is stretched over all of them.
+fn refresh_monitors(
+    window_target: &EventLoopWindowTarget<UserEvent>,
+    overlay: &mut Option<Overlay>,
+) {
+    monitor::set_monitors(
+        window_target
+            .available_monitors()
+            .map(|handle| Monitor::from_handle(&handle))
+            .collect(),
+    );
+    if let Some(overlay) = overlay {
+        overlay.cover(&monitor::list());
+    }
+}
 
-                click_type = click_options.click_type;
-            }
+/// Opens the app window and runs its event loop, optionally with the click
+/// options from the command line and clicking right away.
+pub async fn run(start_minimized: bool, autostart: bool, preset: Option<Box<Args>>) {
+    let event_loop = EventLoopBuilder::<UserEvent>::with_user_event().build();
+    let settings = Settings::load();
+    let (width, height) = settings.window_size.unwrap_or(DEFAULT_SIZE);
+    // Shown once the screen reader interface is set up, see `State::new`.
+    let mut window_builder = WindowBuilder::new()
+        .with_visible(false)
+        .with_resizable(true)
+        .with_inner_size(Size::Logical(LogicalSize {
+            width: width as f64,
+            height: height as f64,
+        }))
+        .with_title("Auto Clicker");
+    // Only restore the position if it is still on a monitor, e.g. not after
+    // the monitor it was on has been unplugged.
+    if let Some((x, y)) = settings.window_position {
+        let on_screen = event_loop.available_monitors().any(|monitor| {
+            let (position, size) = (monitor.position(), monitor.size());
+            (position.x..position.x + size.width as i32).contains(&x)
+                && (position.y..position.y + size.height as i32).contains(&y)
+        });
+        if on_screen {
+            window_builder = window_builder.with_position(PhysicalPosition::new(x, y));
+        }
+    }
+    let window = match window_builder.build(&event_loop) {
+        Ok(window) => window,
+        Err(e) => {
+            alert::show_error(
+                "Auto Clicker could not open its window",
+                &format!("{e}\n\n{}", alert::diagnostics()),
+            );
+            return;
+        }
+    };
+    monitor::set_monitors(
+        event_loop
+            .available_monitors()
+            .map(|handle| Monitor::from_handle(&handle))
+            .collect(),
+    );
+
+    // Transparent, click-through window for the click visualizer, over all
+    // of the screens. It stays hidden until there is a click marker to show.
+    let mut overlay_builder = WindowBuilder::new()
+        .with_title("Auto Clicker Overlay")
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_resizable(false)
+        .with_visible(false)
+        .with_window_level(WindowLevel::AlwaysOnTop);
+    if let Some((position, size)) = overlay::desktop(&monitor::list()) {
+        overlay_builder = overlay_builder
+            .with_position(position)
+            .with_inner_size(size);
+    }
+    let overlay_window = match overlay_builder.build(&event_loop) {
+        Ok(window) => window,
+        Err(e) => {
+            alert::show_error(
+                "Auto Clicker could not open its window",
+                &format!("The overlay window failed: {e}\n\n{}", alert::diagnostics()),
+            );
+            return;
+        }
+    };
 
-            if let Ok(position) = rx_click_position.try_recv() {
-                click_position = position;
-            }
+    // The windows are open on the current display, input can go elsewhere.
+    display::set_input_display(&settings.input_display);
 
-            if is_running {
-                if let ClickPosition::Custom { x, y } = click_position {
-                    send(&EventType::MouseMove {
-                        x: x as f64,
-                        y: y as f64,
-                    });
-                }
+    let engine = match ClickerEngine::spawn() {
+        Ok(engine) => engine,
+        Err(e) => {
+            alert::show_error("Auto Clicker could not start", &e.to_string());
+            return;
+        }
+    };
+    session::spawn_watcher(engine.commands());
+    failsafe::spawn_watcher(engine.commands());
+    takeover::spawn_watcher();
+    idle::spawn_watcher(engine.commands());
+    abort::spawn_watcher(engine.commands());
+    slots::spawn_watcher(engine.commands());
+    jobs::spawn_watcher();
+    history::spawn_recorder();
+    watch::spawn_watcher(engine.commands());
+    reminder::spawn_watcher();
+    activation::spawn_watcher(engine.commands());
+    modifiers::spawn_tracker();
+    #[cfg(feature = "speech")]
+    crate::speech::spawn_announcer();
+    #[cfg(feature = "sound")]
+    crate::sound::spawn_player();
+    #[cfg(feature = "notifications")]
+    crate::notification::spawn_notifier();
+    #[cfg(feature = "webhooks")]
+    if !settings.webhook_url.is_empty() {
+        crate::webhook::spawn_notifier(settings.webhook_url.clone());
+    }
+    #[cfg(feature = "gamepad")]
+    crate::gamepad::spawn_watcher(engine.commands());
 
-                let click_times = match click_type {
-                    ClickType::Single => 1,
-                    ClickType::Double => 2,
-                };
+    #[cfg(feature = "voice")]
+    if !settings.voice_model.is_empty() {
+        crate::voice::spawn_listener(settings.voice_model.clone().into(), engine.commands());
+    }
+    #[cfg(feature = "mqtt")]
+    if !settings.mqtt.host.is_empty() {
+        tokio::spawn(crate::mqtt::run(settings.mqtt.clone(), engine.commands()));
+    }
+    #[cfg(feature = "remote")]
+    if !settings.remote_address.is_empty() {
+        let access = crate::remote::Access {
+            token: settings.remote_token.clone(),
+            allowed_origins: settings.remote_origins.clone(),
+        };
+        if let Err(e) =
+            crate::remote::spawn_server(&settings.remote_address, access, Some(engine.commands()))
+        {
+            log::error!(
+                "Could not start remote control on {}: {e}",
+                settings.remote_address
+            );
+        }
+    }
+    #[cfg(feature = "remote")]
+    if !settings.spectator_address.is_empty() {
+        let access = crate::remote::Access {
+            token: settings.spectator_token.clone(),
+            allowed_origins: settings.remote_origins.clone(),
+        };
+        if let Err(e) = crate::remote::spawn_server(&settings.spectator_address, access, None) {
+            log::error!(
+                "Could not start the view-only server on {}: {e}",
+                settings.spectator_address
+            );
+        }
+    }
 
-                for _ in 0..click_times {
-                    send(&EventType::ButtonPress(mouse_button));
-                    send(&EventType::ButtonRelease(mouse_button));
-                }
-                sleep(delay);
-            }
-            sleep(Duration::from_millis(5));
+    if settings.ipc {
+        if let Err(e) = crate::ipc::spawn_server(engine.commands()) {
+            log::error!(
+                "Could not listen for local commands on {}: {e}",
+                crate::ipc::address()
+            );
         }
-    });
+    }
 
-    let mut state = State::new(
-        window,
-        is_running,
-        tx_click_interval,
-        tx_click_options,
-        tx_click_position,
-    )
-    .await;
+    let proxy = event_loop.create_proxy();
+    let mut state = match State::new(window, overlay_window, engine.commands(), proxy).await {
+        Ok(state) => state,
+        Err(e) => {
+            alert::show_error(
+                "Auto Clicker could not open its window",
+                &format!(
+                    "No graphics adapter could draw the window, not even OpenGL or \
+                     software rendering.\n\n{e}\n\n{}",
+                    alert::diagnostics()
+                ),
+            );
+            return;
+        }
+    };
+    if let Some(preset) = &preset {
+        state.app_gui.apply_args(preset);
+    }
+    if autostart {
+        engine.start();
+    }
 
-    event_loop.run(move |event, _, control_flow| {
+    // Lives as long as the event loop, which never returns.
+    #[cfg(feature = "tray")]
+    let _tray = {
+        let proxy = event_loop.create_proxy();
+        crate::tray::spawn(move |action| {
+            proxy.send_event(UserEvent::Tray(action)).ok();
+        })
+    };
+
+    if start_minimized || state.app_gui.settings().start_minimized {
+        state.window().set_minimized(true);
+    }
+    note_own_window(state.window());
+
+    let mut shown_title = String::new();
+    let mut hidden_for_pick = false;
+    // Closed with `minimize_on_close`, until "Show window" in the tray menu.
+    let mut hidden_to_tray = false;
+    let mut taskbar = Taskbar::default();
+    let mut dock = Dock::default();
+    // F6 to F9 act alone, leaving their combinations to bound hotkeys.
+    let mut modifiers = Modifiers::NONE;
+    let mut monitors_checked = Instant::now();
+    event_loop.run(move |event, window_target, control_flow| {
         use winit::event::Event;
 
         control_flow.set_wait();
-        state.platform.handle_event(&event);
+        // Events the screen reader adapter consumed are not egui's.
+        let consumed = match &event {
+            Event::WindowEvent { event, window_id } if *window_id == state.window().id() => {
+                state.accesskit.on_event(&state.window, event)
+            }
+            _ => false,
+        };
+        if !consumed {
+            state.platform.handle_event(&event);
+        }
 
         match event {
             Event::WindowEvent {
                 ref event,
                 window_id,
             } if window_id == state.window().id() => match event {
+                WindowEvent::CloseRequested if state.app_gui.settings().minimize_on_close => {
+                    #[cfg(feature = "tray")]
+                    let to_tray = crate::tray::is_available();
+                    #[cfg(not(feature = "tray"))]
+                    let to_tray = false;
+                    if to_tray {
+                        state.window().set_visible(false);
+                        hidden_to_tray = true;
+                    } else {
+                        state.window().set_minimized(true);
+                    }
+                }
                 WindowEvent::CloseRequested
                 | WindowEvent::KeyboardInput {
                     input:
@@ -342,73 +697,256 @@ pub async fn run() {
                 } => {}
                 WindowEvent::ThemeChanged(theme) => {
                     use egui::Visuals;
-                    state.platform.context().set_visuals(match theme {
-                        winit::window::Theme::Light => Visuals::light(),
-                        winit::window::Theme::Dark => Visuals::dark(),
-                    });
+                    state.app_gui.set_system_visuals(
+                        &state.platform.context(),
+                        match theme {
+                            winit::window::Theme::Light => Visuals::light(),
+                            winit::window::Theme::Dark => Visuals::dark(),
+                        },
+                    );
                     state.window().request_redraw();
                 }
                 WindowEvent::CursorMoved { .. } => {
                     state.window().request_redraw();
                 }
-                WindowEvent::KeyboardInput { input, .. } => {
-                    if input.state == ElementState::Released {
-                        match input.virtual_keycode {
-                            Some(VirtualKeyCode::F6) => {
-                                *is_running_state_thread.lock().unwrap() = true;
-                            }
-                            Some(VirtualKeyCode::F7) => {
-                                *is_running_state_thread.lock().unwrap() = false;
-                            }
-                            Some(VirtualKeyCode::F8) => {
-                                if let Ok(is_running) = &mut is_running_state_thread.lock() {
-                                    **is_running = !**is_running;
-                                }
-                            }
-                            _ => {}
-                        };
+                WindowEvent::CursorEntered { .. } => {
+                    recorder::set_own_window_hovered(true);
+                    dock.expand(state.window());
+                    state.app_gui.set_collapsed(dock.is_collapsed());
+                }
+                WindowEvent::CursorLeft { .. } => {
+                    recorder::set_own_window_hovered(false);
+                    dock.cursor_left(state.window(), state.app_gui.settings().dock);
+                    state.app_gui.set_collapsed(dock.is_collapsed());
+                    state.window().request_redraw();
+                }
+                WindowEvent::Moved(_) => {
+                    dock.moved();
+                    note_own_window(state.window());
+                }
+                WindowEvent::Resized(size) => {
+                    state.resize(*size);
+                    note_own_window(state.window());
+                    state.window().request_redraw();
+                }
+                WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    // Moving to a screen with another scale, without which
+                    // the old surface is stretched over the new size.
+                    state.resize(**new_inner_size);
+                    state.rescale();
+                    note_own_window(state.window());
+                    state.window().request_redraw();
+                }
+                WindowEvent::Focused(focused) => {
+                    recorder::set_own_window_focused(*focused);
+                    // Screens are usually plugged in or rearranged, and
+                    // permissions granted, while the app is in the
+                    // background.
+                    if *focused {
+                        state.app_gui.recheck_permission();
+                        refresh_monitors(window_target, &mut state.overlay);
+                        monitors_checked = Instant::now();
                     }
                 }
+                WindowEvent::ModifiersChanged(state) => {
+                    modifiers = Modifiers::from_winit(*state);
+                }
+                WindowEvent::KeyboardInput { input, .. }
+                    if input.state == ElementState::Released && modifiers == Modifiers::NONE =>
+                {
+                    match input.virtual_keycode.and_then(hotkeys::window_action) {
+                        Some(Action::Start) => {
+                            state.app_gui.request_start(Command::Start);
+                            state.window().request_redraw();
+                        }
+                        Some(Action::Stop) => {
+                            engine.stop();
+                            jobs::stop_all();
+                        }
+                        Some(Action::Toggle) => {
+                            state.app_gui.request_start(Command::Toggle);
+                            state.window().request_redraw();
+                        }
+                        Some(Action::PauseOrResume) if clicker::is_paused() => {
+                            engine.resume();
+                        }
+                        Some(Action::PauseOrResume) => {
+                            engine.pause();
+                        }
+                        _ => {}
+                    };
+                }
                 _ => {}
             },
             Event::RedrawRequested(window_id) if window_id == state.window().id() => {
                 state.update();
                 match state.render() {
+                    Ok(_) if state.app_gui.exit_requested() => *control_flow = ControlFlow::Exit,
                     Ok(_) => {}
                     // Reconfigure the surface if lost
+                    Err(wgpu::SurfaceError::Lost) => {
+                        let size = state.window().inner_size();
+                        state.resize(size);
+                    }
                     // The system is out of memory, we should probably quit
-                    Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
+                    Err(wgpu::SurfaceError::OutOfMemory) => {
+                        alert::show_error(
+                            "Auto Clicker ran out of graphics memory",
+                            &alert::diagnostics(),
+                        );
+                        *control_flow = ControlFlow::Exit;
+                    }
                     // All other errors (Outdated, Timeout) should be resolved by the next frame
-                    Err(e) => eprintln!("{e:?}"),
+                    Err(e) => log::error!("Could not draw the window: {e:?}"),
+                }
+            }
+            Event::WindowEvent {
+                ref event,
+                window_id,
+            } if state
+                .overlay
+                .as_ref()
+                .is_some_and(|overlay| overlay.window().id() == window_id) =>
+            {
+                if let Some(overlay) = &mut state.overlay {
+                    match event {
+                        WindowEvent::Resized(size) => overlay.resize(&state.device, *size),
+                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                            overlay.resize(&state.device, **new_inner_size);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::RedrawRequested(window_id)
+                if state
+                    .overlay
+                    .as_ref()
+                    .is_some_and(|overlay| overlay.window().id() == window_id) =>
+            {
+                if let Some(overlay) = &mut state.overlay {
+                    match overlay.render(&state.device, &state.queue) {
+                        Ok(()) => {}
+                        Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                            let size = overlay.window().inner_size();
+                            overlay.resize(&state.device, size);
+                        }
+                        Err(e) => log::error!("Could not draw the overlay: {e:?}"),
+                    }
+                }
+            }
+            Event::UserEvent(UserEvent::AccessKit(ActionRequestEvent { request, .. })) => {
+                state
+                    .platform
+                    .raw_input_mut()
+                    .events
+                    .push(egui::Event::AccessKitActionRequest(request));
+                state.window().request_redraw();
+            }
+            #[cfg(feature = "tray")]
+            Event::UserEvent(UserEvent::Tray(action)) => {
+                use crate::tray::TrayAction;
+                match action {
+                    TrayAction::Start => state.app_gui.request_start(Command::Start),
+                    TrayAction::Stop => engine.stop(),
+                    TrayAction::Toggle => state.app_gui.request_start(Command::Toggle),
+                    TrayAction::ShowWindow => {
+                        hidden_to_tray = false;
+                        state.window().set_visible(true);
+                        state.window().set_minimized(false);
+                        state.window().focus_window();
+                    }
+                    TrayAction::Quit => *control_flow = ControlFlow::Exit,
+                }
+                state.window().request_redraw();
+            }
+            Event::NewEvents(StartCause::ResumeTimeReached { .. })
+                if clicker::is_running() || clicker::is_shielded() =>
+            {
+                state.window().request_redraw();
+            }
+            Event::RedrawEventsCleared => {
+                // Show the state in the title and on the taskbar button, so
+                // it can be seen while the window is in the background.
+                let running = clicker::is_running();
+                let (title, progress) = taskbar::status();
+                if title != shown_title {
+                    state.window().set_title(&title);
+                    shown_title = title;
+                }
+                taskbar.set_progress(state.window(), progress);
+
+                // Out of the way while a position is picked on screen, back
+                // once it is done.
+                let picking = pick::is_picking();
+                if picking != hidden_for_pick {
+                    hidden_for_pick = picking;
+                    state.window().set_visible(!picking && !hidden_to_tray);
+                    if !picking {
+                        state.window().request_redraw();
+                    }
+                }
+
+                // Tick the run statistics and countdowns, and poll for the
+                // click that picks a position.
+                if running || clicker::is_shielded() {
+                    control_flow.set_wait_timeout(Duration::from_millis(250));
+                }
+                // Keep drawing every refresh to mark the vblanks.
+                if running && vsync::is_enabled() {
+                    state.window().request_redraw();
+                }
+
+                // Wake up to animate fading click markers and the cursor halo,
+                // and poll for new ones from the clicker thread while either
+                // is on. Screens plugged in meanwhile are covered too.
+                if state.overlay.is_some() && monitors_checked.elapsed() >= MONITOR_CHECK_INTERVAL {
+                    refresh_monitors(window_target, &mut state.overlay);
+                    monitors_checked = Instant::now();
+                }
+                if let Some(overlay) = &mut state.overlay {
+                    if overlay.update(running) {
+                        control_flow.set_wait_timeout(Duration::from_millis(16));
+                    } else if overlay::is_enabled()
+                        || overlay::is_halo_enabled()
+                        || clicker::is_preview()
+                    {
+                        control_flow.set_wait_timeout(Duration::from_millis(50));
+                    }
+                }
+
+                // Snap once the window has settled after being dragged.
+                if let Some(settled) = dock.update(state.window(), state.app_gui.settings().dock) {
+                    match *control_flow {
+                        ControlFlow::WaitUntil(wake) if wake <= settled => {}
+                        _ => control_flow.set_wait_until(settled),
+                    }
                 }
+                state.app_gui.set_collapsed(dock.is_collapsed());
+            }
+            Event::LoopDestroyed => {
+                // A bar's size is not the one to reopen with.
+                let collapsed = dock.is_collapsed();
+                dock.expand(state.window());
+                // A minimized window reports a position off screen.
+                let shown = state.window().is_minimized() != Some(true);
+                let position = shown
+                    .then(|| state.window().outer_position().ok())
+                    .flatten()
+                    .map(|position| (position.x, position.y));
+                let size = (shown && !collapsed).then(|| {
+                    let size: LogicalSize<u32> = state
+                        .window()
+                        .inner_size()
+                        .to_logical(state.window().scale_factor());
+                    (size.width, size.height)
+                });
+                state.app_gui.save_on_exit(position, size);
+                // Do not leave a stray cursor behind.
+                pointer::set_enabled(false);
             }
 
             _ => {}
         }
     });
 }
-
-fn send(event_type: &EventType) {
-    let delay = Duration::from_millis(20);
-    match simulate(event_type) {
-        Ok(()) => (),
-        Err(_) => {
-            eprintln!("We could not send {event_type:?}");
-        }
-    }
-    // Let ths OS catchup (at least MacOS)
-    thread::sleep(delay);
-}
-
-fn convert_time_to_duration(
-    hours: usize,
-    minutes: usize,
-    seconds: usize,
-    milliseconds: usize,
-) -> Duration {
-    let total_milliseconds =
-        milliseconds + (seconds * 1000) + (minutes * 60 * 1000) + (hours * 60 * 60 * 1000);
-    let seconds = total_milliseconds / 1000;
-    let nanos = (total_milliseconds % 1000) * 1_000_000;
-    Duration::new(seconds as u64, nanos as u32)
-}
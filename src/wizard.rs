@@ -0,0 +1,166 @@
+use std::{borrow::Cow, time::Duration};
+
+use egui::Ui;
+
+use crate::{
+    engine::{ClickInterval, ClickOptions, ClickPosition, MouseButton},
+    humanize::Humanize,
+    i18n::{tr, tr_args},
+    profile::Profile,
+};
+
+/// How fast the wizard's profile clicks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Speed {
+    Relaxed,
+    #[default]
+    Steady,
+    Fast,
+}
+
+impl Speed {
+    const ALL: [Speed; 3] = [Speed::Relaxed, Speed::Steady, Speed::Fast];
+
+    fn interval_ms(self) -> usize {
+        match self {
+            Speed::Relaxed => 1000,
+            Speed::Steady => 250,
+            Speed::Fast => 100,
+        }
+    }
+
+    fn label(self) -> Cow<'static, str> {
+        match self {
+            Speed::Relaxed => tr("Relaxed, once a second"),
+            Speed::Steady => tr("Steady, 4 clicks a second"),
+            Speed::Fast => tr("Fast, 10 clicks a second"),
+        }
+    }
+}
+
+/// When a run of the wizard's profile ends by itself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StopAfter {
+    #[default]
+    Manually,
+    OneMinute,
+    TenMinutes,
+    OneHour,
+}
+
+impl StopAfter {
+    const ALL: [StopAfter; 4] = [
+        StopAfter::Manually,
+        StopAfter::OneMinute,
+        StopAfter::TenMinutes,
+        StopAfter::OneHour,
+    ];
+
+    pub fn duration(self) -> Option<Duration> {
+        match self {
+            StopAfter::Manually => None,
+            StopAfter::OneMinute => Some(Duration::from_secs(60)),
+            StopAfter::TenMinutes => Some(Duration::from_secs(10 * 60)),
+            StopAfter::OneHour => Some(Duration::from_secs(60 * 60)),
+        }
+    }
+
+    fn label(self) -> Cow<'static, str> {
+        match self {
+            StopAfter::Manually => tr("When I press Stop (F7)"),
+            StopAfter::OneMinute => tr("After a minute"),
+            StopAfter::TenMinutes => tr("After 10 minutes"),
+            StopAfter::OneHour => tr("After an hour"),
+        }
+    }
+}
+
+/// What the wizard sets up: the profile and how long a run lasts. The
+/// failsafe is turned on as well.
+#[derive(Debug, Clone, Copy)]
+pub struct Setup {
+    pub profile: Profile,
+    pub run_limit: Option<Duration>,
+}
+
+/// Three questions that set up a safe profile for first-time users: which
+/// button, how fast and when to stop. The profile clicks wherever the
+/// cursor is, with humanized timing.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Wizard {
+    step: usize,
+    mouse_button: MouseButton,
+    speed: Speed,
+    stop_after: StopAfter,
+}
+
+impl Wizard {
+    pub fn setup(&self) -> Setup {
+        Setup {
+            profile: Profile {
+                click_interval: ClickInterval::from_millis(self.speed.interval_ms()),
+                click_options: ClickOptions {
+                    mouse_button: self.mouse_button,
+                    humanize: Humanize {
+                        enabled: true,
+                        ..Humanize::default()
+                    },
+                    ..ClickOptions::default()
+                },
+                click_position: ClickPosition::CurrentCursorPosition,
+            },
+            run_limit: self.stop_after.duration(),
+        }
+    }
+
+    /// Asks the current question. Returns the setup once the last one is
+    /// answered.
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<Setup> {
+        ui.label(tr_args(
+            "Step {step} of {steps}",
+            &[("step", &(self.step + 1)), ("steps", &3)],
+        ));
+        match self.step {
+            0 => {
+                ui.heading(tr("Which button should be clicked?"));
+                ui.radio_value(&mut self.mouse_button, MouseButton::Left, tr("Left"));
+                ui.radio_value(&mut self.mouse_button, MouseButton::Right, tr("Right"));
+                ui.radio_value(&mut self.mouse_button, MouseButton::Middle, tr("Middle"));
+            }
+            1 => {
+                ui.heading(tr("How fast?"));
+                for speed in Speed::ALL {
+                    ui.radio_value(&mut self.speed, speed, speed.label());
+                }
+                ui.label(tr("The timing varies a little, like a person clicking."));
+            }
+            _ => {
+                ui.heading(tr("When should it stop?"));
+                for stop_after in StopAfter::ALL {
+                    ui.radio_value(&mut self.stop_after, stop_after, stop_after.label());
+                }
+                ui.label(tr(
+                    "Slamming the mouse into a screen corner always stops it.",
+                ));
+            }
+        }
+
+        let mut done = false;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.step > 0, egui::Button::new(tr("Back")))
+                .clicked()
+            {
+                self.step -= 1;
+            }
+            if self.step < 2 {
+                if ui.button(tr("Next")).clicked() {
+                    self.step += 1;
+                }
+            } else if ui.button(tr("Finish")).clicked() {
+                done = true;
+            }
+        });
+        done.then(|| self.setup())
+    }
+}
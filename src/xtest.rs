@@ -0,0 +1,323 @@
+//! Input straight through the XTEST extension on X11, skipping rdev.
+//!
+//! rdev opens and flushes the display for every event it sends, so the
+//! clicker leaves a gap between events for the X server to catch up. This
+//! keeps one connection open and sends each event on it in order, which
+//! the server processes without needing the gap.
+
+#[cfg(not(target_os = "linux"))]
+use rdev::{EventType, SimulateError};
+
+pub const fn is_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::{is_enabled, set_enabled, simulate};
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_enabled(_enabled: bool) {}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_enabled() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn simulate(_event_type: &EventType) -> Option<Result<(), SimulateError>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        ffi::{c_char, c_int, c_uchar, c_uint, c_ulong, c_void},
+        ptr,
+        sync::{Mutex, PoisonError},
+    };
+
+    use rdev::{Button, EventType, Key, SimulateError};
+
+    #[link(name = "X11")]
+    extern "C" {
+        fn XOpenDisplay(name: *const c_char) -> *mut c_void;
+        fn XCloseDisplay(display: *mut c_void) -> c_int;
+        fn XFlush(display: *mut c_void) -> c_int;
+        fn XKeysymToKeycode(display: *mut c_void, keysym: c_ulong) -> c_uchar;
+    }
+
+    #[link(name = "Xtst")]
+    extern "C" {
+        fn XTestQueryExtension(
+            display: *mut c_void,
+            event_base: *mut c_int,
+            error_base: *mut c_int,
+            major: *mut c_int,
+            minor: *mut c_int,
+        ) -> c_int;
+        fn XTestFakeButtonEvent(
+            display: *mut c_void,
+            button: c_uint,
+            is_press: c_int,
+            delay: c_ulong,
+        ) -> c_int;
+        fn XTestFakeKeyEvent(
+            display: *mut c_void,
+            keycode: c_uint,
+            is_press: c_int,
+            delay: c_ulong,
+        ) -> c_int;
+        fn XTestFakeMotionEvent(
+            display: *mut c_void,
+            screen: c_int,
+            x: c_int,
+            y: c_int,
+            delay: c_ulong,
+        ) -> c_int;
+    }
+
+    /// The X connection events are sent on.
+    struct Display(*mut c_void);
+
+    // SAFETY: the connection is only used while holding `DISPLAY`'s lock.
+    unsafe impl Send for Display {}
+
+    impl Drop for Display {
+        fn drop(&mut self) {
+            // SAFETY: opened in `open` and closed once.
+            unsafe { XCloseDisplay(self.0) };
+        }
+    }
+
+    static DISPLAY: Mutex<Option<Display>> = Mutex::new(None);
+
+    /// Opens the X connection and sends input through XTEST from now on,
+    /// or closes it again.
+    pub fn set_enabled(enabled: bool) {
+        let mut display = DISPLAY.lock().unwrap_or_else(PoisonError::into_inner);
+        if enabled == display.is_some() {
+            return;
+        }
+        if enabled {
+            match open() {
+                Ok(opened) => *display = Some(opened),
+                Err(e) => log::error!("Could not use XTEST: {e}"),
+            }
+        } else {
+            *display = None;
+        }
+    }
+
+    pub fn is_enabled() -> bool {
+        DISPLAY
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    /// Sends moves, buttons, wheel and keys through XTEST while it is
+    /// enabled. Returns `None` for keys missing from the server's keymap,
+    /// which go through rdev.
+    pub fn simulate(event_type: &EventType) -> Option<Result<(), SimulateError>> {
+        let display = DISPLAY.lock().unwrap_or_else(PoisonError::into_inner);
+        let display = display.as_ref()?.0;
+        // SAFETY: the connection is open while in `DISPLAY`.
+        let sent = unsafe {
+            match *event_type {
+                EventType::MouseMove { x, y } => {
+                    XTestFakeMotionEvent(display, -1, x.round() as c_int, y.round() as c_int, 0)
+                }
+                EventType::ButtonPress(button) | EventType::ButtonRelease(button) => {
+                    let button = match button {
+                        Button::Left => 1,
+                        Button::Middle => 2,
+                        Button::Right => 3,
+                        Button::Unknown(button) => button as c_uint,
+                    };
+                    let press = matches!(event_type, EventType::ButtonPress(_)) as c_int;
+                    XTestFakeButtonEvent(display, button, press, 0)
+                }
+                // X reports the wheel as buttons 4 to 7, one click per step.
+                EventType::Wheel { delta_x, delta_y } => {
+                    let steps = [
+                        (if delta_y > 0 { 4 } else { 5 }, delta_y.unsigned_abs()),
+                        (if delta_x > 0 { 7 } else { 6 }, delta_x.unsigned_abs()),
+                    ];
+                    let mut sent = 1;
+                    for (button, count) in steps {
+                        for _ in 0..count {
+                            sent &= XTestFakeButtonEvent(display, button, 1, 0);
+                            sent &= XTestFakeButtonEvent(display, button, 0, 0);
+                        }
+                    }
+                    sent
+                }
+                EventType::KeyPress(key) | EventType::KeyRelease(key) => {
+                    let keycode = keycode(display, key)?;
+                    let press = matches!(event_type, EventType::KeyPress(_)) as c_int;
+                    XTestFakeKeyEvent(display, keycode, press, 0)
+                }
+            }
+        };
+        // SAFETY: as above.
+        unsafe { XFlush(display) };
+        Some(if sent != 0 {
+            Ok(())
+        } else {
+            Err(SimulateError)
+        })
+    }
+
+    /// The keycode the server's keymap gives `key`, looked up by keysym
+    /// since keycodes differ between keymaps. `Key::Unknown` already holds
+    /// an X keycode.
+    ///
+    /// # Safety
+    ///
+    /// `display` must be an open connection.
+    unsafe fn keycode(display: *mut c_void, key: Key) -> Option<c_uint> {
+        if let Key::Unknown(code) = key {
+            return Some(code);
+        }
+        match XKeysymToKeycode(display, keysym(key)?) {
+            0 => None,
+            keycode => Some(keycode.into()),
+        }
+    }
+
+    /// The keysym of `key` on a US layout, from X11's `keysymdef.h`.
+    fn keysym(key: Key) -> Option<c_ulong> {
+        Some(match key {
+            Key::Alt => 0xffe9,
+            Key::AltGr => 0xfe03,
+            Key::Backspace => 0xff08,
+            Key::CapsLock => 0xffe5,
+            Key::ControlLeft => 0xffe3,
+            Key::ControlRight => 0xffe4,
+            Key::Delete => 0xffff,
+            Key::DownArrow => 0xff54,
+            Key::End => 0xff57,
+            Key::Escape => 0xff1b,
+            Key::F1 => 0xffbe,
+            Key::F2 => 0xffbf,
+            Key::F3 => 0xffc0,
+            Key::F4 => 0xffc1,
+            Key::F5 => 0xffc2,
+            Key::F6 => 0xffc3,
+            Key::F7 => 0xffc4,
+            Key::F8 => 0xffc5,
+            Key::F9 => 0xffc6,
+            Key::F10 => 0xffc7,
+            Key::F11 => 0xffc8,
+            Key::F12 => 0xffc9,
+            Key::Home => 0xff50,
+            Key::LeftArrow => 0xff51,
+            Key::MetaLeft => 0xffeb,
+            Key::MetaRight => 0xffec,
+            Key::PageDown => 0xff56,
+            Key::PageUp => 0xff55,
+            Key::Return => 0xff0d,
+            Key::RightArrow => 0xff53,
+            Key::ShiftLeft => 0xffe1,
+            Key::ShiftRight => 0xffe2,
+            Key::Space => 0x20,
+            Key::Tab => 0xff09,
+            Key::UpArrow => 0xff52,
+            Key::PrintScreen => 0xff61,
+            Key::ScrollLock => 0xff14,
+            Key::Pause => 0xff13,
+            Key::NumLock => 0xff7f,
+            Key::BackQuote => 0x60,
+            Key::Num1 => 0x31,
+            Key::Num2 => 0x32,
+            Key::Num3 => 0x33,
+            Key::Num4 => 0x34,
+            Key::Num5 => 0x35,
+            Key::Num6 => 0x36,
+            Key::Num7 => 0x37,
+            Key::Num8 => 0x38,
+            Key::Num9 => 0x39,
+            Key::Num0 => 0x30,
+            Key::Minus => 0x2d,
+            Key::Equal => 0x3d,
+            Key::KeyQ => 0x71,
+            Key::KeyW => 0x77,
+            Key::KeyE => 0x65,
+            Key::KeyR => 0x72,
+            Key::KeyT => 0x74,
+            Key::KeyY => 0x79,
+            Key::KeyU => 0x75,
+            Key::KeyI => 0x69,
+            Key::KeyO => 0x6f,
+            Key::KeyP => 0x70,
+            Key::LeftBracket => 0x5b,
+            Key::RightBracket => 0x5d,
+            Key::KeyA => 0x61,
+            Key::KeyS => 0x73,
+            Key::KeyD => 0x64,
+            Key::KeyF => 0x66,
+            Key::KeyG => 0x67,
+            Key::KeyH => 0x68,
+            Key::KeyJ => 0x6a,
+            Key::KeyK => 0x6b,
+            Key::KeyL => 0x6c,
+            Key::SemiColon => 0x3b,
+            Key::Quote => 0x27,
+            Key::BackSlash => 0x5c,
+            Key::IntlBackslash => 0x3c,
+            Key::KeyZ => 0x7a,
+            Key::KeyX => 0x78,
+            Key::KeyC => 0x63,
+            Key::KeyV => 0x76,
+            Key::KeyB => 0x62,
+            Key::KeyN => 0x6e,
+            Key::KeyM => 0x6d,
+            Key::Comma => 0x2c,
+            Key::Dot => 0x2e,
+            Key::Slash => 0x2f,
+            Key::Insert => 0xff63,
+            Key::KpReturn => 0xff8d,
+            Key::KpMinus => 0xffad,
+            Key::KpPlus => 0xffab,
+            Key::KpMultiply => 0xffaa,
+            Key::KpDivide => 0xffaf,
+            Key::Kp0 => 0xffb0,
+            Key::Kp1 => 0xffb1,
+            Key::Kp2 => 0xffb2,
+            Key::Kp3 => 0xffb3,
+            Key::Kp4 => 0xffb4,
+            Key::Kp5 => 0xffb5,
+            Key::Kp6 => 0xffb6,
+            Key::Kp7 => 0xffb7,
+            Key::Kp8 => 0xffb8,
+            Key::Kp9 => 0xffb9,
+            Key::KpDelete => 0xff9f,
+            Key::Function | Key::Unknown(_) => return None,
+        })
+    }
+
+    /// Opens `DISPLAY` and checks that the server has XTEST.
+    fn open() -> Result<Display, String> {
+        // SAFETY: a null name opens `DISPLAY`; the connection is closed on
+        // drop, or here if XTEST is missing.
+        unsafe {
+            let display = XOpenDisplay(ptr::null());
+            if display.is_null() {
+                return Err("cannot open the X display".to_owned());
+            }
+            let (mut event_base, mut error_base, mut major, mut minor) = (0, 0, 0, 0);
+            let has_xtest = XTestQueryExtension(
+                display,
+                &mut event_base,
+                &mut error_base,
+                &mut major,
+                &mut minor,
+            );
+            if has_xtest == 0 {
+                XCloseDisplay(display);
+                return Err("the X server has no XTEST extension".to_owned());
+            }
+            Ok(Display(display))
+        }
+    }
+}